@@ -0,0 +1,645 @@
+//! Static checks behind `agam check`
+//!
+//! The checker walks a parsed program without running it. It keeps scopes
+//! of its own, the same shape as the resolver's, so that every use of a
+//! name can be matched to its declaration, and it remembers which
+//! declarations were ever read.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::error::AgamError;
+use crate::interpreter::builtin::create_builtins;
+use crate::interpreter::ops::builtin_environment;
+use crate::lexer::{Lexer, Span, Token, TokenType};
+use crate::parser::ast::*;
+use crate::parser::Parser;
+use crate::resolver::{block_declarations, Resolver};
+
+/// How serious a diagnostic is: errors fail `agam check`, warnings do not
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// A problem found in a program; lines and columns are 1-based like `Span`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Short stable name of the check: `undefined-name`, `unused-variable`,
+    /// ..., or the error kind (`parser`, `compile`) for errors that stop
+    /// the program from being analysed further
+    pub code: &'static str,
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Diagnostic {
+    fn from_error(error: &AgamError) -> Self {
+        let (line, column) = error.location();
+        Diagnostic { severity: Severity::Error, code: error.kind(), message: error.message(), line, column }
+    }
+}
+
+/// Check `source` without running it, returning what was found in source order
+pub fn check_source(source: &str) -> Vec<Diagnostic> {
+    let tokens = match Lexer::tokenize(source) {
+        Ok(tokens) => tokens,
+        Err(error) => return vec![Diagnostic::from_error(&error)],
+    };
+    let mut program = match Parser::new(tokens.clone()).parse() {
+        Ok(program) => program,
+        Err(error) => return vec![Diagnostic::from_error(&error)],
+    };
+
+    let mut diagnostics = Vec::new();
+    if let Err(error) = Resolver::new(builtin_environment().borrow().declared()).resolve(&mut program) {
+        diagnostics.push(Diagnostic::from_error(&error));
+    }
+    diagnostics.extend(Checker::check(&program, &tokens));
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+    diagnostics
+}
+
+/// Which warning a declaration gets if it is never read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unused {
+    Variable,
+    Import,
+}
+
+struct Variable {
+    name: String,
+    /// Position of the name once its declaration has been walked; a block's
+    /// declarations are known before then, as in the resolver
+    position: Option<(usize, usize)>,
+    unused: Option<Unused>,
+    used: bool,
+}
+
+struct Checker<'a> {
+    tokens: &'a [Token],
+    /// Built-in functions and their arity, `None` when variadic
+    builtins: HashMap<String, Option<usize>>,
+    /// Enums declared in the program and their variants
+    enums: HashMap<&'a str, Vec<&'a str>>,
+    variables: Vec<Variable>,
+    /// Open scopes, innermost last, mapping names to `variables`
+    scopes: Vec<HashMap<String, usize>>,
+    /// Functions being checked; top-level names are a module's exports, so
+    /// only imports and names declared inside a function can be unused
+    functions: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Checker<'a> {
+    fn check(program: &'a Program, tokens: &'a [Token]) -> Vec<Diagnostic> {
+        let mut checker = Checker {
+            tokens,
+            builtins: create_builtins().into_iter().map(|(name, function)| (name, function.arity)).collect(),
+            enums: HashMap::new(),
+            variables: Vec::new(),
+            scopes: Vec::new(),
+            functions: 0,
+            diagnostics: Vec::new(),
+        };
+        // Functions may match on an enum declared further down
+        for stmt in &program.statements {
+            if let StatementKind::Enum { name, variants, .. } = &stmt.kind {
+                checker.enums.insert(name, variants.iter().map(|(variant, _)| variant.as_str()).collect());
+            }
+        }
+
+        checker.block(&program.statements);
+
+        for variable in &checker.variables {
+            let (Some(unused), Some((line, column))) = (variable.unused, variable.position) else {
+                continue;
+            };
+            if variable.used || variable.name.starts_with('_') {
+                continue;
+            }
+            let (code, message) = match unused {
+                Unused::Variable => ("unused-variable", format!("மாறி '{}' பயன்படுத்தப்படவில்லை", variable.name)),
+                Unused::Import => ("unused-import", format!("இறக்குமதி '{}' பயன்படுத்தப்படவில்லை", variable.name)),
+            };
+            checker.diagnostics.push(Diagnostic { severity: Severity::Warning, code, message, line, column });
+        }
+        checker.diagnostics
+    }
+
+    fn report(&mut self, severity: Severity, code: &'static str, (line, column): (usize, usize), message: String) {
+        self.diagnostics.push(Diagnostic { severity, code, message, line, column });
+    }
+
+    /// Position of the first `name` token at or after `from`, which is
+    /// where a statement declaring it names it; `from` itself if none
+    fn position(&self, name: &str, from: (usize, usize)) -> (usize, usize) {
+        let start = self.tokens.partition_point(|token| (token.line, token.column) < from);
+        self.tokens[start..]
+            .iter()
+            .find(|token| matches!(&token.token_type, TokenType::Identifier(found) if found == name))
+            .map_or(from, |token| (token.line, token.column))
+    }
+
+    fn push_scope(&mut self, names: Vec<&str>) {
+        let mut scope = HashMap::new();
+        for name in names {
+            self.variables.push(Variable { name: name.to_string(), position: None, unused: None, used: false });
+            scope.insert(name.to_string(), self.variables.len() - 1);
+        }
+        self.scopes.push(scope);
+    }
+
+    fn block(&mut self, statements: &'a [Statement]) {
+        self.push_scope(block_declarations(statements).into_iter().map(|(name, _)| name).collect());
+        self.statements(statements);
+        self.scopes.pop();
+    }
+
+    /// Check a block's statements, flagging the first one that follows a
+    /// statement which always leaves the block
+    fn statements(&mut self, statements: &'a [Statement]) {
+        let mut exit = None;
+        let mut reported = false;
+        for stmt in statements {
+            if let Some(keyword) = &exit {
+                if !reported {
+                    reported = true;
+                    self.report(
+                        Severity::Warning,
+                        "unreachable",
+                        (stmt.span.line, stmt.span.column),
+                        format!("'{}' க்குப் பின் உள்ள இந்தக் குறியீடு ஒருபோதும் இயங்காது", keyword),
+                    );
+                }
+            }
+            self.statement(stmt);
+            if exit.is_none() {
+                exit = match stmt.kind {
+                    StatementKind::Return(_) => Some(TokenType::Thirumbu),
+                    StatementKind::Break => Some(TokenType::Niruthu),
+                    StatementKind::Continue => Some(TokenType::Thodar),
+                    StatementKind::Throw(_) => Some(TokenType::Veesu),
+                    _ => None,
+                };
+            }
+        }
+    }
+
+    /// Declare `name` in the innermost scope once its statement is reached
+    fn declare(&mut self, name: &str, from: Span, unused: Option<Unused>) {
+        let position = self.position(name, (from.line, from.column));
+        if self.builtins.contains_key(name) {
+            self.report(Severity::Warning, "shadowed-builtin", position, format!("'{}' உள்ளமைந்த செயலை மறைக்கிறது", name));
+        }
+        let unused = unused.filter(|unused| *unused == Unused::Import || self.functions > 0);
+
+        let scope = self.scopes.last_mut().expect("open scope");
+        match scope.get(name).map(|&index| &mut self.variables[index]) {
+            // Declared ahead by its block, now reached
+            Some(variable) if variable.position.is_none() => {
+                variable.position = Some(position);
+                variable.unused = unused;
+            }
+            _ => {
+                self.variables.push(Variable { name: name.to_string(), position: Some(position), unused, used: false });
+                scope.insert(name.to_string(), self.variables.len() - 1);
+            }
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<usize> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name).copied())
+    }
+
+    /// A read of `name`
+    fn use_name(&mut self, name: &str, span: Span) {
+        match self.lookup(name) {
+            Some(index) => self.variables[index].used = true,
+            None if self.builtins.contains_key(name) => {}
+            None => self.report(
+                Severity::Error,
+                "undefined-name",
+                (span.line, span.column),
+                format!("வரையறுக்கப்படாத மாறி '{}'", name),
+            ),
+        }
+    }
+
+    /// A plain assignment to `name`, which does not count as using it
+    fn assign(&mut self, name: &str, span: Span) {
+        if self.lookup(name).is_none() && !self.builtins.contains_key(name) {
+            self.report(
+                Severity::Error,
+                "undefined-name",
+                (span.line, span.column),
+                format!("வரையறுக்கப்படாத மாறி '{}'", name),
+            );
+        }
+    }
+
+    fn function(&mut self, params: &'a Parameters, body: FunctionBody<'a>, span: Span) {
+        for default in &params.defaults {
+            self.expression(default);
+        }
+        self.functions += 1;
+        self.scopes.push(HashMap::new());
+        for name in params.signature.names() {
+            self.declare(name, span, None);
+        }
+        match body {
+            FunctionBody::Block(statements) => self.block(statements),
+            FunctionBody::Expr(expr) => {
+                self.scopes.push(HashMap::new());
+                self.expression(expr);
+                self.scopes.pop();
+            }
+        }
+        self.scopes.pop();
+        self.functions -= 1;
+    }
+
+    fn statement(&mut self, stmt: &'a Statement) {
+        let span = stmt.span;
+        match &stmt.kind {
+            StatementKind::Let { name, value, .. } => {
+                self.expression(value);
+                self.declare(name, span, Some(Unused::Variable));
+            }
+
+            StatementKind::LetUnpack { names, value, .. } => {
+                self.expression(value);
+                for name in names {
+                    self.declare(name, span, Some(Unused::Variable));
+                }
+            }
+
+            StatementKind::AssignUnpack { targets, value } => {
+                self.expression(value);
+                for target in targets {
+                    match &target.kind {
+                        ExpressionKind::Identifier { name, .. } => self.assign(name, target.span),
+                        _ => self.expression(target),
+                    }
+                }
+            }
+
+            StatementKind::Expression(expr) | StatementKind::Yield(expr) => self.expression(expr),
+
+            StatementKind::Print(args) => {
+                for arg in args {
+                    self.expression(arg);
+                }
+            }
+
+            StatementKind::If { condition, then_branch, elif_branches, else_branch } => {
+                self.expression(condition);
+                self.block(then_branch);
+                for (cond, body) in elif_branches {
+                    self.expression(cond);
+                    self.block(body);
+                }
+                if let Some(body) = else_branch {
+                    self.block(body);
+                }
+            }
+
+            StatementKind::While { condition, body } => {
+                self.expression(condition);
+                self.block(body);
+            }
+
+            StatementKind::For { variables, iterable, body, .. } => {
+                self.expression(iterable);
+                for name in variables {
+                    self.declare(name, span, None);
+                }
+                self.block(body);
+            }
+
+            StatementKind::Function { name, params, body, .. } => {
+                self.function(params, FunctionBody::Block(body), span);
+                self.declare(name, span, None);
+            }
+
+            StatementKind::Return(value) | StatementKind::Throw(value) => {
+                if let Some(expr) = value {
+                    self.expression(expr);
+                }
+            }
+
+            StatementKind::Nonlocal(names) => {
+                for name in names {
+                    self.use_name(name, span);
+                }
+            }
+
+            StatementKind::Break | StatementKind::Continue => {}
+
+            StatementKind::Import { module, items, .. } => match items {
+                Some(items) => {
+                    for item in items {
+                        self.declare(item, span, Some(Unused::Import));
+                    }
+                }
+                None => self.declare(module, span, Some(Unused::Import)),
+            },
+
+            StatementKind::TryCatch { try_block, catch_clauses, finally_block } => {
+                self.block(try_block);
+                for clause in catch_clauses {
+                    self.declare(&clause.error_var, clause.span, None);
+                    self.block(&clause.body);
+                }
+                if let Some(body) = finally_block {
+                    self.block(body);
+                }
+            }
+
+            StatementKind::Struct { name, fields, methods, .. } => {
+                let mut seen = HashSet::new();
+                let mut from = (span.line, span.column);
+                for (field, _) in fields {
+                    from = self.position(field, from);
+                    if !seen.insert(field) {
+                        self.report(
+                            Severity::Error,
+                            "duplicate-field",
+                            from,
+                            format!("கட்டமைப்பு '{}' இல் புலம் '{}' ஏற்கனவே உள்ளது", name, field),
+                        );
+                    }
+                    from.1 += 1;
+                }
+                for method in methods {
+                    self.function(&method.params, FunctionBody::Block(&method.body), method.span);
+                }
+                self.declare(name, span, None);
+            }
+
+            StatementKind::Enum { name, variants, .. } => {
+                self.enums.insert(name, variants.iter().map(|(variant, _)| variant.as_str()).collect());
+                self.declare(name, span, None);
+            }
+
+            StatementKind::Match { value, arms } => {
+                self.expression(value);
+                self.exhaustive(arms.iter().map(|arm| (&arm.pattern, arm.guard.is_some())), span);
+                for arm in arms {
+                    self.pattern(&arm.pattern, arm.span);
+                    if let Some(guard) = &arm.guard {
+                        self.expression(guard);
+                    }
+                    self.block(&arm.body);
+                }
+            }
+        }
+    }
+
+    fn pattern(&mut self, pattern: &'a Pattern, span: Span) {
+        match pattern {
+            Pattern::Literal(expr) => self.expression(expr),
+            Pattern::Variable { name, .. } => self.declare(name, span, Some(Unused::Variable)),
+            Pattern::Wildcard => {}
+            Pattern::EnumVariant { enum_name, payload, .. } => {
+                self.use_name(enum_name, span);
+                for part in payload.iter().flatten() {
+                    self.pattern(part, span);
+                }
+            }
+            Pattern::List { elements, rest } => {
+                for part in elements.iter().chain(rest.as_deref()) {
+                    self.pattern(part, span);
+                }
+            }
+            Pattern::Dict(entries) => {
+                for (_, part) in entries {
+                    self.pattern(part, span);
+                }
+            }
+            Pattern::Struct { name, fields } => {
+                self.use_name(name, span);
+                for (_, part) in fields {
+                    self.pattern(part, span);
+                }
+            }
+        }
+    }
+
+    /// Warn when the arms of a match all name variants of one enum declared
+    /// in the program but leave some of its variants out
+    fn exhaustive(&mut self, arms: impl Iterator<Item = (&'a Pattern, bool)>, span: Span) {
+        let mut matched = None;
+        let mut covered = HashSet::new();
+        for (pattern, guarded) in arms {
+            match pattern {
+                Pattern::Wildcard | Pattern::Variable { .. } if !guarded => return,
+                Pattern::Wildcard | Pattern::Variable { .. } => {}
+                Pattern::EnumVariant { enum_name, variant, payload } => {
+                    if *matched.get_or_insert(enum_name) != enum_name {
+                        return;
+                    }
+                    let binds_all = payload
+                        .iter()
+                        .flatten()
+                        .all(|part| matches!(part, Pattern::Wildcard | Pattern::Variable { .. }));
+                    if !guarded && binds_all {
+                        covered.insert(variant.as_str());
+                    }
+                }
+                _ => return,
+            }
+        }
+
+        let Some(enum_name) = matched else { return };
+        let Some(variants) = self.enums.get(enum_name.as_str()) else { return };
+        let missing: Vec<String> = variants
+            .iter()
+            .filter(|variant| !covered.contains(*variant))
+            .map(|variant| format!("{}.{}", enum_name, variant))
+            .collect();
+        if !missing.is_empty() {
+            self.report(
+                Severity::Warning,
+                "non-exhaustive-match",
+                (span.line, span.column),
+                format!("'{}' இன் எல்லா வகைகளும் பொருத்தப்படவில்லை: {}", enum_name, missing.join(", ")),
+            );
+        }
+    }
+
+    fn comprehension(&mut self, clauses: &'a [ComprehensionClause], elements: &[&'a Expression], span: Span) {
+        self.scopes.push(HashMap::new());
+        for clause in clauses {
+            match clause {
+                ComprehensionClause::For { variables, iterable, .. } => {
+                    self.expression(iterable);
+                    for name in variables {
+                        self.declare(name, span, None);
+                    }
+                }
+                ComprehensionClause::If(condition) => self.expression(condition),
+            }
+        }
+        for element in elements {
+            self.expression(element);
+        }
+        self.scopes.pop();
+    }
+
+    fn expression(&mut self, expr: &'a Expression) {
+        let span = expr.span;
+        match &expr.kind {
+            ExpressionKind::Identifier { name, .. } => self.use_name(name, span),
+
+            ExpressionKind::Assignment { name, operator, value, .. } => {
+                self.expression(value);
+                match operator {
+                    // x += 1 reads x
+                    Some(_) => self.use_name(name, span),
+                    None => self.assign(name, span),
+                }
+            }
+
+            ExpressionKind::Call { callee, arguments, keywords } => {
+                if let ExpressionKind::Identifier { name, .. } = &callee.kind {
+                    let arity = self.builtins.get(name).copied().flatten();
+                    if let (None, Some(arity)) = (self.lookup(name), arity) {
+                        if keywords.is_empty() && arguments.len() != arity {
+                            self.report(
+                                Severity::Error,
+                                "arity",
+                                (callee.span.line, callee.span.column),
+                                format!(
+                                    "'{}' செயலுக்கு {} அளவுருக்கள் தேவை, {} கொடுக்கப்பட்டது",
+                                    name,
+                                    arity,
+                                    arguments.len()
+                                ),
+                            );
+                        }
+                    }
+                }
+                self.expression(callee);
+                for arg in arguments {
+                    self.expression(arg);
+                }
+                for (_, arg) in keywords {
+                    self.expression(arg);
+                }
+            }
+
+            ExpressionKind::Lambda { params, body } => {
+                let body = match body {
+                    LambdaBody::Expression(expr) => FunctionBody::Expr(expr),
+                    LambdaBody::Block(statements) => FunctionBody::Block(statements),
+                };
+                self.function(params, body, span);
+            }
+
+            ExpressionKind::Match { value, arms } => {
+                self.expression(value);
+                self.exhaustive(arms.iter().map(|arm| (&arm.pattern, arm.guard.is_some())), span);
+                for arm in arms {
+                    // Each arm binds its pattern in a block of its own
+                    self.scopes.push(HashMap::new());
+                    self.pattern(&arm.pattern, arm.span);
+                    if let Some(guard) = &arm.guard {
+                        self.expression(guard);
+                    }
+                    self.expression(&arm.value);
+                    self.scopes.pop();
+                }
+            }
+
+            ExpressionKind::ListComprehension { element, clauses } => {
+                self.comprehension(clauses, &[element], span);
+            }
+
+            ExpressionKind::DictComprehension { key, value, clauses } => {
+                self.comprehension(clauses, &[key, value], span);
+            }
+
+            ExpressionKind::StructInit { name, arguments } => {
+                self.use_name(name, span);
+                for arg in arguments {
+                    self.expression(arg);
+                }
+            }
+
+            ExpressionKind::FString { parts } => {
+                for part in parts {
+                    if let FStringPart::Expression(inner) = part {
+                        self.expression(inner);
+                    }
+                }
+            }
+            ExpressionKind::Binary { left, right, .. } => {
+                self.expression(left);
+                self.expression(right);
+            }
+            ExpressionKind::Unary { operand, .. } => self.expression(operand),
+            ExpressionKind::List(elements) | ExpressionKind::Tuple(elements) => {
+                for element in elements {
+                    self.expression(element);
+                }
+            }
+            ExpressionKind::Dict(pairs) => {
+                for (key, value) in pairs {
+                    self.expression(key);
+                    self.expression(value);
+                }
+            }
+            ExpressionKind::Index { object, index } => {
+                self.expression(object);
+                self.expression(index);
+            }
+            ExpressionKind::Slice { object, bounds } => {
+                self.expression(object);
+                for expr in bounds.parts().into_iter().flatten() {
+                    self.expression(expr);
+                }
+            }
+            ExpressionKind::SliceAssignment { object, bounds, value } => {
+                self.expression(object);
+                for expr in bounds.parts().into_iter().flatten() {
+                    self.expression(expr);
+                }
+                self.expression(value);
+            }
+            ExpressionKind::Grouping(inner) => self.expression(inner),
+            ExpressionKind::MemberAccess { object, .. } => self.expression(object),
+            ExpressionKind::IndexAssignment { object, index, value, .. } => {
+                self.expression(object);
+                self.expression(index);
+                self.expression(value);
+            }
+            ExpressionKind::MemberAssignment { object, value, .. } => {
+                self.expression(object);
+                self.expression(value);
+            }
+            ExpressionKind::Integer(_)
+            | ExpressionKind::Number(_)
+            | ExpressionKind::String(_)
+            | ExpressionKind::Boolean(_)
+            | ExpressionKind::Null => {}
+        }
+    }
+}
+
+/// Body of a function or lambda being checked
+enum FunctionBody<'a> {
+    Block(&'a [Statement]),
+    Expr(&'a Expression),
+}
@@ -0,0 +1,10 @@
+//! Static checker for Agam
+//!
+//! `agam check` reports mistakes a program would only hit when it runs,
+//! such as undefined names and wrong built-in arities, together with
+//! likely slips: unreachable or unused code, shadowed built-ins and
+//! matches that miss an enum variant
+
+pub mod checker;
+
+pub use checker::{check_source, Diagnostic, Severity};
@@ -5,9 +5,13 @@
 #![allow(clippy::module_inception)]
 
 use thiserror::Error;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::lexer::Span;
+use crate::types::Value;
 
 /// Main error type for Agam
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone)]
 pub enum AgamError {
     #[error("சொற்பிழை (Lexer Error) [{line}:{column}]: {message}")]
     LexerError {
@@ -23,17 +27,60 @@ pub enum AgamError {
         message: String,
     },
 
+    /// Found by the resolver before the program runs
+    #[error("தொகுப்பு பிழை (Compile Error) [{line}:{column}]: {message}")]
+    CompileError {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
     #[error("இயக்க பிழை (Runtime Error) [{line}:{column}]: {message}")]
     RuntimeError {
         line: usize,
         column: usize,
         message: String,
+        /// Call chain that led to the error, outermost first; empty at top level
+        stack: Vec<StackFrame>,
+    },
+
+    /// A failed `உறுதி` assertion. Shown like any runtime error, but kept
+    /// apart so that `agam test` can tell a failed test from a broken one
+    #[error("இயக்க பிழை (Runtime Error) [{line}:{column}]: {message}")]
+    AssertionError {
+        line: usize,
+        column: usize,
+        /// Not a `String`, so that `AgamError` stays as small as `RuntimeError`
+        /// makes it; every call's result carries one
+        message: Box<str>,
+        /// Call chain that led to the assertion, outermost first; empty at top level
+        stack: Vec<StackFrame>,
+    },
+
+    /// A value raised with `வீசு`, kept intact for `பிடி`
+    #[error("இயக்க பிழை (Runtime Error) [{line}:{column}]: வீசப்பட்ட பிழை: {value}")]
+    Thrown {
+        line: usize,
+        column: usize,
+        value: Box<Value>,
+        /// Call chain that led to the throw, outermost first; empty at top level
+        stack: Vec<StackFrame>,
     },
 
     #[error("கோப்பு பிழை (File Error): {0}")]
     FileError(String),
 }
 
+/// One entry of a traceback: `function` in `file` was executing at `line:column`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackFrame {
+    pub function: String,
+    /// Source file, `None` for the main program when it has no path (REPL)
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
 impl AgamError {
     pub fn lexer_error(line: usize, column: usize, message: String) -> Self {
         AgamError::LexerError { line, column, message }
@@ -43,14 +90,84 @@ impl AgamError {
         AgamError::ParserError { line, column, message }
     }
 
+    /// Compile-time error located at an AST node's span
+    pub fn compile_error_at(span: Span, message: String) -> Self {
+        AgamError::CompileError { line: span.line, column: span.column, message }
+    }
+
     pub fn runtime_error(line: usize, column: usize, message: String) -> Self {
-        AgamError::RuntimeError { line, column, message }
+        AgamError::RuntimeError { line, column, message, stack: Vec::new() }
+    }
+
+    /// Runtime error located at an AST node's span
+    pub fn runtime_error_at(span: Span, message: String) -> Self {
+        AgamError::runtime_error(span.line, span.column, message)
+    }
+
+    /// Failed assertion located at the call's span
+    pub fn assertion_error_at(span: Span, message: String) -> Self {
+        AgamError::AssertionError { line: span.line, column: span.column, message: message.into(), stack: Vec::new() }
     }
 
     pub fn file_error(message: String) -> Self {
         AgamError::FileError(message)
     }
 
+    /// Error raised by `வீசு value` at an AST node's span
+    pub fn thrown_at(span: Span, value: Value) -> Self {
+        AgamError::Thrown { line: span.line, column: span.column, value: Box::new(value), stack: Vec::new() }
+    }
+
+    /// Kind of error as seen by Agam code: lexer, parser, compile, runtime,
+    /// assertion, thrown or file
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AgamError::LexerError { .. } => "lexer",
+            AgamError::ParserError { .. } => "parser",
+            AgamError::CompileError { .. } => "compile",
+            AgamError::RuntimeError { .. } => "runtime",
+            AgamError::AssertionError { .. } => "assertion",
+            AgamError::Thrown { .. } => "thrown",
+            AgamError::FileError(_) => "file",
+        }
+    }
+
+    /// Error message without the kind and location header
+    pub fn message(&self) -> String {
+        match self {
+            AgamError::LexerError { message, .. }
+            | AgamError::ParserError { message, .. }
+            | AgamError::CompileError { message, .. }
+            | AgamError::RuntimeError { message, .. }
+            | AgamError::FileError(message) => message.clone(),
+            AgamError::AssertionError { message, .. } => message.to_string(),
+            AgamError::Thrown { value, .. } => value.to_string(),
+        }
+    }
+
+    /// Source location as (line, column); (0, 0) when unknown
+    pub fn location(&self) -> (usize, usize) {
+        match self {
+            AgamError::LexerError { line, column, .. }
+            | AgamError::ParserError { line, column, .. }
+            | AgamError::CompileError { line, column, .. }
+            | AgamError::RuntimeError { line, column, .. }
+            | AgamError::AssertionError { line, column, .. }
+            | AgamError::Thrown { line, column, .. } => (*line, *column),
+            AgamError::FileError(_) => (0, 0),
+        }
+    }
+
+    /// Call chain recorded for the error, outermost first
+    pub fn stack(&self) -> &[StackFrame] {
+        match self {
+            AgamError::RuntimeError { stack, .. }
+            | AgamError::AssertionError { stack, .. }
+            | AgamError::Thrown { stack, .. } => stack,
+            _ => &[],
+        }
+    }
+
     /// Get a helpful suggestion for common errors
     pub fn suggestion(&self) -> Option<String> {
         match self {
@@ -70,6 +187,15 @@ impl AgamError {
                     None
                 }
             }
+            AgamError::CompileError { message, .. } => {
+                if message.contains("வரையறுக்கும் முன்") {
+                    Some("மாறியை பயன்படுத்தும் முன் 'மாறி' சொல்லுடன் வரையறுக்கவும்".to_string())
+                } else if message.contains("மாறாத") {
+                    Some("மாற்ற வேண்டிய மதிப்புக்கு 'மாறி' பயன்படுத்தவும்".to_string())
+                } else {
+                    None
+                }
+            }
             AgamError::RuntimeError { message, .. } => {
                 if message.contains("வரையறுக்கப்படாத மாறி") {
                     Some("மாறி 'மாறி' சொல்லுடன் முதலில் வரையறுக்கவும்".to_string())
@@ -84,6 +210,9 @@ impl AgamError {
     }
 }
 
+/// Repeated identical frames beyond this many are collapsed (deep recursion)
+const MAX_REPEATED_FRAMES: usize = 3;
+
 /// Pretty print an error with context
 pub fn format_error(error: &AgamError, source: &str) -> String {
     let mut output = String::new();
@@ -91,16 +220,49 @@ pub fn format_error(error: &AgamError, source: &str) -> String {
     match error {
         AgamError::LexerError { line, column, .. }
         | AgamError::ParserError { line, column, .. }
-        | AgamError::RuntimeError { line, column, .. } => {
+        | AgamError::CompileError { line, column, .. }
+        | AgamError::RuntimeError { line, column, .. }
+        | AgamError::AssertionError { line, column, .. }
+        | AgamError::Thrown { line, column, .. } => {
+            let stack = error.stack();
+            // The main program's file is the outermost frame's file;
+            // other files (imported modules) are read from disk
+            let main_file = stack.first().map(|frame| &frame.file);
+            let source_for = |file: &Option<String>| -> Option<String> {
+                if Some(file) == main_file {
+                    Some(source.to_string())
+                } else {
+                    file.as_ref().and_then(|path| std::fs::read_to_string(path).ok())
+                }
+            };
+
+            if !stack.is_empty() {
+                output.push_str(&format_traceback(stack, &source_for));
+            }
+
             output.push_str(&format!("\n{}\n", error));
 
+            // Errors inside imported modules name the file they occurred in
+            let error_source = match stack.last() {
+                Some(frame) if Some(&frame.file) != main_file => {
+                    if let Some(path) = &frame.file {
+                        output.push_str(&format!("  --> கோப்பு \"{}\"\n", path));
+                    }
+                    source_for(&frame.file).unwrap_or_default()
+                }
+                _ => source.to_string(),
+            };
+
             // Show the line with the error
             if *line > 0 {
-                if let Some(source_line) = source.lines().nth(*line - 1) {
+                if let Some(source_line) = error_source.lines().nth(*line - 1) {
                     output.push_str(&format!("  {} | {}\n", line, source_line));
                     
-                    // Show pointer to error location
-                    let padding = format!("{}", line).len() + 3 + column.saturating_sub(1);
+                    // Show pointer to error location. Columns count chars, but
+                    // Tamil vowel signs combine with the previous letter, so
+                    // pad by grapheme clusters to line the caret up on screen
+                    let prefix: String = source_line.chars().take(column.saturating_sub(1)).collect();
+                    let padding = format!("{}", line).len() + 5 + prefix.graphemes(true).count();
                     output.push_str(&format!("{}^\n", " ".repeat(padding)));
                 }
             }
@@ -117,3 +279,39 @@ pub fn format_error(error: &AgamError, source: &str) -> String {
 
     output
 }
+
+/// Render the calling frames of a runtime error, Python style
+fn format_traceback(frames: &[StackFrame], source_for: &dyn Fn(&Option<String>) -> Option<String>) -> String {
+    let mut output = String::from("\nபின்தடம் (Traceback, most recent call last):\n");
+    let mut index = 0;
+
+    while index < frames.len() {
+        let frame = &frames[index];
+        let file = frame.file.as_deref().unwrap_or("<உள்ளீடு>");
+        output.push_str(&format!(
+            "  கோப்பு \"{}\", வரி {}, {} இல்\n",
+            file, frame.line, frame.function
+        ));
+        if let Some(text) = source_for(&frame.file)
+            .and_then(|src| src.lines().nth(frame.line.saturating_sub(1)).map(|l| l.trim().to_string()))
+        {
+            output.push_str(&format!("    {}\n", text));
+        }
+
+        // Collapse runs of identical frames from deep recursion
+        let mut repeats = 0;
+        while index + repeats + 1 < frames.len() && frames[index + repeats + 1] == *frame {
+            repeats += 1;
+        }
+        if repeats >= MAX_REPEATED_FRAMES {
+            output.push_str(&format!(
+                "  [முந்தைய வரி மேலும் {} முறை (previous line repeated {} more times)]\n",
+                repeats, repeats
+            ));
+            index += repeats;
+        }
+        index += 1;
+    }
+
+    output
+}
@@ -0,0 +1,856 @@
+//! Pretty printer behind `agam fmt`
+//!
+//! The program is printed back from its syntax tree: four spaces per
+//! block, spaces around binary operators and after commas, at most one
+//! blank line in a row, and one around each top-level செயல், கட்டமைப்பு
+//! and விருப்பம். The parser never sees comments, so they are put back
+//! by source line: above the statement that follows them, or at the end
+//! of the line they trailed.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::error::AgamError;
+use crate::lexer::{Comment, Lexer, Span, Token, TokenType};
+use crate::parser::{
+    BinaryOp, ComprehensionClause, Expression, ExpressionKind, FStringPart, LambdaBody, MatchArm,
+    MatchExprArm, Method, Parameters, Parser, Pattern, SliceBounds, Statement, StatementKind,
+    UnaryOp, SELF_NAME,
+};
+
+/// Every keyword as a (Tamil, English) pair
+pub const KEYWORD_SPELLINGS: &[(&str, &str)] = &[
+    ("செயல்", "fn"),
+    ("மாறி", "let"),
+    ("மாறாத", "const"),
+    ("என்றால்", "if"),
+    ("இல்லையென்றால்", "elif"),
+    ("இல்லை", "else"),
+    ("வரை", "while"),
+    ("ஒவ்வொரு", "for"),
+    ("உள்ள", "in"),
+    ("திரும்பு", "return"),
+    ("உண்மை", "true"),
+    ("பொய்", "false"),
+    ("இல்லா", "null"),
+    ("நிறுத்து", "break"),
+    ("தொடர்", "continue"),
+    ("மற்றும்", "and"),
+    ("அல்லது", "or"),
+    ("இல்ல", "not"),
+    ("அச்சிடு", "print"),
+    ("உள்ளீடு", "input"),
+    ("இறக்குமதி", "import"),
+    ("இருந்து", "from"),
+    ("முயற்சி", "try"),
+    ("பிடி", "catch"),
+    ("வீசு", "throw"),
+    ("இறுதியாக", "finally"),
+    ("கட்டமைப்பு", "struct"),
+    ("விருப்பம்", "enum"),
+    ("பொருத்து", "match"),
+    ("செயலி", "lambda"),
+    ("தன்", "self"),
+    ("வெளிமாறி", "nonlocal"),
+    ("விளை", "yield"),
+];
+
+/// Spelling of the keywords in formatted code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spelling {
+    Tamil,
+    English,
+}
+
+impl Spelling {
+    /// Parse `tamil`/`english`, or `தமிழ்`/`ஆங்கிலம்`
+    pub fn from_name(name: &str) -> Option<Spelling> {
+        match name {
+            "tamil" | "தமிழ்" => Some(Spelling::Tamil),
+            "english" | "ஆங்கிலம்" => Some(Spelling::English),
+            _ => None,
+        }
+    }
+
+    /// The spelling most keywords in `tokens` already use, Tamil on a tie
+    fn majority(tokens: &[Token]) -> Spelling {
+        let (mut tamil, mut english) = (0, 0);
+        for token in tokens {
+            for (tamil_spelling, english_spelling) in KEYWORD_SPELLINGS {
+                if token.lexeme == *tamil_spelling {
+                    tamil += 1;
+                } else if token.lexeme == *english_spelling {
+                    english += 1;
+                }
+            }
+        }
+        if english > tamil {
+            Spelling::English
+        } else {
+            Spelling::Tamil
+        }
+    }
+}
+
+/// Options of `agam fmt`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FormatOptions {
+    /// Spelling to write every keyword in; by default, the one most of the
+    /// file's keywords use
+    pub keywords: Option<Spelling>,
+}
+
+/// Format a program. Source that does not parse is an error, and so is
+/// output that would not parse back with all of its comments, so that a
+/// formatter bug never overwrites a file.
+pub fn format_source(source: &str, options: &FormatOptions) -> Result<String, AgamError> {
+    let (tokens, comments) = Lexer::tokenize_with_comments(source)?;
+    let program = Parser::new(tokens.clone()).parse()?;
+    let spelling = options.keywords.unwrap_or_else(|| Spelling::majority(&tokens));
+    let comment_count = comments.len();
+
+    let mut printer = Printer::new(source, &tokens, comments, spelling);
+    printer.statements(&program.statements, usize::MAX);
+    let formatted = printer.finish();
+
+    let unformattable = |error: AgamError| {
+        let (line, column) = error.location();
+        AgamError::parser_error(line, column, format!("வடிவமைத்த நிரலைப் பகுக்க இயலவில்லை: {}", error.message()))
+    };
+    let (tokens, kept) = Lexer::tokenize_with_comments(&formatted).map_err(unformattable)?;
+    Parser::new(tokens).parse().map_err(unformattable)?;
+    if kept.len() != comment_count {
+        return Err(AgamError::parser_error(1, 1, "வடிவமைப்பில் குறிப்புரைகள் இழக்கப்பட்டன".to_string()));
+    }
+    Ok(formatted)
+}
+
+/// Whether an item may be preceded by a blank line
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Spacing {
+    /// Where the source had one
+    Source,
+    /// Always, as between top-level definitions
+    Blank,
+    /// Never, as before இல்லை or பிடி
+    Tight,
+}
+
+struct Printer<'a> {
+    tokens: &'a [Token],
+    /// Comments not yet printed, in source order
+    comments: Vec<Comment>,
+    /// Source lines holding code, whose comments trail that code
+    code_lines: HashSet<usize>,
+    /// Whether each source line is blank
+    blank_lines: Vec<bool>,
+    /// Number literals as written, by position, so `௧௦` or `1.50` stay as they are
+    literals: HashMap<(usize, usize), String>,
+    spelling: Spelling,
+    out: String,
+    indent: usize,
+    /// Nothing has been printed in the current block yet
+    fresh: bool,
+    /// Source line of whatever follows the statement being printed
+    limit: usize,
+}
+
+impl<'a> Printer<'a> {
+    fn new(source: &str, tokens: &'a [Token], comments: Vec<Comment>, spelling: Spelling) -> Self {
+        let layout = |token: &Token| {
+            matches!(token.token_type, TokenType::Newline | TokenType::Indent | TokenType::Dedent | TokenType::Eof)
+        };
+        let literals = tokens
+            .iter()
+            .filter(|token| matches!(token.token_type, TokenType::Integer(_) | TokenType::Number(_)))
+            .map(|token| ((token.line, token.column), token.lexeme.clone()))
+            .collect();
+        Printer {
+            tokens,
+            comments,
+            code_lines: tokens.iter().filter(|token| !layout(token)).map(|token| token.line).collect(),
+            blank_lines: source.lines().map(|line| line.trim().is_empty()).collect(),
+            literals,
+            spelling,
+            out: String::new(),
+            indent: 0,
+            fresh: true,
+            limit: usize::MAX,
+        }
+    }
+
+    /// Print the comments left after the last statement
+    fn finish(mut self) -> String {
+        self.indent = 0;
+        self.begin(usize::MAX, Spacing::Tight);
+        self.out
+    }
+
+    // Layout
+
+    fn pad(&self) -> String {
+        "    ".repeat(self.indent)
+    }
+
+    fn keyword(&self, tamil: &'static str) -> &'static str {
+        match self.spelling {
+            Spelling::Tamil => tamil,
+            Spelling::English => KEYWORD_SPELLINGS
+                .iter()
+                .find(|(spelling, _)| *spelling == tamil)
+                .map_or(tamil, |(_, english)| english),
+        }
+    }
+
+    fn blank_before(&self, line: usize) -> bool {
+        line >= 2 && self.blank_lines.get(line - 2).copied().unwrap_or(false)
+    }
+
+    fn gap(&mut self, blank: bool) {
+        if blank && !self.fresh {
+            self.out.push('\n');
+        }
+    }
+
+    /// Write `text` at the current indentation. A block inside it (a block
+    /// lambda) already carries its own indentation; `trailing` goes at the
+    /// end of its first line.
+    fn line(&mut self, text: &str, trailing: Option<Comment>) {
+        let mut text = text.trim_end().to_string();
+        if let Some(comment) = trailing {
+            let end = text.find('\n').unwrap_or(text.len());
+            text.insert_str(end, &format!("  {}", comment.text));
+        }
+        let pad = self.pad();
+        self.out.push_str(&pad);
+        self.out.push_str(&text);
+        self.out.push('\n');
+        self.fresh = false;
+    }
+
+    /// Write the line for source `line`, keeping the comment that trailed it.
+    /// The comment is taken first so a block inside the line cannot claim it.
+    fn write(&mut self, line: usize, render: impl FnOnce(&mut Self) -> String) {
+        let trailing = self.take_trailing(line);
+        let text = render(self);
+        self.line(&text, trailing);
+    }
+
+    fn take_trailing(&mut self, line: usize) -> Option<Comment> {
+        if !self.code_lines.contains(&line) {
+            return None;
+        }
+        let index = self.comments.iter().position(|comment| comment.line == line)?;
+        Some(self.comments.remove(index))
+    }
+
+    /// Start an item at source `line`: print the comments above it, then
+    /// the blank line `spacing` asks for
+    fn begin(&mut self, line: usize, spacing: Spacing) {
+        let mut blank = spacing == Spacing::Blank;
+        while self.comments.first().is_some_and(|comment| comment.line < line) {
+            let comment = self.comments.remove(0);
+            let gap = blank || self.blank_before(comment.line);
+            self.gap(gap);
+            blank = false;
+            self.line(&comment.text, None);
+        }
+        let gap = blank || (spacing == Spacing::Source && self.blank_before(line));
+        self.gap(gap);
+    }
+
+    /// Print the comments indented under the end of a block whose header
+    /// starts at `column`, up to the line `limit` of whatever follows it
+    fn close(&mut self, column: usize, limit: usize) {
+        while self.comments.first().is_some_and(|comment| comment.line < limit && comment.column > column) {
+            let comment = self.comments.remove(0);
+            let gap = self.blank_before(comment.line);
+            self.gap(gap);
+            self.line(&comment.text, None);
+        }
+    }
+
+    /// Line of the last `token` before line `before`, such as the இல்லை
+    /// opening an else block
+    fn keyword_line(&self, token: &TokenType, before: usize) -> usize {
+        self.tokens
+            .iter()
+            .rev()
+            .find(|candidate| candidate.line < before && candidate.token_type == *token)
+            .map_or(before, |candidate| candidate.line)
+    }
+
+    /// Line of the field or variant `name` starting a line after `after`
+    fn member_line(&self, name: &str, after: usize) -> usize {
+        self.tokens
+            .windows(2)
+            .find(|pair| {
+                pair[1].line > after
+                    && matches!(pair[0].token_type, TokenType::Newline | TokenType::Indent | TokenType::Dedent)
+                    && matches!(&pair[1].token_type, TokenType::Identifier(candidate) if candidate == name)
+            })
+            .map_or(after, |pair| pair[1].line)
+    }
+
+    // Statements
+
+    fn statements(&mut self, statements: &[Statement], limit: usize) {
+        let outer = self.limit;
+        for (i, statement) in statements.iter().enumerate() {
+            let next = statements.get(i + 1).map_or(limit, |next| next.span.line);
+            let separate = self.indent == 0
+                && i > 0
+                && (is_definition(statement) || is_definition(&statements[i - 1]));
+            self.begin(statement.span.line, if separate { Spacing::Blank } else { Spacing::Source });
+            self.limit = next;
+            self.statement(statement, next);
+        }
+        self.limit = outer;
+    }
+
+    /// Print an indented block under a header starting at `column`
+    fn block(&mut self, statements: &[Statement], column: usize, limit: usize) {
+        self.indent += 1;
+        self.fresh = true;
+        self.statements(statements, limit);
+        self.close(column, limit);
+        self.indent -= 1;
+    }
+
+    /// Print `header` at source `line` and its block. The header is begun
+    /// by the caller when it is the statement's own first line.
+    fn clause(&mut self, line: usize, header: impl FnOnce(&mut Self) -> String, body: &[Statement], column: usize, limit: usize) {
+        self.write(line, |printer| format!("{}:", header(printer)));
+        self.block(body, column, limit);
+    }
+
+    fn statement(&mut self, statement: &Statement, limit: usize) {
+        let line = statement.span.line;
+        let column = statement.span.column;
+        match &statement.kind {
+            StatementKind::If { condition, then_branch, elif_branches, else_branch } => {
+                let else_line = else_branch
+                    .as_ref()
+                    .map(|body| self.keyword_line(&TokenType::Illai, first_line(body, limit)));
+                let mut follows: Vec<usize> = elif_branches.iter().map(|(condition, _)| condition.span.line).collect();
+                follows.extend(else_line);
+                follows.push(limit);
+
+                let keyword = self.keyword("என்றால்");
+                self.clause(line, |p| format!("{} {}", keyword, p.expression(condition)), then_branch, column, follows[0]);
+                for (i, (condition, body)) in elif_branches.iter().enumerate() {
+                    let keyword = self.keyword("இல்லையென்றால்");
+                    self.begin(follows[i], Spacing::Tight);
+                    self.clause(follows[i], |p| format!("{} {}", keyword, p.expression(condition)), body, column, follows[i + 1]);
+                }
+                if let (Some(body), Some(else_line)) = (else_branch, else_line) {
+                    let keyword = self.keyword("இல்லை");
+                    self.begin(else_line, Spacing::Tight);
+                    self.clause(else_line, |_| keyword.to_string(), body, column, limit);
+                }
+            }
+            StatementKind::While { condition, body } => {
+                let keyword = self.keyword("வரை");
+                self.clause(line, |p| format!("{} {}", keyword, p.expression(condition)), body, column, limit);
+            }
+            StatementKind::For { variables, iterable, body, .. } => {
+                let (keyword, within) = (self.keyword("ஒவ்வொரு"), self.keyword("உள்ள"));
+                let header = |p: &mut Self| format!("{} {} {} {}", keyword, variables.join(", "), within, p.expression(iterable));
+                self.clause(line, header, body, column, limit);
+            }
+            StatementKind::Function { name, params, body, .. } => {
+                let keyword = self.keyword("செயல்");
+                self.clause(line, |p| format!("{} {}({})", keyword, name, p.parameters(params, 0)), body, column, limit);
+            }
+            StatementKind::TryCatch { try_block, catch_clauses, finally_block } => {
+                let finally_line = finally_block
+                    .as_ref()
+                    .map(|body| self.keyword_line(&TokenType::Iruthiyaaga, first_line(body, limit)));
+                let mut follows: Vec<usize> = catch_clauses.iter().map(|clause| clause.span.line).collect();
+                follows.extend(finally_line);
+                follows.push(limit);
+
+                let keyword = self.keyword("முயற்சி");
+                self.clause(line, |_| keyword.to_string(), try_block, column, follows[0]);
+                for (i, clause) in catch_clauses.iter().enumerate() {
+                    let keyword = self.keyword("பிடி");
+                    let header = match &clause.filter {
+                        Some(filter) => format!("{} {} {}", keyword, filter, clause.error_var),
+                        None => format!("{} {}", keyword, clause.error_var),
+                    };
+                    self.begin(clause.span.line, Spacing::Tight);
+                    self.clause(clause.span.line, |_| header, &clause.body, column, follows[i + 1]);
+                }
+                if let (Some(body), Some(finally_line)) = (finally_block, finally_line) {
+                    let keyword = self.keyword("இறுதியாக");
+                    self.begin(finally_line, Spacing::Tight);
+                    self.clause(finally_line, |_| keyword.to_string(), body, column, limit);
+                }
+            }
+            StatementKind::Struct { name, fields, methods, .. } => {
+                self.struct_declaration(statement.span, name, fields, methods, limit);
+            }
+            StatementKind::Enum { name, variants, .. } => {
+                let keyword = self.keyword("விருப்பம்");
+                self.write(line, |_| format!("{} {}:", keyword, name));
+                self.indent += 1;
+                self.fresh = true;
+                let mut after = line;
+                for (variant, fields) in variants {
+                    after = self.member_line(variant, after);
+                    self.begin(after, Spacing::Source);
+                    if fields.is_empty() {
+                        self.write(after, |_| variant.clone());
+                    } else {
+                        self.write(after, |_| format!("{}({})", variant, fields.join(", ")));
+                    }
+                }
+                self.close(column, limit);
+                self.indent -= 1;
+            }
+            StatementKind::Match { value, arms } => {
+                let keyword = self.keyword("பொருத்து");
+                self.write(line, |p| format!("{} {}:", keyword, p.expression(value)));
+                self.indent += 1;
+                self.fresh = true;
+                for (i, arm) in arms.iter().enumerate() {
+                    let next = arms.get(i + 1).map_or(limit, |next| next.span.line);
+                    self.begin(arm.span.line, Spacing::Source);
+                    self.match_arm(arm, next);
+                }
+                self.close(column, limit);
+                self.indent -= 1;
+            }
+            _ => self.write(line, |p| p.simple_statement(statement)),
+        }
+    }
+
+    fn struct_declaration(&mut self, span: Span, name: &str, fields: &[(String, Option<String>)], methods: &[Method], limit: usize) {
+        enum Member<'m> {
+            Field(&'m str, Option<&'m str>),
+            Method(&'m Method),
+        }
+
+        let keyword = self.keyword("கட்டமைப்பு");
+        self.write(span.line, |_| format!("{} {}:", keyword, name));
+
+        // Fields and methods are kept apart in the tree; print them in source order
+        let mut members = Vec::new();
+        let mut after = span.line;
+        for (field, field_type) in fields {
+            after = self.member_line(field, after);
+            members.push((after, Member::Field(field, field_type.as_deref())));
+        }
+        for method in methods {
+            members.push((method.span.line, Member::Method(method)));
+        }
+        members.sort_by_key(|(line, _)| *line);
+
+        self.indent += 1;
+        self.fresh = true;
+        for (i, (line, member)) in members.iter().enumerate() {
+            let next = members.get(i + 1).map_or(limit, |(next, _)| *next);
+            self.begin(*line, Spacing::Source);
+            match member {
+                Member::Field(field, Some(field_type)) => self.write(*line, |_| format!("{}: {}", field, field_type)),
+                Member::Field(field, None) => self.write(*line, |_| field.to_string()),
+                Member::Method(method) => {
+                    let keyword = self.keyword("செயல்");
+                    let header = |p: &mut Self| format!("{} {}({})", keyword, method.name, p.parameters(&method.params, 1));
+                    self.clause(*line, header, &method.body, method.span.column, next);
+                }
+            }
+        }
+        self.close(span.column, limit);
+        self.indent -= 1;
+    }
+
+    /// `pattern => statement` on one line where the source had it so,
+    /// otherwise `pattern =>` over a block
+    fn match_arm(&mut self, arm: &MatchArm, limit: usize) {
+        let line = arm.span.line;
+        match arm.body.as_slice() {
+            [statement] if is_simple(statement) && statement.span.line == line => {
+                self.write(line, |p| format!("{} => {}", p.arm_head(&arm.pattern, arm.guard.as_ref()), p.simple_statement(statement)));
+            }
+            body => {
+                self.write(line, |p| format!("{} =>", p.arm_head(&arm.pattern, arm.guard.as_ref())));
+                self.block(body, arm.span.column, limit);
+            }
+        }
+    }
+
+    fn arm_head(&mut self, pattern: &Pattern, guard: Option<&Expression>) -> String {
+        let pattern = self.pattern(pattern);
+        match guard {
+            Some(guard) => format!("{} {} {}", pattern, self.keyword("என்றால்"), self.expression(guard)),
+            None => pattern,
+        }
+    }
+
+    /// A statement that fits on its line, apart from any block lambda in it
+    fn simple_statement(&mut self, statement: &Statement) -> String {
+        match &statement.kind {
+            StatementKind::Let { name, value, is_const, .. } => {
+                let keyword = self.keyword(if *is_const { "மாறாத" } else { "மாறி" });
+                format!("{} {} = {}", keyword, name, self.value(value))
+            }
+            StatementKind::LetUnpack { names, value, is_const, .. } => {
+                let keyword = self.keyword(if *is_const { "மாறாத" } else { "மாறி" });
+                format!("{} {} = {}", keyword, names.join(", "), self.value(value))
+            }
+            StatementKind::AssignUnpack { targets, value } => {
+                let targets = self.expressions(targets);
+                format!("{} = {}", targets, self.value(value))
+            }
+            StatementKind::Expression(expression) => self.expression_statement(expression),
+            StatementKind::Return(None) => self.keyword("திரும்பு").to_string(),
+            StatementKind::Return(Some(value)) => format!("{} {}", self.keyword("திரும்பு"), self.value(value)),
+            StatementKind::Nonlocal(names) => format!("{} {}", self.keyword("வெளிமாறி"), names.join(", ")),
+            StatementKind::Yield(value) => format!("{} {}", self.keyword("விளை"), self.value(value)),
+            StatementKind::Break => self.keyword("நிறுத்து").to_string(),
+            StatementKind::Continue => self.keyword("தொடர்").to_string(),
+            StatementKind::Print(arguments) => format!("{}({})", self.keyword("அச்சிடு"), self.expressions(arguments)),
+            StatementKind::Import { module, items: None, .. } => format!("{} {}", self.keyword("இறக்குமதி"), module),
+            StatementKind::Import { module, items: Some(items), .. } => {
+                format!("{} {} {} {}", self.keyword("இருந்து"), module, self.keyword("இறக்குமதி"), items.join(", "))
+            }
+            StatementKind::Throw(None) => self.keyword("வீசு").to_string(),
+            StatementKind::Throw(Some(value)) => format!("{} {}", self.keyword("வீசு"), self.expression(value)),
+            _ => unreachable!("compound statements are printed with their blocks"),
+        }
+    }
+
+    /// An expression statement, where an assigned tuple needs no parentheses
+    fn expression_statement(&mut self, expression: &Expression) -> String {
+        match &expression.kind {
+            ExpressionKind::Tuple(elements) if elements.len() > 1 => self.expressions(elements),
+            ExpressionKind::Assignment { name, operator: None, value, .. } => {
+                format!("{} = {}", self.name(name), self.value(value))
+            }
+            ExpressionKind::IndexAssignment { object, index, operator: None, value } => {
+                format!("{}[{}] = {}", self.expression(object), self.expression(index), self.value(value))
+            }
+            ExpressionKind::MemberAssignment { object, member, operator: None, value } => {
+                format!("{}.{} = {}", self.expression(object), member, self.value(value))
+            }
+            _ => self.expression(expression),
+        }
+    }
+
+    /// A declared, assigned, returned or yielded value, without outer parentheses:
+    /// `1, 2` rather than `(1, 2)` and `x + 1` rather than `(x + 1)`
+    fn value(&mut self, mut value: &Expression) -> String {
+        while let ExpressionKind::Grouping(inner) = &value.kind {
+            value = inner;
+        }
+        match &value.kind {
+            ExpressionKind::Tuple(elements) if elements.len() > 1 => self.expressions(elements),
+            _ => self.expression(value),
+        }
+    }
+
+    // Expressions
+
+    fn expression(&mut self, expression: &Expression) -> String {
+        match &expression.kind {
+            ExpressionKind::Integer(n) => self.literal(expression.span).unwrap_or_else(|| n.to_string()),
+            ExpressionKind::Number(n) => self.literal(expression.span).unwrap_or_else(|| number(*n)),
+            ExpressionKind::String(s) => quote(s),
+            ExpressionKind::FString { parts } => self.fstring(parts),
+            ExpressionKind::Boolean(true) => self.keyword("உண்மை").to_string(),
+            ExpressionKind::Boolean(false) => self.keyword("பொய்").to_string(),
+            ExpressionKind::Null => self.keyword("இல்லா").to_string(),
+            ExpressionKind::Identifier { name, .. } => self.name(name),
+            ExpressionKind::Binary { left, operator, right } => {
+                let left = self.expression(left);
+                format!("{} {} {}", left, self.operator(*operator), self.expression(right))
+            }
+            ExpressionKind::Unary { operator: UnaryOp::Not, operand } => {
+                format!("{} {}", self.keyword("இல்ல"), self.expression(operand))
+            }
+            ExpressionKind::Unary { operator, operand } => format!("{}{}", operator, self.expression(operand)),
+            ExpressionKind::Call { callee, arguments, keywords } => {
+                let callee = self.expression(callee);
+                let mut parts: Vec<String> = arguments.iter().map(|argument| self.expression(argument)).collect();
+                for (name, value) in keywords {
+                    parts.push(format!("{} = {}", name, self.expression(value)));
+                }
+                format!("{}({})", callee, parts.join(", "))
+            }
+            ExpressionKind::List(elements) => format!("[{}]", self.expressions(elements)),
+            ExpressionKind::Tuple(elements) if elements.len() == 1 => format!("({},)", self.expression(&elements[0])),
+            ExpressionKind::Tuple(elements) => format!("({})", self.expressions(elements)),
+            ExpressionKind::Dict(pairs) => {
+                let pairs: Vec<String> = pairs
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", self.expression(key), self.expression(value)))
+                    .collect();
+                format!("{{{}}}", pairs.join(", "))
+            }
+            ExpressionKind::ListComprehension { element, clauses } => {
+                let element = self.expression(element);
+                format!("[{}{}]", element, self.clauses(clauses))
+            }
+            ExpressionKind::DictComprehension { key, value, clauses } => {
+                let (key, value) = (self.expression(key), self.expression(value));
+                format!("{{{}: {}{}}}", key, value, self.clauses(clauses))
+            }
+            ExpressionKind::Index { object, index } => {
+                let object = self.expression(object);
+                format!("{}[{}]", object, self.expression(index))
+            }
+            ExpressionKind::Grouping(inner) => format!("({})", self.expression(inner)),
+            ExpressionKind::Assignment { name, operator, value, .. } => {
+                format!("{} {} {}", self.name(name), assignment_operator(*operator), self.expression(value))
+            }
+            ExpressionKind::MemberAccess { object, member } => format!("{}.{}", self.expression(object), member),
+            ExpressionKind::StructInit { name, arguments } => format!("{}({})", name, self.expressions(arguments)),
+            ExpressionKind::IndexAssignment { object, index, operator, value } => {
+                let (object, index) = (self.expression(object), self.expression(index));
+                format!("{}[{}] {} {}", object, index, assignment_operator(*operator), self.expression(value))
+            }
+            ExpressionKind::Slice { object, bounds } => {
+                let object = self.expression(object);
+                format!("{}[{}]", object, self.bounds(bounds))
+            }
+            ExpressionKind::SliceAssignment { object, bounds, value } => {
+                let (object, bounds) = (self.expression(object), self.bounds(bounds));
+                format!("{}[{}] = {}", object, bounds, self.expression(value))
+            }
+            ExpressionKind::MemberAssignment { object, member, operator, value } => {
+                let object = self.expression(object);
+                format!("{}.{} {} {}", object, member, assignment_operator(*operator), self.expression(value))
+            }
+            ExpressionKind::Lambda { params, body } => {
+                let header = format!("{}({}):", self.keyword("செயலி"), self.parameters(params, 0));
+                match body {
+                    LambdaBody::Expression(body) => format!("{} {}", header, self.expression(body)),
+                    LambdaBody::Block(statements) => {
+                        let limit = self.limit;
+                        let body = self.nested(|p| p.statements(statements, limit));
+                        format!("{}\n{}", header, body)
+                    }
+                }
+            }
+            ExpressionKind::Match { value, arms } => self.match_expression(value, arms),
+        }
+    }
+
+    fn expressions(&mut self, expressions: &[Expression]) -> String {
+        let parts: Vec<String> = expressions.iter().map(|expression| self.expression(expression)).collect();
+        parts.join(", ")
+    }
+
+    fn match_expression(&mut self, value: &Expression, arms: &[MatchExprArm]) -> String {
+        let header = format!("{} {}:", self.keyword("பொருத்து"), self.expression(value));
+        let body = self.nested(|p| {
+            for arm in arms {
+                p.begin(arm.span.line, Spacing::Source);
+                p.write(arm.span.line, |p| {
+                    let head = p.arm_head(&arm.pattern, arm.guard.as_ref());
+                    format!("{} => {}", head, p.expression(&arm.value))
+                });
+            }
+        });
+        format!("{}\n{}", header, body)
+    }
+
+    /// Lines of a block inside an expression, one level in, followed by the
+    /// indentation at which the expression goes on
+    fn nested(&mut self, render: impl FnOnce(&mut Self)) -> String {
+        let outer = std::mem::take(&mut self.out);
+        let fresh = self.fresh;
+        self.indent += 1;
+        self.fresh = true;
+        render(self);
+        self.indent -= 1;
+        self.fresh = fresh;
+        let block = std::mem::replace(&mut self.out, outer);
+        block + &self.pad()
+    }
+
+    fn clauses(&mut self, clauses: &[ComprehensionClause]) -> String {
+        let mut text = String::new();
+        for clause in clauses {
+            match clause {
+                ComprehensionClause::For { variables, iterable, .. } => {
+                    let (keyword, within) = (self.keyword("ஒவ்வொரு"), self.keyword("உள்ள"));
+                    text.push_str(&format!(" {} {} {} {}", keyword, variables.join(", "), within, self.expression(iterable)));
+                }
+                ComprehensionClause::If(condition) => {
+                    text.push_str(&format!(" {} {}", self.keyword("என்றால்"), self.expression(condition)));
+                }
+            }
+        }
+        text
+    }
+
+    fn bounds(&mut self, bounds: &SliceBounds) -> String {
+        let [start, end, step] = bounds.parts().map(|bound| bound.map(|bound| self.expression(bound)).unwrap_or_default());
+        if bounds.step.is_some() {
+            format!("{}:{}:{}", start, end, step)
+        } else {
+            format!("{}:{}", start, end)
+        }
+    }
+
+    /// Parameter list without its first `skip` names (a method's receiver)
+    fn parameters(&mut self, params: &Parameters, skip: usize) -> String {
+        let signature = &params.signature;
+        let mut parts = Vec::new();
+        for (i, name) in signature.params.iter().enumerate().skip(skip) {
+            if i < signature.required {
+                parts.push(name.clone());
+            } else {
+                let default = self.expression(&params.defaults[i - signature.required]);
+                parts.push(format!("{} = {}", name, default));
+            }
+        }
+        if let Some(rest) = &signature.rest {
+            parts.push(format!("*{}", rest));
+        }
+        if let Some(keywords) = &signature.keywords {
+            parts.push(format!("**{}", keywords));
+        }
+        parts.join(", ")
+    }
+
+    fn fstring(&mut self, parts: &[FStringPart]) -> String {
+        let mut text = String::from("f\"");
+        for part in parts {
+            match part {
+                FStringPart::Literal(literal) => text.push_str(&literal.replace('{', "{{").replace('}', "}}")),
+                FStringPart::Expression(expression) => {
+                    let expression = self.expression(expression);
+                    // `{ {...} }`: a dict right inside the braces would read as `{{`
+                    if expression.starts_with('{') || expression.ends_with('}') {
+                        text.push_str(&format!("{{ {} }}", expression));
+                    } else {
+                        text.push_str(&format!("{{{}}}", expression));
+                    }
+                }
+            }
+        }
+        text.push('"');
+        text
+    }
+
+    fn pattern(&mut self, pattern: &Pattern) -> String {
+        match pattern {
+            Pattern::Literal(expression) => self.expression(expression),
+            Pattern::Variable { name, .. } => name.clone(),
+            Pattern::Wildcard => "_".to_string(),
+            Pattern::EnumVariant { enum_name, variant, payload: None } => format!("{}.{}", enum_name, variant),
+            Pattern::EnumVariant { enum_name, variant, payload: Some(payload) } => {
+                let payload: Vec<String> = payload.iter().map(|pattern| self.pattern(pattern)).collect();
+                format!("{}.{}({})", enum_name, variant, payload.join(", "))
+            }
+            Pattern::List { elements, rest } => {
+                let mut parts: Vec<String> = elements.iter().map(|pattern| self.pattern(pattern)).collect();
+                if let Some(rest) = rest {
+                    parts.push(format!("...{}", self.pattern(rest)));
+                }
+                format!("[{}]", parts.join(", "))
+            }
+            Pattern::Dict(entries) => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|(key, pattern)| format!("{}: {}", quote(key), self.pattern(pattern)))
+                    .collect();
+                format!("{{{}}}", entries.join(", "))
+            }
+            Pattern::Struct { name, fields } => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(field, pattern)| match pattern {
+                        Pattern::Variable { name, .. } if name == field => field.clone(),
+                        _ => format!("{}: {}", field, self.pattern(pattern)),
+                    })
+                    .collect();
+                format!("{}{{{}}}", name, fields.join(", "))
+            }
+        }
+    }
+
+    /// A variable name; the receiver and input are keywords
+    fn name(&self, name: &str) -> String {
+        match name {
+            SELF_NAME => self.keyword("தன்").to_string(),
+            "உள்ளீடு" => self.keyword("உள்ளீடு").to_string(),
+            _ => name.to_string(),
+        }
+    }
+
+    fn operator(&self, operator: BinaryOp) -> String {
+        match operator {
+            BinaryOp::In => self.keyword("உள்ள").to_string(),
+            BinaryOp::And => self.keyword("மற்றும்").to_string(),
+            BinaryOp::Or => self.keyword("அல்லது").to_string(),
+            _ => operator.to_string(),
+        }
+    }
+
+    fn literal(&self, span: Span) -> Option<String> {
+        self.literals.get(&(span.line, span.column)).cloned()
+    }
+}
+
+/// Top-level definitions get a blank line on either side
+fn is_definition(statement: &Statement) -> bool {
+    matches!(
+        statement.kind,
+        StatementKind::Function { .. } | StatementKind::Struct { .. } | StatementKind::Enum { .. }
+    )
+}
+
+/// Statements without a block of their own
+fn is_simple(statement: &Statement) -> bool {
+    !matches!(
+        statement.kind,
+        StatementKind::If { .. }
+            | StatementKind::While { .. }
+            | StatementKind::For { .. }
+            | StatementKind::Function { .. }
+            | StatementKind::TryCatch { .. }
+            | StatementKind::Struct { .. }
+            | StatementKind::Enum { .. }
+            | StatementKind::Match { .. }
+    )
+}
+
+fn first_line(statements: &[Statement], otherwise: usize) -> usize {
+    statements.first().map_or(otherwise, |statement| statement.span.line)
+}
+
+fn assignment_operator(operator: Option<BinaryOp>) -> String {
+    match operator {
+        Some(operator) => format!("{}=", operator),
+        None => "=".to_string(),
+    }
+}
+
+/// A decimal written so the lexer reads it back as one: `2.0`, not `2`
+fn number(n: f64) -> String {
+    let text = n.to_string();
+    if text.contains('.') {
+        text
+    } else {
+        format!("{}.0", text)
+    }
+}
+
+fn quote(s: &str) -> String {
+    let mut text = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '\\' => text.push_str("\\\\"),
+            '"' => text.push_str("\\\""),
+            '\n' => text.push_str("\\n"),
+            '\t' => text.push_str("\\t"),
+            '\r' => text.push_str("\\r"),
+            _ => text.push(c),
+        }
+    }
+    text.push('"');
+    text
+}
@@ -0,0 +1,12 @@
+//! Source formatter for Agam
+//!
+//! `agam fmt` parses a program and prints it back with canonical
+//! indentation, spacing and blank lines, keeping its comments and
+//! optionally normalising every keyword to one spelling. `agam translate`
+//! rewrites just the keywords and built-in names, leaving the rest as written.
+
+pub mod formatter;
+pub mod translate;
+
+pub use formatter::{format_source, FormatOptions, Spelling, KEYWORD_SPELLINGS};
+pub use translate::translate_source;
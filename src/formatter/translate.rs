@@ -0,0 +1,215 @@
+//! Keyword transliteration behind `agam translate`
+//!
+//! Unlike `agam fmt`, the source is not reprinted: only the tokens that
+//! spell a keyword or a built-in function are replaced, so layout,
+//! identifiers, strings and comments come out exactly as they went in.
+//! A built-in name the program declares itself (`மாறி max = 5`, or a
+//! parameter `len`) is a user identifier and is left alone everywhere.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::error::AgamError;
+use crate::formatter::{Spelling, KEYWORD_SPELLINGS};
+use crate::interpreter::builtin::create_builtins;
+use crate::lexer::{Lexer, Token, TokenType};
+use crate::parser::Parser;
+use crate::resolver::declarations;
+
+/// Rewrite every keyword and built-in function name in `source` to one
+/// spelling. Source that does not parse is an error.
+pub fn translate_source(source: &str, to: Spelling) -> Result<String, AgamError> {
+    let tokens = Lexer::tokenize(source)?;
+    let program = Parser::new(tokens.clone()).parse()?;
+
+    let mut declared = declared_names(&tokens);
+    declared.extend(declarations(&program, &tokens, source).into_iter().map(|declaration| declaration.name.to_string()));
+    let translator = Translator { words: words(to), declared };
+
+    // Replacements as (line, column, length, text), in source order
+    let mut edits = Vec::new();
+    translator.tokens(&tokens, &mut edits);
+    Ok(apply(source, &edits))
+}
+
+/// Each keyword and built-in in the other spelling, mapped to `to`
+fn words(to: Spelling) -> HashMap<String, String> {
+    let keywords = KEYWORD_SPELLINGS.iter().map(|(tamil, english)| (tamil.to_string(), english.to_string()));
+    // Built-ins come in (Tamil, English) pairs
+    let builtins = create_builtins();
+    let builtins = builtins.chunks(2).map(|pair| (pair[0].0.clone(), pair[1].0.clone()));
+    keywords
+        .chain(builtins)
+        .map(|(tamil, english)| match to {
+            Spelling::Tamil => (english, tamil),
+            Spelling::English => (tamil, english),
+        })
+        .collect()
+}
+
+/// Names bound where `declarations` does not look: loop and comprehension
+/// variables, lambda parameters, and match pattern variables
+fn declared_names(tokens: &[Token]) -> HashSet<String> {
+    let mut names = HashSet::new();
+    for (i, token) in tokens.iter().enumerate() {
+        match token.token_type {
+            // ஒவ்வொரு a, b உள்ள ...
+            TokenType::Ovvoru => {
+                names.extend(
+                    tokens[i + 1..]
+                        .iter()
+                        .take_while(|token| token.token_type != TokenType::Ulla)
+                        .filter_map(identifier),
+                );
+            }
+            // செயலி(a, b = 1): ..., (a, b) => ... and Shape.Circle(r) => ...
+            TokenType::Seyali => names.extend(parameter_names(&tokens[i + 1..])),
+            TokenType::Arrow => {
+                match i.checked_sub(1).map(|before| &tokens[before].token_type) {
+                    Some(TokenType::RightParen) => {
+                        let open = tokens[..i].iter().rposition(|token| token.token_type == TokenType::LeftParen);
+                        if let Some(open) = open {
+                            names.extend(parameter_names(&tokens[open..i]));
+                        }
+                    }
+                    Some(TokenType::Identifier(name)) => {
+                        names.insert(name.clone());
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+/// Names in a parenthesised list: each identifier that follows the `(`,
+/// a `,` or a `*`
+fn parameter_names(tokens: &[Token]) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut depth = 0;
+    for pair in tokens.windows(2) {
+        match pair[0].token_type {
+            TokenType::LeftParen | TokenType::LeftBracket | TokenType::LeftBrace => depth += 1,
+            TokenType::RightParen | TokenType::RightBracket | TokenType::RightBrace => depth -= 1,
+            _ => {}
+        }
+        if depth <= 0 {
+            break;
+        }
+        let starts_name = matches!(
+            pair[0].token_type,
+            TokenType::LeftParen | TokenType::Comma | TokenType::Star | TokenType::StarStar
+        );
+        if depth == 1 && starts_name {
+            names.extend(identifier(&pair[1]));
+        }
+    }
+    names
+}
+
+fn identifier(token: &Token) -> Option<String> {
+    match &token.token_type {
+        TokenType::Identifier(name) => Some(name.clone()),
+        _ => None,
+    }
+}
+
+struct Translator {
+    words: HashMap<String, String>,
+    declared: HashSet<String>,
+}
+
+impl Translator {
+    fn tokens(&self, tokens: &[Token], edits: &mut Vec<(usize, usize, usize, String)>) {
+        for (i, token) in tokens.iter().enumerate() {
+            let after_dot = i > 0 && tokens[i - 1].token_type == TokenType::Dot;
+            match &token.token_type {
+                TokenType::FString(content) => self.fstring(content, token, edits),
+                // Fields and module members after `.` are user names
+                TokenType::Identifier(name) if after_dot || self.declared.contains(name) => {}
+                _ => {
+                    if let Some(word) = self.words.get(&token.lexeme) {
+                        edits.push((token.line, token.column, token.length, word.clone()));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Translate the expressions inside `{...}` of an f-string, whose
+    /// content starts after the `f"` of `token`
+    fn fstring(&self, content: &str, token: &Token, edits: &mut Vec<(usize, usize, usize, String)>) {
+        let (mut line, mut column) = (token.line, token.column + 1);
+        let mut chars = content.chars().peekable();
+        while let Some(c) = chars.next() {
+            column += 1;
+            match c {
+                '\n' => {
+                    line += 1;
+                    column = 0;
+                }
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    column += 1;
+                }
+                '{' => {
+                    let origin = (line, column + 1);
+                    let mut expression = String::new();
+                    let mut depth = 1;
+                    for c in chars.by_ref() {
+                        column += 1;
+                        depth += match c {
+                            '{' => 1,
+                            '}' => -1,
+                            _ => 0,
+                        };
+                        if depth == 0 {
+                            break;
+                        }
+                        expression.push(c);
+                    }
+                    // The parser has accepted the f-string, so its expressions lex
+                    let Ok(mut tokens) = Lexer::tokenize(&expression) else { continue };
+                    for token in &mut tokens {
+                        if token.line == 1 {
+                            token.column += origin.1 - 1;
+                        }
+                        token.line += origin.0 - 1;
+                    }
+                    self.tokens(&tokens, edits);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Replace `length` characters at each (line, column) of `source`
+fn apply(source: &str, edits: &[(usize, usize, usize, String)]) -> String {
+    let mut by_line: HashMap<usize, Vec<(usize, usize, &str)>> = HashMap::new();
+    for (line, column, length, text) in edits {
+        by_line.entry(*line).or_default().push((*column, *length, text));
+    }
+
+    let lines: Vec<String> = source
+        .split('\n')
+        .enumerate()
+        .map(|(index, text)| {
+            let Some(edits) = by_line.get_mut(&(index + 1)) else { return text.to_string() };
+            edits.sort_by_key(|(column, _, _)| *column);
+            let chars: Vec<char> = text.chars().collect();
+            let mut result = String::new();
+            let mut next = 0;
+            for (column, length, replacement) in edits.iter() {
+                let start = column - 1;
+                result.extend(&chars[next..start]);
+                result.push_str(replacement);
+                next = start + length;
+            }
+            result.extend(&chars[next.min(chars.len())..]);
+            result
+        })
+        .collect();
+    lines.join("\n")
+}
@@ -2,14 +2,21 @@
 //! 
 //! Native functions available in all programs
 
-use crate::types::{Value, NativeFunction};
-use std::io::{self, Write, Read};
+use crate::types::{range_len, Caller, Integer, Iter, NativeFn, NativeFunction, Value};
+use crate::error::AgamError;
+use crate::interpreter::ops;
+use crate::parser::ast::Span;
+use num_bigint::BigInt;
+use std::cmp::Ordering;
+use std::io::{self, Write};
 use std::rc::Rc;
 use std::cell::RefCell;
 use std::sync::Mutex;
 use std::collections::HashMap;
 use std::net::TcpStream;
 use tungstenite::{WebSocket, Message, connect};
+use unicode_segmentation::UnicodeSegmentation;
+use num_traits::{FromPrimitive, Signed, ToPrimitive};
 
 // Global WebSocket connections storage
 lazy_static::lazy_static! {
@@ -83,20 +90,20 @@ pub fn create_builtins() -> Vec<(String, NativeFunction)> {
         ("abs".to_string(), NativeFunction::new("abs", Some(1), builtin_abs)),
         
         // குறைந்தபட்சம் - min
-        ("குறைந்தபட்சம்".to_string(), NativeFunction::new("குறைந்தபட்சம்", None, builtin_min)),
-        ("min".to_string(), NativeFunction::new("min", None, builtin_min)),
+        ("குறைந்தபட்சம்".to_string(), NativeFunction::with_callback("குறைந்தபட்சம்", None, builtin_min)),
+        ("min".to_string(), NativeFunction::with_callback("min", None, builtin_min)),
         
         // அதிகபட்சம் - max
-        ("அதிகபட்சம்".to_string(), NativeFunction::new("அதிகபட்சம்", None, builtin_max)),
-        ("max".to_string(), NativeFunction::new("max", None, builtin_max)),
+        ("அதிகபட்சம்".to_string(), NativeFunction::with_callback("அதிகபட்சம்", None, builtin_max)),
+        ("max".to_string(), NativeFunction::with_callback("max", None, builtin_max)),
         
         // தற்செயல் - random
         ("தற்செயல்".to_string(), NativeFunction::new("தற்செயல்", None, builtin_random)),
         ("random".to_string(), NativeFunction::new("random", None, builtin_random)),
         
         // கூட்டு - sum
-        ("கூட்டு".to_string(), NativeFunction::new("கூட்டு", Some(1), builtin_sum)),
-        ("sum".to_string(), NativeFunction::new("sum", Some(1), builtin_sum)),
+        ("கூட்டு".to_string(), NativeFunction::with_callback("கூட்டு", Some(1), builtin_sum)),
+        ("sum".to_string(), NativeFunction::with_callback("sum", Some(1), builtin_sum)),
         
         // === String Functions ===
         // பிரி - split
@@ -135,15 +142,81 @@ pub fn create_builtins() -> Vec<(String, NativeFunction)> {
         ("உள்ளதா".to_string(), NativeFunction::new("உள்ளதா", Some(2), builtin_contains)),
         ("contains".to_string(), NativeFunction::new("contains", Some(2), builtin_contains)),
         
+        // எழுத்துகள் - graphemes (user-perceived characters, e.g. "கொ")
+        ("எழுத்துகள்".to_string(), NativeFunction::new("எழுத்துகள்", Some(1), builtin_graphemes)),
+        ("graphemes".to_string(), NativeFunction::new("graphemes", Some(1), builtin_graphemes)),
+        
         // === List Functions ===
         // வரிசை - sort
-        ("வரிசை".to_string(), NativeFunction::new("வரிசை", Some(1), builtin_sort)),
-        ("sort".to_string(), NativeFunction::new("sort", Some(1), builtin_sort)),
+        ("வரிசை".to_string(), NativeFunction::with_callback("வரிசை", Some(1), builtin_sort)),
+        ("sort".to_string(), NativeFunction::with_callback("sort", Some(1), builtin_sort)),
         
         // தலைகீழ் - reverse
         ("தலைகீழ்".to_string(), NativeFunction::new("தலைகீழ்", Some(1), builtin_reverse)),
         ("reverse".to_string(), NativeFunction::new("reverse", Some(1), builtin_reverse)),
         
+        // === Higher-order Functions ===
+        // வரைபடம் - map
+        ("வரைபடம்".to_string(), NativeFunction::with_callback("வரைபடம்", Some(2), builtin_map)),
+        ("map".to_string(), NativeFunction::with_callback("map", Some(2), builtin_map)),
+        
+        // வடிகட்டு - filter
+        ("வடிகட்டு".to_string(), NativeFunction::with_callback("வடிகட்டு", Some(2), builtin_filter)),
+        ("filter".to_string(), NativeFunction::with_callback("filter", Some(2), builtin_filter)),
+        
+        // சுருக்கு - reduce (optional initial value)
+        ("சுருக்கு".to_string(), NativeFunction::with_callback("சுருக்கு", None, builtin_reduce)),
+        ("reduce".to_string(), NativeFunction::with_callback("reduce", None, builtin_reduce)),
+        
+        // ஏதாவது - any (optional predicate)
+        ("ஏதாவது".to_string(), NativeFunction::with_callback("ஏதாவது", None, builtin_any)),
+        ("any".to_string(), NativeFunction::with_callback("any", None, builtin_any)),
+        
+        // அனைத்தும் - all (optional predicate)
+        ("அனைத்தும்".to_string(), NativeFunction::with_callback("அனைத்தும்", None, builtin_all)),
+        ("all".to_string(), NativeFunction::with_callback("all", None, builtin_all)),
+        
+        // கண்டுபிடி - find
+        ("கண்டுபிடி".to_string(), NativeFunction::with_callback("கண்டுபிடி", Some(2), builtin_find)),
+        ("find".to_string(), NativeFunction::with_callback("find", Some(2), builtin_find)),
+        
+        // வரிசைப்படுத்து - sort_by (sort by key)
+        ("வரிசைப்படுத்து".to_string(), NativeFunction::with_callback("வரிசைப்படுத்து", Some(2), builtin_sort_by)),
+        ("sort_by".to_string(), NativeFunction::with_callback("sort_by", Some(2), builtin_sort_by)),
+        
+        // குழுவாக்கு - group_by
+        ("குழுவாக்கு".to_string(), NativeFunction::with_callback("குழுவாக்கு", Some(2), builtin_group_by)),
+        ("group_by".to_string(), NativeFunction::with_callback("group_by", Some(2), builtin_group_by)),
+        
+        // === Iteration ===
+        // பட்டியலாக - list (items of anything iterable)
+        ("பட்டியலாக".to_string(), NativeFunction::with_callback("பட்டியலாக", Some(1), builtin_list)),
+        ("list".to_string(), NativeFunction::with_callback("list", Some(1), builtin_list)),
+        
+        // தொகுதியாக - tuple
+        ("தொகுதியாக".to_string(), NativeFunction::with_callback("தொகுதியாக", Some(1), builtin_tuple)),
+        ("tuple".to_string(), NativeFunction::with_callback("tuple", Some(1), builtin_tuple)),
+        
+        // எண்ணிடு - enumerate (optional start)
+        ("எண்ணிடு".to_string(), NativeFunction::with_callback("எண்ணிடு", None, builtin_enumerate)),
+        ("enumerate".to_string(), NativeFunction::with_callback("enumerate", None, builtin_enumerate)),
+        
+        // ஜோடி - zip
+        ("ஜோடி".to_string(), NativeFunction::with_callback("ஜோடி", None, builtin_zip)),
+        ("zip".to_string(), NativeFunction::with_callback("zip", None, builtin_zip)),
+        
+        // அடுத்து - next (optional value once finished)
+        ("அடுத்து".to_string(), NativeFunction::with_callback("அடுத்து", None, builtin_next)),
+        ("next".to_string(), NativeFunction::with_callback("next", None, builtin_next)),
+        
+        // சாவிகள் - keys, மதிப்புகள் - values, உருப்படிகள் - items
+        ("சாவிகள்".to_string(), NativeFunction::new("சாவிகள்", Some(1), builtin_keys)),
+        ("keys".to_string(), NativeFunction::new("keys", Some(1), builtin_keys)),
+        ("மதிப்புகள்".to_string(), NativeFunction::new("மதிப்புகள்", Some(1), builtin_values)),
+        ("values".to_string(), NativeFunction::new("values", Some(1), builtin_values)),
+        ("உருப்படிகள்".to_string(), NativeFunction::new("உருப்படிகள்", Some(1), builtin_items)),
+        ("items".to_string(), NativeFunction::new("items", Some(1), builtin_items)),
+        
         // === File I/O ===
         // படி - read_file
         ("படி".to_string(), NativeFunction::new("படி", Some(1), builtin_read_file)),
@@ -223,9 +296,95 @@ pub fn create_builtins() -> Vec<(String, NativeFunction)> {
         // JSON பகுப்பு - json_parse
         ("json_படி".to_string(), NativeFunction::new("json_படி", Some(1), builtin_json_parse)),
         ("json_parse".to_string(), NativeFunction::new("json_parse", Some(1), builtin_json_parse)),
+
+        // === Testing ===
+        // உறுதி - assert (condition, optional message)
+        ("உறுதி".to_string(), NativeFunction::with_callback("உறுதி", None, builtin_assert)),
+        ("assert".to_string(), NativeFunction::with_callback("assert", None, builtin_assert)),
+
+        // உறுதி_சமம் - assert_eq (left, right, optional message)
+        ("உறுதி_சமம்".to_string(), NativeFunction::with_callback("உறுதி_சமம்", None, builtin_assert_eq)),
+        ("assert_eq".to_string(), NativeFunction::with_callback("assert_eq", None, builtin_assert_eq)),
+
+        // உறுதி_வேறு - assert_ne (left, right, optional message)
+        ("உறுதி_வேறு".to_string(), NativeFunction::with_callback("உறுதி_வேறு", None, builtin_assert_ne)),
+        ("assert_ne".to_string(), NativeFunction::with_callback("assert_ne", None, builtin_assert_ne)),
     ]
 }
 
+/// Parameters and a one-line description of each builtin, by its Tamil
+/// name, for editor hover and completion. Optional parameters are in `[]`.
+pub const BUILTIN_DOCS: &[(&str, &str, &str)] = &[
+    ("உள்ளீடு", "[prompt]", "Prints the prompt and reads a line of input"),
+    ("நீளம்", "value", "Number of items in a string, list, tuple, dictionary or range"),
+    ("வகை", "value", "Name of the value's type"),
+    ("எண்ணாக", "value", "Converts a number, string or boolean to an integer"),
+    ("தசமாக", "value", "Converts a number or string to a decimal"),
+    ("சரமாக", "value", "The value as a string"),
+    ("வரம்பு", "[start,] end[, step]", "Numbers from start up to, but not including, end"),
+    ("சேர்", "list, item", "Adds an item to the end of a list"),
+    ("நீக்கு", "list", "Removes and returns the last item of a list"),
+    ("வர்க்கம்", "number", "Square root"),
+    ("அடி", "base, exponent", "Raises base to the power exponent"),
+    ("தளம்", "number", "Rounds down to an integer"),
+    ("கூரை", "number", "Rounds up to an integer"),
+    ("முழுமை", "number", "Absolute value"),
+    ("குறைந்தபட்சம்", "*values", "Smallest of the values, or of the items of one list"),
+    ("அதிகபட்சம்", "*values", "Largest of the values, or of the items of one list"),
+    ("தற்செயல்", "[[min,] max]", "Random decimal from 0 to 1, or random integer from min up to max"),
+    ("கூட்டு", "items", "Sum of the items"),
+    ("பிரி", "text, separator", "Splits a string at each separator"),
+    ("இணை", "separator, items", "Joins the items into one string with the separator between them"),
+    ("மேல்", "text", "The string in upper case"),
+    ("கீழ்", "text", "The string in lower case"),
+    ("ஒழுங்கு", "text", "The string without leading and trailing whitespace"),
+    ("மாற்று", "text, old, new", "Replaces every occurrence of old with new"),
+    ("தொடங்கு", "text, prefix", "Whether the string starts with the prefix"),
+    ("முடிவு", "text, suffix", "Whether the string ends with the suffix"),
+    ("உள்ளதா", "container, item", "Whether a string holds a substring or a list holds an item"),
+    ("எழுத்துகள்", "text", "The letters of a string, keeping each letter's marks with it"),
+    ("வரிசை", "items", "The items as a sorted list"),
+    ("தலைகீழ்", "items", "The items, or the string's characters, in reverse order"),
+    ("வரைபடம்", "items, function", "List of the function's result for each item"),
+    ("வடிகட்டு", "items, function", "List of the items the function returns true for"),
+    ("சுருக்கு", "items, function[, initial]", "Combines the items into one value with the function"),
+    ("ஏதாவது", "items[, function]", "Whether any item is true, or passes the function"),
+    ("அனைத்தும்", "items[, function]", "Whether every item is true, or passes the function"),
+    ("கண்டுபிடி", "items, function", "First item the function returns true for, or இல்லா"),
+    ("வரிசைப்படுத்து", "items, function", "The items sorted by the key the function gives each"),
+    ("குழுவாக்கு", "items, function", "Dictionary of the items grouped by the key the function gives each"),
+    ("பட்டியலாக", "items", "The items of anything iterable as a list"),
+    ("தொகுதியாக", "items", "The items of anything iterable as a tuple"),
+    ("எண்ணிடு", "items[, start]", "Pairs each item with its position, counting from start"),
+    ("ஜோடி", "*iterables", "Tuples of the items at the same position in each iterable"),
+    ("அடுத்து", "iterator[, default]", "Next item of an iterator, or default once it is finished"),
+    ("சாவிகள்", "dictionary", "List of the dictionary's keys"),
+    ("மதிப்புகள்", "dictionary", "List of the dictionary's values"),
+    ("உருப்படிகள்", "dictionary", "List of the dictionary's (key, value) pairs"),
+    ("படி", "path", "Contents of a file as a string"),
+    ("எழுது", "path, text", "Writes a string to a file, replacing its contents"),
+    ("உள்ளது", "path", "Whether a file exists"),
+    ("வெளியேறு", "[code]", "Ends the program with an exit code, 0 by default"),
+    ("நேரம்", "", "Seconds since the Unix epoch"),
+    ("தூக்கம்", "seconds", "Pauses for a number of seconds"),
+    ("தேதி", "[timestamp]", "Date and time as YYYY-MM-DD HH:MM:SS, now by default"),
+    ("நாள்", "", "Dictionary of the current year, month, day, hour, minute and second"),
+    ("வலை_படி", "url", "Sends an HTTP GET request"),
+    ("வலை_அனுப்பு", "url, body", "Sends an HTTP POST request"),
+    ("வலை_புதுப்பி", "url, body", "Sends an HTTP PUT request"),
+    ("வலை_நீக்கு", "url", "Sends an HTTP DELETE request"),
+    ("கோப்பு_பதிவேற்று", "url, path", "Uploads a file over HTTP"),
+    ("வலை_கோரிக்கை", "options", "Sends an HTTP request described by a dictionary of url, method, headers and body"),
+    ("சாக்கெட்_இணை", "url", "Opens a WebSocket connection"),
+    ("சாக்கெட்_அனுப்பு", "connection, message", "Sends a message over a WebSocket connection"),
+    ("சாக்கெட்_படி", "connection", "Waits for the next message on a WebSocket connection"),
+    ("சாக்கெட்_மூடு", "connection", "Closes a WebSocket connection"),
+    ("json_படி", "text", "Parses JSON text into Agam values"),
+    ("உறுதி", "condition[, message]", "Fails with an assertion error unless the condition is true"),
+    ("உறுதி_சமம்", "left, right[, message]", "Fails with an assertion error unless the values are equal"),
+    ("உறுதி_வேறு", "left, right[, message]", "Fails with an assertion error if the values are equal"),
+];
+
 // ============= Input/Output =============
 
 fn builtin_input(args: &[Value]) -> Result<Value, String> {
@@ -246,9 +405,11 @@ fn builtin_input(args: &[Value]) -> Result<Value, String> {
 
 fn builtin_len(args: &[Value]) -> Result<Value, String> {
     match args.first() {
-        Some(Value::String(s)) => Ok(Value::Number(s.chars().count() as f64)),
-        Some(Value::List(list)) => Ok(Value::Number(list.borrow().len() as f64)),
-        Some(Value::Dict(dict)) => Ok(Value::Number(dict.borrow().len() as f64)),
+        Some(Value::String(s)) => Ok(Value::int(s.chars().count())),
+        Some(Value::List(list)) => Ok(Value::int(list.borrow().len())),
+        Some(Value::Tuple(items)) => Ok(Value::int(items.len())),
+        Some(Value::Range { start, end, step }) => Ok(Value::int(range_len(*start, *end, *step))),
+        Some(Value::Dict(dict)) => Ok(Value::int(dict.borrow().len())),
         Some(v) => Err(format!("'{}' வகைக்கு நீளம் கணக்கிட இயலாது", v.type_name())),
         None => Err("நீளம்() ஒரு அளவுரு தேவை".to_string()),
     }
@@ -265,22 +426,34 @@ fn builtin_type(args: &[Value]) -> Result<Value, String> {
 
 fn builtin_int(args: &[Value]) -> Result<Value, String> {
     match args.first() {
-        Some(Value::Number(n)) => Ok(Value::Number(n.trunc())),
+        Some(Value::Integer(n)) => Ok(Value::Integer(n.clone())),
+        Some(Value::Number(n)) => float_to_integer(*n),
         Some(Value::String(s)) => {
-            s.trim()
-                .parse::<f64>()
-                .map(|n| Value::Number(n.trunc()))
-                .map_err(|_| format!("'{}' எண்ணாக மாற்ற இயலவில்லை", s))
+            let text = s.trim();
+            match text.parse::<BigInt>() {
+                Ok(n) => Ok(Value::int(n)),
+                Err(_) => text
+                    .parse::<f64>()
+                    .map_err(|_| format!("'{}' எண்ணாக மாற்ற இயலவில்லை", s))
+                    .and_then(float_to_integer),
+            }
         }
-        Some(Value::Boolean(b)) => Ok(Value::Number(if *b { 1.0 } else { 0.0 })),
+        Some(Value::Boolean(b)) => Ok(Value::int(*b as u8)),
         Some(v) => Err(format!("'{}' வகையை எண்ணாக மாற்ற இயலாது", v.type_name())),
         None => Err("எண்ணாக() ஒரு அளவுரு தேவை".to_string()),
     }
 }
 
+/// Integer part of a decimal number
+fn float_to_integer(n: f64) -> Result<Value, String> {
+    BigInt::from_f64(n.trunc())
+        .map(Value::int)
+        .ok_or_else(|| format!("'{}' எண்ணாக மாற்ற இயலவில்லை", n))
+}
+
 fn builtin_float(args: &[Value]) -> Result<Value, String> {
     match args.first() {
-        Some(Value::Number(n)) => Ok(Value::Number(*n)),
+        Some(v @ (Value::Integer(_) | Value::Number(_))) => Ok(Value::Number(v.as_f64().unwrap_or(f64::NAN))),
         Some(Value::String(s)) => {
             s.trim()
                 .parse::<f64>()
@@ -303,38 +476,17 @@ fn builtin_str(args: &[Value]) -> Result<Value, String> {
 // ============= Collection Functions =============
 
 fn builtin_range(args: &[Value]) -> Result<Value, String> {
-    let (start, end, step) = match args.len() {
-        1 => {
-            let end = match &args[0] {
-                Value::Number(n) => *n as i64,
-                _ => return Err("வரம்பு() எண் அளவுருக்கள் தேவை".to_string()),
-            };
-            (0, end, 1)
-        }
-        2 => {
-            let start = match &args[0] {
-                Value::Number(n) => *n as i64,
-                _ => return Err("வரம்பு() எண் அளவுருக்கள் தேவை".to_string()),
-            };
-            let end = match &args[1] {
-                Value::Number(n) => *n as i64,
-                _ => return Err("வரம்பு() எண் அளவுருக்கள் தேவை".to_string()),
-            };
-            (start, end, 1)
-        }
-        3 => {
-            let start = match &args[0] {
-                Value::Number(n) => *n as i64,
-                _ => return Err("வரம்பு() எண் அளவுருக்கள் தேவை".to_string()),
-            };
-            let end = match &args[1] {
-                Value::Number(n) => *n as i64,
-                _ => return Err("வரம்பு() எண் அளவுருக்கள் தேவை".to_string()),
-            };
-            let step = match &args[2] {
-                Value::Number(n) => *n as i64,
-                _ => return Err("வரம்பு() எண் அளவுருக்கள் தேவை".to_string()),
-            };
+    let bounds = args
+        .iter()
+        .map(|arg| match arg {
+            Value::Integer(n) => n.to_i64().ok_or_else(|| format!("வரம்பு() எல்லை {} அனுமதிக்கப்பட்ட வரம்பிற்கு வெளியே", n)),
+            _ => Err("வரம்பு() எண் அளவுருக்கள் தேவை".to_string()),
+        })
+        .collect::<Result<Vec<i64>, String>>()?;
+    let (start, end, step) = match bounds[..] {
+        [end] => (0, end, 1),
+        [start, end] => (start, end, 1),
+        [start, end, step] => {
             if step == 0 {
                 return Err("படி அளவு பூஜ்ஜியமாக இருக்க முடியாது".to_string());
             }
@@ -343,25 +495,8 @@ fn builtin_range(args: &[Value]) -> Result<Value, String> {
         _ => return Err("வரம்பு() 1-3 அளவுருக்கள் எடுக்கும்".to_string()),
     };
 
-    let mut result = Vec::new();
-    let mut i = start;
-    
-    // Security: Limit range size to prevent memory exhaustion
-    const MAX_RANGE_SIZE: usize = 1_000_000;
-    
-    if step > 0 {
-        while i < end && result.len() < MAX_RANGE_SIZE {
-            result.push(Value::Number(i as f64));
-            i += step;
-        }
-    } else {
-        while i > end && result.len() < MAX_RANGE_SIZE {
-            result.push(Value::Number(i as f64));
-            i += step;
-        }
-    }
-
-    Ok(Value::List(Rc::new(RefCell::new(result))))
+    // Numbers are produced as they are iterated, never stored
+    Ok(Value::Range { start, end, step })
 }
 
 fn builtin_append(args: &[Value]) -> Result<Value, String> {
@@ -394,8 +529,9 @@ fn builtin_pop(args: &[Value]) -> Result<Value, String> {
 
 fn builtin_sqrt(args: &[Value]) -> Result<Value, String> {
     match args.first() {
-        Some(Value::Number(n)) => {
-            if *n < 0.0 {
+        Some(v @ (Value::Integer(_) | Value::Number(_))) => {
+            let n = v.as_f64().unwrap_or(f64::NAN);
+            if n < 0.0 {
                 Err("எதிர்மறை எண்ணுக்கு வர்க்கமூலம் இல்லை".to_string())
             } else {
                 Ok(Value::Number(n.sqrt()))
@@ -412,14 +548,20 @@ fn builtin_pow(args: &[Value]) -> Result<Value, String> {
     }
     
     match (&args[0], &args[1]) {
-        (Value::Number(base), Value::Number(exp)) => Ok(Value::Number(base.powf(*exp))),
-        _ => Err("அடி() எண் அளவுருக்கள் தேவை".to_string()),
+        (Value::Integer(base), Value::Integer(exp)) if exp.to_u32().is_some() => {
+            Ok(Value::Integer(base.pow(exp.to_u32().unwrap_or(0))))
+        }
+        (base, exp) => match (base.as_f64(), exp.as_f64()) {
+            (Some(base), Some(exp)) => Ok(Value::Number(base.powf(exp))),
+            _ => Err("அடி() எண் அளவுருக்கள் தேவை".to_string()),
+        },
     }
 }
 
 fn builtin_floor(args: &[Value]) -> Result<Value, String> {
     match args.first() {
-        Some(Value::Number(n)) => Ok(Value::Number(n.floor())),
+        Some(Value::Integer(n)) => Ok(Value::Integer(n.clone())),
+        Some(Value::Number(n)) => float_to_integer(n.floor()),
         Some(v) => Err(format!("'{}' வகைக்கு தளம் கணக்கிட இயலாது", v.type_name())),
         None => Err("தளம்() ஒரு அளவுரு தேவை".to_string()),
     }
@@ -427,7 +569,8 @@ fn builtin_floor(args: &[Value]) -> Result<Value, String> {
 
 fn builtin_ceil(args: &[Value]) -> Result<Value, String> {
     match args.first() {
-        Some(Value::Number(n)) => Ok(Value::Number(n.ceil())),
+        Some(Value::Integer(n)) => Ok(Value::Integer(n.clone())),
+        Some(Value::Number(n)) => float_to_integer(n.ceil()),
         Some(v) => Err(format!("'{}' வகைக்கு கூரை கணக்கிட இயலாது", v.type_name())),
         None => Err("கூரை() ஒரு அளவுரு தேவை".to_string()),
     }
@@ -435,30 +578,28 @@ fn builtin_ceil(args: &[Value]) -> Result<Value, String> {
 
 fn builtin_abs(args: &[Value]) -> Result<Value, String> {
     match args.first() {
+        Some(Value::Integer(n)) => Ok(Value::Integer(n.abs())),
         Some(Value::Number(n)) => Ok(Value::Number(n.abs())),
         Some(v) => Err(format!("'{}' வகைக்கு முழுமை கணக்கிட இயலாது", v.type_name())),
         None => Err("முழுமை() ஒரு அளவுரு தேவை".to_string()),
     }
 }
 
-fn builtin_min(args: &[Value]) -> Result<Value, String> {
+fn min_of(args: &[Value]) -> Result<Value, String> {
     if args.is_empty() {
         return Err("குறைந்தபட்சம்() குறைந்தது ஒரு அளவுரு தேவை".to_string());
     }
     
     // If single list argument, find min in list
     if args.len() == 1 {
-        if let Value::List(list) = &args[0] {
-            let list_ref = list.borrow();
-            if list_ref.is_empty() {
+        if let Some(items) = ops::sequence_items(&args[0])? {
+            if items.is_empty() {
                 return Err("வெற்று பட்டியலுக்கு குறைந்தபட்சம் இல்லை".to_string());
             }
-            let mut min_val = &list_ref[0];
-            for item in list_ref.iter() {
-                if let (Value::Number(a), Value::Number(b)) = (item, min_val) {
-                    if a < b {
-                        min_val = item;
-                    }
+            let mut min_val = &items[0];
+            for item in items.iter() {
+                if compare_numbers(item, min_val) == Some(Ordering::Less) {
+                    min_val = item;
                 }
             }
             return Ok(min_val.clone());
@@ -468,33 +609,28 @@ fn builtin_min(args: &[Value]) -> Result<Value, String> {
     // Multiple arguments
     let mut min_val = &args[0];
     for arg in args.iter() {
-        if let (Value::Number(a), Value::Number(b)) = (arg, min_val) {
-            if a < b {
-                min_val = arg;
-            }
+        if compare_numbers(arg, min_val) == Some(Ordering::Less) {
+            min_val = arg;
         }
     }
     Ok(min_val.clone())
 }
 
-fn builtin_max(args: &[Value]) -> Result<Value, String> {
+fn max_of(args: &[Value]) -> Result<Value, String> {
     if args.is_empty() {
         return Err("அதிகபட்சம்() குறைந்தது ஒரு அளவுரு தேவை".to_string());
     }
     
     // If single list argument, find max in list
     if args.len() == 1 {
-        if let Value::List(list) = &args[0] {
-            let list_ref = list.borrow();
-            if list_ref.is_empty() {
+        if let Some(items) = ops::sequence_items(&args[0])? {
+            if items.is_empty() {
                 return Err("வெற்று பட்டியலுக்கு அதிகபட்சம் இல்லை".to_string());
             }
-            let mut max_val = &list_ref[0];
-            for item in list_ref.iter() {
-                if let (Value::Number(a), Value::Number(b)) = (item, max_val) {
-                    if a > b {
-                        max_val = item;
-                    }
+            let mut max_val = &items[0];
+            for item in items.iter() {
+                if compare_numbers(item, max_val) == Some(Ordering::Greater) {
+                    max_val = item;
                 }
             }
             return Ok(max_val.clone());
@@ -504,10 +640,8 @@ fn builtin_max(args: &[Value]) -> Result<Value, String> {
     // Multiple arguments
     let mut max_val = &args[0];
     for arg in args.iter() {
-        if let (Value::Number(a), Value::Number(b)) = (arg, max_val) {
-            if a > b {
-                max_val = arg;
-            }
+        if compare_numbers(arg, max_val) == Some(Ordering::Greater) {
+            max_val = arg;
         }
     }
     Ok(max_val.clone())
@@ -526,15 +660,15 @@ fn builtin_random(args: &[Value]) -> Result<Value, String> {
     match args.len() {
         0 => Ok(Value::Number(random)),
         1 => {
-            if let Value::Number(max) = &args[0] {
-                Ok(Value::Number((random * max).floor()))
+            if let Some(max) = args[0].as_f64() {
+                float_to_integer((random * max).floor())
             } else {
                 Err("தற்செயல்() எண் அளவுரு தேவை".to_string())
             }
         }
         2 => {
-            if let (Value::Number(min), Value::Number(max)) = (&args[0], &args[1]) {
-                Ok(Value::Number(min + (random * (max - min)).floor()))
+            if let (Some(min), Some(max)) = (args[0].as_f64(), args[1].as_f64()) {
+                float_to_integer(min + (random * (max - min)).floor())
             } else {
                 Err("தற்செயல்() எண் அளவுருக்கள் தேவை".to_string())
             }
@@ -543,21 +677,28 @@ fn builtin_random(args: &[Value]) -> Result<Value, String> {
     }
 }
 
-fn builtin_sum(args: &[Value]) -> Result<Value, String> {
-    match args.first() {
-        Some(Value::List(list)) => {
-            let mut total = 0.0;
-            for item in list.borrow().iter() {
-                if let Value::Number(n) = item {
-                    total += n;
-                } else {
-                    return Err("கூட்டு() எண் பட்டியல் தேவை".to_string());
-                }
+fn sum_of(args: &[Value]) -> Result<Value, String> {
+    let items = match args.first() {
+        Some(value) => ops::sequence_items(value)?,
+        None => None,
+    };
+    match (args.first(), items) {
+        (_, Some(items)) => {
+            // Stays an integer until a decimal is added
+            let mut total = Value::int(0);
+            for item in items.iter() {
+                total = match (&total, item) {
+                    (Value::Integer(a), Value::Integer(b)) => Value::Integer(a + b),
+                    _ => match (total.as_f64(), item.as_f64()) {
+                        (Some(a), Some(b)) => Value::Number(a + b),
+                        _ => return Err("கூட்டு() எண் பட்டியல் தேவை".to_string()),
+                    },
+                };
             }
-            Ok(Value::Number(total))
+            Ok(total)
         }
-        Some(v) => Err(format!("'{}' வகைக்கு கூட்டு கணக்கிட இயலாது", v.type_name())),
-        None => Err("கூட்டு() ஒரு அளவுரு தேவை".to_string()),
+        (Some(v), None) => Err(format!("'{}' வகைக்கு கூட்டு கணக்கிட இயலாது", v.type_name())),
+        (None, None) => Err("கூட்டு() ஒரு அளவுரு தேவை".to_string()),
     }
 }
 
@@ -585,10 +726,9 @@ fn builtin_join(args: &[Value]) -> Result<Value, String> {
         return Err("இணை() இரண்டு அளவுருக்கள் தேவை".to_string());
     }
     
-    match (&args[0], &args[1]) {
-        (Value::String(delim), Value::List(list)) => {
-            let parts: Vec<String> = list
-                .borrow()
+    match (&args[0], ops::sequence_items(&args[1])?) {
+        (Value::String(delim), Some(items)) => {
+            let parts: Vec<String> = items
                 .iter()
                 .map(|v| v.to_string())
                 .collect();
@@ -677,19 +817,40 @@ fn builtin_contains(args: &[Value]) -> Result<Value, String> {
     }
 }
 
+fn builtin_graphemes(args: &[Value]) -> Result<Value, String> {
+    match args.first() {
+        Some(Value::String(s)) => {
+            let letters = s.graphemes(true).map(|g| Value::String(g.to_string())).collect();
+            Ok(Value::List(Rc::new(RefCell::new(letters))))
+        }
+        Some(v) => Err(format!("'{}' வகைக்கு எழுத்துகள்() பயன்படுத்த இயலாது", v.type_name())),
+        None => Err("எழுத்துகள்() ஒரு அளவுரு தேவை".to_string()),
+    }
+}
+
 // ============= List Functions =============
 
-fn builtin_sort(args: &[Value]) -> Result<Value, String> {
+/// Order of two numbers, integers compared exactly; `None` for non-numbers
+fn compare_numbers(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Integer(a), Value::Integer(b)) => Some(a.cmp(b)),
+        _ => a.as_f64()?.partial_cmp(&b.as_f64()?),
+    }
+}
+
+/// Order used for sorting: numbers and strings compare, anything else is equal
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        _ => compare_numbers(a, b).unwrap_or(Ordering::Equal),
+    }
+}
+
+fn sorted(args: &[Value]) -> Result<Value, String> {
     match args.first() {
-        Some(Value::List(list)) => {
-            let mut sorted = list.borrow().clone();
-            sorted.sort_by(|a, b| {
-                match (a, b) {
-                    (Value::Number(a), Value::Number(b)) => a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal),
-                    (Value::String(a), Value::String(b)) => a.cmp(b),
-                    _ => std::cmp::Ordering::Equal,
-                }
-            });
+        Some(v) if !matches!(v, Value::String(_)) && ops::sequence_items(v)?.is_some() => {
+            let mut sorted = ops::sequence_items(v)?.unwrap_or_default();
+            sorted.sort_by(compare_values);
             Ok(Value::List(Rc::new(RefCell::new(sorted))))
         }
         Some(v) => Err(format!("'{}' வகைக்கு வரிசை() பயன்படுத்த இயலாது", v.type_name())),
@@ -699,19 +860,251 @@ fn builtin_sort(args: &[Value]) -> Result<Value, String> {
 
 fn builtin_reverse(args: &[Value]) -> Result<Value, String> {
     match args.first() {
-        Some(Value::List(list)) => {
-            let mut reversed = list.borrow().clone();
-            reversed.reverse();
-            Ok(Value::List(Rc::new(RefCell::new(reversed))))
-        }
         Some(Value::String(s)) => {
             Ok(Value::String(s.chars().rev().collect()))
         }
+        Some(v) if ops::sequence_items(v)?.is_some() => {
+            let mut reversed = ops::sequence_items(v)?.unwrap_or_default();
+            reversed.reverse();
+            Ok(Value::List(Rc::new(RefCell::new(reversed))))
+        }
         Some(v) => Err(format!("'{}' வகைக்கு தலைகீழ்() பயன்படுத்த இயலாது", v.type_name())),
         None => Err("தலைகீழ்() ஒரு அளவுரு தேவை".to_string()),
     }
 }
 
+// ============= Higher-order Functions =============
+// These take an Agam function (செயல், lambda or built-in) and call it
+// through the running interpreter
+
+fn new_list(items: Vec<Value>) -> Value {
+    Value::List(Rc::new(RefCell::new(items)))
+}
+
+fn builtin_map(args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    let mut result = Vec::new();
+    for item in ops::iteration_items(&args[0], caller, span)? {
+        result.push(caller.call(&args[1], vec![item], span)?);
+    }
+    Ok(new_list(result))
+}
+
+fn builtin_filter(args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    let mut result = Vec::new();
+    for item in ops::iteration_items(&args[0], caller, span)? {
+        if caller.call(&args[1], vec![item.clone()], span)?.is_truthy() {
+            result.push(item);
+        }
+    }
+    Ok(new_list(result))
+}
+
+fn builtin_reduce(args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    if args.len() != 2 && args.len() != 3 {
+        return Err(AgamError::runtime_error_at(
+            span,
+            "சுருக்கு() இரண்டு அல்லது மூன்று அளவுருக்கள் தேவை".to_string(),
+        ));
+    }
+
+    let mut items = ops::iteration_items(&args[0], caller, span)?.into_iter();
+    let mut acc = match args.get(2) {
+        Some(initial) => initial.clone(),
+        None => items.next().ok_or_else(|| {
+            AgamError::runtime_error_at(span, "சுருக்கு() வெற்று பட்டியலுக்கு தொடக்க மதிப்பு தேவை".to_string())
+        })?,
+    };
+    for item in items {
+        acc = caller.call(&args[1], vec![acc, item], span)?;
+    }
+    Ok(acc)
+}
+
+/// Items to test for any/all, which take a collection and an optional
+/// predicate; taken one at a time so that the test can stop early
+fn predicate_items(name: &str, args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Iter, AgamError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(AgamError::runtime_error_at(
+            span,
+            format!("{}() ஒன்று அல்லது இரண்டு அளவுருக்கள் தேவை", name),
+        ));
+    }
+    ops::iterate(&args[0], caller, span)
+}
+
+/// Whether `item` passes the predicate in `args[1]`, or is truthy without one
+fn passes(args: &[Value], item: Value, caller: &mut dyn Caller, span: Span) -> Result<bool, AgamError> {
+    match args.get(1) {
+        Some(predicate) => Ok(caller.call(predicate, vec![item], span)?.is_truthy()),
+        None => Ok(item.is_truthy()),
+    }
+}
+
+fn builtin_any(args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    let mut items = predicate_items("ஏதாவது", args, caller, span)?;
+    while let Some(item) = items.next(caller, span)? {
+        if passes(args, item, caller, span)? {
+            return Ok(Value::Boolean(true));
+        }
+    }
+    Ok(Value::Boolean(false))
+}
+
+fn builtin_all(args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    let mut items = predicate_items("அனைத்தும்", args, caller, span)?;
+    while let Some(item) = items.next(caller, span)? {
+        if !passes(args, item, caller, span)? {
+            return Ok(Value::Boolean(false));
+        }
+    }
+    Ok(Value::Boolean(true))
+}
+
+fn builtin_find(args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    let mut items = ops::iterate(&args[0], caller, span)?;
+    while let Some(item) = items.next(caller, span)? {
+        if caller.call(&args[1], vec![item.clone()], span)?.is_truthy() {
+            return Ok(item);
+        }
+    }
+    Ok(Value::Null)
+}
+
+fn builtin_sort_by(args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    let mut keyed = Vec::new();
+    for item in ops::iteration_items(&args[0], caller, span)? {
+        let key = caller.call(&args[1], vec![item.clone()], span)?;
+        keyed.push((key, item));
+    }
+    // Stable, so items with equal keys keep their order
+    keyed.sort_by(|(a, _), (b, _)| compare_values(a, b));
+    Ok(new_list(keyed.into_iter().map(|(_, item)| item).collect()))
+}
+
+fn builtin_group_by(args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    let mut groups: HashMap<String, Vec<Value>> = HashMap::new();
+    for item in ops::iteration_items(&args[0], caller, span)? {
+        let key = ops::dict_key(&caller.call(&args[1], vec![item.clone()], span)?);
+        groups.entry(key).or_default().push(item);
+    }
+    let groups = groups.into_iter().map(|(key, items)| (key, new_list(items))).collect();
+    Ok(Value::Dict(Rc::new(RefCell::new(groups))))
+}
+
+// ============= Iteration =============
+
+/// Call `function`, which takes a list, with an iterator's items in its place
+fn with_iterator_items(
+    args: &[Value],
+    caller: &mut dyn Caller,
+    span: Span,
+    function: NativeFn,
+) -> Result<Value, AgamError> {
+    let args = match args {
+        [iter @ Value::Iterator(_)] => vec![new_list(ops::iteration_items(iter, caller, span)?)],
+        _ => args.to_vec(),
+    };
+    function(&args).map_err(|msg| AgamError::runtime_error_at(span, msg))
+}
+
+fn builtin_min(args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    with_iterator_items(args, caller, span, min_of)
+}
+
+fn builtin_max(args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    with_iterator_items(args, caller, span, max_of)
+}
+
+fn builtin_sum(args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    with_iterator_items(args, caller, span, sum_of)
+}
+
+fn builtin_sort(args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    with_iterator_items(args, caller, span, sorted)
+}
+
+fn builtin_list(args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    Ok(new_list(ops::iteration_items(&args[0], caller, span)?))
+}
+
+fn builtin_tuple(args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    Ok(Value::Tuple(ops::iteration_items(&args[0], caller, span)?.into()))
+}
+
+fn lazy(iter: Iter) -> Value {
+    Value::Iterator(Rc::new(RefCell::new(iter)))
+}
+
+fn builtin_enumerate(args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    let count = match args {
+        [_] => Integer::Small(0),
+        [_, Value::Integer(start)] => start.clone(),
+        [_, start] => {
+            return Err(AgamError::runtime_error_at(
+                span,
+                format!("எண்ணிடு() தொடக்கம் முழுஎண்ணாக இருக்க வேண்டும், '{}' கொடுக்கப்பட்டது", start.type_name()),
+            ))
+        }
+        _ => {
+            return Err(AgamError::runtime_error_at(
+                span,
+                "எண்ணிடு() ஒன்று அல்லது இரண்டு அளவுருக்கள் தேவை".to_string(),
+            ))
+        }
+    };
+    let inner = Box::new(ops::iterate(&args[0], caller, span)?);
+    Ok(lazy(Iter::Enumerate { inner, count }))
+}
+
+fn builtin_zip(args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    let iters = args
+        .iter()
+        .map(|arg| ops::iterate(arg, caller, span))
+        .collect::<Result<Vec<Iter>, AgamError>>()?;
+    Ok(lazy(Iter::Zip(iters)))
+}
+
+fn builtin_next(args: &[Value], caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    match args {
+        [Value::Iterator(iter)] | [Value::Iterator(iter), _] => {
+            let item = Iter::Shared(Rc::clone(iter)).next(caller, span)?;
+            Ok(item.unwrap_or_else(|| args.get(1).cloned().unwrap_or(Value::Null)))
+        }
+        [value] | [value, _] => Err(AgamError::runtime_error_at(
+            span,
+            format!("அடுத்து() ஒரு மீளி தேவை, '{}' கொடுக்கப்பட்டது", value.type_name()),
+        )),
+        _ => Err(AgamError::runtime_error_at(
+            span,
+            "அடுத்து() ஒன்று அல்லது இரண்டு அளவுருக்கள் தேவை".to_string(),
+        )),
+    }
+}
+
+fn dict_entries<T>(name: &str, args: &[Value], entry: impl Fn(&String, &Value) -> T) -> Result<Vec<T>, String> {
+    match &args[0] {
+        Value::Dict(dict) => {
+            let dict = dict.borrow();
+            Ok(ops::sorted_keys(&dict).iter().map(|key| entry(key, &dict[key])).collect())
+        }
+        v => Err(format!("'{}' வகைக்கு {}() பயன்படுத்த இயலாது", v.type_name(), name)),
+    }
+}
+
+fn builtin_keys(args: &[Value]) -> Result<Value, String> {
+    Ok(new_list(dict_entries("சாவிகள்", args, |key, _| Value::String(key.clone()))?))
+}
+
+fn builtin_values(args: &[Value]) -> Result<Value, String> {
+    Ok(new_list(dict_entries("மதிப்புகள்", args, |_, value| value.clone())?))
+}
+
+fn builtin_items(args: &[Value]) -> Result<Value, String> {
+    Ok(new_list(dict_entries("உருப்படிகள்", args, |key, value| {
+        Value::Tuple(Rc::from([Value::String(key.clone()), value.clone()]))
+    })?))
+}
+
 // ============= File I/O =============
 
 fn builtin_read_file(args: &[Value]) -> Result<Value, String> {
@@ -763,7 +1156,7 @@ fn builtin_file_exists(args: &[Value]) -> Result<Value, String> {
 
 fn builtin_exit(args: &[Value]) -> Result<Value, String> {
     let code = match args.first() {
-        Some(Value::Number(n)) => *n as i32,
+        Some(Value::Integer(n)) => n.to_i32().unwrap_or(1),
         None => 0,
         _ => return Err("வெளியேறு() எண் அளவுரு தேவை".to_string()),
     };
@@ -789,11 +1182,12 @@ fn builtin_sleep(args: &[Value]) -> Result<Value, String> {
     use std::time::Duration;
     
     match args.first() {
-        Some(Value::Number(secs)) => {
-            if *secs < 0.0 {
+        Some(v @ (Value::Integer(_) | Value::Number(_))) => {
+            let secs = v.as_f64().unwrap_or(0.0);
+            if secs < 0.0 {
                 return Err("தூக்கம்() நேர்மறை எண் தேவை".to_string());
             }
-            let millis = (*secs * 1000.0) as u64;
+            let millis = (secs * 1000.0) as u64;
             thread::sleep(Duration::from_millis(millis));
             Ok(Value::Null)
         }
@@ -807,7 +1201,7 @@ fn builtin_date(args: &[Value]) -> Result<Value, String> {
     use std::time::{SystemTime, UNIX_EPOCH};
     
     let timestamp = match args.first() {
-        Some(Value::Number(ts)) => *ts as u64,
+        Some(v @ (Value::Integer(_) | Value::Number(_))) => v.as_f64().unwrap_or(0.0) as u64,
         None => {
             SystemTime::now()
                 .duration_since(UNIX_EPOCH)
@@ -910,18 +1304,18 @@ fn builtin_now(_args: &[Value]) -> Result<Value, String> {
     
     // Create dictionary with time components
     let mut map = HashMap::new();
-    map.insert("ஆண்டு".to_string(), Value::Number(year as f64));
-    map.insert("year".to_string(), Value::Number(year as f64));
-    map.insert("மாதம்".to_string(), Value::Number(month as f64));
-    map.insert("month".to_string(), Value::Number(month as f64));
-    map.insert("நாள்".to_string(), Value::Number(day as f64));
-    map.insert("day".to_string(), Value::Number(day as f64));
-    map.insert("மணி".to_string(), Value::Number(hours as f64));
-    map.insert("hour".to_string(), Value::Number(hours as f64));
-    map.insert("நிமிடம்".to_string(), Value::Number(minutes as f64));
-    map.insert("minute".to_string(), Value::Number(minutes as f64));
-    map.insert("வினாடி".to_string(), Value::Number(seconds as f64));
-    map.insert("second".to_string(), Value::Number(seconds as f64));
+    map.insert("ஆண்டு".to_string(), Value::int(year));
+    map.insert("year".to_string(), Value::int(year));
+    map.insert("மாதம்".to_string(), Value::int(month));
+    map.insert("month".to_string(), Value::int(month));
+    map.insert("நாள்".to_string(), Value::int(day));
+    map.insert("day".to_string(), Value::int(day));
+    map.insert("மணி".to_string(), Value::int(hours));
+    map.insert("hour".to_string(), Value::int(hours));
+    map.insert("நிமிடம்".to_string(), Value::int(minutes));
+    map.insert("minute".to_string(), Value::int(minutes));
+    map.insert("வினாடி".to_string(), Value::int(seconds));
+    map.insert("second".to_string(), Value::int(seconds));
     
     Ok(Value::Dict(Rc::new(RefCell::new(map))))
 }
@@ -942,8 +1336,8 @@ fn builtin_http_get(args: &[Value]) -> Result<Value, String> {
             
             // Return a dictionary with status and body
             let mut map = std::collections::HashMap::new();
-            map.insert("நிலை".to_string(), Value::Number(status as f64));
-            map.insert("status".to_string(), Value::Number(status as f64));
+            map.insert("நிலை".to_string(), Value::int(status));
+            map.insert("status".to_string(), Value::int(status));
             map.insert("உடல்".to_string(), Value::String(body.clone()));
             map.insert("body".to_string(), Value::String(body));
             
@@ -986,8 +1380,8 @@ fn builtin_http_post(args: &[Value]) -> Result<Value, String> {
         .map_err(|e| format!("பதில் படிக்க இயலவில்லை: {}", e))?;
     
     let mut map = std::collections::HashMap::new();
-    map.insert("நிலை".to_string(), Value::Number(status as f64));
-    map.insert("status".to_string(), Value::Number(status as f64));
+    map.insert("நிலை".to_string(), Value::int(status));
+    map.insert("status".to_string(), Value::int(status));
     map.insert("உடல்".to_string(), Value::String(response_body.clone()));
     map.insert("body".to_string(), Value::String(response_body));
     
@@ -1025,8 +1419,8 @@ fn builtin_http_put(args: &[Value]) -> Result<Value, String> {
         .map_err(|e| format!("பதில் படிக்க இயலவில்லை: {}", e))?;
     
     let mut map = std::collections::HashMap::new();
-    map.insert("நிலை".to_string(), Value::Number(status as f64));
-    map.insert("status".to_string(), Value::Number(status as f64));
+    map.insert("நிலை".to_string(), Value::int(status));
+    map.insert("status".to_string(), Value::int(status));
     map.insert("உடல்".to_string(), Value::String(response_body.clone()));
     map.insert("body".to_string(), Value::String(response_body));
     
@@ -1046,8 +1440,8 @@ fn builtin_http_delete(args: &[Value]) -> Result<Value, String> {
                 .map_err(|e| format!("பதில் படிக்க இயலவில்லை: {}", e))?;
             
             let mut map = std::collections::HashMap::new();
-            map.insert("நிலை".to_string(), Value::Number(status as f64));
-            map.insert("status".to_string(), Value::Number(status as f64));
+            map.insert("நிலை".to_string(), Value::int(status));
+            map.insert("status".to_string(), Value::int(status));
             map.insert("உடல்".to_string(), Value::String(body.clone()));
             map.insert("body".to_string(), Value::String(body));
             
@@ -1131,8 +1525,8 @@ fn builtin_file_upload(args: &[Value]) -> Result<Value, String> {
         .map_err(|e| format!("பதில் படிக்க இயலவில்லை: {}", e))?;
     
     let mut map = std::collections::HashMap::new();
-    map.insert("நிலை".to_string(), Value::Number(status as f64));
-    map.insert("status".to_string(), Value::Number(status as f64));
+    map.insert("நிலை".to_string(), Value::int(status));
+    map.insert("status".to_string(), Value::int(status));
     map.insert("உடல்".to_string(), Value::String(response_body.clone()));
     map.insert("body".to_string(), Value::String(response_body));
     map.insert("கோப்பு".to_string(), Value::String(filename.clone()));
@@ -1218,8 +1612,8 @@ fn builtin_http_request(args: &[Value]) -> Result<Value, String> {
     let response_body = response.into_string()
         .map_err(|e| format!("பதில் படிக்க இயலவில்லை: {}", e))?;
     let mut map = std::collections::HashMap::new();
-    map.insert("நிலை".to_string(), Value::Number(status as f64));
-    map.insert("status".to_string(), Value::Number(status as f64));
+    map.insert("நிலை".to_string(), Value::int(status));
+    map.insert("status".to_string(), Value::int(status));
     map.insert("உடல்".to_string(), Value::String(response_body.clone()));
     map.insert("body".to_string(), Value::String(response_body));
     
@@ -1255,10 +1649,10 @@ fn builtin_ws_connect(args: &[Value]) -> Result<Value, String> {
     
     // Return connection info
     let mut map = std::collections::HashMap::new();
-    map.insert("id".to_string(), Value::Number(conn_id as f64));
-    map.insert("அடையாளம்".to_string(), Value::Number(conn_id as f64));
-    map.insert("status".to_string(), Value::Number(response.status().as_u16() as f64));
-    map.insert("நிலை".to_string(), Value::Number(response.status().as_u16() as f64));
+    map.insert("id".to_string(), Value::int(conn_id));
+    map.insert("அடையாளம்".to_string(), Value::int(conn_id));
+    map.insert("status".to_string(), Value::int(response.status().as_u16()));
+    map.insert("நிலை".to_string(), Value::int(response.status().as_u16()));
     map.insert("connected".to_string(), Value::Boolean(true));
     map.insert("இணைந்தது".to_string(), Value::Boolean(true));
     
@@ -1268,10 +1662,10 @@ fn builtin_ws_connect(args: &[Value]) -> Result<Value, String> {
 /// Send a message through WebSocket
 fn builtin_ws_send(args: &[Value]) -> Result<Value, String> {
     let conn_id = match args.first() {
-        Some(Value::Number(n)) => *n as u64,
+        Some(Value::Integer(n)) => n.to_u64().unwrap_or(u64::MAX),
         Some(Value::Dict(d)) => {
             match d.borrow().get("id") {
-                Some(Value::Number(n)) => *n as u64,
+                Some(Value::Integer(n)) => n.to_u64().unwrap_or(u64::MAX),
                 _ => return Err("connection dictionary 'id' தேவை".to_string()),
             }
         }
@@ -1302,10 +1696,10 @@ fn builtin_ws_send(args: &[Value]) -> Result<Value, String> {
 /// Receive a message from WebSocket
 fn builtin_ws_receive(args: &[Value]) -> Result<Value, String> {
     let conn_id = match args.first() {
-        Some(Value::Number(n)) => *n as u64,
+        Some(Value::Integer(n)) => n.to_u64().unwrap_or(u64::MAX),
         Some(Value::Dict(d)) => {
             match d.borrow().get("id") {
-                Some(Value::Number(n)) => *n as u64,
+                Some(Value::Integer(n)) => n.to_u64().unwrap_or(u64::MAX),
                 _ => return Err("connection dictionary 'id' தேவை".to_string()),
             }
         }
@@ -1345,10 +1739,10 @@ fn builtin_ws_receive(args: &[Value]) -> Result<Value, String> {
 /// Close a WebSocket connection
 fn builtin_ws_close(args: &[Value]) -> Result<Value, String> {
     let conn_id = match args.first() {
-        Some(Value::Number(n)) => *n as u64,
+        Some(Value::Integer(n)) => n.to_u64().unwrap_or(u64::MAX),
         Some(Value::Dict(d)) => {
             match d.borrow().get("id") {
-                Some(Value::Number(n)) => *n as u64,
+                Some(Value::Integer(n)) => n.to_u64().unwrap_or(u64::MAX),
                 _ => return Err("connection dictionary 'id' தேவை".to_string()),
             }
         }
@@ -1372,6 +1766,7 @@ fn builtin_ws_close(args: &[Value]) -> Result<Value, String> {
 /// Convert Value to JSON string representation
 fn value_to_json(value: &Value) -> String {
     match value {
+        Value::Integer(n) => n.to_string(),
         Value::Number(n) => n.to_string(),
         Value::String(s) => format!("\"{}\"", s.replace('\"', "\\\"")),
         Value::Boolean(b) => b.to_string(),
@@ -1380,6 +1775,10 @@ fn value_to_json(value: &Value) -> String {
             let items: Vec<String> = l.borrow().iter().map(value_to_json).collect();
             format!("[{}]", items.join(", "))
         }
+        Value::Tuple(t) => {
+            let items: Vec<String> = t.iter().map(value_to_json).collect();
+            format!("[{}]", items.join(", "))
+        }
         Value::Dict(d) => {
             let pairs: Vec<String> = d.borrow().iter()
                 .map(|(k, v)| format!("\"{}\": {}", k, value_to_json(v)))
@@ -1423,6 +1822,9 @@ fn parse_json_value(s: &str) -> Result<Value, String> {
     }
     
     // Number
+    if let Ok(n) = s.parse::<BigInt>() {
+        return Ok(Value::int(n));
+    }
     if let Ok(n) = s.parse::<f64>() {
         return Ok(Value::Number(n));
     }
@@ -1569,3 +1971,137 @@ fn find_colon_outside_string(s: &str) -> Option<usize> {
     }
     None
 }
+
+// ============= Testing =============
+
+/// Start of every message from a failed உறுதி
+const ASSERTION_FAILED: &str = "உறுதிப்படுத்தல் தோல்வி";
+
+/// Failure message, with the caller's own message when one was given
+fn assertion_failure(reason: &str, message: Option<&Value>) -> String {
+    let mut text = ASSERTION_FAILED.to_string();
+    if !reason.is_empty() {
+        text.push_str(": ");
+        text.push_str(reason);
+    }
+    if let Some(message) = message {
+        text.push_str(&format!(" ({})", message));
+    }
+    text
+}
+
+fn builtin_assert(args: &[Value], _caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    match args {
+        [condition] | [condition, _] if condition.is_truthy() => Ok(Value::Null),
+        [_] | [_, _] => Err(AgamError::assertion_error_at(span, assertion_failure("", args.get(1)))),
+        _ => Err(AgamError::runtime_error_at(span, "உறுதி() 1-2 அளவுருக்கள் எடுக்கும்".to_string())),
+    }
+}
+
+fn builtin_assert_eq(args: &[Value], _caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    match args {
+        [left, right] | [left, right, _] if same(left, right) => Ok(Value::Null),
+        [left, right] | [left, right, _] => {
+            let mut text = assertion_failure("மதிப்புகள் சமமில்லை", args.get(2));
+            text.push_str(&value_diff(left, right));
+            Err(AgamError::assertion_error_at(span, text))
+        }
+        _ => Err(AgamError::runtime_error_at(span, "உறுதி_சமம்() 2-3 அளவுருக்கள் எடுக்கும்".to_string())),
+    }
+}
+
+fn builtin_assert_ne(args: &[Value], _caller: &mut dyn Caller, span: Span) -> Result<Value, AgamError> {
+    match args {
+        [left, right] | [left, right, _] if !same(left, right) => Ok(Value::Null),
+        [left, _] | [left, _, _] => {
+            let mut text = assertion_failure("மதிப்புகள் சமம்", args.get(2));
+            text.push_str(&format!("\n  இரண்டும்: {}", shown(left)));
+            Err(AgamError::assertion_error_at(span, text))
+        }
+        _ => Err(AgamError::runtime_error_at(span, "உறுதி_வேறு() 2-3 அளவுருக்கள் எடுக்கும்".to_string())),
+    }
+}
+
+/// Equality for assertions: like `==`, except that lists, dictionaries
+/// and struct instances are compared by contents rather than identity
+fn same(left: &Value, right: &Value) -> bool {
+    let same_map = |a: &HashMap<String, Value>, b: &HashMap<String, Value>| {
+        a.len() == b.len() && a.iter().all(|(key, value)| b.get(key).is_some_and(|other| same(value, other)))
+    };
+    let same_items = |a: &[Value], b: &[Value]| a.len() == b.len() && a.iter().zip(b).all(|(x, y)| same(x, y));
+    match (left, right) {
+        (Value::List(a), Value::List(b)) => same_items(&a.borrow(), &b.borrow()),
+        (Value::Tuple(a), Value::Tuple(b)) => same_items(a, b),
+        (Value::Dict(a), Value::Dict(b)) => same_map(&a.borrow(), &b.borrow()),
+        (Value::Struct { name: n1, fields: f1, .. }, Value::Struct { name: n2, fields: f2, .. }) => {
+            n1 == n2 && same_map(&f1.borrow(), &f2.borrow())
+        }
+        (Value::EnumVariant { enum_name: e1, variant: v1, payload: p1 },
+         Value::EnumVariant { enum_name: e2, variant: v2, payload: p2 }) => {
+            e1 == e2
+                && v1 == v2
+                && p1.len() == p2.len()
+                && p1.iter().zip(p2).all(|((_, a), (_, b))| same(a, b))
+        }
+        _ => left == right,
+    }
+}
+
+/// A value as an assertion shows it: strings quoted, so `"1"` and `1` differ
+fn shown(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("\"{}\"", s),
+        _ => value.to_string(),
+    }
+}
+
+/// The two sides of a failed equality: side by side when both fit on a
+/// line, otherwise a line diff, `-` for lines only on the left and `+`
+/// for lines only on the right
+fn value_diff(left: &Value, right: &Value) -> String {
+    let (mut left_text, mut right_text) = (shown(left), shown(right));
+    if left_text == right_text {
+        left_text = format!("{} ({})", left_text, left.type_name());
+        right_text = format!("{} ({})", right_text, right.type_name());
+    }
+    if !left_text.contains('\n') && !right_text.contains('\n') {
+        return format!("\n  இடது:  {}\n  வலது: {}", left_text, right_text);
+    }
+
+    // Multi-line strings are compared as written, without their quotes
+    let unquoted = |value: &Value, text: String| match value {
+        Value::String(s) => s.clone(),
+        _ => text,
+    };
+    let (left_text, right_text) = (unquoted(left, left_text), unquoted(right, right_text));
+    let left_lines: Vec<&str> = left_text.lines().collect();
+    let right_lines: Vec<&str> = right_text.lines().collect();
+    // Longest common subsequence of lines, filled from the end
+    let mut common = vec![vec![0usize; right_lines.len() + 1]; left_lines.len() + 1];
+    for i in (0..left_lines.len()).rev() {
+        for j in (0..right_lines.len()).rev() {
+            common[i][j] = if left_lines[i] == right_lines[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut text = String::from("\n  --- இடது\n  +++ வலது");
+    let (mut i, mut j) = (0, 0);
+    while i < left_lines.len() || j < right_lines.len() {
+        if i < left_lines.len() && j < right_lines.len() && left_lines[i] == right_lines[j] {
+            text.push_str(&format!("\n    {}", left_lines[i]));
+            i += 1;
+            j += 1;
+        } else if i < left_lines.len() && (j == right_lines.len() || common[i + 1][j] >= common[i][j + 1]) {
+            text.push_str(&format!("\n  - {}", left_lines[i]));
+            i += 1;
+        } else {
+            text.push_str(&format!("\n  + {}", right_lines[j]));
+            j += 1;
+        }
+    }
+    text
+}
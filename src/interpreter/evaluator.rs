@@ -2,20 +2,16 @@
 //! 
 //! Executes the Abstract Syntax Tree
 
+use std::any::Any;
 use std::rc::Rc;
 use std::cell::RefCell;
 use std::collections::HashMap;
 
 use crate::parser::ast::*;
-use crate::types::{Value, Environment, AgamFunction};
-use crate::error::AgamError;
-use crate::interpreter::builtin::create_builtins;
-
-/// Maximum recursion depth to prevent stack overflow
-const MAX_RECURSION_DEPTH: usize = 1000;
-
-/// Maximum loop iterations to prevent infinite loops
-const MAX_LOOP_ITERATIONS: usize = 10_000_000;
+use crate::types::{Value, Environment, AgamFunction, Caller, Iter};
+use crate::error::{AgamError, StackFrame};
+use crate::interpreter::ops;
+use crate::resolver::Resolver;
 
 /// Control flow signals
 pub enum ControlFlow {
@@ -23,89 +19,390 @@ pub enum ControlFlow {
     Return(Value),
     Break,
     Continue,
+    /// A generator handed out a value and is suspending
+    Yield(Value),
+}
+
+/// Where a suspended generator stopped. Recorded innermost first as a
+/// விளை unwinds, and taken outermost first when the generator resumes
+enum Resume {
+    /// Position in a block, together with the block's variables
+    Block { environment: Rc<RefCell<Environment>>, index: usize },
+    /// Branch of an என்றால் or arm of a பொருத்து being run
+    Branch(usize),
+    /// Items a ஒவ்வொரு loop has still to take
+    For(Iter),
+    /// Inside a முயற்சி block
+    Try,
+    /// Inside the `index`th பிடி clause, handling `error`
+    Catch(usize, AgamError),
+}
+
+/// A call to a generator function, made by the evaluator
+struct Generator {
+    function: AgamFunction,
+    /// The call's parameters
+    environment: Rc<RefCell<Environment>>,
+    /// Where the body stopped, empty before it starts; `None` once finished
+    suspended: Option<Vec<Resume>>,
+}
+
+/// An active call, recorded for tracebacks
+struct CallFrame {
+    /// Name of the called function (or module being imported)
+    function: String,
+    /// File containing the call site
+    caller_file: Option<Rc<str>>,
+    /// Location of the call in the caller
+    call_site: Span,
 }
 
 /// The interpreter
 pub struct Evaluator {
     environment: Rc<RefCell<Environment>>,
-    #[allow(dead_code)]
     globals: Rc<RefCell<Environment>>,
-    /// Current call stack depth for recursion protection
-    call_depth: usize,
+    /// Active calls, outermost first; its length guards recursion depth
+    call_stack: Vec<CallFrame>,
+    /// File whose code is currently executing
+    current_file: Option<Rc<str>>,
+    /// Errors being handled by enclosing பிடி blocks, innermost last
+    handled_errors: Vec<AgamError>,
+    /// Position of the generator being suspended or resumed
+    resume: Vec<Resume>,
 }
 
 impl Evaluator {
     pub fn new() -> Self {
-        let globals = Rc::new(RefCell::new(Environment::new()));
-        
-        // Register built-in functions
-        for (name, func) in create_builtins() {
-            globals.borrow_mut().define(
-                name,
-                Value::NativeFunction(func),
-                true,
-            );
-        }
+        // Global environment with the built-in functions registered
+        let globals = ops::builtin_environment();
 
         Evaluator {
             environment: Rc::clone(&globals),
             globals,
-            call_depth: 0,
+            call_stack: Vec::new(),
+            current_file: None,
+            handled_errors: Vec::new(),
+            resume: Vec::new(),
         }
     }
 
-    /// Execute a program
+    /// Set the path of the main program, used in tracebacks
+    pub fn set_file(&mut self, path: &str) {
+        self.current_file = Some(Rc::from(path));
+    }
+
+    /// The value of a global variable, if it is defined
+    pub fn global(&self, name: &str) -> Option<Value> {
+        self.globals.borrow().get(name)
+    }
+
+    /// Snapshot the call chain for an error raised at `line:column`
+    fn stack_trace(&self, line: usize, column: usize) -> Vec<StackFrame> {
+        let mut frames = Vec::with_capacity(self.call_stack.len() + 1);
+        let mut function = ops::TOP_LEVEL_NAME;
+        for frame in &self.call_stack {
+            frames.push(StackFrame {
+                function: function.to_string(),
+                file: frame.caller_file.as_deref().map(str::to_string),
+                line: frame.call_site.line,
+                column: frame.call_site.column,
+            });
+            function = &frame.function;
+        }
+        frames.push(StackFrame {
+            function: function.to_string(),
+            file: self.current_file.as_deref().map(str::to_string),
+            line,
+            column,
+        });
+        frames
+    }
+
+    /// Record the call chain on an error leaving a function or module,
+    /// unless a deeper frame has already done so
+    fn attach_stack(&self, error: AgamError) -> AgamError {
+        match error {
+            AgamError::RuntimeError { line, column, message, stack } if stack.is_empty() => {
+                AgamError::RuntimeError {
+                    line,
+                    column,
+                    message,
+                    stack: self.stack_trace(line, column),
+                }
+            }
+            AgamError::AssertionError { line, column, message, stack } if stack.is_empty() => {
+                AgamError::AssertionError {
+                    line,
+                    column,
+                    message,
+                    stack: self.stack_trace(line, column),
+                }
+            }
+            AgamError::Thrown { line, column, value, stack } if stack.is_empty() => {
+                AgamError::Thrown {
+                    line,
+                    column,
+                    value,
+                    stack: self.stack_trace(line, column),
+                }
+            }
+            other => other,
+        }
+    }
+
+    /// Enter a function or module body defined in `file`
+    fn push_frame(&mut self, function: &str, file: Option<Rc<str>>, call_site: Span) {
+        let caller_file = std::mem::replace(&mut self.current_file, file);
+        self.call_stack.push(CallFrame {
+            function: function.to_string(),
+            caller_file,
+            call_site,
+        });
+    }
+
+    /// Leave the innermost frame, attaching the traceback to any error
+    fn pop_frame<T>(&mut self, result: Result<T, AgamError>) -> Result<T, AgamError> {
+        let result = result.map_err(|e| self.attach_stack(e));
+        if let Some(frame) = self.call_stack.pop() {
+            self.current_file = frame.caller_file;
+        }
+        result
+    }
+
+    /// Resolve and execute a program. Its value is that of its final
+    /// statement when that is an expression
     pub fn execute(&mut self, program: &Program) -> Result<Value, AgamError> {
+        let mut program = program.clone();
+        Resolver::new(self.globals.borrow().declared()).resolve(&mut program)?;
+
+        let mut result = Value::Null;
         for statement in &program.statements {
+            if let StatementKind::Expression(expr) = &statement.kind {
+                result = self.evaluate(expr)?;
+                continue;
+            }
+            result = Value::Null;
             match self.execute_statement(statement)? {
-                ControlFlow::Return(v) => return Ok(v),
+                // The resolver keeps திரும்பு and விளை inside functions
+                ControlFlow::Return(v) | ControlFlow::Yield(v) => return Ok(v),
                 ControlFlow::Break => {
-                    return Err(AgamError::runtime_error(
-                        0, 0,
+                    return Err(AgamError::runtime_error_at(
+                        statement.span,
                         "'நிறுத்து' வளைய வெளியே பயன்படுத்த முடியாது".to_string(),
                     ))
                 }
                 ControlFlow::Continue => {
-                    return Err(AgamError::runtime_error(
-                        0, 0,
+                    return Err(AgamError::runtime_error_at(
+                        statement.span,
                         "'தொடர்' வளைய வெளியே பயன்படுத்த முடியாது".to_string(),
                     ))
                 }
                 ControlFlow::None => {}
             }
-            
-            // In REPL mode, keep track of last expression value
-            if let Statement::Expression(_) = statement {
-                // For now, just continue
+        }
+
+        Ok(result)
+    }
+
+    /// Define a resolved variable in the current environment
+    fn define(&mut self, name: &str, binding: Binding, value: Value, is_const: bool) {
+        match binding {
+            Binding::Global => self.environment.borrow_mut().define(name.to_string(), value, is_const),
+            Binding::Local { slot, .. } => self.environment.borrow_mut().define_slot(slot, value, is_const),
+        }
+    }
+
+    /// Assign a resolved variable
+    fn assign(&mut self, name: &str, binding: Binding, value: Value, span: Span) -> Result<(), AgamError> {
+        let mut env = self.environment.borrow_mut();
+        match binding {
+            Binding::Global => env.assign_global(name, value),
+            Binding::Local { depth, slot } => env.assign_at(depth, slot, name, value),
+        }
+        .map_err(|msg| AgamError::runtime_error_at(span, msg))
+    }
+
+    /// Run an ஒவ்வொரு loop, taking one item at a time from the iterable.
+    /// `resumed` holds the items left when a generator stopped inside it
+    fn execute_for(
+        &mut self,
+        variables: &[String],
+        iterable: &Expression,
+        body: &[Statement],
+        bindings: &[Binding],
+        resumed: Option<Iter>,
+        span: Span,
+    ) -> Result<ControlFlow, AgamError> {
+        let mut resuming = resumed.is_some();
+        // Counted from the last resume, like a வரை loop's
+        let mut iterations = 0usize;
+        let mut items = match resumed {
+            Some(items) => items,
+            None => {
+                let iter_val = self.evaluate(iterable)?;
+                ops::iterate(&iter_val, self, iterable.span)?
+            }
+        };
+
+        loop {
+            if !resuming {
+                let Some(item) = items.next(self, span)? else {
+                    break;
+                };
+                iterations += 1;
+                if iterations > ops::MAX_LOOP_ITERATIONS {
+                    return Err(ops::loop_limit_error(span));
+                }
+                if let [variable] = variables {
+                    self.define(variable, bindings[0], item, false);
+                } else {
+                    self.define_unpacked(variables, bindings, &item, false, span)?;
+                }
+            }
+            resuming = false;
+
+            match self.execute_block(body)? {
+                ControlFlow::Break => break,
+                ControlFlow::Continue => continue,
+                ControlFlow::Return(v) => return Ok(ControlFlow::Return(v)),
+                ControlFlow::Yield(value) => {
+                    self.resume.push(Resume::For(items));
+                    return Ok(ControlFlow::Yield(value));
+                }
+                ControlFlow::None => {}
             }
         }
 
-        Ok(Value::Null)
+        Ok(ControlFlow::None)
+    }
+
+    /// Fill the list or dictionary `result` of a comprehension, in an
+    /// environment of its own
+    fn comprehension(
+        &mut self,
+        clauses: &[ComprehensionClause],
+        element: &Expression,
+        value: Option<&Expression>,
+        result: &Value,
+        span: Span,
+    ) -> Result<(), AgamError> {
+        let previous = Rc::clone(&self.environment);
+        self.environment = Rc::new(RefCell::new(Environment::with_parent(previous.clone())));
+        let outcome = self.comprehension_clauses(clauses, element, value, result, span);
+        self.environment = previous;
+        outcome
+    }
+
+    /// Apply the first clause, running the rest for each item it lets
+    /// through, and add the element once no clauses are left
+    fn comprehension_clauses(
+        &mut self,
+        clauses: &[ComprehensionClause],
+        element: &Expression,
+        value: Option<&Expression>,
+        result: &Value,
+        span: Span,
+    ) -> Result<(), AgamError> {
+        match clauses.split_first() {
+            None => {
+                let element = self.evaluate(element)?;
+                match (result, value) {
+                    (Value::Dict(map), Some(value)) => {
+                        let value = self.evaluate(value)?;
+                        map.borrow_mut().insert(ops::dict_key(&element), value);
+                    }
+                    (Value::List(list), _) => list.borrow_mut().push(element),
+                    _ => unreachable!("comprehension result"),
+                }
+            }
+            Some((ComprehensionClause::For { variables, iterable, bindings }, rest)) => {
+                let iter_val = self.evaluate(iterable)?;
+                let mut items = ops::iterate(&iter_val, self, iterable.span)?;
+                while let Some(item) = items.next(self, span)? {
+                    if let [variable] = variables.as_slice() {
+                        self.define(variable, bindings[0], item, false);
+                    } else {
+                        self.define_unpacked(variables, bindings, &item, false, span)?;
+                    }
+                    self.comprehension_clauses(rest, element, value, result, span)?;
+                }
+            }
+            Some((ComprehensionClause::If(condition), rest)) => {
+                if self.evaluate(condition)?.is_truthy() {
+                    self.comprehension_clauses(rest, element, value, result, span)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Define one variable per item of a tuple or list: `மாறி a, b = pair`
+    fn define_unpacked(&mut self, names: &[String], bindings: &[Binding], value: &Value, is_const: bool, span: Span) -> Result<(), AgamError> {
+        let values = ops::unpack(value, names.len(), span)?;
+        for ((name, binding), val) in names.iter().zip(bindings).zip(values) {
+            self.define(name, *binding, val, is_const);
+        }
+        Ok(())
+    }
+
+    /// `a, b = b, a`: every value is computed before any target is assigned
+    fn assign_unpacked(&mut self, targets: &[Expression], value: &Expression) -> Result<(), AgamError> {
+        let val = self.evaluate(value)?;
+        let values = ops::unpack(&val, targets.len(), value.span)?;
+        for (target, val) in targets.iter().zip(values) {
+            let span = target.span;
+            match &target.kind {
+                ExpressionKind::Identifier { name, binding } => self.assign(name, *binding, val, span)?,
+                ExpressionKind::Index { object, index } => {
+                    let obj = self.evaluate(object)?;
+                    let idx = self.evaluate(index)?;
+                    ops::index_set(&obj, &idx, val, span)?;
+                }
+                ExpressionKind::MemberAccess { object, member } => {
+                    let obj = self.evaluate(object)?;
+                    ops::member_set(&obj, member, val, span)?;
+                }
+                _ => unreachable!("the parser only allows assignable targets"),
+            }
+        }
+        Ok(())
     }
 
     fn execute_statement(&mut self, stmt: &Statement) -> Result<ControlFlow, AgamError> {
-        match stmt {
-            Statement::Let { name, value, is_const } => {
+        let span = stmt.span;
+        match &stmt.kind {
+            StatementKind::Let { name, value, is_const, binding } => {
                 let val = self.evaluate(value)?;
-                self.environment.borrow_mut().define(name.clone(), val, *is_const);
+                self.define(name, *binding, val, *is_const);
                 Ok(ControlFlow::None)
             }
 
-            Statement::Expression(expr) => {
+            StatementKind::LetUnpack { names, value, is_const, bindings } => {
+                let val = self.evaluate(value)?;
+                self.define_unpacked(names, bindings, &val, *is_const, value.span)?;
+                Ok(ControlFlow::None)
+            }
+
+            StatementKind::AssignUnpack { targets, value } => {
+                self.assign_unpacked(targets, value)?;
+                Ok(ControlFlow::None)
+            }
+
+            StatementKind::Expression(expr) => {
                 self.evaluate(expr)?;
                 Ok(ControlFlow::None)
             }
 
-            Statement::Print(args) => {
+            StatementKind::Print(args) => {
                 let values: Vec<String> = args
                     .iter()
                     .map(|arg| self.evaluate(arg).map(|v| v.to_string()))
                     .collect::<Result<Vec<_>, _>>()?;
-                println!("{}", values.join(" "));
+                ops::print_line(&values.join(" "));
                 Ok(ControlFlow::None)
             }
 
-            Statement::If {
+            StatementKind::If {
                 condition,
                 then_branch,
                 elif_branches,
@@ -114,97 +411,36 @@ impl Evaluator {
                 let cond_val = self.evaluate(condition)?;
                 
                 if cond_val.is_truthy() {
-                    return self.execute_block(then_branch);
+                    return self.branch(0, then_branch);
                 }
 
-                for (elif_cond, elif_body) in elif_branches {
+                for (index, (elif_cond, elif_body)) in elif_branches.iter().enumerate() {
                     let elif_val = self.evaluate(elif_cond)?;
                     if elif_val.is_truthy() {
-                        return self.execute_block(elif_body);
+                        return self.branch(index + 1, elif_body);
                     }
                 }
 
                 if let Some(else_body) = else_branch {
-                    return self.execute_block(else_body);
+                    return self.branch(elif_branches.len() + 1, else_body);
                 }
 
                 Ok(ControlFlow::None)
             }
 
-            Statement::While { condition, body } => {
-                let mut iterations = 0usize;
-                loop {
-                    // Check iteration limit
-                    iterations += 1;
-                    if iterations > MAX_LOOP_ITERATIONS {
-                        return Err(AgamError::runtime_error(
-                            0, 0,
-                            format!(
-                                "அதிகபட்ச வளைய மீளமுறை ({}) அடைந்தது - முடிவிலா வளையம் இருக்கலாம்",
-                                MAX_LOOP_ITERATIONS
-                            ),
-                        ));
-                    }
-
-                    let cond_val = self.evaluate(condition)?;
-                    if !cond_val.is_truthy() {
-                        break;
-                    }
-
-                    match self.execute_block(body)? {
-                        ControlFlow::Break => break,
-                        ControlFlow::Continue => continue,
-                        ControlFlow::Return(v) => return Ok(ControlFlow::Return(v)),
-                        ControlFlow::None => {}
-                    }
-                }
-                Ok(ControlFlow::None)
-            }
-
-            Statement::For { variable, iterable, body } => {
-                let iter_val = self.evaluate(iterable)?;
-                
-                let items = match iter_val {
-                    Value::List(list) => list.borrow().clone(),
-                    Value::String(s) => s.chars().map(|c| Value::String(c.to_string())).collect(),
-                    _ => {
-                        return Err(AgamError::runtime_error(
-                            0, 0,
-                            format!("'{}' வகையை மீளமுடியாது", iter_val.type_name()),
-                        ))
-                    }
-                };
-
-                for item in items {
-                    self.environment.borrow_mut().define(variable.clone(), item, false);
-                    
-                    match self.execute_block(body)? {
-                        ControlFlow::Break => break,
-                        ControlFlow::Continue => continue,
-                        ControlFlow::Return(v) => return Ok(ControlFlow::Return(v)),
-                        ControlFlow::None => {}
-                    }
-                }
+            StatementKind::While { condition, body } => self.execute_while(condition, body, false, span),
 
-                Ok(ControlFlow::None)
+            StatementKind::For { variables, iterable, body, bindings } => {
+                self.execute_for(variables, iterable, body, bindings, None, span)
             }
 
-            Statement::Function { name, params, body } => {
-                let func = AgamFunction::new(
-                    name.clone(),
-                    params.clone(),
-                    body.clone(),
-                    Rc::clone(&self.environment),
-                );
-                self.environment.borrow_mut().define(
-                    name.clone(),
-                    Value::Function(func),
-                    true,
-                );
+            StatementKind::Function { name, params, body, binding } => {
+                let func = self.function(name.clone(), params, body.clone())?;
+                self.define(name, *binding, Value::Function(func), true);
                 Ok(ControlFlow::None)
             }
 
-            Statement::Return(value) => {
+            StatementKind::Return(value) => {
                 let val = match value {
                     Some(expr) => self.evaluate(expr)?,
                     None => Value::Null,
@@ -212,181 +448,306 @@ impl Evaluator {
                 Ok(ControlFlow::Return(val))
             }
 
-            Statement::Break => Ok(ControlFlow::Break),
-            Statement::Continue => Ok(ControlFlow::Continue),
+            // வெளிமாறி only affects what the resolver allows
+            StatementKind::Nonlocal(_) => Ok(ControlFlow::None),
+
+            StatementKind::Yield(value) => Ok(ControlFlow::Yield(self.evaluate(value)?)),
+
+            StatementKind::Break => Ok(ControlFlow::Break),
+            StatementKind::Continue => Ok(ControlFlow::Continue),
             
             // Import statement - load and execute external module
-            Statement::Import { module, items } => {
-                // Try to find and load the module file
-                let module_path = format!("{}.agam", module);
-                
-                if let Ok(source) = std::fs::read_to_string(&module_path) {
-                    // Parse and execute the module
-                    let tokens = crate::lexer::Lexer::tokenize(&source).map_err(|e| {
-                        AgamError::runtime_error(0, 0, format!("Module load error: {}", e))
-                    })?;
-                    
-                    let mut parser = crate::parser::Parser::new(tokens);
-                    let program = parser.parse().map_err(|e| {
-                        AgamError::runtime_error(0, 0, format!("Module parse error: {}", e))
-                    })?;
-                    
-                    // Create a temporary environment for module execution
-                    let module_env = Rc::new(RefCell::new(Environment::new()));
-                    
-                    // Register built-ins in module environment
-                    for (name, func) in crate::interpreter::builtin::create_builtins() {
-                        module_env.borrow_mut().define(name, Value::NativeFunction(func), true);
-                    }
-                    
-                    // Execute module in temporary environment
-                    let previous_env = Rc::clone(&self.environment);
-                    self.environment = Rc::clone(&module_env);
-                    
-                    for stmt in &program.statements {
-                        self.execute_statement(stmt)?;
-                    }
-                    
-                    self.environment = previous_env;
-                    
-                    // Import items from module environment to current environment
-                    match items {
-                        Some(item_names) => {
-                            // Selective import - only import specified items
-                            for item_name in item_names {
-                                if let Some(value) = module_env.borrow().get(item_name) {
-                                    self.environment.borrow_mut().define(item_name.clone(), value, true);
-                                } else {
-                                    return Err(AgamError::runtime_error(
-                                        0, 0,
-                                        format!("'{}' கூறில் '{}' கிடைக்கவில்லை", module, item_name),
-                                    ));
-                                }
-                            }
-                        }
-                        None => {
-                            // Namespace import - create a Module value with all exports
-                            let mut exports = std::collections::HashMap::new();
-                            let module_values: Vec<_> = module_env.borrow().get_all_names();
-                            for name in module_values {
-                                // Skip builtins (they're already in globals)
-                                if let Some(value) = module_env.borrow().get(&name) {
-                                    if !matches!(value, Value::NativeFunction(_)) {
-                                        exports.insert(name, value);
-                                    }
-                                }
-                            }
-                            
-                            // Store the module as a namespace
-                            let module_value = Value::Module {
-                                name: module.clone(),
-                                exports: Rc::new(RefCell::new(exports)),
-                            };
-                            self.environment.borrow_mut().define(module.clone(), module_value, true);
+            StatementKind::Import { module, items, bindings } => {
+                // Find, parse and execute the module file
+                let program = ops::parse_module(module, span)?;
+                let module_path = ops::module_path(module);
+
+                // Execute module in a fresh environment with the built-ins
+                let module_env = ops::builtin_environment();
+                let previous_env = Rc::clone(&self.environment);
+                self.environment = Rc::clone(&module_env);
+                self.push_frame(&ops::module_frame_name(module), Some(Rc::from(module_path.as_str())), span);
+
+                let result = program
+                    .statements
+                    .iter()
+                    .try_for_each(|stmt| self.execute_statement(stmt).map(|_| ()));
+
+                self.environment = previous_env;
+                self.pop_frame(result)?;
+
+                // Import items from module environment to current environment
+                match items {
+                    Some(item_names) => {
+                        // Selective import - only import specified items
+                        for (item_name, binding) in item_names.iter().zip(bindings) {
+                            let value = ops::module_item(module, &module_env, item_name, span)?;
+                            self.define(item_name, *binding, value, true);
                         }
                     }
-                    
-                    Ok(ControlFlow::None)
-                } else {
-                    Err(AgamError::runtime_error(
-                        0, 0,
-                        format!("கூறு காணவில்லை: '{}'", module),
-                    ))
+                    None => {
+                        // Namespace import - create a Module value with all exports
+                        let module_value = ops::module_value(module, &module_env);
+                        let binding = bindings.first().copied().unwrap_or_default();
+                        self.define(module, binding, module_value, true);
+                    }
                 }
+
+                Ok(ControlFlow::None)
             }
             
             // Try-catch statement for error handling
-            Statement::TryCatch { try_block, error_var, catch_block } => {
-                match self.execute_block(try_block) {
-                    Ok(flow) => Ok(flow),
-                    Err(e) => {
-                        // Bind error message to error_var and execute catch block
-                        let error_msg = format!("{}", e);
-                        self.environment.borrow_mut().define(
-                            error_var.clone(),
-                            Value::String(error_msg),
-                            false,
-                        );
-                        self.execute_block(catch_block)
-                    }
-                }
+            StatementKind::TryCatch { try_block, catch_clauses, finally_block } => {
+                self.execute_try(try_block, catch_clauses, finally_block.as_deref(), None)
             }
             
             // Throw statement - raise an error
-            Statement::Throw(expr) => {
-                let error_val = self.evaluate(expr)?;
-                Err(AgamError::runtime_error(
-                    0, 0,
-                    format!("வீசப்பட்ட பிழை: {}", error_val),
-                ))
+            StatementKind::Throw(expr) => {
+                let Some(expr) = expr else {
+                    return Err(self.handled_errors.last().cloned().unwrap_or_else(|| ops::nothing_to_rethrow(span)));
+                };
+                let value = self.evaluate(expr)?;
+                Err(ops::throw_value(value, span))
             }
 
             // Struct definition - store the struct type
-            Statement::Struct { name, fields } => {
+            StatementKind::Struct { name, fields, methods, binding } => {
                 let field_names: Vec<String> = fields.iter().map(|(n, _)| n.clone()).collect();
+                let methods = methods
+                    .iter()
+                    .map(|method| {
+                        let func = self.function(ops::method_name(name, &method.name), &method.params, method.body.clone())?;
+                        Ok((method.name.clone(), Value::Function(func)))
+                    })
+                    .collect::<Result<_, AgamError>>()?;
                 let struct_def = Value::StructDef {
                     name: name.clone(),
                     field_names,
+                    methods: Rc::new(methods),
                 };
-                self.environment.borrow_mut().define(name.clone(), struct_def, true);
+                self.define(name, *binding, struct_def, true);
                 Ok(ControlFlow::None)
             }
 
             // Enum definition - store the enum type
-            Statement::Enum { name, variants } => {
+            StatementKind::Enum { name, variants, binding } => {
                 let enum_def = Value::EnumDef {
                     name: name.clone(),
                     variants: variants.clone(),
                 };
-                self.environment.borrow_mut().define(name.clone(), enum_def, true);
+                self.define(name, *binding, enum_def, true);
                 Ok(ControlFlow::None)
             }
 
             // Match statement - pattern matching
-            Statement::Match { value, arms } => {
+            StatementKind::Match { value, arms } => {
                 let val = self.evaluate(value)?;
                 
-                for arm in arms {
-                    if self.pattern_matches(&arm.pattern, &val)? {
-                        return self.execute_block(&arm.body);
+                for (index, arm) in arms.iter().enumerate() {
+                    if !self.pattern_matches(&arm.pattern, &val)? {
+                        continue;
+                    }
+                    if let Some(guard) = &arm.guard {
+                        if !self.evaluate(guard)?.is_truthy() {
+                            continue;
+                        }
                     }
+                    return self.branch(index, &arm.body);
                 }
                 
                 // No pattern matched - this is an error
-                Err(AgamError::runtime_error(
-                    0, 0,
-                    format!("எந்த வடிவமும் பொருந்தவில்லை: {}", val),
-                ))
+                Err(ops::no_match(&val, span))
             }
         }
     }
 
     fn execute_block(&mut self, statements: &[Statement]) -> Result<ControlFlow, AgamError> {
         let previous = Rc::clone(&self.environment);
-        self.environment = Rc::new(RefCell::new(Environment::with_parent(previous.clone())));
+        // A resuming generator carries on where it stopped, with the same variables
+        let (environment, start) = match self.resume.pop_if(|resume| matches!(resume, Resume::Block { .. })) {
+            Some(Resume::Block { environment, index }) => (environment, Some(index)),
+            _ => (Rc::new(RefCell::new(Environment::with_parent(previous.clone()))), None),
+        };
+        self.environment = environment;
+
+        let result = self.run_statements(statements, start);
+
+        self.environment = previous;
+        result
+    }
 
-        let result = (|| {
-            for stmt in statements {
-                let flow = self.execute_statement(stmt)?;
-                match flow {
-                    ControlFlow::None => {}
-                    _ => return Ok(flow),
+    /// Run a block's statements in order; `resumed_at` is the statement a
+    /// resuming generator stopped at
+    fn run_statements(&mut self, statements: &[Statement], resumed_at: Option<usize>) -> Result<ControlFlow, AgamError> {
+        let mut index = resumed_at.unwrap_or(0);
+        // With nothing left to resume, the statement was the விளை itself
+        let mut resuming = resumed_at.is_some() && !self.resume.is_empty();
+        if resumed_at.is_some() && !resuming {
+            index += 1;
+        }
+
+        while let Some(stmt) = statements.get(index) {
+            let flow = if resuming {
+                resuming = false;
+                self.resume_statement(stmt)?
+            } else {
+                self.execute_statement(stmt)?
+            };
+            match flow {
+                ControlFlow::None => index += 1,
+                ControlFlow::Yield(value) => {
+                    self.resume.push(Resume::Block { environment: Rc::clone(&self.environment), index });
+                    return Ok(ControlFlow::Yield(value));
                 }
+                flow => return Ok(flow),
             }
-            Ok(ControlFlow::None)
-        })();
+        }
+        Ok(ControlFlow::None)
+    }
 
-        self.environment = previous;
+    /// Run the `index`th branch of a statement, remembering which if it yields
+    fn branch(&mut self, index: usize, body: &[Statement]) -> Result<ControlFlow, AgamError> {
+        let flow = self.execute_block(body)?;
+        if let ControlFlow::Yield(_) = flow {
+            self.resume.push(Resume::Branch(index));
+        }
+        Ok(flow)
+    }
+
+    /// Carry on with a statement a resuming generator stopped inside
+    fn resume_statement(&mut self, stmt: &Statement) -> Result<ControlFlow, AgamError> {
+        let span = stmt.span;
+        match (&stmt.kind, self.resume.pop()) {
+            (StatementKind::If { then_branch, elif_branches, else_branch, .. }, Some(Resume::Branch(index))) => {
+                let body = match index {
+                    0 => then_branch,
+                    i if i <= elif_branches.len() => &elif_branches[i - 1].1,
+                    _ => else_branch.as_ref().expect("else branch"),
+                };
+                self.branch(index, body)
+            }
+            (StatementKind::Match { arms, .. }, Some(Resume::Branch(index))) => self.branch(index, &arms[index].body),
+            (StatementKind::While { condition, body }, Some(block)) => {
+                // A loop records nothing of its own; the block goes back
+                self.resume.push(block);
+                self.execute_while(condition, body, true, span)
+            }
+            (StatementKind::For { variables, iterable, body, bindings }, Some(Resume::For(items))) => {
+                self.execute_for(variables, iterable, body, bindings, Some(items), span)
+            }
+            (StatementKind::TryCatch { try_block, catch_clauses, finally_block }, Some(Resume::Try)) => {
+                self.execute_try(try_block, catch_clauses, finally_block.as_deref(), None)
+            }
+            (StatementKind::TryCatch { try_block, catch_clauses, finally_block }, Some(Resume::Catch(index, error))) => {
+                self.execute_try(try_block, catch_clauses, finally_block.as_deref(), Some((index, error)))
+            }
+            _ => unreachable!("generator resumed outside the statement it stopped in"),
+        }
+    }
+
+    /// Run a வரை loop; `resuming` enters the body without checking the
+    /// condition first, to carry on a generator that stopped inside it
+    fn execute_while(&mut self, condition: &Expression, body: &[Statement], mut resuming: bool, span: Span) -> Result<ControlFlow, AgamError> {
+        // Counted from the last resume, so a generator may loop for ever
+        let mut iterations = 0usize;
+        loop {
+            if !resuming {
+                // Check iteration limit
+                iterations += 1;
+                if iterations > ops::MAX_LOOP_ITERATIONS {
+                    return Err(ops::loop_limit_error(span));
+                }
+
+                let cond_val = self.evaluate(condition)?;
+                if !cond_val.is_truthy() {
+                    break;
+                }
+            }
+            resuming = false;
+
+            match self.execute_block(body)? {
+                ControlFlow::Break => break,
+                ControlFlow::Continue => continue,
+                ControlFlow::None => {}
+                flow => return Ok(flow),
+            }
+        }
+        Ok(ControlFlow::None)
+    }
+
+    /// Run a முயற்சி statement; `in_catch` resumes a generator that stopped
+    /// inside a பிடி clause
+    fn execute_try(
+        &mut self,
+        try_block: &[Statement],
+        catch_clauses: &[CatchClause],
+        finally_block: Option<&[Statement]>,
+        in_catch: Option<(usize, AgamError)>,
+    ) -> Result<ControlFlow, AgamError> {
+        let result = match in_catch {
+            Some((index, error)) => self.execute_catch(index, &catch_clauses[index], error),
+            None => match self.execute_block(try_block) {
+                Ok(ControlFlow::Yield(value)) => {
+                    self.resume.push(Resume::Try);
+                    return Ok(ControlFlow::Yield(value));
+                }
+                Err(e) => {
+                    // Record the traceback up to this point, then run the
+                    // first clause whose filter matches
+                    let error = self.attach_stack(e);
+                    match catch_clauses.iter().position(|clause| ops::catch_matches(clause, &error)) {
+                        Some(index) => {
+                            let clause = &catch_clauses[index];
+                            self.define(
+                                &clause.error_var,
+                                clause.binding,
+                                Value::Error(Rc::new(error.clone())),
+                                false,
+                            );
+                            self.execute_catch(index, clause, error)
+                        }
+                        None => Err(error),
+                    }
+                }
+                result => result,
+            },
+        };
+        // The generator is only pausing, so the finally block waits
+        if let Ok(ControlFlow::Yield(_)) = result {
+            return result;
+        }
+
+        // The finally block always runs; its own error or control
+        // flow replaces the pending outcome
+        if let Some(finally_block) = finally_block {
+            match self.execute_block(finally_block)? {
+                ControlFlow::None => {}
+                flow => return Ok(flow),
+            }
+        }
+        result
+    }
+
+    /// Run the `index`th பிடி clause while it handles `error`
+    fn execute_catch(&mut self, index: usize, clause: &CatchClause, error: AgamError) -> Result<ControlFlow, AgamError> {
+        self.handled_errors.push(error);
+        let result = self.execute_block(&clause.body);
+        let error = self.handled_errors.pop().expect("handled error");
+        if let Ok(ControlFlow::Yield(_)) = result {
+            self.resume.push(Resume::Catch(index, error));
+        }
         result
     }
 
     fn evaluate(&mut self, expr: &Expression) -> Result<Value, AgamError> {
-        match expr {
-            Expression::Number(n) => Ok(Value::Number(*n)),
-            Expression::String(s) => Ok(Value::String(s.clone())),
+        let span = expr.span;
+        match &expr.kind {
+            ExpressionKind::Integer(n) => Ok(Value::Integer(n.clone())),
+            ExpressionKind::Number(n) => Ok(Value::Number(*n)),
+            ExpressionKind::String(s) => Ok(Value::String(s.clone())),
             
             // Interpolated f-string: f"Hello {name}!"
-            Expression::FString { parts } => {
+            ExpressionKind::FString { parts } => {
                 let mut result = String::new();
                 for part in parts {
                     match part {
@@ -400,22 +761,12 @@ impl Evaluator {
                 Ok(Value::String(result))
             }
             
-            Expression::Boolean(b) => Ok(Value::Boolean(*b)),
-            Expression::Null => Ok(Value::Null),
+            ExpressionKind::Boolean(b) => Ok(Value::Boolean(*b)),
+            ExpressionKind::Null => Ok(Value::Null),
 
-            Expression::Identifier(name) => {
-                self.environment
-                    .borrow()
-                    .get(name)
-                    .ok_or_else(|| {
-                        AgamError::runtime_error(
-                            0, 0,
-                            format!("வரையறுக்கப்படாத மாறி '{}'", name),
-                        )
-                    })
-            }
+            ExpressionKind::Identifier { name, binding } => self.variable(name, *binding, span),
 
-            Expression::Binary { left, operator, right } => {
+            ExpressionKind::Binary { left, operator, right } => {
                 let left_val = self.evaluate(left)?;
                 
                 // Short-circuit for logical operators
@@ -436,395 +787,279 @@ impl Evaluator {
                 }
 
                 let right_val = self.evaluate(right)?;
-                self.apply_binary_op(operator, &left_val, &right_val)
+                ops::binary_op(operator, &left_val, &right_val, span)
             }
 
-            Expression::Unary { operator, operand } => {
+            ExpressionKind::Unary { operator, operand } => {
                 let val = self.evaluate(operand)?;
-                match operator {
-                    UnaryOp::Negate => match val {
-                        Value::Number(n) => Ok(Value::Number(-n)),
-                        _ => Err(AgamError::runtime_error(
-                            0, 0,
-                            format!("'{}' எதிர்மறை செய்ய இயலாது", val.type_name()),
-                        )),
-                    },
-                    UnaryOp::Not => Ok(Value::Boolean(!val.is_truthy())),
-                }
+                ops::unary_op(operator, val, span)
             }
 
-            Expression::Call { callee, arguments } => {
-                let callee_val = self.evaluate(callee)?;
-                
-                let args: Vec<Value> = arguments
+            ExpressionKind::Call { callee, arguments, keywords } => self.call(callee, arguments, keywords, span),
+
+            ExpressionKind::List(elements) => {
+                let values: Vec<Value> = elements
                     .iter()
-                    .map(|arg| self.evaluate(arg))
+                    .map(|e| self.evaluate(e))
                     .collect::<Result<Vec<_>, _>>()?;
-
-                self.call_function(callee_val, args)
+                Ok(Value::List(Rc::new(RefCell::new(values))))
             }
 
-            Expression::List(elements) => {
+            ExpressionKind::Tuple(elements) => {
                 let values: Vec<Value> = elements
                     .iter()
                     .map(|e| self.evaluate(e))
                     .collect::<Result<Vec<_>, _>>()?;
-                Ok(Value::List(Rc::new(RefCell::new(values))))
+                Ok(Value::Tuple(values.into()))
             }
 
-            Expression::Dict(pairs) => {
+            ExpressionKind::Dict(pairs) => {
                 let mut map = HashMap::new();
                 for (key, value) in pairs {
-                    let k = match self.evaluate(key)? {
-                        Value::String(s) => s,
-                        v => v.to_string(),
-                    };
+                    let k = ops::dict_key(&self.evaluate(key)?);
                     let v = self.evaluate(value)?;
                     map.insert(k, v);
                 }
                 Ok(Value::Dict(Rc::new(RefCell::new(map))))
             }
 
-            Expression::Index { object, index } => {
+            ExpressionKind::ListComprehension { element, clauses } => {
+                let result = Value::List(Rc::new(RefCell::new(Vec::new())));
+                self.comprehension(clauses, element, None, &result, span)?;
+                Ok(result)
+            }
+
+            ExpressionKind::DictComprehension { key, value, clauses } => {
+                let result = Value::Dict(Rc::new(RefCell::new(HashMap::new())));
+                self.comprehension(clauses, key, Some(value), &result, span)?;
+                Ok(result)
+            }
+
+            ExpressionKind::Index { object, index } => {
                 let obj = self.evaluate(object)?;
                 let idx = self.evaluate(index)?;
+                ops::index_get(&obj, &idx, span)
+            }
 
-                match (&obj, &idx) {
-                    (Value::List(list), Value::Number(n)) => {
-                        let i = *n as i64;
-                        let list = list.borrow();
-                        let len = list.len() as i64;
-                        
-                        let actual_idx = if i < 0 { len + i } else { i };
-                        
-                        if actual_idx < 0 || actual_idx >= len {
-                            return Err(AgamError::runtime_error(
-                                0, 0,
-                                format!("குறியீட்டு {} வரம்பிற்கு வெளியே", n),
-                            ));
-                        }
-                        Ok(list[actual_idx as usize].clone())
-                    }
-                    (Value::String(s), Value::Number(n)) => {
-                        let i = *n as i64;
-                        let chars: Vec<char> = s.chars().collect();
-                        let len = chars.len() as i64;
-                        
-                        let actual_idx = if i < 0 { len + i } else { i };
-                        
-                        if actual_idx < 0 || actual_idx >= len {
-                            return Err(AgamError::runtime_error(
-                                0, 0,
-                                format!("குறியீட்டு {} வரம்பிற்கு வெளியே", n),
-                            ));
-                        }
-                        Ok(Value::String(chars[actual_idx as usize].to_string()))
-                    }
-                    (Value::Dict(dict), _) => {
-                        let key = match &idx {
-                            Value::String(s) => s.clone(),
-                            v => v.to_string(),
-                        };
-                        dict.borrow()
-                            .get(&key)
-                            .cloned()
-                            .ok_or_else(|| {
-                                AgamError::runtime_error(
-                                    0, 0,
-                                    format!("சாவி '{}' காணப்படவில்லை", key),
-                                )
-                            })
-                    }
-                    _ => Err(AgamError::runtime_error(
-                        0, 0,
-                        format!("'{}' வகையை குறியீட்டு செய்ய இயலாது", obj.type_name()),
-                    )),
-                }
+            // Slice: list[1:3], text[::-1]
+            ExpressionKind::Slice { object, bounds } => {
+                let obj = self.evaluate(object)?;
+                let [start, end, step] = self.slice_bounds(bounds)?;
+                ops::slice_get(&obj, &start, &end, &step, span)
             }
 
-            Expression::Grouping(inner) => self.evaluate(inner),
+            ExpressionKind::Grouping(inner) => self.evaluate(inner),
 
-            Expression::Assignment { name, value } => {
-                let val = self.evaluate(value)?;
-                self.environment
-                    .borrow_mut()
-                    .assign(name, val.clone())
-                    .map_err(|msg| AgamError::runtime_error(0, 0, msg))?;
+            ExpressionKind::Assignment { name, operator, value, binding } => {
+                let val = match operator {
+                    // x += value: combine with the current value
+                    Some(operator) => {
+                        let current = self.variable(name, *binding, span)?;
+                        let val = self.evaluate(value)?;
+                        ops::binary_op(operator, &current, &val, span)?
+                    }
+                    None => self.evaluate(value)?,
+                };
+                self.assign(name, *binding, val.clone(), span)?;
                 Ok(val)
             }
 
-            Expression::MemberAccess { object, member } => {
+            ExpressionKind::MemberAccess { object, member } => {
                 let obj = self.evaluate(object)?;
-                match obj {
-                    Value::Struct { name: _, fields } => {
-                        fields.borrow().get(member).cloned().ok_or_else(|| {
-                            AgamError::runtime_error(
-                                0, 0,
-                                format!("புலம் '{}' கிடைக்கவில்லை", member),
-                            )
-                        })
-                    }
-                    Value::EnumDef { name: enum_name, variants } => {
-                        if variants.contains(member) {
-                            Ok(Value::EnumVariant {
-                                enum_name,
-                                variant: member.clone(),
-                            })
-                        } else {
-                            Err(AgamError::runtime_error(
-                                0, 0,
-                                format!("மாறுபாடு '{}' கிடைக்கவில்லை", member),
-                            ))
-                        }
-                    }
-                    // Module namespace access: module.function
-                    Value::Module { name: _, exports } => {
-                        exports.borrow().get(member).cloned().ok_or_else(|| {
-                            AgamError::runtime_error(
-                                0, 0,
-                                format!("'{}' கூறில் கிடைக்கவில்லை", member),
-                            )
-                        })
-                    }
-                    _ => Err(AgamError::runtime_error(
-                        0, 0,
-                        format!("'{}' வகையில் புலம் அணுக இயலாது", obj.type_name()),
-                    )),
-                }
+                ops::member_get(obj, member, span)
             }
 
-            Expression::StructInit { name, arguments } => {
+            ExpressionKind::StructInit { name, arguments } => {
                 // Look up the struct definition
                 let struct_def = self.environment
                     .borrow()
                     .get(name)
                     .ok_or_else(|| {
-                        AgamError::runtime_error(
-                            0, 0,
+                        AgamError::runtime_error_at(
+                            span,
                             format!("கட்டமைப்பு '{}' வரையறுக்கப்படவில்லை", name),
                         )
                     })?;
 
                 match struct_def {
-                    Value::StructDef { name: struct_name, field_names } => {
-                        if arguments.len() != field_names.len() {
-                            return Err(AgamError::runtime_error(
-                                0, 0,
-                                format!(
-                                    "கட்டமைப்பு '{}' {} புலங்களை எதிர்பார்க்கிறது, {} கொடுக்கப்பட்டது",
-                                    struct_name, field_names.len(), arguments.len()
-                                ),
-                            ));
-                        }
-
-                        let mut fields = std::collections::HashMap::new();
-                        for (field_name, arg) in field_names.iter().zip(arguments.iter()) {
-                            let val = self.evaluate(arg)?;
-                            fields.insert(field_name.clone(), val);
-                        }
-
-                        Ok(Value::Struct {
-                            name: struct_name,
-                            fields: Rc::new(RefCell::new(fields)),
-                        })
+                    Value::StructDef { name: struct_name, field_names, methods } => {
+                        let args = arguments
+                            .iter()
+                            .map(|arg| self.evaluate(arg))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        ops::construct_struct(struct_name, &field_names, &methods, args, span)
                     }
-                    _ => Err(AgamError::runtime_error(
-                        0, 0,
+                    _ => Err(AgamError::runtime_error_at(
+                        span,
                         format!("'{}' ஒரு கட்டமைப்பு அல்ல", name),
                     )),
                 }
             }
 
             // Index assignment: list[0] = value, dict["key"] = value
-            Expression::IndexAssignment { object, index, value } => {
+            ExpressionKind::IndexAssignment { object, index, operator, value } => {
                 let obj = self.evaluate(object)?;
                 let idx = self.evaluate(index)?;
+                let val = match operator {
+                    Some(operator) => {
+                        let current = ops::index_get(&obj, &idx, span)?;
+                        let val = self.evaluate(value)?;
+                        ops::binary_op(operator, &current, &val, span)?
+                    }
+                    None => self.evaluate(value)?,
+                };
+                ops::index_set(&obj, &idx, val.clone(), span)?;
+                Ok(val)
+            }
+
+            // Slice assignment: list[1:3] = values
+            ExpressionKind::SliceAssignment { object, bounds, value } => {
+                let obj = self.evaluate(object)?;
+                let [start, end, step] = self.slice_bounds(bounds)?;
                 let val = self.evaluate(value)?;
+                ops::slice_set(&obj, &start, &end, &step, &val, span)?;
+                Ok(val)
+            }
 
-                match (&obj, &idx) {
-                    (Value::List(list), Value::Number(n)) => {
-                        let i = *n as i64;
-                        let mut list_ref = list.borrow_mut();
-                        let len = list_ref.len() as i64;
-                        
-                        let actual_idx = if i < 0 { len + i } else { i };
-                        
-                        if actual_idx < 0 || actual_idx >= len {
-                            return Err(AgamError::runtime_error(
-                                0, 0,
-                                format!("குறியீட்டு {} வரம்பிற்கு வெளியே", n),
-                            ));
-                        }
-                        list_ref[actual_idx as usize] = val.clone();
-                        Ok(val)
+            // Member assignment: struct.field = value
+            ExpressionKind::MemberAssignment { object, member, operator, value } => {
+                let obj = self.evaluate(object)?;
+                let val = match operator {
+                    Some(operator) => {
+                        let current = ops::member_get(obj.clone(), member, span)?;
+                        let val = self.evaluate(value)?;
+                        ops::binary_op(operator, &current, &val, span)?
                     }
-                    (Value::Dict(dict), _) => {
-                        let key = match &idx {
-                            Value::String(s) => s.clone(),
-                            v => v.to_string(),
-                        };
-                        dict.borrow_mut().insert(key, val.clone());
-                        Ok(val)
+                    None => self.evaluate(value)?,
+                };
+                ops::member_set(&obj, member, val.clone(), span)?;
+                Ok(val)
+            }
+
+            // Lambda/anonymous function: செயலி(x): x * 2 or (x) => x * 2
+            ExpressionKind::Lambda { params, body } => {
+                let body = match body {
+                    // An expression body becomes a return statement
+                    LambdaBody::Expression(expr) => {
+                        vec![Statement::new(StatementKind::Return(Some((**expr).clone())), expr.span)]
                     }
-                    _ => Err(AgamError::runtime_error(
-                        0, 0,
-                        format!("'{}' வகையை குறியீட்டு ஒதுக்க இயலாது", obj.type_name()),
-                    )),
-                }
+                    LambdaBody::Block(statements) => statements.clone(),
+                };
+                let func = self.function("<lambda>".to_string(), params, body)?;
+                Ok(Value::Function(func))
             }
 
-            // Member assignment: struct.field = value
-            Expression::MemberAssignment { object, member, value } => {
-                // Evaluate the struct object - this returns a Value::Struct with Rc<RefCell> fields
-                // Since fields use Rc<RefCell>, modifications will persist in the original struct
-                let obj = self.evaluate(object)?;
+            // Match expression: the value of the first arm that matches
+            ExpressionKind::Match { value, arms } => {
                 let val = self.evaluate(value)?;
-                
-                match obj {
-                    Value::Struct { name: _, fields } => {
-                        if !fields.borrow().contains_key(member) {
-                            return Err(AgamError::runtime_error(
-                                0, 0,
-                                format!("புலம் '{}' கிடைக்கவில்லை", member),
-                            ));
+
+                for arm in arms {
+                    // Each arm binds its pattern in an environment of its own
+                    let previous = Rc::clone(&self.environment);
+                    self.environment = Rc::new(RefCell::new(Environment::with_parent(previous.clone())));
+
+                    let result = (|| {
+                        if !self.pattern_matches(&arm.pattern, &val)? {
+                            return Ok(None);
                         }
-                        fields.borrow_mut().insert(member.clone(), val.clone());
-                        Ok(val)
+                        if let Some(guard) = &arm.guard {
+                            if !self.evaluate(guard)?.is_truthy() {
+                                return Ok(None);
+                            }
+                        }
+                        self.evaluate(&arm.value).map(Some)
+                    })();
+
+                    self.environment = previous;
+                    if let Some(result) = result? {
+                        return Ok(result);
                     }
-                    _ => Err(AgamError::runtime_error(
-                        0, 0,
-                        format!("'{}' வகையில் புலம் ஒதுக்க இயலாது", obj.type_name()),
-                    )),
                 }
-            }
 
-            // Lambda/anonymous function: செயலி(x): x * 2 or (x) => x * 2
-            Expression::Lambda { params, body } => {
-                // Convert lambda to a function value
-                // We wrap the body expression in a return statement
-                let body_stmt = vec![Statement::Return(Some((**body).clone()))];
-                
-                let func = AgamFunction::new(
-                    "<lambda>".to_string(),
-                    params.clone(),
-                    body_stmt,
-                    Rc::clone(&self.environment),
-                );
-                
-                Ok(Value::Function(func))
+                Err(ops::no_match(&val, span))
             }
         }
     }
 
-    fn apply_binary_op(
-        &self,
-        op: &BinaryOp,
-        left: &Value,
-        right: &Value,
+    /// Evaluate a call expression's callee and arguments, then call it
+    fn call(
+        &mut self,
+        callee: &Expression,
+        arguments: &[Expression],
+        keywords: &[(String, Expression)],
+        span: Span,
     ) -> Result<Value, AgamError> {
-        match (op, left, right) {
-            // Arithmetic
-            (BinaryOp::Add, Value::Number(a), Value::Number(b)) => Ok(Value::Number(a + b)),
-            (BinaryOp::Subtract, Value::Number(a), Value::Number(b)) => Ok(Value::Number(a - b)),
-            (BinaryOp::Multiply, Value::Number(a), Value::Number(b)) => Ok(Value::Number(a * b)),
-            (BinaryOp::Divide, Value::Number(a), Value::Number(b)) => {
-                if *b == 0.0 {
-                    return Err(AgamError::runtime_error(0, 0, "பூஜ்ஜியத்தால் வகுக்க இயலாது".to_string()));
-                }
-                Ok(Value::Number(a / b))
-            }
-            (BinaryOp::Modulo, Value::Number(a), Value::Number(b)) => {
-                if *b == 0.0 {
-                    return Err(AgamError::runtime_error(0, 0, "பூஜ்ஜியத்தால் வகுக்க இயலாது".to_string()));
-                }
-                Ok(Value::Number(a % b))
-            }
-
-            // String concatenation
-            (BinaryOp::Add, Value::String(a), Value::String(b)) => {
-                Ok(Value::String(format!("{}{}", a, b)))
-            }
-            (BinaryOp::Add, Value::String(a), b) => {
-                Ok(Value::String(format!("{}{}", a, b)))
-            }
-            (BinaryOp::Add, a, Value::String(b)) => {
-                Ok(Value::String(format!("{}{}", a, b)))
-            }
-
-            // String repetition
-            (BinaryOp::Multiply, Value::String(s), Value::Number(n)) => {
-                let count = *n as usize;
-                Ok(Value::String(s.repeat(count)))
-            }
-
-            // Comparison
-            (BinaryOp::Equal, a, b) => Ok(Value::Boolean(a == b)),
-            (BinaryOp::NotEqual, a, b) => Ok(Value::Boolean(a != b)),
-            (BinaryOp::Less, Value::Number(a), Value::Number(b)) => Ok(Value::Boolean(a < b)),
-            (BinaryOp::Greater, Value::Number(a), Value::Number(b)) => Ok(Value::Boolean(a > b)),
-            (BinaryOp::LessEqual, Value::Number(a), Value::Number(b)) => Ok(Value::Boolean(a <= b)),
-            (BinaryOp::GreaterEqual, Value::Number(a), Value::Number(b)) => Ok(Value::Boolean(a >= b)),
-
-            // String comparison
-            (BinaryOp::Less, Value::String(a), Value::String(b)) => Ok(Value::Boolean(a < b)),
-            (BinaryOp::Greater, Value::String(a), Value::String(b)) => Ok(Value::Boolean(a > b)),
-            (BinaryOp::LessEqual, Value::String(a), Value::String(b)) => Ok(Value::Boolean(a <= b)),
-            (BinaryOp::GreaterEqual, Value::String(a), Value::String(b)) => Ok(Value::Boolean(a >= b)),
-
-            _ => Err(AgamError::runtime_error(
-                0, 0,
-                format!(
-                    "'{}' மற்றும் '{}' வகைகளுக்கு '{}' செயல்படாது",
-                    left.type_name(),
-                    right.type_name(),
-                    op
-                ),
-            )),
-        }
+        let callee = self.evaluate(callee)?;
+        let args = arguments
+            .iter()
+            .map(|arg| self.evaluate(arg))
+            .collect::<Result<Vec<_>, _>>()?;
+        let keywords = keywords
+            .iter()
+            .map(|(name, arg)| Ok((name.clone(), self.evaluate(arg)?)))
+            .collect::<Result<Vec<_>, AgamError>>()?;
+        self.call_function(callee, args, keywords, 0, span)
+    }
+
+    /// Create a function value, evaluating its parameters' defaults here
+    fn function(&mut self, name: String, params: &Parameters, body: Vec<Statement>) -> Result<AgamFunction, AgamError> {
+        let defaults = params
+            .defaults
+            .iter()
+            .map(|default| self.evaluate(default))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(AgamFunction::new(
+            name,
+            Rc::new(params.signature.clone()),
+            defaults.into(),
+            body.into(),
+            Rc::clone(&self.environment),
+            self.current_file.clone(),
+            params.is_generator,
+        ))
     }
 
-    fn call_function(&mut self, callee: Value, args: Vec<Value>) -> Result<Value, AgamError> {
+    /// Call `callee`; the first `bound` arguments were supplied by the call
+    /// itself (a method's receiver)
+    fn call_function(
+        &mut self,
+        callee: Value,
+        args: Vec<Value>,
+        keywords: Vec<(String, Value)>,
+        bound: usize,
+        span: Span,
+    ) -> Result<Value, AgamError> {
         // Check recursion depth limit
-        if self.call_depth >= MAX_RECURSION_DEPTH {
-            return Err(AgamError::runtime_error(
-                0, 0,
-                format!(
-                    "அதிகபட்ச மறுநிகழ்வு ஆழம் ({}) அடைந்தது - முடிவிலா மறுநிகழ்வு இருக்கலாம்",
-                    MAX_RECURSION_DEPTH
-                ),
-            ));
+        if self.call_stack.len() >= ops::MAX_RECURSION_DEPTH {
+            return Err(ops::recursion_limit_error(span));
         }
 
         match callee {
             Value::Function(func) => {
-                if args.len() != func.params.len() {
-                    return Err(AgamError::runtime_error(
-                        0, 0,
-                        format!(
-                            "'{}' செயலுக்கு {} அளவுருக்கள் தேவை, {} கொடுக்கப்பட்டது",
-                            func.name,
-                            func.params.len(),
-                            args.len()
-                        ),
-                    ));
-                }
+                let args = ops::bind_arguments(&func.name, &func.signature, &func.defaults, bound, args, keywords, span)?;
 
                 // Create new environment with closure as parent
                 let previous = Rc::clone(&self.environment);
                 let func_env = Rc::new(RefCell::new(Environment::with_parent(Rc::clone(&func.closure))));
 
                 // Bind parameters
-                for (param, arg) in func.params.iter().zip(args) {
-                    func_env.borrow_mut().define(param.clone(), arg, false);
+                for (slot, arg) in args.into_iter().enumerate() {
+                    func_env.borrow_mut().define_slot(slot, arg, false);
+                }
+
+                // A generator's body waits until its first item is asked for
+                if func.is_generator {
+                    let generator = Generator { function: func, environment: func_env, suspended: Some(Vec::new()) };
+                    return Ok(Value::Iterator(Rc::new(RefCell::new(Iter::Generator(Box::new(generator))))));
                 }
 
                 self.environment = func_env;
-                self.call_depth += 1;
+                self.push_frame(&func.name, func.file.clone(), span);
 
                 let result = self.execute_block(&func.body);
 
-                self.call_depth -= 1;
                 self.environment = previous;
+                let result = self.pop_frame(result);
 
                 match result? {
                     ControlFlow::Return(v) => Ok(v),
@@ -833,52 +1068,82 @@ impl Evaluator {
             }
 
             Value::NativeFunction(func) => {
-                if let Some(arity) = func.arity {
-                    if args.len() != arity {
-                        return Err(AgamError::runtime_error(
-                            0, 0,
-                            format!(
-                                "'{}' செயலுக்கு {} அளவுருக்கள் தேவை, {} கொடுக்கப்பட்டது",
-                                func.name,
-                                arity,
-                                args.len()
-                            ),
-                        ));
-                    }
-                }
-
-                (func.function)(&args)
-                    .map_err(|msg| AgamError::runtime_error(0, 0, msg))
+                ops::check_native_keywords(&func, &keywords, span)?;
+                ops::call_native(&func, &args, span, self)
             }
 
             // Struct instantiation via constructor call: StructName(arg1, arg2, ...)
-            Value::StructDef { name: struct_name, field_names } => {
-                if args.len() != field_names.len() {
-                    return Err(AgamError::runtime_error(
-                        0, 0,
-                        format!(
-                            "கட்டமைப்பு '{}' {} புலங்களை எதிர்பார்க்கிறது, {} கொடுக்கப்பட்டது",
-                            struct_name, field_names.len(), args.len()
-                        ),
-                    ));
-                }
+            Value::StructDef { name: struct_name, field_names, methods } => {
+                let args = ops::constructor_arguments(&struct_name, &field_names, args, keywords, span)?;
+                ops::construct_struct(struct_name, &field_names, &methods, args, span)
+            }
 
-                let mut fields = std::collections::HashMap::new();
-                for (field_name, arg) in field_names.iter().zip(args.into_iter()) {
-                    fields.insert(field_name.clone(), arg);
-                }
+            Value::VariantConstructor { enum_name, variant, fields } => {
+                let args = ops::constructor_arguments(&variant, &fields, args, keywords, span)?;
+                ops::construct_variant(enum_name, variant, &fields, args, span)
+            }
+
+            // Method call: the receiver goes first
+            Value::BoundMethod { receiver, method } => {
+                let mut args = args;
+                args.insert(0, *receiver);
+                self.call_function(*method, args, keywords, bound + 1, span)
+            }
+
+            _ => Err(ops::not_callable(&callee, span)),
+        }
+    }
+
+    /// Run a generator's body from where it stopped until its next விளை
+    fn resume_generator(&mut self, generator: &mut Generator, span: Span) -> Result<Option<Value>, AgamError> {
+        let Some(position) = generator.suspended.take() else {
+            return Ok(None);
+        };
+        if self.call_stack.len() >= ops::MAX_RECURSION_DEPTH {
+            return Err(ops::recursion_limit_error(span));
+        }
+
+        let previous = std::mem::replace(&mut self.environment, Rc::clone(&generator.environment));
+        self.resume = position;
+        self.push_frame(&generator.function.name, generator.function.file.clone(), span);
+
+        let result = self.execute_block(&generator.function.body);
 
-                Ok(Value::Struct {
-                    name: struct_name,
-                    fields: Rc::new(RefCell::new(fields)),
-                })
+        self.environment = previous;
+        let position = std::mem::take(&mut self.resume);
+        // Finished, by returning or failing, unless it yielded
+        match self.pop_frame(result)? {
+            ControlFlow::Yield(value) => {
+                generator.suspended = Some(position);
+                Ok(Some(value))
             }
+            _ => Ok(None),
+        }
+    }
+
+    /// Read a variable through its resolved binding
+    fn variable(&self, name: &str, binding: Binding, span: Span) -> Result<Value, AgamError> {
+        let value = match binding {
+            Binding::Global => self.environment.borrow().get_global(name),
+            Binding::Local { depth, slot } => self.environment.borrow().get_at(depth, slot),
+        };
+        value.ok_or_else(|| {
+            AgamError::runtime_error_at(
+                span,
+                format!("வரையறுக்கப்படாத மாறி '{}'", name),
+            )
+        })
+    }
 
-            _ => Err(AgamError::runtime_error(
-                0, 0,
-                format!("'{}' வகையை செயல்படுத்த இயலாது", callee.type_name()),
-            )),
+    /// Evaluate slice bounds, with null for the omitted ones
+    fn slice_bounds(&mut self, bounds: &SliceBounds) -> Result<[Value; 3], AgamError> {
+        let mut values = [Value::Null, Value::Null, Value::Null];
+        for (value, part) in values.iter_mut().zip(bounds.parts()) {
+            if let Some(expr) = part {
+                *value = self.evaluate(expr)?;
+            }
         }
+        Ok(values)
     }
 
     fn pattern_matches(&mut self, pattern: &crate::parser::ast::Pattern, value: &Value) -> Result<bool, AgamError> {
@@ -889,36 +1154,42 @@ impl Evaluator {
             
             Pattern::Literal(expr) => {
                 let lit_val = self.evaluate(expr)?;
-                Ok(self.values_equal(&lit_val, value))
+                Ok(ops::values_equal(&lit_val, value))
             }
             
-            Pattern::Variable(name) => {
+            Pattern::Variable { name, binding } => {
                 // Bind the value to the variable
-                self.environment.borrow_mut().define(name.clone(), value.clone(), false);
+                self.define(name, *binding, value.clone(), false);
                 Ok(true)
             }
             
-            Pattern::EnumVariant(enum_name, variant_name) => {
-                match value {
-                    Value::EnumVariant { enum_name: val_enum, variant: val_variant } => {
-                        Ok(enum_name == val_enum && variant_name == val_variant)
+            // Destructuring: check the shape, then match each part
+            _ => {
+                let (shape, parts) = ops::pattern_parts(pattern).expect("destructuring pattern");
+                let values = match ops::destructure(&shape, value) {
+                    Some(values) => values,
+                    None => return Ok(false),
+                };
+                for (part, value) in parts.into_iter().zip(&values) {
+                    if !self.pattern_matches(part, value)? {
+                        return Ok(false);
                     }
-                    _ => Ok(false),
                 }
+                Ok(true)
             }
         }
     }
 
-    fn values_equal(&self, left: &Value, right: &Value) -> bool {
-        match (left, right) {
-            (Value::Number(a), Value::Number(b)) => (a - b).abs() < f64::EPSILON,
-            (Value::String(a), Value::String(b)) => a == b,
-            (Value::Boolean(a), Value::Boolean(b)) => a == b,
-            (Value::Null, Value::Null) => true,
-            (Value::EnumVariant { enum_name: e1, variant: v1 }, 
-             Value::EnumVariant { enum_name: e2, variant: v2 }) => e1 == e2 && v1 == v2,
-            _ => false,
-        }
+}
+
+impl Caller for Evaluator {
+    fn call(&mut self, function: &Value, args: Vec<Value>, span: Span) -> Result<Value, AgamError> {
+        self.call_function(function.clone(), args, Vec::new(), 0, span)
+    }
+
+    fn resume(&mut self, generator: &mut dyn Any, span: Span) -> Result<Option<Value>, AgamError> {
+        let generator = generator.downcast_mut::<Generator>().expect("generator made by the evaluator");
+        self.resume_generator(generator, span)
     }
 }
 
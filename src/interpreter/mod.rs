@@ -4,28 +4,75 @@
 
 pub mod evaluator;
 pub mod builtin;
+pub mod ops;
 
 pub use evaluator::Evaluator;
 
-use crate::parser::Program;
+use crate::parser::{Program, Span};
 use crate::error::AgamError;
-use crate::types::Value;
+use crate::types::{Caller, Value};
+use crate::vm::Vm;
+
+/// Execution engine used by the interpreter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// Walk the AST directly (reference implementation)
+    #[default]
+    TreeWalker,
+    /// Compile to bytecode and run it on the VM
+    Vm,
+}
+
+enum Engine {
+    TreeWalker(Evaluator),
+    Vm(Vm),
+}
 
 /// Convenience struct for the interpreter
 pub struct Interpreter {
-    evaluator: Evaluator,
+    engine: Engine,
 }
 
 impl Interpreter {
     pub fn new() -> Self {
-        Interpreter {
-            evaluator: Evaluator::new(),
+        Self::with_backend(Backend::default())
+    }
+
+    /// Create an interpreter running on the given backend
+    pub fn with_backend(backend: Backend) -> Self {
+        let engine = match backend {
+            Backend::TreeWalker => Engine::TreeWalker(Evaluator::new()),
+            Backend::Vm => Engine::Vm(Vm::new()),
+        };
+        Interpreter { engine }
+    }
+
+    /// Set the path of the program being run, shown in tracebacks
+    pub fn set_file(&mut self, path: &str) {
+        match &mut self.engine {
+            Engine::TreeWalker(evaluator) => evaluator.set_file(path),
+            Engine::Vm(vm) => vm.set_file(path),
         }
     }
 
     /// Execute a parsed program
     pub fn execute(&mut self, program: &Program) -> Result<Value, AgamError> {
-        self.evaluator.execute(program)
+        match &mut self.engine {
+            Engine::TreeWalker(evaluator) => evaluator.execute(program),
+            Engine::Vm(vm) => vm.execute(program),
+        }
+    }
+
+    /// Call the global function `name` with no arguments, as if called at
+    /// `span`, after the program defining it has been executed
+    pub fn call(&mut self, name: &str, span: Span) -> Result<Value, AgamError> {
+        let (function, caller): (Option<Value>, &mut dyn Caller) = match &mut self.engine {
+            Engine::TreeWalker(evaluator) => (evaluator.global(name), evaluator),
+            Engine::Vm(vm) => (vm.global(name), vm),
+        };
+        let function = function
+            .ok_or_else(|| AgamError::runtime_error_at(span, format!("வரையறுக்கப்படாத மாறி '{}'", name)))?;
+        caller.call(&function, Vec::new(), span)
     }
 }
 
@@ -0,0 +1,1192 @@
+//! Value operations shared by the tree-walker and the bytecode VM
+//!
+//! Both backends call these so that programs behave the same on either
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer as _;
+use num_traits::{FromPrimitive, Signed, ToPrimitive, Zero};
+
+use crate::error::AgamError;
+use crate::interpreter::builtin::create_builtins;
+use crate::parser::ast::{BinaryOp, CatchClause, Pattern, Program, Signature, Span, UnaryOp};
+use crate::resolver::Resolver;
+use crate::types::{error_kind_tamil, range_len, Caller, Environment, Integer, Iter, NativeFunction, NativeImpl, Value};
+
+/// Maximum recursion depth to prevent stack overflow
+pub const MAX_RECURSION_DEPTH: usize = 1000;
+
+/// Maximum loop iterations to prevent infinite loops
+pub const MAX_LOOP_ITERATIONS: usize = 10_000_000;
+
+/// Largest integer `<<` and `**` may produce, in bits; anything bigger
+/// would exhaust memory rather than fail cleanly
+pub const MAX_INTEGER_BITS: u64 = 1 << 26;
+
+/// Longest string `*` may build, in bytes
+pub const MAX_STRING_LENGTH: usize = 1 << 30;
+
+/// Name shown in tracebacks for code outside any function
+pub const TOP_LEVEL_NAME: &str = "<நிரல்>";
+
+thread_local! {
+    /// Lines printed while `capture_output` runs, kept instead of written out
+    static CAPTURED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Print a line of program output, as அச்சிடு does
+pub fn print_line(line: &str) {
+    CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+        Some(output) => {
+            output.push_str(line);
+            output.push('\n');
+        }
+        None => println!("{}", line),
+    });
+}
+
+/// Run `f`, returning what the program printed meanwhile instead of
+/// writing it to standard output
+pub fn capture_output<T>(f: impl FnOnce() -> T) -> (T, String) {
+    let outer = CAPTURED.with(|captured| captured.replace(Some(String::new())));
+    let result = f();
+    let output = CAPTURED.with(|captured| captured.replace(outer)).unwrap_or_default();
+    (result, output)
+}
+
+/// Name shown in tracebacks while a module runs
+pub fn module_frame_name(module: &str) -> String {
+    format!("<கூறு {}>", module)
+}
+
+/// Error for a call past `MAX_RECURSION_DEPTH`
+pub fn recursion_limit_error(span: Span) -> AgamError {
+    AgamError::runtime_error_at(
+        span,
+        format!(
+            "அதிகபட்ச மறுநிகழ்வு ஆழம் ({}) அடைந்தது - முடிவிலா மறுநிகழ்வு இருக்கலாம்",
+            MAX_RECURSION_DEPTH
+        ),
+    )
+}
+
+/// Error for a `வரை` or `ஒவ்வொரு` loop running past `MAX_LOOP_ITERATIONS`
+pub fn loop_limit_error(span: Span) -> AgamError {
+    AgamError::runtime_error_at(
+        span,
+        format!(
+            "அதிகபட்ச வளைய மீளமுறை ({}) அடைந்தது - முடிவிலா வளையம் இருக்கலாம்",
+            MAX_LOOP_ITERATIONS
+        ),
+    )
+}
+
+/// Apply a (non short-circuit) binary operator
+pub fn binary_op(op: &BinaryOp, left: &Value, right: &Value, span: Span) -> Result<Value, AgamError> {
+    // Integers stay exact; anything involving a decimal is done in floats
+    if let (Value::Integer(a), Value::Integer(b)) = (left, right) {
+        if let Some(result) = integer_op(op, a, b, span) {
+            return result;
+        }
+    }
+    if let (Some(a), Some(b)) = (left.as_f64(), right.as_f64()) {
+        if let Some(result) = float_op(op, a, b, span) {
+            return result;
+        }
+    }
+
+    match (op, left, right) {
+        // Membership
+        (BinaryOp::In, item, Value::List(list)) => {
+            Ok(Value::Boolean(list.borrow().iter().any(|v| v == item)))
+        }
+        (BinaryOp::In, item, Value::Tuple(items)) => {
+            Ok(Value::Boolean(items.iter().any(|v| v == item)))
+        }
+        (BinaryOp::In, item, Value::Range { start, end, step }) => {
+            let found = item.as_i64().is_some_and(|n| {
+                let (n, start, step) = (n as i128, *start as i128, *step as i128);
+                let in_bounds = if step > 0 { n >= start && n < *end as i128 } else { n <= start && n > *end as i128 };
+                in_bounds && (n - start) % step == 0
+            });
+            Ok(Value::Boolean(found))
+        }
+        (BinaryOp::In, key, Value::Dict(dict)) => {
+            Ok(Value::Boolean(dict.borrow().contains_key(&dict_key(key))))
+        }
+        (BinaryOp::In, Value::String(sub), Value::String(s)) => {
+            Ok(Value::Boolean(s.contains(sub.as_str())))
+        }
+
+        // String concatenation
+        (BinaryOp::Add, Value::String(a), Value::String(b)) => {
+            Ok(Value::String(format!("{}{}", a, b)))
+        }
+        (BinaryOp::Add, Value::String(a), b) => {
+            Ok(Value::String(format!("{}{}", a, b)))
+        }
+        (BinaryOp::Add, a, Value::String(b)) => {
+            Ok(Value::String(format!("{}{}", a, b)))
+        }
+
+        // String repetition
+        (BinaryOp::Multiply, Value::String(s), Value::Integer(n)) => {
+            if n.is_negative() || s.is_empty() {
+                return Ok(Value::String(String::new()));
+            }
+            match n.to_usize().filter(|count| s.len().saturating_mul(*count) <= MAX_STRING_LENGTH) {
+                Some(count) => Ok(Value::String(s.repeat(count))),
+                None => Err(AgamError::runtime_error_at(
+                    span,
+                    format!("சரம் மிக நீளமாகும்: {} முறை திரும்பச் செய்ய இயலாது", n),
+                )),
+            }
+        }
+
+        // Comparison
+        (BinaryOp::Equal, a, b) => Ok(Value::Boolean(a == b)),
+        (BinaryOp::NotEqual, a, b) => Ok(Value::Boolean(a != b)),
+
+        // String comparison
+        (BinaryOp::Less, Value::String(a), Value::String(b)) => Ok(Value::Boolean(a < b)),
+        (BinaryOp::Greater, Value::String(a), Value::String(b)) => Ok(Value::Boolean(a > b)),
+        (BinaryOp::LessEqual, Value::String(a), Value::String(b)) => Ok(Value::Boolean(a <= b)),
+        (BinaryOp::GreaterEqual, Value::String(a), Value::String(b)) => Ok(Value::Boolean(a >= b)),
+
+        _ => Err(AgamError::runtime_error_at(
+            span,
+            format!(
+                "'{}' மற்றும் '{}' வகைகளுக்கு '{}' செயல்படாது",
+                left.type_name(),
+                right.type_name(),
+                op
+            ),
+        )),
+    }
+}
+
+fn division_by_zero(span: Span) -> AgamError {
+    AgamError::runtime_error_at(span, "பூஜ்ஜியத்தால் வகுக்க இயலாது".to_string())
+}
+
+fn integer_too_large(span: Span) -> AgamError {
+    AgamError::runtime_error_at(
+        span,
+        format!("முழுஎண் முடிவு மிகப் பெரியது ({} பிட்களுக்கு மேல்)", MAX_INTEGER_BITS),
+    )
+}
+
+/// Exact integer arithmetic; `None` for operators that produce a decimal
+/// (`/`, or `**` with a negative exponent) or do not apply to numbers
+fn integer_op(op: &BinaryOp, a: &Integer, b: &Integer, span: Span) -> Option<Result<Value, AgamError>> {
+    if let (Integer::Small(x), Integer::Small(y)) = (a, b) {
+        if let Some(value) = small_integer_op(op, *x, *y) {
+            return Some(Ok(value));
+        }
+        // Only arithmetic that overflowed or failed needs the exact version
+        if !matches!(
+            op,
+            BinaryOp::Add | BinaryOp::Subtract | BinaryOp::Multiply | BinaryOp::Modulo
+                | BinaryOp::FloorDivide | BinaryOp::Power | BinaryOp::ShiftLeft | BinaryOp::ShiftRight
+        ) {
+            return None;
+        }
+    }
+    let (a, b) = (a.big(), b.big());
+    let (a, b) = (a.as_ref(), b.as_ref());
+    let value = match op {
+        BinaryOp::Add => Value::int(a + b),
+        BinaryOp::Subtract => Value::int(a - b),
+        BinaryOp::Multiply => Value::int(a * b),
+        BinaryOp::Modulo | BinaryOp::FloorDivide if b.is_zero() => {
+            return Some(Err(division_by_zero(span)))
+        }
+        BinaryOp::Modulo => Value::int(a.mod_floor(b)),
+        BinaryOp::FloorDivide => Value::int(a.div_floor(b)),
+        BinaryOp::Power if b.is_negative() => return None,
+        // 0, 1 and -1 stay small whatever the exponent
+        BinaryOp::Power if a.magnitude() <= &BigUint::from(1u8) => {
+            let exponent = if b.is_zero() { 0 } else if b.is_even() { 2 } else { 1 };
+            Value::int(a.pow(exponent))
+        }
+        BinaryOp::Power => match b.to_u32().filter(|exponent| (a.bits() - 1) * *exponent as u64 <= MAX_INTEGER_BITS) {
+            Some(exponent) => Value::int(a.pow(exponent)),
+            None => return Some(Err(integer_too_large(span))),
+        },
+        BinaryOp::BitAnd => Value::int(a & b),
+        BinaryOp::BitOr => Value::int(a | b),
+        BinaryOp::BitXor => Value::int(a ^ b),
+        BinaryOp::ShiftLeft | BinaryOp::ShiftRight if b.is_negative() => {
+            return Some(Err(AgamError::runtime_error_at(
+                span,
+                format!("தவறான நகர்வு எண்ணிக்கை: {}", b),
+            )));
+        }
+        BinaryOp::ShiftLeft if a.is_zero() => Value::int(0),
+        BinaryOp::ShiftLeft => match b.to_u64().filter(|count| a.bits() + count <= MAX_INTEGER_BITS) {
+            Some(count) => Value::int(a << count),
+            None => return Some(Err(integer_too_large(span))),
+        },
+        // Shifting right by more bits than `a` has leaves its sign
+        BinaryOp::ShiftRight => match b.to_u64().filter(|count| *count < a.bits()) {
+            Some(count) => Value::int(a >> count),
+            None => Value::int(if a.is_negative() { -1 } else { 0 }),
+        },
+        BinaryOp::Less => Value::Boolean(a < b),
+        BinaryOp::Greater => Value::Boolean(a > b),
+        BinaryOp::LessEqual => Value::Boolean(a <= b),
+        BinaryOp::GreaterEqual => Value::Boolean(a >= b),
+        _ => return None,
+    };
+    Some(Ok(value))
+}
+
+/// `integer_op` on machine words; `None` when the result overflows `i64`,
+/// needs an error or is not an integer operation
+fn small_integer_op(op: &BinaryOp, a: i64, b: i64) -> Option<Value> {
+    let value = match op {
+        BinaryOp::Add => Value::int(a.checked_add(b)?),
+        BinaryOp::Subtract => Value::int(a.checked_sub(b)?),
+        BinaryOp::Multiply => Value::int(a.checked_mul(b)?),
+        BinaryOp::Modulo if b != 0 => Value::int(a.checked_rem_euclid(b).map(|r| if r != 0 && b < 0 { r + b } else { r })?),
+        BinaryOp::FloorDivide if b != 0 => Value::int(a.checked_div(b).map(|q| if a % b != 0 && (a < 0) != (b < 0) { q - 1 } else { q })?),
+        BinaryOp::Power if b >= 0 => Value::int(a.checked_pow(u32::try_from(b).ok()?)?),
+        BinaryOp::BitAnd => Value::int(a & b),
+        BinaryOp::BitOr => Value::int(a | b),
+        BinaryOp::BitXor => Value::int(a ^ b),
+        BinaryOp::ShiftLeft if (0..64).contains(&b) && (a << b) >> b == a => Value::int(a << b),
+        BinaryOp::ShiftRight if b >= 0 => Value::int(a >> b.min(63)),
+        BinaryOp::Less => Value::Boolean(a < b),
+        BinaryOp::Greater => Value::Boolean(a > b),
+        BinaryOp::LessEqual => Value::Boolean(a <= b),
+        BinaryOp::GreaterEqual => Value::Boolean(a >= b),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Decimal arithmetic; `%` and `//` round towards negative infinity like
+/// their integer versions
+fn float_op(op: &BinaryOp, a: f64, b: f64, span: Span) -> Option<Result<Value, AgamError>> {
+    let value = match op {
+        BinaryOp::Add => Value::Number(a + b),
+        BinaryOp::Subtract => Value::Number(a - b),
+        BinaryOp::Multiply => Value::Number(a * b),
+        BinaryOp::Divide | BinaryOp::Modulo | BinaryOp::FloorDivide if b == 0.0 => {
+            return Some(Err(division_by_zero(span)))
+        }
+        BinaryOp::Divide => Value::Number(a / b),
+        BinaryOp::Modulo => {
+            let r = a % b;
+            Value::Number(if r != 0.0 && (r < 0.0) != (b < 0.0) { r + b } else { r })
+        }
+        BinaryOp::FloorDivide => Value::Number((a / b).floor()),
+        BinaryOp::Power => Value::Number(a.powf(b)),
+        BinaryOp::Less => Value::Boolean(a < b),
+        BinaryOp::Greater => Value::Boolean(a > b),
+        BinaryOp::LessEqual => Value::Boolean(a <= b),
+        BinaryOp::GreaterEqual => Value::Boolean(a >= b),
+        _ => return None,
+    };
+    Some(Ok(value))
+}
+
+/// Apply a unary operator
+pub fn unary_op(op: &UnaryOp, value: Value, span: Span) -> Result<Value, AgamError> {
+    match op {
+        UnaryOp::Negate => match value {
+            Value::Integer(n) => Ok(Value::Integer(-n)),
+            Value::Number(n) => Ok(Value::Number(-n)),
+            _ => Err(AgamError::runtime_error_at(
+                span,
+                format!("'{}' எதிர்மறை செய்ய இயலாது", value.type_name()),
+            )),
+        },
+        UnaryOp::Not => Ok(Value::Boolean(!value.is_truthy())),
+        UnaryOp::BitNot => match value {
+            Value::Integer(n) => Ok(Value::Integer(!n)),
+            _ => Err(AgamError::runtime_error_at(
+                span,
+                format!("'{}' வகைக்கு '~' செயல்படாது", value.type_name()),
+            )),
+        },
+    }
+}
+
+/// Dictionary key for an index value. A whole decimal gives the same key
+/// as the equal integer, so `d[6 / 2]` finds `d[3]`
+pub fn dict_key(index: &Value) -> String {
+    match index {
+        Value::String(s) => s.clone(),
+        Value::Number(n) if n.fract() == 0.0 => match BigInt::from_f64(*n) {
+            Some(whole) => whole.to_string(),
+            None => index.to_string(),
+        },
+        v => v.to_string(),
+    }
+}
+
+/// Resolve a possibly negative integer index against a length
+fn resolve_index(index: &Value, len: usize, span: Span) -> Result<usize, AgamError> {
+    let Value::Integer(n) = index else {
+        return Err(AgamError::runtime_error_at(
+            span,
+            format!("குறியீடு முழுஎண்ணாக இருக்க வேண்டும், '{}' கொடுக்கப்பட்டது", index.type_name()),
+        ));
+    };
+    let len = len as i64;
+    let actual_idx = match n.to_i64() {
+        Some(i) if i < 0 => len + i,
+        Some(i) => i,
+        None => -1,
+    };
+    if actual_idx < 0 || actual_idx >= len {
+        return Err(AgamError::runtime_error_at(
+            span,
+            format!("குறியீட்டு {} வரம்பிற்கு வெளியே", n),
+        ));
+    }
+    Ok(actual_idx as usize)
+}
+
+/// Read `object[index]`
+pub fn index_get(object: &Value, index: &Value, span: Span) -> Result<Value, AgamError> {
+    match (object, index) {
+        (Value::List(list), _) => {
+            let list = list.borrow();
+            let i = resolve_index(index, list.len(), span)?;
+            Ok(list[i].clone())
+        }
+        (Value::Tuple(items), _) => {
+            let i = resolve_index(index, items.len(), span)?;
+            Ok(items[i].clone())
+        }
+        (Value::Range { start, end, step }, _) => {
+            let i = resolve_index(index, range_len(*start, *end, *step) as usize, span)?;
+            Ok(Value::int(*start as i128 + i as i128 * *step as i128))
+        }
+        (Value::String(s), _) => {
+            let chars: Vec<char> = s.chars().collect();
+            let i = resolve_index(index, chars.len(), span)?;
+            Ok(Value::String(chars[i].to_string()))
+        }
+        (Value::Dict(dict), _) => {
+            let key = dict_key(index);
+            dict.borrow()
+                .get(&key)
+                .cloned()
+                .ok_or_else(|| {
+                    AgamError::runtime_error_at(
+                        span,
+                        format!("சாவி '{}' காணப்படவில்லை", key),
+                    )
+                })
+        }
+        _ => Err(AgamError::runtime_error_at(
+            span,
+            format!("'{}' வகையை குறியீட்டு செய்ய இயலாது", object.type_name()),
+        )),
+    }
+}
+
+/// Write `object[index] = value`
+pub fn index_set(object: &Value, index: &Value, value: Value, span: Span) -> Result<(), AgamError> {
+    match (object, index) {
+        (Value::List(list), _) => {
+            let mut list_ref = list.borrow_mut();
+            let i = resolve_index(index, list_ref.len(), span)?;
+            list_ref[i] = value;
+            Ok(())
+        }
+        (Value::Dict(dict), _) => {
+            dict.borrow_mut().insert(dict_key(index), value);
+            Ok(())
+        }
+        (Value::Tuple(_), _) => Err(AgamError::runtime_error_at(
+            span,
+            "தொகுதியை மாற்ற இயலாது".to_string(),
+        )),
+        _ => Err(AgamError::runtime_error_at(
+            span,
+            format!("'{}' வகையை குறியீட்டு ஒதுக்க இயலாது", object.type_name()),
+        )),
+    }
+}
+
+/// Resolve `[start:end:step]` against a sequence of `len` items. Omitted
+/// (null) bounds cover the whole sequence in the step's direction; negative
+/// bounds count from the end and out-of-range bounds are clamped
+fn slice_bounds(start: &Value, end: &Value, step: &Value, len: usize, span: Span) -> Result<(i64, i64, i64), AgamError> {
+    let bound = |value: &Value| match value {
+        Value::Null => Ok(None),
+        // Bounds past the i64 range are clamped like any out-of-range bound
+        Value::Integer(n) => Ok(Some(n.to_i64().unwrap_or(if n.is_negative() { i64::MIN / 2 } else { i64::MAX / 2 }))),
+        other => Err(AgamError::runtime_error_at(
+            span,
+            format!("துண்டு எல்லை முழுஎண்ணாக இருக்க வேண்டும், '{}' கொடுக்கப்பட்டது", other.type_name()),
+        )),
+    };
+    let step = bound(step)?.unwrap_or(1);
+    if step == 0 {
+        return Err(AgamError::runtime_error_at(span, "துண்டு படி பூஜ்ஜியமாக இருக்க முடியாது".to_string()));
+    }
+
+    let len = len as i64;
+    let (lower, upper) = if step > 0 { (0, len) } else { (-1, len - 1) };
+    let clamp = |i: i64| {
+        if i < 0 {
+            (i + len).max(lower)
+        } else {
+            i.min(upper)
+        }
+    };
+    let start = bound(start)?.map(clamp).unwrap_or(if step > 0 { lower } else { upper });
+    let end = bound(end)?.map(clamp).unwrap_or(if step > 0 { upper } else { lower });
+    Ok((start, end, step))
+}
+
+/// Indices selected by resolved slice bounds
+fn slice_indices((start, end, step): (i64, i64, i64)) -> Vec<usize> {
+    let mut indices = Vec::new();
+    let mut i = start;
+    while (step > 0 && i < end) || (step < 0 && i > end) {
+        indices.push(i as usize);
+        i += step;
+    }
+    indices
+}
+
+/// Read `object[start:end:step]` from a list, tuple, range or string (by character)
+pub fn slice_get(object: &Value, start: &Value, end: &Value, step: &Value, span: Span) -> Result<Value, AgamError> {
+    match object {
+        // A range slices to another range, without producing its numbers
+        Value::Range { start: from, end: to, step: by } => {
+            let (i, j, k) = slice_bounds(start, end, step, range_len(*from, *to, *by) as usize, span)?;
+            let (from, by) = (*from as i128, *by as i128);
+            let (first, last, step) = (from + i as i128 * by, from + j as i128 * by, by * k as i128);
+            match (i64::try_from(first), i64::try_from(last), i64::try_from(step)) {
+                (Ok(start), Ok(end), Ok(step)) => Ok(Value::Range { start, end, step }),
+                // Near the ends of the i64 range the bounds may not fit:
+                // stop just past the last number instead
+                _ => {
+                    let count = range_len(i, j, k) as i128;
+                    if count == 0 {
+                        return Ok(Value::Range { start: 0, end: 0, step: 1 });
+                    }
+                    let step = if count > 1 { step } else { 1 };
+                    let last = first + (count - 1) * step;
+                    match (i64::try_from(step), i64::try_from(last + step.signum())) {
+                        (Ok(step), Ok(end)) => Ok(Value::Range { start: first as i64, end, step }),
+                        _ => Err(AgamError::runtime_error_at(span, "துண்டு வரம்பின் எல்லைகள் மிகப் பெரியவை".to_string())),
+                    }
+                }
+            }
+        }
+        Value::List(list) => {
+            let list = list.borrow();
+            let indices = slice_indices(slice_bounds(start, end, step, list.len(), span)?);
+            let items = indices.into_iter().map(|i| list[i].clone()).collect();
+            Ok(Value::List(Rc::new(RefCell::new(items))))
+        }
+        Value::Tuple(items) => {
+            let indices = slice_indices(slice_bounds(start, end, step, items.len(), span)?);
+            Ok(Value::Tuple(indices.into_iter().map(|i| items[i].clone()).collect()))
+        }
+        Value::String(s) => {
+            let chars: Vec<char> = s.chars().collect();
+            let indices = slice_indices(slice_bounds(start, end, step, chars.len(), span)?);
+            Ok(Value::String(indices.into_iter().map(|i| chars[i]).collect()))
+        }
+        _ => Err(AgamError::runtime_error_at(
+            span,
+            format!("'{}' வகையை துண்டாக்க இயலாது", object.type_name()),
+        )),
+    }
+}
+
+/// Write `list[start:end:step] = values`. A plain slice may change the
+/// list's length; a slice with a step needs exactly one value per index
+pub fn slice_set(object: &Value, start: &Value, end: &Value, step: &Value, value: &Value, span: Span) -> Result<(), AgamError> {
+    let list = match object {
+        Value::List(list) => list,
+        _ => {
+            return Err(AgamError::runtime_error_at(
+                span,
+                format!("'{}' வகையை துண்டு ஒதுக்க இயலாது", object.type_name()),
+            ))
+        }
+    };
+    // Copy first: the values may be the list itself
+    let values = match value {
+        Value::List(values) => values.borrow().clone(),
+        other => {
+            return Err(AgamError::runtime_error_at(
+                span,
+                format!("துண்டுக்கு பட்டியல் தேவை, '{}' கொடுக்கப்பட்டது", other.type_name()),
+            ))
+        }
+    };
+
+    let mut list = list.borrow_mut();
+    let bounds = slice_bounds(start, end, step, list.len(), span)?;
+    if bounds.2 == 1 {
+        let (from, to) = (bounds.0 as usize, bounds.1.max(bounds.0) as usize);
+        list.splice(from..to, values);
+        return Ok(());
+    }
+
+    let indices = slice_indices(bounds);
+    if values.len() != indices.len() {
+        return Err(AgamError::runtime_error_at(
+            span,
+            format!("துண்டுக்கு {} மதிப்புகள் தேவை, {} கொடுக்கப்பட்டது", indices.len(), values.len()),
+        ));
+    }
+    for (i, value) in indices.into_iter().zip(values) {
+        list[i] = value;
+    }
+    Ok(())
+}
+
+/// Read `object.member`
+pub fn member_get(object: Value, member: &str, span: Span) -> Result<Value, AgamError> {
+    match object {
+        // Fields first, then methods bound to the instance
+        Value::Struct { ref fields, ref methods, .. } => {
+            if let Some(value) = fields.borrow().get(member) {
+                return Ok(value.clone());
+            }
+            match methods.get(member) {
+                Some(method) => Ok(Value::BoundMethod {
+                    method: Box::new(method.clone()),
+                    receiver: Box::new(object.clone()),
+                }),
+                None => Err(AgamError::runtime_error_at(
+                    span,
+                    format!("புலம் '{}' கிடைக்கவில்லை", member),
+                )),
+            }
+        }
+        // Variants without a payload are values; the others are constructors
+        Value::EnumDef { name: enum_name, variants } => {
+            match variants.into_iter().find(|(v, _)| v == member) {
+                Some((variant, fields)) if fields.is_empty() => Ok(Value::EnumVariant {
+                    enum_name,
+                    variant,
+                    payload: Vec::new(),
+                }),
+                Some((variant, fields)) => Ok(Value::VariantConstructor { enum_name, variant, fields }),
+                None => Err(AgamError::runtime_error_at(
+                    span,
+                    format!("மாறுபாடு '{}' கிடைக்கவில்லை", member),
+                )),
+            }
+        }
+        // Payload fields: shape.radius
+        Value::EnumVariant { payload, .. } => {
+            payload.into_iter().find(|(field, _)| field == member).map(|(_, value)| value).ok_or_else(|| {
+                AgamError::runtime_error_at(
+                    span,
+                    format!("புலம் '{}' கிடைக்கவில்லை", member),
+                )
+            })
+        }
+        // Caught error fields: e.message, e.வகை, ...
+        Value::Error(error) => Value::error_field(&error, member).ok_or_else(|| {
+            AgamError::runtime_error_at(
+                span,
+                format!("பிழையில் புலம் '{}' இல்லை", member),
+            )
+        }),
+        // Module namespace access: module.function
+        Value::Module { name: _, exports } => {
+            exports.borrow().get(member).cloned().ok_or_else(|| {
+                AgamError::runtime_error_at(
+                    span,
+                    format!("'{}' கூறில் கிடைக்கவில்லை", member),
+                )
+            })
+        }
+        _ => Err(AgamError::runtime_error_at(
+            span,
+            format!("'{}' வகையில் புலம் அணுக இயலாது", object.type_name()),
+        )),
+    }
+}
+
+/// Write `object.member = value`
+pub fn member_set(object: &Value, member: &str, value: Value, span: Span) -> Result<(), AgamError> {
+    // Struct fields live behind Rc<RefCell>, so the change is seen by
+    // every reference to the struct
+    match object {
+        Value::Struct { name: _, fields, .. } => {
+            if !fields.borrow().contains_key(member) {
+                return Err(AgamError::runtime_error_at(
+                    span,
+                    format!("புலம் '{}' கிடைக்கவில்லை", member),
+                ));
+            }
+            fields.borrow_mut().insert(member.to_string(), value);
+            Ok(())
+        }
+        _ => Err(AgamError::runtime_error_at(
+            span,
+            format!("'{}' வகையில் புலம் ஒதுக்க இயலாது", object.type_name()),
+        )),
+    }
+}
+
+/// Build a struct instance from its definition and field values
+pub fn construct_struct(
+    struct_name: String,
+    field_names: &[String],
+    methods: &Rc<HashMap<String, Value>>,
+    args: Vec<Value>,
+    span: Span,
+) -> Result<Value, AgamError> {
+    if args.len() != field_names.len() {
+        return Err(AgamError::runtime_error_at(
+            span,
+            format!(
+                "கட்டமைப்பு '{}' {} புலங்களை எதிர்பார்க்கிறது, {} கொடுக்கப்பட்டது",
+                struct_name, field_names.len(), args.len()
+            ),
+        ));
+    }
+
+    let mut fields = HashMap::new();
+    for (field_name, arg) in field_names.iter().zip(args) {
+        fields.insert(field_name.clone(), arg);
+    }
+
+    Ok(Value::Struct {
+        name: struct_name,
+        fields: Rc::new(RefCell::new(fields)),
+        methods: Rc::clone(methods),
+    })
+}
+
+/// Build an enum variant from its constructor and payload values
+pub fn construct_variant(
+    enum_name: String,
+    variant: String,
+    fields: &[String],
+    args: Vec<Value>,
+    span: Span,
+) -> Result<Value, AgamError> {
+    if args.len() != fields.len() {
+        return Err(AgamError::runtime_error_at(
+            span,
+            format!(
+                "மாறுபாடு '{}.{}' {} மதிப்புகளை எதிர்பார்க்கிறது, {} கொடுக்கப்பட்டது",
+                enum_name, variant, fields.len(), args.len()
+            ),
+        ));
+    }
+    Ok(Value::EnumVariant {
+        enum_name,
+        variant,
+        payload: fields.iter().cloned().zip(args).collect(),
+    })
+}
+
+/// Name of a method in tracebacks: `Struct.method`
+pub fn method_name(struct_name: &str, method: &str) -> String {
+    format!("{}.{}", struct_name, method)
+}
+
+/// Match a call's arguments to a function's parameters, returning the values
+/// of its parameter slots. The first `bound` arguments were supplied by the
+/// call itself (a method's receiver) and are not counted in errors
+pub fn bind_arguments(
+    name: &str,
+    signature: &Signature,
+    defaults: &[Value],
+    bound: usize,
+    mut args: Vec<Value>,
+    keywords: Vec<(String, Value)>,
+    span: Span,
+) -> Result<Vec<Value>, AgamError> {
+    let count = signature.params.len();
+    if signature.is_simple() && keywords.is_empty() {
+        if args.len() != count {
+            return Err(arity_error(name, count - bound, args.len() - bound, span));
+        }
+        return Ok(args);
+    }
+
+    let rest = if args.len() > count {
+        if signature.rest.is_none() {
+            return Err(too_many_arguments(name, signature, count - bound, args.len() - bound, span));
+        }
+        args.split_off(count)
+    } else {
+        Vec::new()
+    };
+
+    let mut slots: Vec<Option<Value>> = args.into_iter().map(Some).collect();
+    slots.resize(count, None);
+    let mut extra = HashMap::new();
+    for (keyword, value) in keywords {
+        match signature.params.iter().position(|param| *param == keyword) {
+            Some(i) if slots[i].is_some() => {
+                return Err(AgamError::runtime_error_at(
+                    span,
+                    format!("'{}' செயலுக்கு '{}' அளவுரு இருமுறை கொடுக்கப்பட்டது", name, keyword),
+                ));
+            }
+            Some(i) => slots[i] = Some(value),
+            None if signature.keywords.is_some() => {
+                extra.insert(keyword, value);
+            }
+            None => {
+                return Err(AgamError::runtime_error_at(
+                    span,
+                    format!("'{}' செயலுக்கு '{}' என்ற அளவுரு இல்லை", name, keyword),
+                ));
+            }
+        }
+    }
+
+    let mut values = Vec::with_capacity(signature.slot_count());
+    for (i, slot) in slots.into_iter().enumerate() {
+        match slot {
+            Some(value) => values.push(value),
+            None if i >= signature.required => values.push(defaults[i - signature.required].clone()),
+            None => {
+                return Err(AgamError::runtime_error_at(
+                    span,
+                    format!("'{}' செயலுக்கு '{}' அளவுரு கொடுக்கப்படவில்லை", name, signature.params[i]),
+                ));
+            }
+        }
+    }
+    if signature.rest.is_some() {
+        values.push(Value::List(Rc::new(RefCell::new(rest))));
+    }
+    if signature.keywords.is_some() {
+        values.push(Value::Dict(Rc::new(RefCell::new(extra))));
+    }
+    Ok(values)
+}
+
+/// More positional arguments than a function without `*rest` takes
+fn too_many_arguments(name: &str, signature: &Signature, expected: usize, given: usize, span: Span) -> AgamError {
+    if signature.required == signature.params.len() {
+        return arity_error(name, expected, given, span);
+    }
+    AgamError::runtime_error_at(
+        span,
+        format!("'{}' செயலுக்கு அதிகபட்சம் {} அளவுருக்கள், {} கொடுக்கப்பட்டது", name, expected, given),
+    )
+}
+
+/// Arguments for a constructor, with keyword arguments put in field order
+pub fn constructor_arguments(
+    name: &str,
+    fields: &[String],
+    args: Vec<Value>,
+    keywords: Vec<(String, Value)>,
+    span: Span,
+) -> Result<Vec<Value>, AgamError> {
+    if keywords.is_empty() {
+        return Ok(args);
+    }
+    bind_arguments(name, &Signature::positional(fields.to_vec()), &[], 0, args, keywords, span)
+}
+
+/// Reject keyword arguments passed to a built-in function
+pub fn check_native_keywords(func: &NativeFunction, keywords: &[(String, Value)], span: Span) -> Result<(), AgamError> {
+    if keywords.is_empty() {
+        return Ok(());
+    }
+    Err(AgamError::runtime_error_at(
+        span,
+        format!("'{}' செயல் பெயரிட்ட அளவுருக்களை ஏற்காது", func.name),
+    ))
+}
+
+/// Call a built-in function after checking its arity
+pub fn call_native(func: &NativeFunction, args: &[Value], span: Span, caller: &mut dyn Caller) -> Result<Value, AgamError> {
+    if let Some(arity) = func.arity {
+        if args.len() != arity {
+            return Err(arity_error(&func.name, arity, args.len(), span));
+        }
+    }
+
+    match func.function {
+        NativeImpl::Plain(function) => function(args).map_err(|msg| AgamError::runtime_error_at(span, msg)),
+        NativeImpl::Callback(function) => function(args, caller, span),
+    }
+}
+
+/// Wrong number of arguments passed to a function
+pub fn arity_error(name: &str, expected: usize, given: usize, span: Span) -> AgamError {
+    AgamError::runtime_error_at(
+        span,
+        format!(
+            "'{}' செயலுக்கு {} அளவுருக்கள் தேவை, {} கொடுக்கப்பட்டது",
+            name, expected, given
+        ),
+    )
+}
+
+/// Value that cannot be called
+pub fn not_callable(callee: &Value, span: Span) -> AgamError {
+    AgamError::runtime_error_at(
+        span,
+        format!("'{}' வகையை செயல்படுத்த இயலாது", callee.type_name()),
+    )
+}
+
+/// Start iterating `value` for `ஒவ்வொரு x உள்ள value`: the numbers of a
+/// range, the items of a list or tuple, the letters of a string, the keys
+/// of a dictionary, the rest of an iterator, or what a struct's `மீள்`
+/// method returns or its `அடுத்து` method produces
+pub fn iterate(value: &Value, caller: &mut dyn Caller, span: Span) -> Result<Iter, AgamError> {
+    match value {
+        Value::Range { start, end, step } => Ok(Iter::Range { next: *start, end: *end, step: *step }),
+        Value::List(list) => Ok(Iter::List { list: Rc::clone(list), index: 0, end: list.borrow().len() }),
+        Value::Tuple(items) => Ok(Iter::Items(items.iter().cloned().collect::<Vec<_>>().into_iter())),
+        Value::String(s) => Ok(Iter::Items(
+            s.chars().map(|c| Value::String(c.to_string())).collect::<Vec<_>>().into_iter(),
+        )),
+        Value::Dict(dict) => Ok(Iter::Items(
+            sorted_keys(&dict.borrow()).into_iter().map(Value::String).collect::<Vec<_>>().into_iter(),
+        )),
+        Value::Iterator(iter) => Ok(Iter::Shared(Rc::clone(iter))),
+        Value::Struct { .. } => {
+            // A struct returned by மீள் (often தன்) produces the items itself
+            let iterable = match struct_method(value, &ITER_METHODS) {
+                Some(method) => caller.call(&method, Vec::new(), span)?,
+                None => value.clone(),
+            };
+            if !matches!(iterable, Value::Struct { .. }) {
+                return iterate(&iterable, caller, span);
+            }
+            match struct_method(&iterable, &NEXT_METHODS) {
+                Some(method) => Ok(Iter::Method(method)),
+                None => Err(not_iterable(&iterable, span)),
+            }
+        }
+        _ => Err(not_iterable(value, span)),
+    }
+}
+
+/// The keys of a dictionary in sorted order, so that loops over it
+/// visit them the same way every run
+pub fn sorted_keys(dict: &HashMap<String, Value>) -> Vec<String> {
+    let mut keys: Vec<String> = dict.keys().cloned().collect();
+    keys.sort();
+    keys
+}
+
+/// Method names that make a struct iterable by returning something to iterate
+const ITER_METHODS: [&str; 2] = ["மீள்", "iter"];
+
+/// Method names that make a struct produce its own items, one per call
+const NEXT_METHODS: [&str; 2] = ["அடுத்து", "next"];
+
+/// The first of `names` that `value` has as a method, bound to it
+fn struct_method(value: &Value, names: &[&str]) -> Option<Value> {
+    let Value::Struct { methods, .. } = value else {
+        return None;
+    };
+    let method = names.iter().find_map(|name| methods.get(*name))?;
+    Some(Value::BoundMethod { receiver: Box::new(value.clone()), method: Box::new(method.clone()) })
+}
+
+fn not_iterable(value: &Value, span: Span) -> AgamError {
+    AgamError::runtime_error_at(
+        span,
+        format!("'{}' வகையை மீளமுடியாது", value.type_name()),
+    )
+}
+
+/// All the items of an iteration, for functions that need them together.
+/// Stops with an error rather than running forever on an endless iterator
+pub fn iteration_items(value: &Value, caller: &mut dyn Caller, span: Span) -> Result<Vec<Value>, AgamError> {
+    if let Some(items) = sequence_items(value).map_err(|msg| AgamError::runtime_error_at(span, msg))? {
+        return Ok(items);
+    }
+    let mut iter = iterate(value, caller, span)?;
+    let mut items = Vec::new();
+    while let Some(item) = iter.next(caller, span)? {
+        if items.len() >= MAX_LOOP_ITERATIONS {
+            return Err(loop_limit_error(span));
+        }
+        items.push(item);
+    }
+    Ok(items)
+}
+
+/// Largest range whose numbers are gathered into one list
+pub const MAX_RANGE_SIZE: i64 = 1_000_000;
+
+/// Items of a list, tuple or range, for built-ins that work on a sequence
+/// without calling back into Agam code; `None` for other values
+pub fn sequence_items(value: &Value) -> Result<Option<Vec<Value>>, String> {
+    match value {
+        Value::List(list) => Ok(Some(list.borrow().clone())),
+        Value::Tuple(items) => Ok(Some(items.to_vec())),
+        Value::Range { start, end, step } => {
+            let len = range_len(*start, *end, *step);
+            if len > MAX_RANGE_SIZE {
+                return Err(format!("வரம்பு மிகப் பெரியது ({} எண்கள்; அதிகபட்சம் {})", len, MAX_RANGE_SIZE));
+            }
+            Ok(Some((0..len).map(|i| Value::int(*start as i128 + i as i128 * *step as i128)).collect()))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// The `count` values bound by `மாறி a, b = value` and `a, b = value`,
+/// taken from a tuple or list of exactly that length
+pub fn unpack(value: &Value, count: usize, span: Span) -> Result<Vec<Value>, AgamError> {
+    let items = match value {
+        Value::Tuple(items) => items.to_vec(),
+        Value::List(list) => list.borrow().clone(),
+        _ => {
+            return Err(AgamError::runtime_error_at(
+                span,
+                format!("'{}' வகையைப் பிரிக்க இயலாது", value.type_name()),
+            ))
+        }
+    };
+    if items.len() != count {
+        return Err(AgamError::runtime_error_at(
+            span,
+            format!("{} மதிப்புகளைப் பிரிக்க எதிர்பார்க்கப்பட்டது, {} உள்ளன", count, items.len()),
+        ));
+    }
+    Ok(items)
+}
+
+/// Equality used by literal patterns in பொருத்து
+pub fn values_equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Integer(a), Value::Integer(b)) => a == b,
+        (Value::Number(a), Value::Number(b)) => (a - b).abs() < f64::EPSILON,
+        (Value::Integer(_) | Value::Number(_), Value::Integer(_) | Value::Number(_)) => {
+            left.as_f64() == right.as_f64()
+        }
+        (Value::String(a), Value::String(b)) => a == b,
+        (Value::Boolean(a), Value::Boolean(b)) => a == b,
+        (Value::Null, Value::Null) => true,
+        (Value::EnumVariant { enum_name: e1, variant: v1, payload: p1 },
+         Value::EnumVariant { enum_name: e2, variant: v2, payload: p2 }) => {
+            e1 == e2
+                && v1 == v2
+                && p1.len() == p2.len()
+                && p1.iter().zip(p2).all(|((_, a), (_, b))| values_equal(a, b))
+        }
+        _ => false,
+    }
+}
+
+/// What a destructuring pattern requires of a value before its parts are
+/// matched against the sub-patterns
+#[derive(Debug, Clone)]
+pub enum PatternShape {
+    /// `Enum.Variant`, with the payload length when it is destructured
+    Variant { enum_name: String, variant: String, arity: Option<usize> },
+    /// A list or tuple of exactly `len` items, or at least `len` with a rest pattern
+    List { len: usize, rest: bool },
+    /// A dictionary holding all of these keys
+    Dict(Vec<String>),
+    /// An instance of the named struct with all of these fields
+    Struct { name: String, fields: Vec<String> },
+}
+
+/// Shape and sub-patterns of a destructuring pattern, in the order
+/// `destructure` returns the parts; `None` for simple patterns
+pub fn pattern_parts(pattern: &Pattern) -> Option<(PatternShape, Vec<&Pattern>)> {
+    match pattern {
+        Pattern::Literal(_) | Pattern::Variable { .. } | Pattern::Wildcard => None,
+        Pattern::EnumVariant { enum_name, variant, payload } => Some((
+            PatternShape::Variant {
+                enum_name: enum_name.clone(),
+                variant: variant.clone(),
+                arity: payload.as_ref().map(Vec::len),
+            },
+            payload.iter().flatten().collect(),
+        )),
+        Pattern::List { elements, rest } => Some((
+            PatternShape::List { len: elements.len(), rest: rest.is_some() },
+            elements.iter().chain(rest.as_deref()).collect(),
+        )),
+        Pattern::Dict(entries) => Some((
+            PatternShape::Dict(entries.iter().map(|(key, _)| key.clone()).collect()),
+            entries.iter().map(|(_, p)| p).collect(),
+        )),
+        Pattern::Struct { name, fields } => Some((
+            PatternShape::Struct {
+                name: name.clone(),
+                fields: fields.iter().map(|(field, _)| field.clone()).collect(),
+            },
+            fields.iter().map(|(_, p)| p).collect(),
+        )),
+    }
+}
+
+/// The parts of `value` to match against a pattern's sub-patterns, or
+/// `None` when the value does not have the pattern's shape
+pub fn destructure(shape: &PatternShape, value: &Value) -> Option<Vec<Value>> {
+    match (shape, value) {
+        (PatternShape::Variant { enum_name, variant, arity },
+         Value::EnumVariant { enum_name: e, variant: v, payload }) => {
+            if enum_name != e || variant != v {
+                return None;
+            }
+            match arity {
+                None => Some(Vec::new()),
+                Some(n) if *n == payload.len() => Some(payload.iter().map(|(_, v)| v.clone()).collect()),
+                Some(_) => None,
+            }
+        }
+        (PatternShape::List { len, rest }, Value::List(list)) => sequence_parts(&list.borrow(), *len, *rest),
+        (PatternShape::List { len, rest }, Value::Tuple(items)) => sequence_parts(items, *len, *rest),
+        (PatternShape::Dict(keys), Value::Dict(dict)) => {
+            let dict = dict.borrow();
+            keys.iter().map(|key| dict.get(key).cloned()).collect()
+        }
+        (PatternShape::Struct { name, fields: wanted }, Value::Struct { name: n, fields, .. }) => {
+            if name != n {
+                return None;
+            }
+            let fields = fields.borrow();
+            wanted.iter().map(|field| fields.get(field).cloned()).collect()
+        }
+        _ => None,
+    }
+}
+
+/// The first `len` items of a sequence matched by a list pattern, plus a
+/// list of the remaining ones when the pattern has a rest
+fn sequence_parts(items: &[Value], len: usize, rest: bool) -> Option<Vec<Value>> {
+    if items.len() < len || (!rest && items.len() != len) {
+        return None;
+    }
+    let mut parts = items[..len].to_vec();
+    if rest {
+        parts.push(Value::List(Rc::new(RefCell::new(items[len..].to_vec()))));
+    }
+    Some(parts)
+}
+
+/// Whether a catch clause filter handles `error`: a matching error kind
+/// (Tamil or English), or the struct name of a thrown value
+pub fn catch_filter_matches(filter: &str, error: &AgamError) -> bool {
+    let kind = error.kind();
+    if filter == kind || filter == error_kind_tamil(kind) {
+        return true;
+    }
+    match error {
+        AgamError::Thrown { value, .. } => matches!(&**value, Value::Struct { name, .. } if name == filter),
+        _ => false,
+    }
+}
+
+/// Whether a catch clause handles `error`; clauses without a filter catch everything
+pub fn catch_matches(clause: &CatchClause, error: &AgamError) -> bool {
+    clause
+        .filter
+        .as_deref()
+        .is_none_or(|filter| catch_filter_matches(filter, error))
+}
+
+/// Error raised by `வீசு value`; throwing a caught error raises it again unchanged
+pub fn throw_value(value: Value, span: Span) -> AgamError {
+    match value {
+        Value::Error(error) => (*error).clone(),
+        value => AgamError::thrown_at(span, value),
+    }
+}
+
+/// Error for a bare `வீசு` outside any பிடி block
+pub fn nothing_to_rethrow(span: Span) -> AgamError {
+    AgamError::runtime_error_at(
+        span,
+        "மீண்டும் வீச பிழை இல்லை; தனி 'வீசு' 'பிடி' தொகுதிக்குள் மட்டுமே".to_string(),
+    )
+}
+
+/// Error when no பொருத்து arm matches
+pub fn no_match(value: &Value, span: Span) -> AgamError {
+    AgamError::runtime_error_at(
+        span,
+        format!("எந்த வடிவமும் பொருந்தவில்லை: {}", value),
+    )
+}
+
+/// Path of the file backing `இறக்குமதி module`
+pub fn module_path(module: &str) -> String {
+    format!("{}.agam", module)
+}
+
+/// Read, parse and resolve an imported module. Syntax and compile errors
+/// keep their kind but point at the import
+pub fn parse_module(module: &str, span: Span) -> Result<Program, AgamError> {
+    let Ok(source) = std::fs::read_to_string(module_path(module)) else {
+        return Err(AgamError::runtime_error_at(
+            span,
+            format!("கூறு காணவில்லை: '{}'", module),
+        ));
+    };
+
+    let tokens = crate::lexer::Lexer::tokenize(&source).map_err(|e| {
+        AgamError::lexer_error(span.line, span.column, format!("Module load error: {}", e))
+    })?;
+
+    let mut parser = crate::parser::Parser::new(tokens);
+    let mut program = parser.parse().map_err(|e| {
+        AgamError::parser_error(span.line, span.column, format!("Module parse error: {}", e))
+    })?;
+
+    // Modules start with only the built-ins defined
+    Resolver::new(builtin_environment().borrow().declared())
+        .resolve(&mut program)
+        .map_err(|e| AgamError::compile_error_at(span, format!("Module compile error: {}", e)))?;
+    Ok(program)
+}
+
+/// Fresh global environment for a program or module, holding the built-ins
+pub fn builtin_environment() -> Rc<RefCell<Environment>> {
+    let env = Rc::new(RefCell::new(Environment::new()));
+    for (name, func) in create_builtins() {
+        env.borrow_mut().define(name, Value::NativeFunction(func), true);
+    }
+    env
+}
+
+/// Module namespace value holding everything a module defined
+pub fn module_value(module: &str, module_env: &Rc<RefCell<Environment>>) -> Value {
+    let mut exports = HashMap::new();
+    let module_values: Vec<_> = module_env.borrow().get_all_names();
+    for name in module_values {
+        // Skip builtins (they're already in globals)
+        if let Some(value) = module_env.borrow().get(&name) {
+            if !matches!(value, Value::NativeFunction(_)) {
+                exports.insert(name, value);
+            }
+        }
+    }
+
+    Value::Module {
+        name: module.to_string(),
+        exports: Rc::new(RefCell::new(exports)),
+    }
+}
+
+/// Item named in a selective import
+pub fn module_item(module: &str, module_env: &Rc<RefCell<Environment>>, item: &str, span: Span) -> Result<Value, AgamError> {
+    module_env.borrow().get(item).ok_or_else(|| {
+        AgamError::runtime_error_at(
+            span,
+            format!("'{}' கூறில் '{}' கிடைக்கவில்லை", module, item),
+        )
+    })
+}
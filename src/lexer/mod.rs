@@ -5,8 +5,8 @@
 pub mod token;
 pub mod scanner;
 
-pub use token::{Token, TokenType};
-pub use scanner::Scanner;
+pub use token::{Comment, Span, Token, TokenType};
+pub use scanner::{keywords, Scanner};
 
 /// Convenience struct for the lexer
 pub struct Lexer;
@@ -17,4 +17,11 @@ impl Lexer {
         let mut scanner = Scanner::new(source);
         scanner.scan_tokens()
     }
+
+    /// Tokenize source code, also returning its comments
+    pub fn tokenize_with_comments(source: &str) -> Result<(Vec<Token>, Vec<Comment>), crate::error::AgamError> {
+        let mut scanner = Scanner::new(source);
+        let tokens = scanner.scan_tokens()?;
+        Ok((tokens, scanner.comments().to_vec()))
+    }
 }
@@ -2,18 +2,116 @@
 //! 
 //! Converts source code into a stream of tokens, handling Tamil Unicode characters
 
-use crate::lexer::token::{Token, TokenType};
+use crate::lexer::token::{Comment, Token, TokenType};
 use crate::error::AgamError;
+use num_bigint::BigInt;
+use num_traits::Zero;
 use std::collections::HashMap;
 use std::iter::Peekable;
 use std::str::Chars;
 
+/// Every keyword with its token, Tamil and English spellings alike
+pub fn keywords() -> HashMap<&'static str, TokenType> {
+    let mut keywords = HashMap::new();
+    
+    // Tamil keywords
+    keywords.insert("செயல்", TokenType::Seyal);
+    keywords.insert("மாறி", TokenType::Maari);
+    keywords.insert("மாறாத", TokenType::Maaraadha);
+    keywords.insert("என்றால்", TokenType::Endraal);
+    keywords.insert("இல்லையென்றால்", TokenType::Illayendraal);
+    keywords.insert("இல்லை", TokenType::Illai);
+    keywords.insert("வரை", TokenType::Varai);
+    keywords.insert("ஒவ்வொரு", TokenType::Ovvoru);
+    keywords.insert("உள்ள", TokenType::Ulla);
+    keywords.insert("திரும்பு", TokenType::Thirumbu);
+    keywords.insert("உண்மை", TokenType::Unmai);
+    keywords.insert("பொய்", TokenType::Poi);
+    keywords.insert("இல்லா", TokenType::Illa);
+    keywords.insert("நிறுத்து", TokenType::Niruthu);
+    keywords.insert("தொடர்", TokenType::Thodar);
+    keywords.insert("மற்றும்", TokenType::Matrum);
+    keywords.insert("அல்லது", TokenType::Alladhu);
+    keywords.insert("இல்ல", TokenType::Illamal);
+    
+    // New keywords for modules and error handling
+    keywords.insert("இறக்குமதி", TokenType::Irakkumadhi);
+    keywords.insert("இருந்து", TokenType::Irundhu);
+    keywords.insert("முயற்சி", TokenType::Muyarchi);
+    keywords.insert("பிடி", TokenType::Pidi);
+    keywords.insert("வீசு", TokenType::Veesu);
+    keywords.insert("இறுதியாக", TokenType::Iruthiyaaga);
+    
+    // Built-in functions - only print and input need special keyword handling
+    // நீளம்/len, வகை/type are handled as regular built-in functions
+    keywords.insert("அச்சிடு", TokenType::Achidu);
+    keywords.insert("உள்ளீடு", TokenType::Ulleedu);
+    
+    // English equivalents for bilingual support
+    keywords.insert("fn", TokenType::Seyal);
+    keywords.insert("let", TokenType::Maari);
+    keywords.insert("const", TokenType::Maaraadha);
+    keywords.insert("if", TokenType::Endraal);
+    keywords.insert("elif", TokenType::Illayendraal);
+    keywords.insert("else", TokenType::Illai);
+    keywords.insert("while", TokenType::Varai);
+    keywords.insert("for", TokenType::Ovvoru);
+    keywords.insert("in", TokenType::Ulla);
+    keywords.insert("return", TokenType::Thirumbu);
+    keywords.insert("true", TokenType::Unmai);
+    keywords.insert("false", TokenType::Poi);
+    keywords.insert("null", TokenType::Illa);
+    keywords.insert("break", TokenType::Niruthu);
+    keywords.insert("continue", TokenType::Thodar);
+    keywords.insert("and", TokenType::Matrum);
+    keywords.insert("or", TokenType::Alladhu);
+    keywords.insert("not", TokenType::Illamal);
+    keywords.insert("print", TokenType::Achidu);
+    keywords.insert("input", TokenType::Ulleedu);
+    
+    // English equivalents for new keywords
+    keywords.insert("import", TokenType::Irakkumadhi);
+    keywords.insert("from", TokenType::Irundhu);
+    keywords.insert("try", TokenType::Muyarchi);
+    keywords.insert("catch", TokenType::Pidi);
+    keywords.insert("throw", TokenType::Veesu);
+    keywords.insert("finally", TokenType::Iruthiyaaga);
+    
+    // New keywords for structs, enums, pattern matching
+    keywords.insert("கட்டமைப்பு", TokenType::Kattamaippu);
+    keywords.insert("struct", TokenType::Kattamaippu);
+    keywords.insert("விருப்பம்", TokenType::Viruppam);
+    keywords.insert("enum", TokenType::Viruppam);
+    keywords.insert("பொருத்து", TokenType::Poruthu);
+    keywords.insert("match", TokenType::Poruthu);
+    keywords.insert("_", TokenType::Underscore);
+    
+    // Lambda/anonymous functions
+    keywords.insert("செயலி", TokenType::Seyali);
+    keywords.insert("lambda", TokenType::Seyali);
+
+    // Method receiver
+    keywords.insert("தன்", TokenType::Than);
+    keywords.insert("self", TokenType::Than);
+
+    // Assigning a variable of an enclosing function
+    keywords.insert("வெளிமாறி", TokenType::Velimaari);
+    keywords.insert("nonlocal", TokenType::Velimaari);
+
+    // Generators
+    keywords.insert("விளை", TokenType::Vilai);
+    keywords.insert("yield", TokenType::Vilai);
+
+    keywords
+}
+
 /// The lexical scanner
 pub struct Scanner<'a> {
     #[allow(dead_code)]
     source: &'a str,
     chars: Peekable<Chars<'a>>,
     tokens: Vec<Token>,
+    comments: Vec<Comment>,
     line: usize,
     column: usize,
     start_column: usize,
@@ -24,86 +122,13 @@ pub struct Scanner<'a> {
 
 impl<'a> Scanner<'a> {
     pub fn new(source: &'a str) -> Self {
-        let mut keywords = HashMap::new();
-        
-        // Tamil keywords
-        keywords.insert("செயல்", TokenType::Seyal);
-        keywords.insert("மாறி", TokenType::Maari);
-        keywords.insert("மாறாத", TokenType::Maaraadha);
-        keywords.insert("என்றால்", TokenType::Endraal);
-        keywords.insert("இல்லையென்றால்", TokenType::Illayendraal);
-        keywords.insert("இல்லை", TokenType::Illai);
-        keywords.insert("வரை", TokenType::Varai);
-        keywords.insert("ஒவ்வொரு", TokenType::Ovvoru);
-        keywords.insert("உள்ள", TokenType::Ulla);
-        keywords.insert("திரும்பு", TokenType::Thirumbu);
-        keywords.insert("உண்மை", TokenType::Unmai);
-        keywords.insert("பொய்", TokenType::Poi);
-        keywords.insert("இல்லா", TokenType::Illa);
-        keywords.insert("நிறுத்து", TokenType::Niruthu);
-        keywords.insert("தொடர்", TokenType::Thodar);
-        keywords.insert("மற்றும்", TokenType::Matrum);
-        keywords.insert("அல்லது", TokenType::Alladhu);
-        keywords.insert("இல்ல", TokenType::Illamal);
-        
-        // New keywords for modules and error handling
-        keywords.insert("இறக்குமதி", TokenType::Irakkumadhi);
-        keywords.insert("இருந்து", TokenType::Irundhu);
-        keywords.insert("முயற்சி", TokenType::Muyarchi);
-        keywords.insert("பிடி", TokenType::Pidi);
-        keywords.insert("வீசு", TokenType::Veesu);
-        
-        // Built-in functions - only print and input need special keyword handling
-        // நீளம்/len, வகை/type are handled as regular built-in functions
-        keywords.insert("அச்சிடு", TokenType::Achidu);
-        keywords.insert("உள்ளீடு", TokenType::Ulleedu);
-        
-        // English equivalents for bilingual support
-        keywords.insert("fn", TokenType::Seyal);
-        keywords.insert("let", TokenType::Maari);
-        keywords.insert("const", TokenType::Maaraadha);
-        keywords.insert("if", TokenType::Endraal);
-        keywords.insert("elif", TokenType::Illayendraal);
-        keywords.insert("else", TokenType::Illai);
-        keywords.insert("while", TokenType::Varai);
-        keywords.insert("for", TokenType::Ovvoru);
-        keywords.insert("in", TokenType::Ulla);
-        keywords.insert("return", TokenType::Thirumbu);
-        keywords.insert("true", TokenType::Unmai);
-        keywords.insert("false", TokenType::Poi);
-        keywords.insert("null", TokenType::Illa);
-        keywords.insert("break", TokenType::Niruthu);
-        keywords.insert("continue", TokenType::Thodar);
-        keywords.insert("and", TokenType::Matrum);
-        keywords.insert("or", TokenType::Alladhu);
-        keywords.insert("not", TokenType::Illamal);
-        keywords.insert("print", TokenType::Achidu);
-        keywords.insert("input", TokenType::Ulleedu);
-        
-        // English equivalents for new keywords
-        keywords.insert("import", TokenType::Irakkumadhi);
-        keywords.insert("from", TokenType::Irundhu);
-        keywords.insert("try", TokenType::Muyarchi);
-        keywords.insert("catch", TokenType::Pidi);
-        keywords.insert("throw", TokenType::Veesu);
-        
-        // New keywords for structs, enums, pattern matching
-        keywords.insert("கட்டமைப்பு", TokenType::Kattamaippu);
-        keywords.insert("struct", TokenType::Kattamaippu);
-        keywords.insert("விருப்பம்", TokenType::Viruppam);
-        keywords.insert("enum", TokenType::Viruppam);
-        keywords.insert("பொருத்து", TokenType::Poruthu);
-        keywords.insert("match", TokenType::Poruthu);
-        keywords.insert("_", TokenType::Underscore);
-        
-        // Lambda/anonymous functions
-        keywords.insert("செயலி", TokenType::Seyali);
-        keywords.insert("lambda", TokenType::Seyali);
+        let keywords = keywords();
 
         Scanner {
             source,
             chars: source.chars().peekable(),
             tokens: Vec::new(),
+            comments: Vec::new(),
             line: 1,
             column: 1,
             start_column: 1,
@@ -136,11 +161,17 @@ impl<'a> Scanner<'a> {
         Ok(self.tokens.clone())
     }
 
+    /// Comments skipped so far, in source order
+    pub fn comments(&self) -> &[Comment] {
+        &self.comments
+    }
+
     fn scan_token(&mut self) -> Result<(), AgamError> {
         // Handle indentation at line start
         if self.at_line_start {
             self.handle_indentation()?;
             self.at_line_start = false;
+            self.start_column = self.column;
         }
 
         let c = match self.advance() {
@@ -158,14 +189,46 @@ impl<'a> Scanner<'a> {
             '}' => self.add_token(TokenType::RightBrace, c.to_string()),
             ',' => self.add_token(TokenType::Comma, c.to_string()),
             ':' => self.add_token(TokenType::Colon, c.to_string()),
-            '.' => self.add_token(TokenType::Dot, c.to_string()),
-            '+' => self.add_token(TokenType::Plus, c.to_string()),
-            '-' => self.add_token(TokenType::Minus, c.to_string()),
-            '*' => self.add_token(TokenType::Star, c.to_string()),
-            '/' => self.add_token(TokenType::Slash, c.to_string()),
-            '%' => self.add_token(TokenType::Percent, c.to_string()),
+
+            // Arithmetic operators, optionally followed by '=': x += 1
+            '+' | '-' | '*' | '/' | '%' => {
+                let doubled = (c == '*' || c == '/') && self.match_char(c);
+                let (plain, compound) = match (c, doubled) {
+                    ('+', _) => (TokenType::Plus, TokenType::PlusEqual),
+                    ('-', _) => (TokenType::Minus, TokenType::MinusEqual),
+                    ('*', false) => (TokenType::Star, TokenType::StarEqual),
+                    ('*', true) => (TokenType::StarStar, TokenType::StarStarEqual),
+                    ('/', false) => (TokenType::Slash, TokenType::SlashEqual),
+                    ('/', true) => (TokenType::SlashSlash, TokenType::SlashSlashEqual),
+                    _ => (TokenType::Percent, TokenType::PercentEqual),
+                };
+                let lexeme = if doubled { format!("{}{}", c, c) } else { c.to_string() };
+                if self.match_char('=') {
+                    self.add_token(compound, format!("{}=", lexeme));
+                } else {
+                    self.add_token(plain, lexeme);
+                }
+            }
+            '&' => self.add_token(TokenType::Ampersand, c.to_string()),
+            '|' => self.add_token(TokenType::Pipe, c.to_string()),
+            '^' => self.add_token(TokenType::Caret, c.to_string()),
+            '~' => self.add_token(TokenType::Tilde, c.to_string()),
 
             // Two-character tokens
+            '.' => {
+                if self.match_char('.') {
+                    if !self.match_char('.') {
+                        return Err(AgamError::lexer_error(
+                            self.line,
+                            self.column,
+                            "எதிர்பாராத எழுத்து '..'".to_string(),
+                        ));
+                    }
+                    self.add_token(TokenType::Ellipsis, "...".to_string());
+                } else {
+                    self.add_token(TokenType::Dot, c.to_string());
+                }
+            }
             '=' => {
                 if self.match_char('=') {
                     self.add_token(TokenType::EqualEqual, "==".to_string());
@@ -189,6 +252,8 @@ impl<'a> Scanner<'a> {
             '<' => {
                 if self.match_char('=') {
                     self.add_token(TokenType::LessEqual, "<=".to_string());
+                } else if self.match_char('<') {
+                    self.add_token(TokenType::LessLess, "<<".to_string());
                 } else {
                     self.add_token(TokenType::Less, "<".to_string());
                 }
@@ -196,17 +261,15 @@ impl<'a> Scanner<'a> {
             '>' => {
                 if self.match_char('=') {
                     self.add_token(TokenType::GreaterEqual, ">=".to_string());
+                } else if self.match_char('>') {
+                    self.add_token(TokenType::GreaterGreater, ">>".to_string());
                 } else {
                     self.add_token(TokenType::Greater, ">".to_string());
                 }
             }
 
             // Comments
-            '#' => {
-                while self.peek() != Some('\n') && !self.is_at_end() {
-                    self.advance();
-                }
-            }
+            '#' => self.comment(self.start_column),
 
             // Whitespace
             ' ' | '\t' | '\r' => {}
@@ -265,9 +328,8 @@ impl<'a> Scanner<'a> {
                 }
                 '#' => {
                     // Comment line, skip to end
-                    while self.peek() != Some('\n') && !self.is_at_end() {
-                        self.advance();
-                    }
+                    self.advance();
+                    self.comment(self.column - 1);
                     return Ok(());
                 }
                 _ => break,
@@ -364,15 +426,22 @@ impl<'a> Scanner<'a> {
             }
         }
 
-        let num: f64 = value.parse().map_err(|_| {
+        let invalid = || {
             AgamError::lexer_error(
                 self.line,
                 self.start_column,
                 format!("தவறான எண் '{}'", value),
             )
-        })?;
+        };
 
-        self.add_token(TokenType::Number(num), value);
+        // Literals without a decimal point are integers
+        let token = if value.contains('.') {
+            TokenType::Number(value.parse().map_err(|_| invalid())?)
+        } else {
+            TokenType::Integer(value.parse().map_err(|_| invalid())?)
+        };
+
+        self.add_token(token, value);
         Ok(())
     }
 
@@ -389,7 +458,7 @@ impl<'a> Scanner<'a> {
         }
 
         let num = tamil_to_number(&value)?;
-        self.add_token(TokenType::Number(num), value);
+        self.add_token(TokenType::Integer(num), value);
         Ok(())
     }
 
@@ -453,6 +522,21 @@ impl<'a> Scanner<'a> {
         Ok(())
     }
 
+    /// Skip the rest of a comment whose `#` at `column` was just consumed,
+    /// keeping its text for tools that reproduce the source
+    fn comment(&mut self, column: usize) {
+        let mut text = String::from("#");
+        while let Some(c) = self.peek() {
+            if c == '\n' {
+                break;
+            }
+            text.push(c);
+            self.advance();
+        }
+        let text = text.trim_end().to_string();
+        self.comments.push(Comment { text, line: self.line, column });
+    }
+
     fn advance(&mut self) -> Option<char> {
         let c = self.chars.next();
         if c.is_some() {
@@ -479,12 +563,14 @@ impl<'a> Scanner<'a> {
     }
 
     fn add_token(&mut self, token_type: TokenType, lexeme: String) {
-        self.tokens.push(Token::new(
-            token_type,
-            lexeme,
-            self.line,
-            self.start_column,
-        ));
+        let mut token = Token::new(token_type, lexeme, self.line, self.start_column);
+        // Measure the width actually consumed, so escapes and synthetic
+        // lexemes (strings, NEWLINE, INDENT) still report accurate spans
+        token.length = match token.token_type {
+            TokenType::Indent | TokenType::Dedent => 0,
+            _ => self.column.saturating_sub(self.start_column),
+        };
+        self.tokens.push(token);
     }
 }
 
@@ -512,20 +598,20 @@ fn is_tamil_numeral(c: char) -> bool {
 }
 
 /// Convert Tamil numeral string to f64
-fn tamil_to_number(s: &str) -> Result<f64, AgamError> {
-    let mut result = 0.0;
+fn tamil_to_number(s: &str) -> Result<BigInt, AgamError> {
+    let mut result = BigInt::zero();
     for c in s.chars() {
         let digit = match c {
-            '௦' => 0.0,
-            '௧' => 1.0,
-            '௨' => 2.0,
-            '௩' => 3.0,
-            '௪' => 4.0,
-            '௫' => 5.0,
-            '௬' => 6.0,
-            '௭' => 7.0,
-            '௮' => 8.0,
-            '௯' => 9.0,
+            '௦' => 0,
+            '௧' => 1,
+            '௨' => 2,
+            '௩' => 3,
+            '௪' => 4,
+            '௫' => 5,
+            '௬' => 6,
+            '௭' => 7,
+            '௮' => 8,
+            '௯' => 9,
             _ => {
                 return Err(AgamError::lexer_error(
                     0,
@@ -534,7 +620,7 @@ fn tamil_to_number(s: &str) -> Result<f64, AgamError> {
                 ))
             }
         };
-        result = result * 10.0 + digit;
+        result = result * 10 + digit;
     }
     Ok(result)
 }
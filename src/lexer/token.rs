@@ -4,6 +4,8 @@
 
 use std::fmt;
 
+use num_bigint::BigInt;
+
 /// Token types for the Agam language
 #[derive(Debug, Clone, PartialEq)]
 pub enum TokenType {
@@ -33,6 +35,7 @@ pub enum TokenType {
     Muyarchi,        // முயற்சி - try
     Pidi,            // பிடி - catch
     Veesu,           // வீசு - throw
+    Iruthiyaaga,     // இறுதியாக - finally
     
     // New keywords for structs, enums, pattern matching
     Kattamaippu,     // கட்டமைப்பு - struct
@@ -44,6 +47,15 @@ pub enum TokenType {
     // Lambda/anonymous functions
     Seyali,          // செயலி - lambda
 
+    // Methods
+    Than,            // தன் - self (method receiver)
+
+    // Closures
+    Velimaari,       // வெளிமாறி - nonlocal
+
+    // Generators
+    Vilai,           // விளை - yield
+
     // Built-in functions
     Achidu,          // அச்சிடு - print
     Ulleedu,         // உள்ளீடு - input
@@ -51,7 +63,8 @@ pub enum TokenType {
     Vagai,           // வகை - type
 
     // Literals
-    Number(f64),     // Numbers (எண்)
+    Integer(BigInt), // Whole numbers (முழுஎண்)
+    Number(f64),     // Decimal numbers (தசமஎண்)
     String(String),  // Strings (சரம்)
     FString(String), // Interpolated strings: f"Hello {name}!"
     Identifier(String), // Identifiers
@@ -62,6 +75,21 @@ pub enum TokenType {
     Star,            // *
     Slash,           // /
     Percent,         // %
+    PlusEqual,       // +=
+    MinusEqual,      // -=
+    StarEqual,       // *=
+    SlashEqual,      // /=
+    PercentEqual,    // %=
+    StarStar,        // **
+    SlashSlash,      // //
+    StarStarEqual,   // **=
+    SlashSlashEqual, // //=
+    Ampersand,       // &
+    Pipe,            // |
+    Caret,           // ^
+    Tilde,           // ~
+    LessLess,        // <<
+    GreaterGreater,  // >>
     Equal,           // =
     EqualEqual,      // ==
     NotEqual,        // !=
@@ -80,6 +108,7 @@ pub enum TokenType {
     Comma,           // ,
     Colon,           // :
     Dot,             // .
+    Ellipsis,        // ...
 
     // Special tokens
     Newline,         // Line ending
@@ -114,10 +143,12 @@ impl fmt::Display for TokenType {
             TokenType::Muyarchi => write!(f, "முயற்சி"),
             TokenType::Pidi => write!(f, "பிடி"),
             TokenType::Veesu => write!(f, "வீசு"),
+            TokenType::Iruthiyaaga => write!(f, "இறுதியாக"),
             TokenType::Achidu => write!(f, "அச்சிடு"),
             TokenType::Ulleedu => write!(f, "உள்ளீடு"),
             TokenType::Neelam => write!(f, "நீளம்"),
             TokenType::Vagai => write!(f, "வகை"),
+            TokenType::Integer(n) => write!(f, "{}", n),
             TokenType::Number(n) => write!(f, "{}", n),
             TokenType::String(s) => write!(f, "\"{}\"", s),
             TokenType::FString(s) => write!(f, "f\"{}\"", s),
@@ -127,6 +158,21 @@ impl fmt::Display for TokenType {
             TokenType::Star => write!(f, "*"),
             TokenType::Slash => write!(f, "/"),
             TokenType::Percent => write!(f, "%"),
+            TokenType::PlusEqual => write!(f, "+="),
+            TokenType::MinusEqual => write!(f, "-="),
+            TokenType::StarEqual => write!(f, "*="),
+            TokenType::SlashEqual => write!(f, "/="),
+            TokenType::PercentEqual => write!(f, "%="),
+            TokenType::StarStar => write!(f, "**"),
+            TokenType::SlashSlash => write!(f, "//"),
+            TokenType::StarStarEqual => write!(f, "**="),
+            TokenType::SlashSlashEqual => write!(f, "//="),
+            TokenType::Ampersand => write!(f, "&"),
+            TokenType::Pipe => write!(f, "|"),
+            TokenType::Caret => write!(f, "^"),
+            TokenType::Tilde => write!(f, "~"),
+            TokenType::LessLess => write!(f, "<<"),
+            TokenType::GreaterGreater => write!(f, ">>"),
             TokenType::Equal => write!(f, "="),
             TokenType::EqualEqual => write!(f, "=="),
             TokenType::NotEqual => write!(f, "!="),
@@ -143,6 +189,7 @@ impl fmt::Display for TokenType {
             TokenType::Comma => write!(f, ","),
             TokenType::Colon => write!(f, ":"),
             TokenType::Dot => write!(f, "."),
+            TokenType::Ellipsis => write!(f, "..."),
             TokenType::Newline => write!(f, "NEWLINE"),
             TokenType::Indent => write!(f, "INDENT"),
             TokenType::Dedent => write!(f, "DEDENT"),
@@ -152,11 +199,38 @@ impl fmt::Display for TokenType {
             TokenType::Underscore => write!(f, "_"),
             TokenType::Arrow => write!(f, "=>"),
             TokenType::Seyali => write!(f, "செயலி"),
+            TokenType::Than => write!(f, "தன்"),
+            TokenType::Velimaari => write!(f, "வெளிமாறி"),
+            TokenType::Vilai => write!(f, "விளை"),
             TokenType::Eof => write!(f, "EOF"),
         }
     }
 }
 
+/// A location in the source: 1-based line and column, length in characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub length: usize,
+}
+
+impl Span {
+    pub fn new(line: usize, column: usize, length: usize) -> Self {
+        Span { line, column, length }
+    }
+
+    /// Extend this span so that it ends where `end` ends.
+    /// Spans crossing lines keep their first-line length.
+    pub fn to(self, end: Span) -> Span {
+        if end.line == self.line && end.column + end.length > self.column {
+            Span::new(self.line, self.column, end.column + end.length - self.column)
+        } else {
+            self
+        }
+    }
+}
+
 /// A token with position information
 #[derive(Debug, Clone, PartialEq)]
 pub struct Token {
@@ -164,17 +238,33 @@ pub struct Token {
     pub lexeme: String,
     pub line: usize,
     pub column: usize,
+    pub length: usize,
 }
 
 impl Token {
     pub fn new(token_type: TokenType, lexeme: String, line: usize, column: usize) -> Self {
+        let length = lexeme.chars().count();
         Token {
             token_type,
             lexeme,
             line,
             column,
+            length,
         }
     }
+
+    /// Source span covered by this token
+    pub fn span(&self) -> Span {
+        Span::new(self.line, self.column, self.length)
+    }
+}
+
+/// A `#` comment, which the parser never sees: `text` includes the `#`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub text: String,
+    pub line: usize,
+    pub column: usize,
 }
 
 impl fmt::Display for Token {
@@ -7,9 +7,15 @@
 
 pub mod lexer;
 pub mod parser;
+pub mod resolver;
 pub mod interpreter;
 pub mod types;
 pub mod error;
+pub mod vm;
+pub mod lsp;
+pub mod formatter;
+pub mod checker;
+pub mod testing;
 
 pub use lexer::Lexer;
 pub use parser::Parser;
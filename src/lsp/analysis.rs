@@ -0,0 +1,284 @@
+//! Static analysis behind the language server
+//!
+//! Checks a document the way `agam` would before running it, and matches
+//! positions in the editor to the declarations `resolver::declarations`
+//! finds, without running anything
+
+use std::collections::HashSet;
+
+use crate::error::AgamError;
+use crate::interpreter::builtin::{create_builtins, BUILTIN_DOCS};
+use crate::interpreter::ops::builtin_environment;
+use crate::lexer::{keywords, Lexer, Span, Token, TokenType};
+use crate::parser::ast::{Parameters, SELF_NAME};
+use crate::parser::Parser;
+use crate::resolver::{declarations, Declaration, Declared, Resolver};
+
+/// What a declared name is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Variable,
+    Constant,
+    Parameter,
+    Function,
+    Struct,
+    Field,
+    Method,
+    Enum,
+    Variant,
+    Module,
+}
+
+impl SymbolKind {
+    /// Names reached through a value, `obj.field`, rather than by scope
+    pub fn is_member(self) -> bool {
+        matches!(self, SymbolKind::Field | SymbolKind::Method | SymbolKind::Variant)
+    }
+}
+
+/// A name declared in a document; lines and columns are 1-based like `Span`
+#[derive(Debug, Clone)]
+pub struct Definition {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Position of the name itself
+    pub line: usize,
+    pub column: usize,
+    /// First and last line of the whole declaration
+    pub lines: (usize, usize),
+    /// First and last line of the block the name is visible in
+    pub scope: (usize, usize),
+    /// Declaration shown on hover: `செயல் f(a, b = 2)`
+    pub detail: String,
+    /// Index of the struct or enum a field, method or variant belongs to
+    pub parent: Option<usize>,
+}
+
+/// Outcome of checking a document
+#[derive(Debug, Clone, Default)]
+pub struct Analysis {
+    /// The lexer, parser or resolver error, if any
+    pub error: Option<AgamError>,
+    /// Tokens of the document; empty when it does not tokenize
+    pub tokens: Vec<Token>,
+    /// Declared names; `None` when the document does not parse
+    pub definitions: Option<Vec<Definition>>,
+}
+
+/// A completion candidate
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    pub label: String,
+    pub kind: CompletionKind,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    Keyword,
+    Builtin,
+    Symbol(SymbolKind),
+}
+
+/// Tokenize, parse and resolve `source`, stopping at the first error
+pub fn analyze(source: &str) -> Analysis {
+    let tokens = match Lexer::tokenize(source) {
+        Ok(tokens) => tokens,
+        Err(error) => return Analysis { error: Some(error), ..Analysis::default() },
+    };
+    let mut program = match Parser::new(tokens.clone()).parse() {
+        Ok(program) => program,
+        Err(error) => return Analysis { error: Some(error), tokens, definitions: None },
+    };
+
+    let definitions = definitions(&declarations(&program, &tokens, source), source);
+    let error = Resolver::new(builtin_environment().borrow().declared())
+        .resolve(&mut program)
+        .err();
+    Analysis { error, tokens, definitions: Some(definitions) }
+}
+
+/// Where the name at `line:column` is declared
+pub fn definition_at<'a>(definitions: &'a [Definition], tokens: &[Token], line: usize, column: usize) -> Option<&'a Definition> {
+    let (name, member) = identifier_at(tokens, line, column)?;
+    lookup(definitions, name, line, member)
+}
+
+/// Hover text for the name at `line:column`: its declaration, or the
+/// description of a builtin
+pub fn hover_at(definitions: &[Definition], tokens: &[Token], line: usize, column: usize) -> Option<String> {
+    let (name, member) = identifier_at(tokens, line, column)?;
+    match lookup(definitions, name, line, member) {
+        Some(definition) => Some(format!("```agam\n{}\n```", definition.detail)),
+        None if !member => builtin_doc(name),
+        None => None,
+    }
+}
+
+/// Description of a builtin from `BUILTIN_DOCS`: its call as written with
+/// `name`, then both spellings and what it does. Builtins are registered in
+/// pairs, the Tamil name followed by its English twin
+pub fn builtin_doc(name: &str) -> Option<String> {
+    let builtins = create_builtins();
+    let index = builtins.iter().position(|(builtin, _)| builtin == name)?;
+    let (tamil, english) = (&builtins[index & !1].0, &builtins.get(index | 1)?.0);
+    let (params, description) = builtin_entry(tamil)?;
+    Some(format!("```agam\n{}\n```\n\n**{}** / `{}` · {}", signature(name, params), tamil, english, description))
+}
+
+/// Parameters and description of the builtin named `tamil` in `BUILTIN_DOCS`
+fn builtin_entry(tamil: &str) -> Option<(&'static str, &'static str)> {
+    BUILTIN_DOCS.iter().find(|(builtin, _, _)| *builtin == tamil).map(|(_, params, description)| (*params, *description))
+}
+
+/// A builtin's call as hover and completion show it: `செயல் len(value)`
+fn signature(name: &str, params: &str) -> String {
+    format!("செயல் {}({})", name, params)
+}
+
+/// Names worth offering on `line`: members after a `.`, otherwise the
+/// declarations in scope, builtins and keywords
+pub fn completions(definitions: &[Definition], line: usize, after_dot: bool) -> Vec<Completion> {
+    let mut seen = HashSet::new();
+    let mut items = Vec::new();
+
+    for definition in definitions {
+        let visible = if after_dot {
+            definition.kind.is_member()
+        } else {
+            !definition.kind.is_member() && definition.scope.0 <= line && line <= definition.scope.1
+        };
+        if !visible || seen.contains(&definition.name) {
+            continue;
+        }
+        // The innermost declaration of a name is the one in effect
+        let Some(effective) = lookup(definitions, &definition.name, line, after_dot) else {
+            continue;
+        };
+        seen.insert(effective.name.clone());
+        items.push(Completion {
+            label: effective.name.clone(),
+            kind: CompletionKind::Symbol(effective.kind),
+            detail: effective.detail.clone(),
+        });
+    }
+    if after_dot {
+        return items;
+    }
+
+    let builtins = create_builtins();
+    for (i, (name, _)) in builtins.iter().enumerate() {
+        if seen.insert(name.clone()) {
+            let params = builtin_entry(&builtins[i & !1].0).map_or("", |(params, _)| params);
+            items.push(Completion { label: name.clone(), kind: CompletionKind::Builtin, detail: signature(name, params) });
+        }
+    }
+    let mut words: Vec<&str> = keywords().into_keys().collect();
+    words.sort_unstable();
+    for word in words {
+        if seen.insert(word.to_string()) {
+            items.push(Completion { label: word.to_string(), kind: CompletionKind::Keyword, detail: String::new() });
+        }
+    }
+    items
+}
+
+/// The identifier token covering `line:column`, and whether it follows a `.`
+fn identifier_at(tokens: &[Token], line: usize, column: usize) -> Option<(&str, bool)> {
+    let index = tokens.iter().position(|token| {
+        token.line == line && token.column <= column && column <= token.column + token.length
+            && matches!(token.token_type, TokenType::Identifier(_))
+    })?;
+    let TokenType::Identifier(name) = &tokens[index].token_type else {
+        return None;
+    };
+    let member = index > 0 && tokens[index - 1].token_type == TokenType::Dot;
+    Some((name, member))
+}
+
+/// The declaration of `name` in effect on `line`: the innermost visible one,
+/// preferring the latest before `line` over later ones such as functions
+/// called before their definition
+fn lookup<'a>(definitions: &'a [Definition], name: &str, line: usize, member: bool) -> Option<&'a Definition> {
+    definitions
+        .iter()
+        .filter(|d| d.name == name && d.kind.is_member() == member)
+        .filter(|d| member || (d.scope.0 <= line && line <= d.scope.1))
+        .max_by_key(|d| {
+            let before = d.line <= line;
+            (d.scope.0, before, if before { d.line } else { usize::MAX - d.line })
+        })
+}
+
+/// The declarations as the editor shows them, each with its kind and the
+/// declaration text shown on hover
+fn definitions(declarations: &[Declaration], source: &str) -> Vec<Definition> {
+    let lines: Vec<&str> = source.lines().collect();
+    declarations
+        .iter()
+        .map(|declaration| {
+            let name = declaration.name;
+            let owner = declaration.parent.map_or("", |parent| declarations[parent].name);
+            let (kind, detail) = match declaration.declared {
+                Declared::Variable { is_const: false } => (SymbolKind::Variable, format!("மாறி {}", name)),
+                Declared::Variable { is_const: true } => (SymbolKind::Constant, format!("மாறாத {}", name)),
+                Declared::Parameter => (SymbolKind::Parameter, format!("அளவுரு {}", name)),
+                Declared::Function(params) => {
+                    (SymbolKind::Function, format!("செயல் {}({})", name, parameter_list(params, &lines)))
+                }
+                Declared::Method(params) => {
+                    (SymbolKind::Method, format!("செயல் {}({})", name, parameter_list(params, &lines)))
+                }
+                Declared::Struct(fields) => {
+                    let names: Vec<&str> = fields.iter().map(|(field, _)| field.as_str()).collect();
+                    (SymbolKind::Struct, format!("கட்டமைப்பு {}({})", name, names.join(", ")))
+                }
+                Declared::Field(Some(type_name)) => (SymbolKind::Field, format!("{}.{}: {}", owner, name, type_name)),
+                Declared::Field(None) => (SymbolKind::Field, format!("{}.{}", owner, name)),
+                Declared::Enum => (SymbolKind::Enum, format!("விருப்பம் {}", name)),
+                Declared::Variant([]) => (SymbolKind::Variant, format!("{}.{}", owner, name)),
+                Declared::Variant(payload) => {
+                    (SymbolKind::Variant, format!("{}.{}({})", owner, name, payload.join(", ")))
+                }
+                Declared::Module => (SymbolKind::Module, format!("இறக்குமதி {}", name)),
+                Declared::Import { module } => (SymbolKind::Variable, format!("இருந்து {} இறக்குமதி {}", module, name)),
+                Declared::CaughtError => (SymbolKind::Variable, format!("பிடி {}", name)),
+                Declared::PatternVariable => (SymbolKind::Variable, format!("மாறி {}", name)),
+            };
+            Definition {
+                name: name.to_string(),
+                kind,
+                line: declaration.line,
+                column: declaration.column,
+                lines: declaration.lines,
+                scope: declaration.scope,
+                detail,
+                parent: declaration.parent,
+            }
+        })
+        .collect()
+}
+
+/// Parameters as written: `a, b = 2, *rest, **options`, without `தன்`
+fn parameter_list(params: &Parameters, lines: &[&str]) -> String {
+    let signature = &params.signature;
+    let mut parts = Vec::new();
+    for (i, param) in signature.params.iter().enumerate() {
+        if param == SELF_NAME {
+            continue;
+        }
+        match i.checked_sub(signature.required).and_then(|d| params.defaults.get(d)) {
+            Some(default) => parts.push(format!("{} = {}", param, span_text(lines, default.span))),
+            None => parts.push(param.clone()),
+        }
+    }
+    parts.extend(signature.rest.iter().map(|rest| format!("*{}", rest)));
+    parts.extend(signature.keywords.iter().map(|keywords| format!("**{}", keywords)));
+    parts.join(", ")
+}
+
+/// Source text of a single-line span
+fn span_text(lines: &[&str], span: Span) -> String {
+    let line = lines.get(span.line.wrapping_sub(1)).copied().unwrap_or("");
+    line.chars().skip(span.column.saturating_sub(1)).take(span.length.max(1)).collect()
+}
@@ -0,0 +1,10 @@
+//! Language server for Agam
+//!
+//! `agam lsp` speaks the Language Server Protocol over stdio, giving
+//! editors diagnostics, go-to-definition, hover, completion and document
+//! symbols built on the lexer, parser and resolver
+
+pub mod analysis;
+pub mod server;
+
+pub use server::{serve, Server};
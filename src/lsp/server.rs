@@ -0,0 +1,395 @@
+//! JSON-RPC transport and request handling for `agam lsp`
+//!
+//! Messages are framed with a `Content-Length` header as the Language
+//! Server Protocol specifies. Documents are synced in full on every change.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+use serde_json::{json, Value as Json};
+
+use crate::lsp::analysis::{self, Analysis, CompletionKind, Definition, SymbolKind};
+
+/// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// An open document with its latest analysis
+struct Document {
+    text: String,
+    analysis: Analysis,
+    /// Declarations from the last version that parsed, so completion and
+    /// navigation keep working while a line is half typed
+    definitions: Vec<Definition>,
+}
+
+impl Document {
+    fn new(text: String) -> Self {
+        let mut document = Document { text: String::new(), analysis: Analysis::default(), definitions: Vec::new() };
+        document.update(text);
+        document
+    }
+
+    fn update(&mut self, text: String) {
+        self.analysis = analysis::analyze(&text);
+        if let Some(definitions) = &self.analysis.definitions {
+            self.definitions = definitions.clone();
+        }
+        self.text = text;
+    }
+
+    /// 1-based line and character column of an LSP position
+    fn position(&self, params: &Json) -> Option<(usize, usize)> {
+        let position = &params["position"];
+        let line = position["line"].as_u64()? as usize;
+        let character = position["character"].as_u64()? as usize;
+        let text = self.text.lines().nth(line).unwrap_or("");
+        Some((line + 1, char_column(text, character) + 1))
+    }
+
+    /// LSP position of a 1-based line and character column
+    fn lsp_position(&self, line: usize, column: usize) -> Json {
+        let line = line.max(1) - 1;
+        let text = self.text.lines().nth(line).unwrap_or("");
+        let character: usize = text.chars().take(column.max(1) - 1).map(char::len_utf16).sum();
+        json!({ "line": line, "character": character })
+    }
+
+    /// LSP range from `line:column` spanning `length` characters
+    fn range(&self, line: usize, column: usize, length: usize) -> Json {
+        json!({
+            "start": self.lsp_position(line, column),
+            "end": self.lsp_position(line, column + length),
+        })
+    }
+
+    /// Range covering whole lines `first..=last`
+    fn line_range(&self, first: usize, last: usize) -> Json {
+        let end = self.text.lines().nth(last.max(1) - 1).map_or(0, |text| text.chars().count());
+        json!({
+            "start": self.lsp_position(first, 1),
+            "end": self.lsp_position(last, end + 1),
+        })
+    }
+}
+
+/// Character index of a UTF-16 offset within `text`
+fn char_column(text: &str, utf16: usize) -> usize {
+    let mut units = 0;
+    for (index, c) in text.chars().enumerate() {
+        if units >= utf16 {
+            return index;
+        }
+        units += c.len_utf16();
+    }
+    text.chars().count()
+}
+
+/// Language server state: the open documents and where replies go
+pub struct Server<W: Write> {
+    output: W,
+    documents: HashMap<String, Document>,
+    shutdown: bool,
+}
+
+/// Serve LSP messages from `input` until `exit` or end of input.
+/// Returns whether the client asked for a shutdown first, as the exit
+/// code should say
+pub fn serve(input: impl BufRead, output: impl Write) -> io::Result<bool> {
+    let mut server = Server::new(output);
+    server.run(input)?;
+    Ok(server.shutdown)
+}
+
+impl<W: Write> Server<W> {
+    pub fn new(output: W) -> Self {
+        Server { output, documents: HashMap::new(), shutdown: false }
+    }
+
+    pub fn run(&mut self, mut input: impl BufRead) -> io::Result<()> {
+        while let Some(body) = read_message(&mut input)? {
+            let message: Json = match serde_json::from_str(&body) {
+                Ok(message) => message,
+                Err(e) => {
+                    self.reply_error(Json::Null, PARSE_ERROR, &format!("தவறான JSON: {}", e))?;
+                    continue;
+                }
+            };
+            if message["method"] == "exit" {
+                break;
+            }
+            self.handle(&message)?;
+        }
+        Ok(())
+    }
+
+    fn handle(&mut self, message: &Json) -> io::Result<()> {
+        let id = message.get("id").cloned();
+        let params = &message["params"];
+        let Some(method) = message["method"].as_str() else {
+            // Responses to requests we never send are ignored
+            return match id {
+                Some(id) if message.get("result").is_none() && message.get("error").is_none() => {
+                    self.reply_error(id, INVALID_REQUEST, "'method' இல்லை")
+                }
+                _ => Ok(()),
+            };
+        };
+
+        let result = match method {
+            "initialize" => Some(capabilities()),
+            "shutdown" => {
+                self.shutdown = true;
+                Some(Json::Null)
+            }
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                let text = document["text"].as_str().unwrap_or_default().to_string();
+                self.open(document_uri(params), text)?;
+                None
+            }
+            "textDocument/didChange" => {
+                // Full sync: the last change holds the whole text
+                if let Some(text) = params["contentChanges"].as_array().and_then(|c| c.last()).and_then(|c| c["text"].as_str()) {
+                    self.open(document_uri(params), text.to_string())?;
+                }
+                None
+            }
+            "textDocument/didClose" => {
+                let uri = document_uri(params);
+                self.documents.remove(&uri);
+                self.notify("textDocument/publishDiagnostics", json!({ "uri": uri, "diagnostics": [] }))?;
+                None
+            }
+            "textDocument/definition" => Some(self.definition(params)),
+            "textDocument/hover" => Some(self.hover(params)),
+            "textDocument/completion" => Some(self.completion(params)),
+            "textDocument/documentSymbol" => Some(self.document_symbols(params)),
+            _ => match id {
+                Some(id) => return self.reply_error(id, METHOD_NOT_FOUND, &format!("தெரியாத முறை: {}", method)),
+                // Unknown notifications, such as `initialized`, need no answer
+                None => None,
+            },
+        };
+
+        match (id, result) {
+            (Some(id), Some(result)) => self.send(json!({ "jsonrpc": "2.0", "id": id, "result": result })),
+            _ => Ok(()),
+        }
+    }
+
+    fn open(&mut self, uri: String, text: String) -> io::Result<()> {
+        let document = match self.documents.remove(&uri) {
+            Some(mut document) => {
+                document.update(text);
+                document
+            }
+            None => Document::new(text),
+        };
+        let diagnostics = diagnostics(&document);
+        self.documents.insert(uri.clone(), document);
+        self.notify("textDocument/publishDiagnostics", json!({ "uri": uri, "diagnostics": diagnostics }))
+    }
+
+    fn definition(&self, params: &Json) -> Json {
+        let uri = document_uri(params);
+        let Some((document, line, column)) = self.locate(&uri, params) else {
+            return Json::Null;
+        };
+        match analysis::definition_at(&document.definitions, &document.analysis.tokens, line, column) {
+            Some(definition) => json!({
+                "uri": uri,
+                "range": document.range(definition.line, definition.column, definition.name.chars().count()),
+            }),
+            None => Json::Null,
+        }
+    }
+
+    fn hover(&self, params: &Json) -> Json {
+        let Some((document, line, column)) = self.locate(&document_uri(params), params) else {
+            return Json::Null;
+        };
+        match analysis::hover_at(&document.definitions, &document.analysis.tokens, line, column) {
+            Some(text) => json!({ "contents": { "kind": "markdown", "value": text } }),
+            None => Json::Null,
+        }
+    }
+
+    fn completion(&self, params: &Json) -> Json {
+        let Some((document, line, column)) = self.locate(&document_uri(params), params) else {
+            return json!([]);
+        };
+        // Skip back over the partly typed name to see whether it follows a `.`
+        let text = document.text.lines().nth(line - 1).unwrap_or("");
+        let before: Vec<char> = text.chars().take(column - 1).collect();
+        let word = before.iter().rev().take_while(|c| c.is_alphanumeric() || **c == '_' || is_tamil_mark(**c)).count();
+        let after_dot = before.len() > word && before[before.len() - word - 1] == '.';
+
+        let items: Vec<Json> = analysis::completions(&document.definitions, line, after_dot)
+            .into_iter()
+            .map(|item| {
+                let kind = match item.kind {
+                    CompletionKind::Keyword => 14,
+                    CompletionKind::Builtin => 3,
+                    CompletionKind::Symbol(kind) => completion_item_kind(kind),
+                };
+                json!({ "label": item.label, "kind": kind, "detail": item.detail })
+            })
+            .collect();
+        Json::Array(items)
+    }
+
+    fn document_symbols(&self, params: &Json) -> Json {
+        let Some(document) = self.documents.get(&document_uri(params)) else {
+            return json!([]);
+        };
+        let definitions = &document.definitions;
+        let symbol = |definition: &Definition, children: Vec<Json>| {
+            json!({
+                "name": definition.name,
+                "detail": definition.detail,
+                "kind": symbol_kind(definition.kind),
+                "range": document.line_range(definition.lines.0, definition.lines.1),
+                "selectionRange": document.range(definition.line, definition.column, definition.name.chars().count()),
+                "children": children,
+            })
+        };
+
+        let symbols: Vec<Json> = definitions
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| matches!(d.kind, SymbolKind::Function | SymbolKind::Struct | SymbolKind::Enum))
+            .map(|(index, definition)| {
+                let children = definitions
+                    .iter()
+                    .filter(|d| d.parent == Some(index))
+                    .map(|d| symbol(d, Vec::new()))
+                    .collect();
+                symbol(definition, children)
+            })
+            .collect();
+        Json::Array(symbols)
+    }
+
+    /// The document a request is about, with its position as a 1-based line and column
+    fn locate(&self, uri: &str, params: &Json) -> Option<(&Document, usize, usize)> {
+        let document = self.documents.get(uri)?;
+        let (line, column) = document.position(params)?;
+        Some((document, line, column))
+    }
+
+    fn notify(&mut self, method: &str, params: Json) -> io::Result<()> {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+    }
+
+    fn reply_error(&mut self, id: Json, code: i64, message: &str) -> io::Result<()> {
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }))
+    }
+
+    fn send(&mut self, message: Json) -> io::Result<()> {
+        let body = message.to_string();
+        write!(self.output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+        self.output.flush()
+    }
+}
+
+/// Read one framed message body; `None` at end of input
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let mut body = vec![0; length.unwrap_or(0)];
+    input.read_exact(&mut body)?;
+    String::from_utf8(body).map(Some).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn capabilities() -> Json {
+    json!({
+        "capabilities": {
+            "textDocumentSync": 1,
+            "definitionProvider": true,
+            "hoverProvider": true,
+            "completionProvider": { "triggerCharacters": ["."] },
+            "documentSymbolProvider": true,
+        },
+        "serverInfo": { "name": "agam", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+fn document_uri(params: &Json) -> String {
+    params["textDocument"]["uri"].as_str().unwrap_or_default().to_string()
+}
+
+fn diagnostics(document: &Document) -> Vec<Json> {
+    let Some(error) = &document.analysis.error else {
+        return Vec::new();
+    };
+    let (line, column) = error.location();
+    // Underline the token the error points at, or one character
+    let length = document
+        .analysis
+        .tokens
+        .iter()
+        .find(|token| token.line == line && token.column == column)
+        .map_or(1, |token| token.length.max(1));
+    vec![json!({
+        "range": document.range(line, column, length),
+        "severity": 1,
+        "code": error.kind(),
+        "source": "agam",
+        "message": error.message(),
+    })]
+}
+
+/// Vowel signs and the virama are part of a Tamil word without being alphanumeric
+fn is_tamil_mark(c: char) -> bool {
+    ('\u{0B82}'..='\u{0BCD}').contains(&c) || c == '\u{0BD7}'
+}
+
+/// LSP `CompletionItemKind` for a declaration
+fn completion_item_kind(kind: SymbolKind) -> u8 {
+    match kind {
+        SymbolKind::Variable | SymbolKind::Parameter => 6,
+        SymbolKind::Constant => 21,
+        SymbolKind::Function => 3,
+        SymbolKind::Method => 2,
+        SymbolKind::Field => 5,
+        SymbolKind::Struct => 22,
+        SymbolKind::Enum => 13,
+        SymbolKind::Variant => 20,
+        SymbolKind::Module => 9,
+    }
+}
+
+/// LSP `SymbolKind` for a declaration
+fn symbol_kind(kind: SymbolKind) -> u8 {
+    match kind {
+        SymbolKind::Variable | SymbolKind::Parameter => 13,
+        SymbolKind::Constant => 14,
+        SymbolKind::Function => 12,
+        SymbolKind::Method => 6,
+        SymbolKind::Field => 8,
+        SymbolKind::Struct => 23,
+        SymbolKind::Enum => 10,
+        SymbolKind::Variant => 22,
+        SymbolKind::Module => 2,
+    }
+}
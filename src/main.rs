@@ -10,6 +10,10 @@ use std::io::{self, Write};
 use std::process;
 
 use agam::{Lexer, Parser, Interpreter};
+use agam::interpreter::Backend;
+use agam::formatter::{format_source, translate_source, FormatOptions, Spelling};
+use agam::checker::{check_source, Diagnostic, Severity};
+use agam::testing::{discover, junit, run_tests, tap};
 use agam::error::{AgamError, format_error};
 
 const VERSION: &str = "0.1.2";
@@ -24,20 +28,36 @@ const WELCOME_MESSAGE: &str = r#"
 "#;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    match args.len() {
-        1 => run_repl(),
-        2 => {
-            let arg = &args[1];
-            match arg.as_str() {
-                "-h" | "--help" | "--உதவி" => print_help(),
-                "-v" | "--version" | "--பதிப்பு" => print_version(),
-                _ => run_file(arg),
-            }
+    let mut args: Vec<String> = env::args().skip(1).collect();
+
+    // Backend selection may appear anywhere on the command line
+    let mut backend = Backend::TreeWalker;
+    args.retain(|arg| match arg.as_str() {
+        "--vm" | "--மெய்நிகர்" => {
+            backend = Backend::Vm;
+            false
         }
+        _ => true,
+    });
+
+    match args.as_slice() {
+        [] => run_repl(backend),
+        [command, rest @ ..] if command == "fmt" => run_fmt(rest),
+        [command, rest @ ..] if command == "translate" => run_translate(rest),
+        [command, rest @ ..] if command == "check" => run_check(rest),
+        [command, rest @ ..] if command == "test" => run_test(rest, backend),
+        [arg] => match arg.as_str() {
+            "-h" | "--help" | "--உதவி" => print_help(),
+            "-v" | "--version" | "--பதிப்பு" => print_version(),
+            "lsp" => run_lsp(),
+            _ => run_file(arg, backend),
+        },
         _ => {
-            eprintln!("பயன்பாடு: agam [கோப்பு.agam]");
+            eprintln!("பயன்பாடு: agam [--vm] [கோப்பு.agam]");
+            eprintln!("         agam fmt [--check] [--keywords=tamil|english] [கோப்புகள்...]");
+            eprintln!("         agam translate --to tamil|english [கோப்புகள்...]");
+            eprintln!("         agam check [--format human|json] [கோப்புகள்...]");
+            eprintln!("         agam test [--vm] [--format tap|junit] [அடைவு அல்லது கோப்புகள்...]");
             eprintln!("         agam --help");
             process::exit(1);
         }
@@ -52,6 +72,18 @@ fn print_help() {
 பயன்பாடு:
     agam                        REPL முறையில் தொடங்கு
     agam <கோப்பு>               கோப்பை இயக்கு
+    agam --vm <கோப்பு>          பைட்கோட் மெய்நிகர் இயந்திரத்தில் இயக்கு (bytecode VM)
+    agam lsp                    மொழி சேவையகம் (Language Server, stdio)
+    agam fmt <கோப்புகள்>        கோப்புகளை வடிவமை (format in place)
+    agam fmt --check <கோப்புகள்> வடிவமைக்கப்படாத கோப்புகளை பட்டியலிடு
+    agam fmt --keywords=english முக்கிய சொற்களை ஆங்கிலத்தில் எழுது (or tamil)
+    agam translate --to english <கோப்புகள்>
+                                முக்கிய சொற்கள், உள்ளமைந்த செயல்களை மொழிபெயர் (or tamil)
+    agam check <கோப்புகள்>      இயக்காமல் பிழைகளைச் சரிபார் (static check)
+    agam check --format json <கோப்புகள்>
+                                கண்டவற்றை JSON ஆக அச்சிடு
+    agam test [அடைவு]           சோதனை_/test_ செயல்களை இயக்கு (TAP)
+    agam test --format junit    முடிவுகளை JUnit XML ஆக அச்சிடு
     agam --help                 உதவி காட்டு
     agam --version              பதிப்பு காட்டு
 
@@ -79,7 +111,7 @@ fn print_version() {
     println!("Tamil Programming Language");
 }
 
-fn run_file(path: &str) {
+fn run_file(path: &str, backend: Backend) {
     let source = match fs::read_to_string(path) {
         Ok(content) => content,
         Err(e) => {
@@ -88,16 +120,277 @@ fn run_file(path: &str) {
         }
     };
 
-    if let Err(error) = run(&source) {
+    if let Err(error) = run(&source, path, backend) {
         eprintln!("{}", format_error(&error, &source));
         process::exit(1);
     }
 }
 
-fn run_repl() {
+/// Language server over stdio, for editors
+fn run_lsp() {
+    let stdin = io::stdin();
+    match agam::lsp::serve(stdin.lock(), io::stdout().lock()) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(e) => {
+            eprintln!("மொழி சேவையக பிழை: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Format files in place, or standard input to standard output when no
+/// files are given. With --check nothing is written: files that would
+/// change are listed and the exit code is 1.
+fn run_fmt(args: &[String]) {
+    let mut check = false;
+    let mut options = FormatOptions::default();
+    let mut paths = Vec::new();
+    for arg in args {
+        if arg == "--check" {
+            check = true;
+        } else if let Some(name) = arg.strip_prefix("--keywords=") {
+            options.keywords = Some(spelling(name));
+        } else {
+            paths.push(arg);
+        }
+    }
+    rewrite(&paths, check, |source| format_source(source, &options));
+}
+
+/// Rewrite keywords and built-in names to one language, in place or
+/// from standard input to standard output
+fn run_translate(args: &[String]) {
+    let mut to = None;
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--to" {
+            to = args.next().map(|name| spelling(name));
+        } else if let Some(name) = arg.strip_prefix("--to=") {
+            to = Some(spelling(name));
+        } else {
+            paths.push(arg);
+        }
+    }
+    let Some(to) = to else {
+        eprintln!("பயன்பாடு: agam translate --to tamil|english [கோப்புகள்...]");
+        process::exit(1);
+    };
+    rewrite(&paths, false, |source| translate_source(source, to));
+}
+
+/// Check files, or standard input when no files are given, without
+/// running them. Exits with 1 if any file has an error; warnings alone
+/// do not fail the check.
+fn run_check(args: &[String]) {
+    let mut json = false;
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let format = if arg == "--format" {
+            args.next().map(String::as_str)
+        } else if let Some(format) = arg.strip_prefix("--format=") {
+            Some(format)
+        } else {
+            paths.push(arg.clone());
+            continue;
+        };
+        json = match format {
+            Some("json") => true,
+            Some("human") => false,
+            _ => {
+                eprintln!("பயன்பாடு: agam check [--format human|json] [கோப்புகள்...]");
+                process::exit(1);
+            }
+        };
+    }
+
+    let mut sources = Vec::new();
+    let mut failed = false;
+    if paths.is_empty() {
+        let mut source = String::new();
+        if let Err(e) = io::Read::read_to_string(&mut io::stdin(), &mut source) {
+            eprintln!("உள்ளீடு பிழை: {}", e);
+            process::exit(1);
+        }
+        sources.push(("<stdin>".to_string(), source));
+    }
+    for path in paths {
+        match fs::read_to_string(&path) {
+            Ok(source) => sources.push((path, source)),
+            Err(e) => {
+                eprintln!("கோப்பை படிக்க இயலவில்லை '{}': {}", path, e);
+                failed = true;
+            }
+        }
+    }
+
+    let results: Vec<(String, Vec<Diagnostic>)> =
+        sources.into_iter().map(|(path, source)| { let found = check_source(&source); (path, found) }).collect();
+    let all = || results.iter().flat_map(|(path, found)| found.iter().map(move |diagnostic| (path, diagnostic)));
+    failed |= all().any(|(_, diagnostic)| diagnostic.severity == Severity::Error);
+
+    if json {
+        let entries: Vec<_> = all()
+            .map(|(path, diagnostic)| {
+                serde_json::json!({
+                    "file": path,
+                    "line": diagnostic.line,
+                    "column": diagnostic.column,
+                    "severity": diagnostic.severity.name(),
+                    "code": diagnostic.code,
+                    "message": diagnostic.message,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::Value::Array(entries));
+    } else {
+        for (path, diagnostic) in all() {
+            let severity = match diagnostic.severity {
+                Severity::Error => "பிழை (error)",
+                Severity::Warning => "எச்சரிக்கை (warning)",
+            };
+            println!(
+                "{}:{}:{}: {} [{}]: {}",
+                path, diagnostic.line, diagnostic.column, severity, diagnostic.code, diagnostic.message
+            );
+        }
+        let errors = all().filter(|(_, diagnostic)| diagnostic.severity == Severity::Error).count();
+        let warnings = all().count() - errors;
+        println!("{} பிழைகள், {} எச்சரிக்கைகள்", errors, warnings);
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+/// Run the tests in the given files and directories, the current
+/// directory by default, printing a TAP or JUnit report. Exits with 1 if
+/// any test did not pass.
+fn run_test(args: &[String], backend: Backend) {
+    let mut junit_format = false;
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let format = if arg == "--format" {
+            args.next().map(String::as_str)
+        } else if let Some(format) = arg.strip_prefix("--format=") {
+            Some(format)
+        } else {
+            paths.push(arg.clone());
+            continue;
+        };
+        junit_format = match format {
+            Some("junit") => true,
+            Some("tap") => false,
+            _ => {
+                eprintln!("பயன்பாடு: agam test [--vm] [--format tap|junit] [அடைவு அல்லது கோப்புகள்...]");
+                process::exit(1);
+            }
+        };
+    }
+    if paths.is_empty() {
+        paths.push(".".to_string());
+    }
+
+    let mut results = Vec::new();
+    for path in &paths {
+        let files = match discover(std::path::Path::new(path)) {
+            Ok(files) => files,
+            Err(e) => {
+                eprintln!("அடைவைப் படிக்க இயலவில்லை '{}': {}", path, e);
+                process::exit(1);
+            }
+        };
+        for file in files {
+            let name = file.to_string_lossy().into_owned();
+            match fs::read_to_string(&file) {
+                Ok(source) => results.extend(run_tests(&name, &source, backend)),
+                Err(e) => {
+                    eprintln!("கோப்பை படிக்க இயலவில்லை '{}': {}", name, e);
+                    process::exit(1);
+                }
+            }
+        }
+    }
+
+    print!("{}", if junit_format { junit(&results) } else { tap(&results) });
+    if !results.iter().all(|result| result.passed()) {
+        process::exit(1);
+    }
+}
+
+fn spelling(name: &str) -> Spelling {
+    Spelling::from_name(name).unwrap_or_else(|| {
+        eprintln!("தெரியாத சொல் வடிவம் '{}': tamil அல்லது english", name);
+        process::exit(1);
+    })
+}
+
+/// Apply `transform` to each file in place, or to standard input when no
+/// files are given. With `check`, changed files are only listed. Exits
+/// with 1 if anything failed or would change under `check`.
+fn rewrite(paths: &[&String], check: bool, transform: impl Fn(&str) -> Result<String, AgamError>) {
+    if paths.is_empty() {
+        let mut source = String::new();
+        if let Err(e) = io::Read::read_to_string(&mut io::stdin(), &mut source) {
+            eprintln!("உள்ளீடு பிழை: {}", e);
+            process::exit(1);
+        }
+        match transform(&source) {
+            Ok(result) if check => {
+                if result != source {
+                    process::exit(1);
+                }
+            }
+            Ok(result) => print!("{}", result),
+            Err(error) => {
+                eprintln!("{}", format_error(&error, &source));
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let mut failed = false;
+    for path in paths {
+        let source = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("கோப்பை படிக்க இயலவில்லை '{}': {}", path, e);
+                failed = true;
+                continue;
+            }
+        };
+        match transform(&source) {
+            Ok(result) if result == source => {}
+            Ok(_) if check => {
+                println!("{}", path);
+                failed = true;
+            }
+            Ok(result) => {
+                if let Err(e) = fs::write(path, result) {
+                    eprintln!("கோப்பை எழுத இயலவில்லை '{}': {}", path, e);
+                    failed = true;
+                }
+            }
+            Err(error) => {
+                eprintln!("{}:{}", path, format_error(&error, &source));
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn run_repl(backend: Backend) {
     println!("{}", WELCOME_MESSAGE);
 
-    let mut interpreter = Interpreter::new();
+    let mut interpreter = Interpreter::with_backend(backend);
     let mut line_buffer = String::new();
     let mut continuation = false;
     let mut history: Vec<String> = Vec::new();
@@ -257,8 +550,9 @@ fn print_repl_help() {
 "#);
 }
 
-fn run(source: &str) -> Result<(), AgamError> {
-    let mut interpreter = Interpreter::new();
+fn run(source: &str, path: &str, backend: Backend) -> Result<(), AgamError> {
+    let mut interpreter = Interpreter::with_backend(backend);
+    interpreter.set_file(path);
     run_with_interpreter(source, &mut interpreter)?;
     Ok(())
 }
@@ -4,20 +4,60 @@
 
 use std::fmt;
 
+pub use crate::lexer::Span;
+use crate::types::Integer;
+
+/// Where a variable is stored, filled in by the resolver
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Binding {
+    /// Top-level variable of a program or module, looked up by name
+    #[default]
+    Global,
+    /// Slot `slot` of the scope `depth` levels out from the current one
+    Local { depth: usize, slot: usize },
+}
+
 /// A complete program
 #[derive(Debug, Clone)]
 pub struct Program {
     pub statements: Vec<Statement>,
 }
 
+/// A statement with its source location
+#[derive(Debug, Clone)]
+pub struct Statement {
+    pub kind: StatementKind,
+    pub span: Span,
+}
+
+impl Statement {
+    pub fn new(kind: StatementKind, span: Span) -> Self {
+        Statement { kind, span }
+    }
+}
+
 /// Statement types
 #[derive(Debug, Clone)]
-pub enum Statement {
+pub enum StatementKind {
     /// Variable declaration: மாறி x = 5
     Let {
         name: String,
         value: Expression,
         is_const: bool,
+        binding: Binding,
+    },
+    /// Destructuring declaration: மாறி a, b = pair
+    LetUnpack {
+        names: Vec<String>,
+        value: Expression,
+        is_const: bool,
+        bindings: Vec<Binding>,
+    },
+    /// Multiple assignment: a, b = b, a. Each target is a variable, an
+    /// index `list[i]` or a member `obj.field`
+    AssignUnpack {
+        targets: Vec<Expression>,
+        value: Expression,
     },
     /// Expression statement
     Expression(Expression),
@@ -33,20 +73,28 @@ pub enum Statement {
         condition: Expression,
         body: Vec<Statement>,
     },
-    /// For loop: ஒவ்வொரு item உள்ள list:
+    /// For loop: ஒவ்வொரு item உள்ள list:, or ஒவ்வொரு k, v உள்ள pairs:
+    /// unpacking each item into several variables
     For {
-        variable: String,
+        variables: Vec<String>,
         iterable: Expression,
         body: Vec<Statement>,
+        bindings: Vec<Binding>,
     },
     /// Function definition: செயல் name(params):
     Function {
         name: String,
-        params: Vec<String>,
+        params: Parameters,
         body: Vec<Statement>,
+        binding: Binding,
     },
     /// Return statement: திரும்பு value
     Return(Option<Expression>),
+    /// Nonlocal declaration: வெளிமாறி name, allowing a function to assign a
+    /// variable of an enclosing function
+    Nonlocal(Vec<String>),
+    /// Yield statement: விளை value, handing a value out of a generator
+    Yield(Expression),
     /// Break statement: நிறுத்து
     Break,
     /// Continue statement: தொடர்
@@ -57,24 +105,29 @@ pub enum Statement {
     Import {
         module: String,
         items: Option<Vec<String>>,
+        /// One per imported item, or one for the module namespace
+        bindings: Vec<Binding>,
     },
-    /// Try-catch statement: முயற்சி...பிடி
+    /// Try-catch statement: முயற்சி...பிடி...இறுதியாக
     TryCatch {
         try_block: Vec<Statement>,
-        error_var: String,
-        catch_block: Vec<Statement>,
+        catch_clauses: Vec<CatchClause>,
+        finally_block: Option<Vec<Statement>>,
     },
-    /// Throw statement: வீசு error
-    Throw(Expression),
-    /// Struct definition: கட்டமைப்பு Name:
+    /// Throw statement: வீசு error, or bare வீசு to re-raise the current error
+    Throw(Option<Expression>),
+    /// Struct definition with its methods: கட்டமைப்பு Name:
     Struct {
         name: String,
         fields: Vec<(String, Option<String>)>, // (field_name, optional_type)
+        methods: Vec<Method>,
+        binding: Binding,
     },
     /// Enum definition: விருப்பம் Name:
     Enum {
         name: String,
-        variants: Vec<String>,
+        variants: Vec<(String, Vec<String>)>, // (variant_name, payload field names)
+        binding: Binding,
     },
     /// Match statement: பொருத்து value:
     Match {
@@ -83,11 +136,47 @@ pub enum Statement {
     },
 }
 
-/// Match arm for pattern matching
+/// Name of a method's receiver, written `தன்` or `self`
+pub const SELF_NAME: &str = "தன்";
+
+/// Method defined in a கட்டமைப்பு block: செயல் name(params):
+#[derive(Debug, Clone)]
+pub struct Method {
+    pub name: String,
+    /// Parameters, starting with the implicit receiver `தன்`
+    pub params: Parameters,
+    pub body: Vec<Statement>,
+    pub span: Span,
+}
+
+/// Catch clause: பிடி [filter] name:
+#[derive(Debug, Clone)]
+pub struct CatchClause {
+    /// Error kind (இயக்கம்/runtime, ...) or struct name of the thrown value;
+    /// `None` catches everything
+    pub filter: Option<String>,
+    pub error_var: String,
+    pub binding: Binding,
+    pub body: Vec<Statement>,
+    pub span: Span,
+}
+
+/// Match arm for pattern matching: pattern [என்றால் guard] => body
 #[derive(Debug, Clone)]
 pub struct MatchArm {
     pub pattern: Pattern,
+    pub guard: Option<Expression>,
     pub body: Vec<Statement>,
+    pub span: Span,
+}
+
+/// Arm of a match expression: pattern [என்றால் guard] => value
+#[derive(Debug, Clone)]
+pub struct MatchExprArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expression>,
+    pub value: Expression,
+    pub span: Span,
 }
 
 /// Pattern for matching
@@ -96,11 +185,27 @@ pub enum Pattern {
     /// Literal value
     Literal(Expression),
     /// Variable binding
-    Variable(String),
+    Variable { name: String, binding: Binding },
     /// Wildcard (_)
     Wildcard,
-    /// Enum variant
-    EnumVariant(String, String), // (enum_name, variant_name)
+    /// Enum variant: Shape.Circle, or Shape.Circle(r) to match its payload
+    EnumVariant {
+        enum_name: String,
+        variant: String,
+        payload: Option<Vec<Pattern>>,
+    },
+    /// List: [first, second, ...rest]
+    List {
+        elements: Vec<Pattern>,
+        rest: Option<Box<Pattern>>,
+    },
+    /// Dictionary: {"key": pattern}; other keys are ignored
+    Dict(Vec<(String, Pattern)>),
+    /// Struct instance: Point{x, y: 0}
+    Struct {
+        name: String,
+        fields: Vec<(String, Pattern)>,
+    },
 }
 
 /// Part of an interpolated f-string
@@ -112,10 +217,25 @@ pub enum FStringPart {
     Expression(Box<Expression>),
 }
 
+/// An expression with its source location
+#[derive(Debug, Clone)]
+pub struct Expression {
+    pub kind: ExpressionKind,
+    pub span: Span,
+}
+
+impl Expression {
+    pub fn new(kind: ExpressionKind, span: Span) -> Self {
+        Expression { kind, span }
+    }
+}
+
 /// Expression types
 #[derive(Debug, Clone)]
-pub enum Expression {
-    /// Number literal
+pub enum ExpressionKind {
+    /// Integer literal
+    Integer(Integer),
+    /// Decimal number literal
     Number(f64),
     /// String literal
     String(String),
@@ -128,7 +248,7 @@ pub enum Expression {
     /// Null literal
     Null,
     /// Variable reference
-    Identifier(String),
+    Identifier { name: String, binding: Binding },
     /// Binary operation: a + b
     Binary {
         left: Box<Expression>,
@@ -140,15 +260,29 @@ pub enum Expression {
         operator: UnaryOp,
         operand: Box<Expression>,
     },
-    /// Function call: name(args)
+    /// Function call: name(args), with keyword arguments `name = value` last
     Call {
         callee: Box<Expression>,
         arguments: Vec<Expression>,
+        keywords: Vec<(String, Expression)>,
     },
     /// List literal: [1, 2, 3]
     List(Vec<Expression>),
+    /// Tuple literal: (1, 2), (1,), (), or a bare `1, 2` after திரும்பு or `=`
+    Tuple(Vec<Expression>),
     /// Dictionary literal: {"key": value}
     Dict(Vec<(Expression, Expression)>),
+    /// List comprehension: [x * 2 ஒவ்வொரு x உள்ள xs என்றால் x > 0]
+    ListComprehension {
+        element: Box<Expression>,
+        clauses: Vec<ComprehensionClause>,
+    },
+    /// Dictionary comprehension: {k: v * 2 ஒவ்வொரு k, v உள்ள pairs}
+    DictComprehension {
+        key: Box<Expression>,
+        value: Box<Expression>,
+        clauses: Vec<ComprehensionClause>,
+    },
     /// Index access: list[0]
     Index {
         object: Box<Expression>,
@@ -156,10 +290,12 @@ pub enum Expression {
     },
     /// Grouping: (expr)
     Grouping(Box<Expression>),
-    /// Assignment: name = value
+    /// Assignment: name = value, or name += value with `operator`
     Assignment {
         name: String,
+        operator: Option<BinaryOp>,
         value: Box<Expression>,
+        binding: Binding,
     },
     /// Member access: obj.field
     MemberAccess {
@@ -171,25 +307,139 @@ pub enum Expression {
         name: String,
         arguments: Vec<Expression>,
     },
-    /// Index assignment: list[0] = value, dict["key"] = value
+    /// Index assignment: list[0] = value, dict["key"] = value, list[0] += value
     IndexAssignment {
         object: Box<Expression>,
         index: Box<Expression>,
+        operator: Option<BinaryOp>,
         value: Box<Expression>,
     },
-    /// Member assignment: struct.field = value
+    /// Slice: list[start:end:step]
+    Slice {
+        object: Box<Expression>,
+        bounds: SliceBounds,
+    },
+    /// Slice assignment: list[start:end] = values
+    SliceAssignment {
+        object: Box<Expression>,
+        bounds: SliceBounds,
+        value: Box<Expression>,
+    },
+    /// Member assignment: struct.field = value, struct.field += value
     MemberAssignment {
         object: Box<Expression>,
         member: String,
+        operator: Option<BinaryOp>,
         value: Box<Expression>,
     },
-    /// Lambda/anonymous function: செயலி(x, y): x + y or (x, y) => x + y
+    /// Lambda/anonymous function: செயலி(x, y): x + y or (x, y) => x + y,
+    /// or செயலி(x): followed by an indented block
     Lambda {
-        params: Vec<String>,
-        body: Box<Expression>,
+        params: Parameters,
+        body: LambdaBody,
+    },
+    /// Match expression: மாறி x = பொருத்து value: with `pattern => expression` arms
+    Match {
+        value: Box<Expression>,
+        arms: Vec<MatchExprArm>,
     },
 }
 
+/// A clause of a comprehension, applied left to right: each `ஒவ்வொரு`
+/// loops over the clauses after it, each `என்றால்` filters them
+#[derive(Debug, Clone)]
+pub enum ComprehensionClause {
+    /// ஒவ்வொரு x உள்ள xs, or ஒவ்வொரு k, v உள்ள pairs
+    For {
+        variables: Vec<String>,
+        iterable: Expression,
+        bindings: Vec<Binding>,
+    },
+    /// என்றால் condition
+    If(Expression),
+}
+
+/// Body of a lambda
+#[derive(Debug, Clone)]
+pub enum LambdaBody {
+    /// A single expression whose value is returned
+    Expression(Box<Expression>),
+    /// An indented block, returning with திரும்பு like a செயல்
+    Block(Vec<Statement>),
+}
+
+/// Parameter list of a function, method or lambda
+#[derive(Debug, Clone, Default)]
+pub struct Parameters {
+    pub signature: Signature,
+    /// Default values of the optional parameters, `signature.params[signature.required..]`
+    pub defaults: Vec<Expression>,
+    /// The body contains `விளை`, so a call returns a generator instead of
+    /// running it; set by the resolver
+    pub is_generator: bool,
+}
+
+impl Parameters {
+    /// Parameters without defaults or rest parameters
+    pub fn positional(names: Vec<String>) -> Self {
+        Parameters { signature: Signature::positional(names), defaults: Vec::new(), is_generator: false }
+    }
+}
+
+/// Names and shape of a parameter list: `(a, b = 2, *rest, **options)`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Signature {
+    /// Named parameters, in order
+    pub params: Vec<String>,
+    /// How many of `params` have no default
+    pub required: usize,
+    /// `*name`: extra positional arguments, collected into a list
+    pub rest: Option<String>,
+    /// `**name`: extra keyword arguments, collected into a dictionary
+    pub keywords: Option<String>,
+}
+
+impl Signature {
+    pub fn positional(params: Vec<String>) -> Self {
+        let required = params.len();
+        Signature { params, required, rest: None, keywords: None }
+    }
+
+    /// True when every call must pass exactly `params.len()` positional arguments
+    pub fn is_simple(&self) -> bool {
+        self.required == self.params.len() && self.rest.is_none() && self.keywords.is_none()
+    }
+
+    /// Every parameter name in slot order: named, then `*rest`, then `**keywords`
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.params.iter().chain(&self.rest).chain(&self.keywords)
+    }
+
+    /// Number of frame slots the parameters occupy
+    pub fn slot_count(&self) -> usize {
+        self.names().count()
+    }
+}
+
+/// Bounds of a slice `[start:end:step]`; omitted bounds are `None`
+#[derive(Debug, Clone)]
+pub struct SliceBounds {
+    pub start: Option<Box<Expression>>,
+    pub end: Option<Box<Expression>>,
+    pub step: Option<Box<Expression>>,
+}
+
+impl SliceBounds {
+    /// Start, end and step, in evaluation order
+    pub fn parts(&self) -> [Option<&Expression>; 3] {
+        [self.start.as_deref(), self.end.as_deref(), self.step.as_deref()]
+    }
+
+    pub fn parts_mut(&mut self) -> [Option<&mut Expression>; 3] {
+        [self.start.as_deref_mut(), self.end.as_deref_mut(), self.step.as_deref_mut()]
+    }
+}
+
 /// Binary operators
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum BinaryOp {
@@ -199,6 +449,18 @@ pub enum BinaryOp {
     Multiply, // *
     Divide,   // /
     Modulo,   // %
+    Power,       // **
+    FloorDivide, // //
+
+    // Bitwise (integral numbers only)
+    BitAnd,     // &
+    BitOr,      // |
+    BitXor,     // ^
+    ShiftLeft,  // <<
+    ShiftRight, // >>
+
+    // Membership
+    In, // உள்ள / in
     
     // Comparison
     Equal,        // ==
@@ -221,6 +483,14 @@ impl fmt::Display for BinaryOp {
             BinaryOp::Multiply => write!(f, "*"),
             BinaryOp::Divide => write!(f, "/"),
             BinaryOp::Modulo => write!(f, "%"),
+            BinaryOp::Power => write!(f, "**"),
+            BinaryOp::FloorDivide => write!(f, "//"),
+            BinaryOp::BitAnd => write!(f, "&"),
+            BinaryOp::BitOr => write!(f, "|"),
+            BinaryOp::BitXor => write!(f, "^"),
+            BinaryOp::ShiftLeft => write!(f, "<<"),
+            BinaryOp::ShiftRight => write!(f, ">>"),
+            BinaryOp::In => write!(f, "உள்ள"),
             BinaryOp::Equal => write!(f, "=="),
             BinaryOp::NotEqual => write!(f, "!="),
             BinaryOp::Less => write!(f, "<"),
@@ -238,6 +508,7 @@ impl fmt::Display for BinaryOp {
 pub enum UnaryOp {
     Negate, // -
     Not,    // இல்ல / not
+    BitNot, // ~
 }
 
 impl fmt::Display for UnaryOp {
@@ -245,6 +516,7 @@ impl fmt::Display for UnaryOp {
         match self {
             UnaryOp::Negate => write!(f, "-"),
             UnaryOp::Not => write!(f, "இல்ல"),
+            UnaryOp::BitNot => write!(f, "~"),
         }
     }
 }
@@ -5,6 +5,7 @@
 use crate::lexer::{Token, TokenType};
 use crate::parser::ast::*;
 use crate::error::AgamError;
+use crate::types::Integer;
 
 /// The parser
 pub struct Parser {
@@ -47,50 +48,118 @@ impl Parser {
         } else if self.check(&TokenType::Irakkumadhi) {
             self.import_statement()
         } else if self.check(&TokenType::Irundhu) {
-            self.from_import_statement()
+            self.selective_import_statement()
         } else {
             self.statement()
         }
     }
 
     fn function_declaration(&mut self) -> Result<Statement, AgamError> {
+        let (name, params, body, span) = self.function_parts(Vec::new())?;
+        Ok(Statement::new(StatementKind::Function { name, params, body, binding: Binding::Global }, span))
+    }
+
+    /// Parse `செயல் name(params): body`, with `params` coming after `leading`
+    fn function_parts(&mut self, leading: Vec<String>) -> Result<(String, Parameters, Vec<Statement>, Span), AgamError> {
+        let start = self.current_span();
         self.advance(); // consume செயல்
 
         let name = self.consume_identifier("செயல் பெயர் எதிர்பார்க்கப்படுகிறது")?;
         
         self.consume(&TokenType::LeftParen, "'(' எதிர்பார்க்கப்படுகிறது")?;
-        
-        let mut params = Vec::new();
-        if !self.check(&TokenType::RightParen) {
-            loop {
-                params.push(self.consume_identifier("அளவுரு பெயர் எதிர்பார்க்கப்படுகிறது")?);
-                if !self.match_token(&[TokenType::Comma]) {
-                    break;
-                }
-            }
-        }
-        
+        let params = self.parameters(leading)?;
         self.consume(&TokenType::RightParen, "')' எதிர்பார்க்கப்படுகிறது")?;
         self.consume(&TokenType::Colon, "':' எதிர்பார்க்கப்படுகிறது")?;
+        let span = self.span_from(start);
         
         let body = self.block()?;
 
-        Ok(Statement::Function { name, params, body })
+        Ok((name, params, body, span))
+    }
+
+    /// Parse a parameter list up to its `)`: plain names, then names with
+    /// defaults (`b = 2`), then `*rest`, then `**options`
+    fn parameters(&mut self, leading: Vec<String>) -> Result<Parameters, AgamError> {
+        let mut params = Parameters::positional(leading);
+        if self.check(&TokenType::RightParen) {
+            return Ok(params);
+        }
+
+        loop {
+            let signature = &params.signature;
+            if signature.keywords.is_some() {
+                return Err(self.error("'**' அளவுருவுக்குப் பின் அளவுருக்கள் வர இயலாது"));
+            }
+
+            if self.match_token(&[TokenType::StarStar]) {
+                let name = self.parameter_name(&params)?;
+                params.signature.keywords = Some(name);
+            } else if self.match_token(&[TokenType::Star]) {
+                if signature.rest.is_some() {
+                    return Err(self.error("ஒரே ஒரு '*' அளவுரு மட்டுமே அனுமதிக்கப்படும்"));
+                }
+                let name = self.parameter_name(&params)?;
+                params.signature.rest = Some(name);
+            } else {
+                if signature.rest.is_some() {
+                    return Err(self.error("'*' அளவுருவுக்குப் பின் பெயரிட்ட அளவுருக்கள் வர இயலாது"));
+                }
+                let name = self.parameter_name(&params)?;
+                if self.match_token(&[TokenType::Equal]) {
+                    params.defaults.push(self.expression()?);
+                } else if !params.defaults.is_empty() {
+                    return Err(self.error("இயல்புநிலை மதிப்புள்ள அளவுருவுக்குப் பின் இயல்புநிலை இல்லாத அளவுரு வர இயலாது"));
+                } else {
+                    params.signature.required += 1;
+                }
+                params.signature.params.push(name);
+            }
+
+            if !self.match_token(&[TokenType::Comma]) {
+                return Ok(params);
+            }
+        }
+    }
+
+    /// Parse a parameter name, rejecting one already in the list
+    fn parameter_name(&mut self, params: &Parameters) -> Result<String, AgamError> {
+        if let Some(TokenType::Identifier(name)) = self.peek_token_type() {
+            if params.signature.names().any(|existing| existing == name) {
+                return Err(self.error(&format!("'{}' அளவுரு இருமுறை வரையறுக்கப்பட்டுள்ளது", name)));
+            }
+        }
+        self.consume_identifier("அளவுரு பெயர் எதிர்பார்க்கப்படுகிறது")
     }
 
     fn var_declaration(&mut self) -> Result<Statement, AgamError> {
+        let start = self.current_span();
         let is_const = self.check(&TokenType::Maaraadha);
         self.advance(); // consume மாறி or மாறாத
 
-        let name = self.consume_identifier("மாறி பெயர் எதிர்பார்க்கப்படுகிறது")?;
+        let mut names = self.variable_names()?;
         
         self.consume(&TokenType::Equal, "'=' எதிர்பார்க்கப்படுகிறது")?;
         
-        let value = self.expression()?;
+        let value = self.tuple_expression()?;
+        let span = self.span_from(start);
         
         self.consume_newline_or_eof()?;
 
-        Ok(Statement::Let { name, value, is_const })
+        if names.len() > 1 {
+            let bindings = vec![Binding::Global; names.len()];
+            return Ok(Statement::new(StatementKind::LetUnpack { names, value, is_const, bindings }, span));
+        }
+        let name = names.remove(0);
+        Ok(Statement::new(StatementKind::Let { name, value, is_const, binding: Binding::Global }, span))
+    }
+
+    /// Parse the names of a declaration or loop: `a` or `a, b, ...`
+    fn variable_names(&mut self) -> Result<Vec<String>, AgamError> {
+        let mut names = vec![self.consume_identifier("மாறி பெயர் எதிர்பார்க்கப்படுகிறது")?];
+        while self.match_token(&[TokenType::Comma]) {
+            names.push(self.consume_identifier("மாறி பெயர் எதிர்பார்க்கப்படுகிறது")?);
+        }
+        Ok(names)
     }
 
     fn statement(&mut self) -> Result<Statement, AgamError> {
@@ -102,6 +171,10 @@ impl Parser {
             self.for_statement()
         } else if self.check(&TokenType::Thirumbu) {
             self.return_statement()
+        } else if self.check(&TokenType::Velimaari) {
+            self.nonlocal_statement()
+        } else if self.check(&TokenType::Vilai) {
+            self.yield_statement()
         } else if self.check(&TokenType::Niruthu) {
             self.break_statement()
         } else if self.check(&TokenType::Thodar) {
@@ -120,10 +193,12 @@ impl Parser {
     }
 
     fn if_statement(&mut self) -> Result<Statement, AgamError> {
+        let start = self.current_span();
         self.advance(); // consume என்றால்
 
         let condition = self.expression()?;
         self.consume(&TokenType::Colon, "':' எதிர்பார்க்கப்படுகிறது")?;
+        let span = self.span_from(start);
         
         let then_branch = self.block()?;
 
@@ -144,71 +219,116 @@ impl Parser {
             None
         };
 
-        Ok(Statement::If {
-            condition,
-            then_branch,
-            elif_branches,
-            else_branch,
-        })
+        Ok(Statement::new(
+            StatementKind::If {
+                condition,
+                then_branch,
+                elif_branches,
+                else_branch,
+            },
+            span,
+        ))
     }
 
     fn while_statement(&mut self) -> Result<Statement, AgamError> {
+        let start = self.current_span();
         self.advance(); // consume வரை
 
         let condition = self.expression()?;
         self.consume(&TokenType::Colon, "':' எதிர்பார்க்கப்படுகிறது")?;
+        let span = self.span_from(start);
         
         let body = self.block()?;
 
-        Ok(Statement::While { condition, body })
+        Ok(Statement::new(StatementKind::While { condition, body }, span))
     }
 
     fn for_statement(&mut self) -> Result<Statement, AgamError> {
+        let start = self.current_span();
         self.advance(); // consume ஒவ்வொரு
 
-        let variable = self.consume_identifier("மாறி பெயர் எதிர்பார்க்கப்படுகிறது")?;
+        let variables = self.variable_names()?;
         
         self.consume(&TokenType::Ulla, "'உள்ள' எதிர்பார்க்கப்படுகிறது")?;
         
         let iterable = self.expression()?;
         self.consume(&TokenType::Colon, "':' எதிர்பார்க்கப்படுகிறது")?;
+        let span = self.span_from(start);
         
         let body = self.block()?;
 
-        Ok(Statement::For {
-            variable,
-            iterable,
-            body,
-        })
+        Ok(Statement::new(
+            StatementKind::For {
+                bindings: vec![Binding::Global; variables.len()],
+                variables,
+                iterable,
+                body,
+            },
+            span,
+        ))
     }
 
     fn return_statement(&mut self) -> Result<Statement, AgamError> {
+        let start = self.current_span();
         self.advance(); // consume திரும்பு
 
         let value = if self.check(&TokenType::Newline) || self.is_at_end() {
             None
         } else {
-            Some(self.expression()?)
+            Some(self.tuple_expression()?)
         };
+        let span = self.span_from(start);
+
+        self.consume_newline_or_eof()?;
+
+        Ok(Statement::new(StatementKind::Return(value), span))
+    }
+
+    /// Parse `வெளிமாறி name, ...`
+    fn nonlocal_statement(&mut self) -> Result<Statement, AgamError> {
+        let start = self.current_span();
+        self.advance(); // consume வெளிமாறி
+
+        let mut names = vec![self.consume_identifier("மாறி பெயர் எதிர்பார்க்கப்படுகிறது")?];
+        while self.match_token(&[TokenType::Comma]) {
+            names.push(self.consume_identifier("மாறி பெயர் எதிர்பார்க்கப்படுகிறது")?);
+        }
+        let span = self.span_from(start);
 
         self.consume_newline_or_eof()?;
 
-        Ok(Statement::Return(value))
+        Ok(Statement::new(StatementKind::Nonlocal(names), span))
+    }
+
+    /// Parse `விளை value`
+    fn yield_statement(&mut self) -> Result<Statement, AgamError> {
+        let start = self.current_span();
+        self.advance(); // consume விளை
+
+        let value = self.tuple_expression()?;
+        let span = self.span_from(start);
+
+        self.consume_newline_or_eof()?;
+
+        Ok(Statement::new(StatementKind::Yield(value), span))
     }
 
     fn break_statement(&mut self) -> Result<Statement, AgamError> {
+        let span = self.current_span();
         self.advance(); // consume நிறுத்து
         self.consume_newline_or_eof()?;
-        Ok(Statement::Break)
+        Ok(Statement::new(StatementKind::Break, span))
     }
 
     fn continue_statement(&mut self) -> Result<Statement, AgamError> {
+        let span = self.current_span();
         self.advance(); // consume தொடர்
         self.consume_newline_or_eof()?;
-        Ok(Statement::Continue)
+        Ok(Statement::new(StatementKind::Continue, span))
     }
 
     fn print_statement(&mut self) -> Result<Statement, AgamError> {
+        let start = self.current_span();
         self.advance(); // consume அச்சிடு
         
         self.consume(&TokenType::LeftParen, "'(' எதிர்பார்க்கப்படுகிறது")?;
@@ -224,53 +344,97 @@ impl Parser {
         }
         
         self.consume(&TokenType::RightParen, "')' எதிர்பார்க்கப்படுகிறது")?;
+        let span = self.span_from(start);
         self.consume_newline_or_eof()?;
 
-        Ok(Statement::Print(args))
+        Ok(Statement::new(StatementKind::Print(args), span))
     }
 
     fn try_catch_statement(&mut self) -> Result<Statement, AgamError> {
+        let start = self.current_span();
         self.advance(); // consume முயற்சி
         self.consume(&TokenType::Colon, "':' எதிர்பார்க்கப்படுகிறது")?;
+        let span = self.span_from(start);
 
         let try_block = self.block()?;
 
-        // Expect catch block
+        // Catch clauses: பிடி name: or பிடி filter name:
+        let mut catch_clauses = Vec::new();
         self.skip_newlines();
-        self.consume(&TokenType::Pidi, "'பிடி' எதிர்பார்க்கப்படுகிறது")?;
-        let error_var = self.consume_identifier("பிழை மாறி பெயர் எதிர்பார்க்கப்படுகிறது")?;
-        self.consume(&TokenType::Colon, "':' எதிர்பார்க்கப்படுகிறது")?;
+        while self.check(&TokenType::Pidi) {
+            let clause_start = self.current_span();
+            self.advance();
+            let first = self.consume_identifier("பிழை மாறி பெயர் எதிர்பார்க்கப்படுகிறது")?;
+            let (filter, error_var) = if self.check(&TokenType::Colon) {
+                (None, first)
+            } else {
+                let name = self.consume_identifier("பிழை மாறி பெயர் எதிர்பார்க்கப்படுகிறது")?;
+                (Some(first), name)
+            };
+            self.consume(&TokenType::Colon, "':' எதிர்பார்க்கப்படுகிறது")?;
+            let clause_span = self.span_from(clause_start);
+            let body = self.block()?;
+            catch_clauses.push(CatchClause { filter, error_var, binding: Binding::Global, body, span: clause_span });
+            self.skip_newlines();
+        }
+
+        let finally_block = if self.check(&TokenType::Iruthiyaaga) {
+            self.advance();
+            self.consume(&TokenType::Colon, "':' எதிர்பார்க்கப்படுகிறது")?;
+            Some(self.block()?)
+        } else {
+            None
+        };
 
-        let catch_block = self.block()?;
+        if catch_clauses.is_empty() && finally_block.is_none() {
+            return Err(self.error("'பிடி' அல்லது 'இறுதியாக' எதிர்பார்க்கப்படுகிறது"));
+        }
 
-        Ok(Statement::TryCatch {
-            try_block,
-            error_var,
-            catch_block,
-        })
+        Ok(Statement::new(
+            StatementKind::TryCatch {
+                try_block,
+                catch_clauses,
+                finally_block,
+            },
+            span,
+        ))
     }
 
     fn throw_statement(&mut self) -> Result<Statement, AgamError> {
+        let start = self.current_span();
         self.advance(); // consume வீசு
-        let expr = self.expression()?;
+        // Bare வீசு re-raises the error being handled
+        let expr = if self.is_at_end() || self.check(&TokenType::Newline) || self.check(&TokenType::Dedent) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+        let span = self.span_from(start);
         self.consume_newline_or_eof()?;
-        Ok(Statement::Throw(expr))
+        Ok(Statement::new(StatementKind::Throw(expr), span))
     }
 
     fn import_statement(&mut self) -> Result<Statement, AgamError> {
+        let start = self.current_span();
         self.advance(); // consume இறக்குமதி
         
         let module = self.consume_identifier("கூறு பெயர் எதிர்பார்க்கப்படுகிறது")?;
+        let span = self.span_from(start);
         self.consume_newline_or_eof()?;
         
-        Ok(Statement::Import {
-            module,
-            items: None, // Import all items for now
-        })
+        Ok(Statement::new(
+            StatementKind::Import {
+                module,
+                items: None, // Import all items for now
+                bindings: vec![Binding::Global],
+            },
+            span,
+        ))
     }
 
     // Selective import: இருந்து module இறக்குமதி func1, func2
-    fn from_import_statement(&mut self) -> Result<Statement, AgamError> {
+    fn selective_import_statement(&mut self) -> Result<Statement, AgamError> {
+        let start = self.current_span();
         self.advance(); // consume இருந்து
         
         let module = self.consume_identifier("கூறு பெயர் எதிர்பார்க்கப்படுகிறது")?;
@@ -284,32 +448,47 @@ impl Parser {
         while self.match_token(&[TokenType::Comma]) {
             items.push(self.consume_identifier("இறக்குமதி பொருள் பெயர் எதிர்பார்க்கப்படுகிறது")?);
         }
+        let span = self.span_from(start);
         
         self.consume_newline_or_eof()?;
         
-        Ok(Statement::Import {
-            module,
-            items: Some(items),
-        })
+        Ok(Statement::new(
+            StatementKind::Import {
+                module,
+                bindings: vec![Binding::Global; items.len()],
+                items: Some(items),
+            },
+            span,
+        ))
     }
 
     fn struct_declaration(&mut self) -> Result<Statement, AgamError> {
+        let start = self.current_span();
         self.advance(); // consume கட்டமைப்பு
 
         let name = self.consume_identifier("கட்டமைப்பு பெயர் எதிர்பார்க்கப்படுகிறது")?;
         self.consume(&TokenType::Colon, "':' எதிர்பார்க்கப்படுகிறது")?;
+        let span = self.span_from(start);
 
         // Parse fields in block
         self.skip_newlines();
         self.consume(&TokenType::Indent, "உள்தள்ளுதல் எதிர்பார்க்கப்படுகிறது")?;
 
         let mut fields = Vec::new();
+        let mut methods = Vec::new();
         while !self.check(&TokenType::Dedent) && !self.is_at_end() {
             self.skip_newlines();
             if self.check(&TokenType::Dedent) || self.is_at_end() {
                 break;
             }
 
+            // Method: செயல் name(params): with an implicit தன் receiver
+            if self.check(&TokenType::Seyal) {
+                let (name, params, body, span) = self.function_parts(vec![SELF_NAME.to_string()])?;
+                methods.push(Method { name, params, body, span });
+                continue;
+            }
+
             let field_name = self.consume_identifier("புலம் பெயர் எதிர்பார்க்கப்படுகிறது")?;
             let field_type = if self.match_token(&[TokenType::Colon]) {
                 Some(self.consume_identifier("வகை எதிர்பார்க்கப்படுகிறது")?)
@@ -324,14 +503,16 @@ impl Parser {
             self.advance();
         }
 
-        Ok(Statement::Struct { name, fields })
+        Ok(Statement::new(StatementKind::Struct { name, fields, methods, binding: Binding::Global }, span))
     }
 
     fn enum_declaration(&mut self) -> Result<Statement, AgamError> {
+        let start = self.current_span();
         self.advance(); // consume விருப்பம்
 
         let name = self.consume_identifier("விருப்பம் பெயர் எதிர்பார்க்கப்படுகிறது")?;
         self.consume(&TokenType::Colon, "':' எதிர்பார்க்கப்படுகிறது")?;
+        let span = self.span_from(start);
 
         // Parse variants in block
         self.skip_newlines();
@@ -345,7 +526,20 @@ impl Parser {
             }
 
             let variant = self.consume_identifier("மாறுபாடு பெயர் எதிர்பார்க்கப்படுகிறது")?;
-            variants.push(variant);
+            // Payload fields: Circle(radius)
+            let mut fields = Vec::new();
+            if self.match_token(&[TokenType::LeftParen]) {
+                if !self.check(&TokenType::RightParen) {
+                    loop {
+                        fields.push(self.consume_identifier("புலம் பெயர் எதிர்பார்க்கப்படுகிறது")?);
+                        if !self.match_token(&[TokenType::Comma]) {
+                            break;
+                        }
+                    }
+                }
+                self.consume(&TokenType::RightParen, "')' எதிர்பார்க்கப்படுகிறது")?;
+            }
+            variants.push((variant, fields));
             self.consume_newline_or_eof()?;
         }
 
@@ -353,14 +547,16 @@ impl Parser {
             self.advance();
         }
 
-        Ok(Statement::Enum { name, variants })
+        Ok(Statement::new(StatementKind::Enum { name, variants, binding: Binding::Global }, span))
     }
 
     fn match_statement(&mut self) -> Result<Statement, AgamError> {
+        let start = self.current_span();
         self.advance(); // consume பொருத்து
 
         let value = self.expression()?;
         self.consume(&TokenType::Colon, "':' எதிர்பார்க்கப்படுகிறது")?;
+        let span = self.span_from(start);
 
         // Parse arms in block
         self.skip_newlines();
@@ -373,9 +569,7 @@ impl Parser {
                 break;
             }
 
-            // Parse pattern
-            let pattern = self.parse_pattern()?;
-            self.consume(&TokenType::Arrow, "'=>' எதிர்பார்க்கப்படுகிறது")?;
+            let (pattern, guard, arm_span) = self.match_arm_head()?;
 
             // Parse body (single statement or block)
             let body = if self.check(&TokenType::Newline) {
@@ -385,14 +579,55 @@ impl Parser {
                 vec![self.statement()?]
             };
 
-            arms.push(MatchArm { pattern, body });
+            arms.push(MatchArm { pattern, guard, body, span: arm_span });
         }
 
         if self.check(&TokenType::Dedent) {
             self.advance();
         }
 
-        Ok(Statement::Match { value, arms })
+        Ok(Statement::new(StatementKind::Match { value, arms }, span))
+    }
+
+    /// Match expression: arms are `pattern => expression`, one per line
+    fn match_expression(&mut self, start: Span) -> Result<Expression, AgamError> {
+        let value = self.expression()?;
+        self.consume(&TokenType::Colon, "':' எதிர்பார்க்கப்படுகிறது")?;
+        let span = self.span_from(start);
+
+        self.skip_newlines();
+        self.consume(&TokenType::Indent, "உள்தள்ளுதல் எதிர்பார்க்கப்படுகிறது")?;
+
+        let mut arms = Vec::new();
+        while !self.check(&TokenType::Dedent) && !self.is_at_end() {
+            self.skip_newlines();
+            if self.check(&TokenType::Dedent) || self.is_at_end() {
+                break;
+            }
+
+            let (pattern, guard, arm_span) = self.match_arm_head()?;
+            let value = self.expression()?;
+            self.consume_newline_or_eof()?;
+            arms.push(MatchExprArm { pattern, guard, value, span: arm_span });
+        }
+
+        self.end_expression_block();
+
+        Ok(Expression::new(ExpressionKind::Match { value: Box::new(value), arms }, span))
+    }
+
+    /// Pattern and optional guard of a match arm, up to and including `=>`
+    fn match_arm_head(&mut self) -> Result<(Pattern, Option<Expression>, Span), AgamError> {
+        let arm_start = self.current_span();
+        let pattern = self.parse_pattern()?;
+        let arm_span = self.span_from(arm_start);
+        let guard = if self.match_token(&[TokenType::Endraal]) {
+            Some(self.expression()?)
+        } else {
+            None
+        };
+        self.consume(&TokenType::Arrow, "'=>' எதிர்பார்க்கப்படுகிறது")?;
+        Ok((pattern, guard, arm_span))
     }
 
     fn parse_pattern(&mut self) -> Result<Pattern, AgamError> {
@@ -401,53 +636,217 @@ impl Parser {
             return Ok(Pattern::Wildcard);
         }
 
+        let span = self.current_span();
+
         // Number literal pattern
+        if let Some(TokenType::Integer(n)) = self.peek_token_type() {
+            let n = Integer::from(n.clone());
+            self.advance();
+            return Ok(Pattern::Literal(Expression::new(ExpressionKind::Integer(n), span)));
+        }
         if let Some(TokenType::Number(n)) = self.peek_token_type() {
             let n = *n;
             self.advance();
-            return Ok(Pattern::Literal(Expression::Number(n)));
+            return Ok(Pattern::Literal(Expression::new(ExpressionKind::Number(n), span)));
         }
 
         // String literal pattern
         if let Some(TokenType::String(s)) = self.peek_token_type() {
             let s = s.clone();
             self.advance();
-            return Ok(Pattern::Literal(Expression::String(s)));
+            return Ok(Pattern::Literal(Expression::new(ExpressionKind::String(s), span)));
         }
 
         // Boolean patterns
         if self.match_token(&[TokenType::Unmai]) {
-            return Ok(Pattern::Literal(Expression::Boolean(true)));
+            return Ok(Pattern::Literal(Expression::new(ExpressionKind::Boolean(true), span)));
         }
         if self.match_token(&[TokenType::Poi]) {
-            return Ok(Pattern::Literal(Expression::Boolean(false)));
+            return Ok(Pattern::Literal(Expression::new(ExpressionKind::Boolean(false), span)));
         }
 
-        // Identifier (could be variable binding or enum variant)
+        // List pattern: [first, ...rest]
+        if self.match_token(&[TokenType::LeftBracket]) {
+            let mut elements = Vec::new();
+            let mut rest = None;
+            while !self.check(&TokenType::RightBracket) {
+                if self.match_token(&[TokenType::Ellipsis]) {
+                    rest = Some(Box::new(self.rest_pattern()?));
+                    if !self.check(&TokenType::RightBracket) {
+                        return Err(self.error("'...' பட்டியல் வடிவத்தின் கடைசியில் மட்டுமே வர வேண்டும்"));
+                    }
+                    break;
+                }
+                elements.push(self.parse_pattern()?);
+                if !self.match_token(&[TokenType::Comma]) {
+                    break;
+                }
+            }
+            self.consume(&TokenType::RightBracket, "']' எதிர்பார்க்கப்படுகிறது")?;
+            return Ok(Pattern::List { elements, rest });
+        }
+
+        // Dictionary pattern: {"key": pattern}
+        if self.match_token(&[TokenType::LeftBrace]) {
+            let mut entries = Vec::new();
+            while !self.check(&TokenType::RightBrace) {
+                let key = match self.peek_token_type() {
+                    Some(TokenType::String(s)) => s.clone(),
+                    _ => return Err(self.error("அகராதி வடிவத்தில் சர திறவுகோல் எதிர்பார்க்கப்படுகிறது")),
+                };
+                self.advance();
+                self.consume(&TokenType::Colon, "':' எதிர்பார்க்கப்படுகிறது")?;
+                entries.push((key, self.parse_pattern()?));
+                if !self.match_token(&[TokenType::Comma]) {
+                    break;
+                }
+            }
+            self.consume(&TokenType::RightBrace, "'}' எதிர்பார்க்கப்படுகிறது")?;
+            return Ok(Pattern::Dict(entries));
+        }
+
+        // Identifier (variable binding, enum variant or struct pattern)
         if let Some(TokenType::Identifier(name)) = self.peek_token_type() {
             let name = name.clone();
             self.advance();
 
-            // Check for enum variant pattern (EnumName.Variant)
+            // Enum variant pattern: EnumName.Variant or EnumName.Variant(p, ...)
             if self.match_token(&[TokenType::Dot]) {
                 let variant = self.consume_identifier("மாறுபாடு பெயர் எதிர்பார்க்கப்படுகிறது")?;
-                return Ok(Pattern::EnumVariant(name, variant));
+                let payload = if self.match_token(&[TokenType::LeftParen]) {
+                    let mut parts = Vec::new();
+                    while !self.check(&TokenType::RightParen) {
+                        parts.push(self.parse_pattern()?);
+                        if !self.match_token(&[TokenType::Comma]) {
+                            break;
+                        }
+                    }
+                    self.consume(&TokenType::RightParen, "')' எதிர்பார்க்கப்படுகிறது")?;
+                    Some(parts)
+                } else {
+                    None
+                };
+                return Ok(Pattern::EnumVariant { enum_name: name, variant, payload });
+            }
+
+            // Struct pattern: Point{x, y: pattern}
+            if self.match_token(&[TokenType::LeftBrace]) {
+                let mut fields = Vec::new();
+                while !self.check(&TokenType::RightBrace) {
+                    let field = self.consume_identifier("புலம் பெயர் எதிர்பார்க்கப்படுகிறது")?;
+                    let pattern = if self.match_token(&[TokenType::Colon]) {
+                        self.parse_pattern()?
+                    } else {
+                        // Shorthand binds the field to a variable of the same name
+                        Pattern::Variable { name: field.clone(), binding: Binding::Global }
+                    };
+                    fields.push((field, pattern));
+                    if !self.match_token(&[TokenType::Comma]) {
+                        break;
+                    }
+                }
+                self.consume(&TokenType::RightBrace, "'}' எதிர்பார்க்கப்படுகிறது")?;
+                return Ok(Pattern::Struct { name, fields });
             }
 
             // Variable binding pattern
-            return Ok(Pattern::Variable(name));
+            return Ok(Pattern::Variable { name, binding: Binding::Global });
         }
 
         Err(self.error("வடிவம் எதிர்பார்க்கப்படுகிறது"))
     }
 
+    /// The name after `...` in a list pattern, or `_` to ignore the rest
+    fn rest_pattern(&mut self) -> Result<Pattern, AgamError> {
+        if self.match_token(&[TokenType::Underscore]) {
+            return Ok(Pattern::Wildcard);
+        }
+        let name = self.consume_identifier("'...' பின் பெயர் எதிர்பார்க்கப்படுகிறது")?;
+        Ok(Pattern::Variable { name, binding: Binding::Global })
+    }
+
     fn expression_statement(&mut self) -> Result<Statement, AgamError> {
-        let expr = self.expression()?;
+        let mut expr = self.expression()?;
+        if self.check(&TokenType::Comma) {
+            match &mut expr.kind {
+                // `x = 1, 2` assigns a tuple
+                ExpressionKind::Assignment { operator: None, value, .. }
+                | ExpressionKind::IndexAssignment { operator: None, value, .. }
+                | ExpressionKind::MemberAssignment { operator: None, value, .. } => {
+                    let first = std::mem::replace(&mut **value, Expression::new(ExpressionKind::Null, expr.span));
+                    **value = self.tuple_rest(first)?;
+                    expr.span = expr.span.to(value.span);
+                }
+                _ => return self.multiple_assignment(expr),
+            }
+        }
+        let span = expr.span;
+        self.consume_newline_or_eof()?;
+        Ok(Statement::new(StatementKind::Expression(expr), span))
+    }
+
+    /// Parse the rest of `a, b = b, a` after its first target, or of a
+    /// bare tuple statement `a, b`
+    fn multiple_assignment(&mut self, first: Expression) -> Result<Statement, AgamError> {
+        let mut targets = vec![first];
+        while self.match_token(&[TokenType::Comma]) {
+            targets.push(self.or()?);
+        }
+        let start = targets[0].span;
+
+        if !self.match_token(&[TokenType::Equal]) {
+            let span = start.to(targets[targets.len() - 1].span);
+            self.consume_newline_or_eof()?;
+            return Ok(Statement::new(StatementKind::Expression(Expression::new(ExpressionKind::Tuple(targets), span)), span));
+        }
+        for target in &targets {
+            let assignable = matches!(
+                target.kind,
+                ExpressionKind::Identifier { .. } | ExpressionKind::Index { .. } | ExpressionKind::MemberAccess { .. }
+            );
+            if !assignable {
+                return Err(AgamError::parser_error(
+                    target.span.line,
+                    target.span.column,
+                    "தவறான ஒதுக்கீடு இலக்கு".to_string(),
+                ));
+            }
+        }
+        let value = self.tuple_expression()?;
+        let span = start.to(value.span);
         self.consume_newline_or_eof()?;
-        Ok(Statement::Expression(expr))
+        Ok(Statement::new(StatementKind::AssignUnpack { targets, value }, span))
+    }
+
+    /// Parse an expression, or a bare tuple `a, b` where a statement allows one
+    fn tuple_expression(&mut self) -> Result<Expression, AgamError> {
+        let first = self.expression()?;
+        self.tuple_rest(first)
+    }
+
+    /// Continue `first` into a bare tuple if a comma follows it
+    fn tuple_rest(&mut self, first: Expression) -> Result<Expression, AgamError> {
+        if !self.check(&TokenType::Comma) {
+            return Ok(first);
+        }
+        let mut elements = vec![first];
+        while self.match_token(&[TokenType::Comma]) {
+            elements.push(self.or()?);
+        }
+        let span = elements[0].span.to(elements[elements.len() - 1].span);
+        Ok(Expression::new(ExpressionKind::Tuple(elements), span))
     }
 
     fn block(&mut self) -> Result<Vec<Statement>, AgamError> {
+        let statements = self.block_contents()?;
+        if self.check(&TokenType::Dedent) {
+            self.advance();
+        }
+        Ok(statements)
+    }
+
+    /// Parse an indented block, stopping at the dedent that closes it
+    fn block_contents(&mut self) -> Result<Vec<Statement>, AgamError> {
         self.skip_newlines();
         self.consume(&TokenType::Indent, "உள்தள்ளுதல் எதிர்பார்க்கப்படுகிறது")?;
         
@@ -460,14 +859,27 @@ impl Parser {
             }
             statements.push(self.declaration()?);
         }
-        
-        if self.check(&TokenType::Dedent) {
-            self.advance();
-        }
 
         Ok(statements)
     }
 
+    /// Close an indented block that is part of an expression. Its dedent
+    /// also ends the line holding the expression, unless the expression
+    /// goes on with `)`, `]` or `,` (a block lambda passed as an argument)
+    fn end_expression_block(&mut self) {
+        if !self.check(&TokenType::Dedent) {
+            return;
+        }
+        let dedent = self.advance().clone();
+        let continues = [TokenType::RightParen, TokenType::RightBracket, TokenType::Comma]
+            .iter()
+            .any(|token| self.check(token));
+        if !continues {
+            let newline = Token::new(TokenType::Newline, String::new(), dedent.line, dedent.column);
+            self.tokens.insert(self.current, newline);
+        }
+    }
+
     // Expression parsing with precedence climbing
     fn expression(&mut self) -> Result<Expression, AgamError> {
         self.assignment()
@@ -476,39 +888,65 @@ impl Parser {
     fn assignment(&mut self) -> Result<Expression, AgamError> {
         let expr = self.or()?;
 
-        if self.match_token(&[TokenType::Equal]) {
-            let value = self.assignment()?;
-            
+        // `=`, or a compound operator such as `+=`
+        let operator = if self.match_token(&[TokenType::Equal]) {
+            None
+        } else if let Some(operator) = self.compound_operator() {
+            self.advance();
+            Some(operator)
+        } else {
+            return Ok(expr);
+        };
+
+        let value = self.assignment()?;
+        let span = expr.span.to(value.span);
+        let value = Box::new(value);
+
+        match expr.kind {
             // Simple identifier assignment
-            if let Expression::Identifier(name) = expr {
-                return Ok(Expression::Assignment {
-                    name,
-                    value: Box::new(value),
-                });
-            }
-            
+            ExpressionKind::Identifier { name, .. } => Ok(Expression::new(
+                ExpressionKind::Assignment { name, operator, value, binding: Binding::Global },
+                span,
+            )),
+
             // Index assignment: list[0] = value, dict["key"] = value
-            if let Expression::Index { object, index } = expr {
-                return Ok(Expression::IndexAssignment {
-                    object,
-                    index,
-                    value: Box::new(value),
-                });
-            }
-            
+            ExpressionKind::Index { object, index } => Ok(Expression::new(
+                ExpressionKind::IndexAssignment { object, index, operator, value },
+                span,
+            )),
+
+            // Slice assignment: list[1:3] = values
+            ExpressionKind::Slice { object, bounds } if operator.is_none() => Ok(Expression::new(
+                ExpressionKind::SliceAssignment { object, bounds, value },
+                span,
+            )),
+
             // Member assignment: struct.field = value
-            if let Expression::MemberAccess { object, member } = expr {
-                return Ok(Expression::MemberAssignment {
-                    object,
-                    member,
-                    value: Box::new(value),
-                });
-            }
-            
-            return Err(self.error("தவறான ஒதுக்கீடு இலக்கு"));
+            ExpressionKind::MemberAccess { object, member } => Ok(Expression::new(
+                ExpressionKind::MemberAssignment { object, member, operator, value },
+                span,
+            )),
+
+            _ => Err(AgamError::parser_error(
+                expr.span.line,
+                expr.span.column,
+                "தவறான ஒதுக்கீடு இலக்கு".to_string(),
+            )),
         }
+    }
 
-        Ok(expr)
+    /// The operator of a compound assignment token: `+=` is `+`
+    fn compound_operator(&self) -> Option<BinaryOp> {
+        match self.peek_token_type()? {
+            TokenType::PlusEqual => Some(BinaryOp::Add),
+            TokenType::MinusEqual => Some(BinaryOp::Subtract),
+            TokenType::StarEqual => Some(BinaryOp::Multiply),
+            TokenType::SlashEqual => Some(BinaryOp::Divide),
+            TokenType::PercentEqual => Some(BinaryOp::Modulo),
+            TokenType::StarStarEqual => Some(BinaryOp::Power),
+            TokenType::SlashSlashEqual => Some(BinaryOp::FloorDivide),
+            _ => None,
+        }
     }
 
     fn or(&mut self) -> Result<Expression, AgamError> {
@@ -516,31 +954,41 @@ impl Parser {
 
         while self.match_token(&[TokenType::Alladhu]) {
             let right = self.and()?;
-            expr = Expression::Binary {
-                left: Box::new(expr),
-                operator: BinaryOp::Or,
-                right: Box::new(right),
-            };
+            expr = Self::binary(expr, BinaryOp::Or, right);
         }
 
         Ok(expr)
     }
 
     fn and(&mut self) -> Result<Expression, AgamError> {
-        let mut expr = self.equality()?;
+        let mut expr = self.not()?;
 
         while self.match_token(&[TokenType::Matrum]) {
-            let right = self.equality()?;
-            expr = Expression::Binary {
-                left: Box::new(expr),
-                operator: BinaryOp::And,
-                right: Box::new(right),
-            };
+            let right = self.not()?;
+            expr = Self::binary(expr, BinaryOp::And, right);
         }
 
         Ok(expr)
     }
 
+    /// `இல்ல` binds looser than comparisons, so `இல்ல x உள்ள l` is `இல்ல (x உள்ள l)`
+    fn not(&mut self) -> Result<Expression, AgamError> {
+        let start = self.current_span();
+
+        if !self.match_token(&[TokenType::Illamal]) {
+            return self.equality();
+        }
+
+        let operand = self.not()?;
+        Ok(Expression::new(
+            ExpressionKind::Unary {
+                operator: UnaryOp::Not,
+                operand: Box::new(operand),
+            },
+            self.span_from(start),
+        ))
+    }
+
     fn equality(&mut self) -> Result<Expression, AgamError> {
         let mut expr = self.comparison()?;
 
@@ -551,38 +999,81 @@ impl Parser {
                 _ => unreachable!(),
             };
             let right = self.comparison()?;
-            expr = Expression::Binary {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
-            };
+            expr = Self::binary(expr, operator, right);
         }
 
         Ok(expr)
     }
 
     fn comparison(&mut self) -> Result<Expression, AgamError> {
-        let mut expr = self.term()?;
+        let mut expr = self.bit_or()?;
 
         while self.match_token(&[
             TokenType::Less,
             TokenType::Greater,
             TokenType::LessEqual,
             TokenType::GreaterEqual,
+            TokenType::Ulla,
         ]) {
             let operator = match self.previous().token_type {
                 TokenType::Less => BinaryOp::Less,
                 TokenType::Greater => BinaryOp::Greater,
                 TokenType::LessEqual => BinaryOp::LessEqual,
                 TokenType::GreaterEqual => BinaryOp::GreaterEqual,
+                TokenType::Ulla => BinaryOp::In,
                 _ => unreachable!(),
             };
-            let right = self.term()?;
-            expr = Expression::Binary {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
+            let right = self.bit_or()?;
+            expr = Self::binary(expr, operator, right);
+        }
+
+        Ok(expr)
+    }
+
+    fn bit_or(&mut self) -> Result<Expression, AgamError> {
+        let mut expr = self.bit_xor()?;
+
+        while self.match_token(&[TokenType::Pipe]) {
+            let right = self.bit_xor()?;
+            expr = Self::binary(expr, BinaryOp::BitOr, right);
+        }
+
+        Ok(expr)
+    }
+
+    fn bit_xor(&mut self) -> Result<Expression, AgamError> {
+        let mut expr = self.bit_and()?;
+
+        while self.match_token(&[TokenType::Caret]) {
+            let right = self.bit_and()?;
+            expr = Self::binary(expr, BinaryOp::BitXor, right);
+        }
+
+        Ok(expr)
+    }
+
+    fn bit_and(&mut self) -> Result<Expression, AgamError> {
+        let mut expr = self.shift()?;
+
+        while self.match_token(&[TokenType::Ampersand]) {
+            let right = self.shift()?;
+            expr = Self::binary(expr, BinaryOp::BitAnd, right);
+        }
+
+        Ok(expr)
+    }
+
+    fn shift(&mut self) -> Result<Expression, AgamError> {
+        let mut expr = self.term()?;
+
+        while self.match_token(&[TokenType::LessLess, TokenType::GreaterGreater]) {
+            let operator = match self.previous().token_type {
+                TokenType::LessLess => BinaryOp::ShiftLeft,
+                TokenType::GreaterGreater => BinaryOp::ShiftRight,
+                _ => unreachable!(),
             };
+            let right = self.term()?;
+            expr = Self::binary(expr, operator, right);
         }
 
         Ok(expr)
@@ -598,11 +1089,7 @@ impl Parser {
                 _ => unreachable!(),
             };
             let right = self.factor()?;
-            expr = Expression::Binary {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
-            };
+            expr = Self::binary(expr, operator, right);
         }
 
         Ok(expr)
@@ -611,63 +1098,120 @@ impl Parser {
     fn factor(&mut self) -> Result<Expression, AgamError> {
         let mut expr = self.unary()?;
 
-        while self.match_token(&[TokenType::Star, TokenType::Slash, TokenType::Percent]) {
+        while self.match_token(&[
+            TokenType::Star,
+            TokenType::Slash,
+            TokenType::SlashSlash,
+            TokenType::Percent,
+        ]) {
             let operator = match self.previous().token_type {
                 TokenType::Star => BinaryOp::Multiply,
                 TokenType::Slash => BinaryOp::Divide,
+                TokenType::SlashSlash => BinaryOp::FloorDivide,
                 TokenType::Percent => BinaryOp::Modulo,
                 _ => unreachable!(),
             };
             let right = self.unary()?;
-            expr = Expression::Binary {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
-            };
+            expr = Self::binary(expr, operator, right);
         }
 
         Ok(expr)
     }
 
+    /// Build a binary expression spanning both operands
+    fn binary(left: Expression, operator: BinaryOp, right: Expression) -> Expression {
+        let span = left.span.to(right.span);
+        Expression::new(
+            ExpressionKind::Binary {
+                left: Box::new(left),
+                operator,
+                right: Box::new(right),
+            },
+            span,
+        )
+    }
+
     fn unary(&mut self) -> Result<Expression, AgamError> {
-        if self.match_token(&[TokenType::Minus]) {
-            let operand = self.unary()?;
-            return Ok(Expression::Unary {
-                operator: UnaryOp::Negate,
-                operand: Box::new(operand),
-            });
-        }
+        let start = self.current_span();
 
-        if self.match_token(&[TokenType::Illamal]) {
-            let operand = self.unary()?;
-            return Ok(Expression::Unary {
-                operator: UnaryOp::Not,
+        let operator = if self.match_token(&[TokenType::Minus]) {
+            UnaryOp::Negate
+        } else if self.match_token(&[TokenType::Tilde]) {
+            UnaryOp::BitNot
+        } else {
+            return self.power();
+        };
+
+        let operand = self.unary()?;
+        Ok(Expression::new(
+            ExpressionKind::Unary {
+                operator,
                 operand: Box::new(operand),
-            });
+            },
+            self.span_from(start),
+        ))
+    }
+
+    /// `**` binds tighter than unary minus on its left and is right-associative
+    fn power(&mut self) -> Result<Expression, AgamError> {
+        let expr = self.call()?;
+
+        if self.match_token(&[TokenType::StarStar]) {
+            let right = self.unary()?;
+            return Ok(Self::binary(expr, BinaryOp::Power, right));
         }
 
-        self.call()
+        Ok(expr)
     }
 
     fn call(&mut self) -> Result<Expression, AgamError> {
+        let start = self.current_span();
         let mut expr = self.primary()?;
 
         loop {
             if self.match_token(&[TokenType::LeftParen]) {
                 expr = self.finish_call(expr)?;
             } else if self.match_token(&[TokenType::LeftBracket]) {
-                let index = self.expression()?;
-                self.consume(&TokenType::RightBracket, "']' எதிர்பார்க்கப்படுகிறது")?;
-                expr = Expression::Index {
-                    object: Box::new(expr),
-                    index: Box::new(index),
+                let index = self.slice_bound()?;
+                if self.match_token(&[TokenType::Colon]) {
+                    // Slice: [start:end] or [start:end:step]
+                    let end = self.slice_bound()?;
+                    let step = if self.match_token(&[TokenType::Colon]) {
+                        self.slice_bound()?
+                    } else {
+                        None
+                    };
+                    self.consume(&TokenType::RightBracket, "']' எதிர்பார்க்கப்படுகிறது")?;
+                    expr = Expression::new(
+                        ExpressionKind::Slice {
+                            object: Box::new(expr),
+                            bounds: SliceBounds { start: index, end, step },
+                        },
+                        self.span_from(start),
+                    );
+                    continue;
+                }
+                let index = match index {
+                    Some(index) => index,
+                    None => return Err(self.error("குறியீடு எதிர்பார்க்கப்படுகிறது")),
                 };
+                self.consume(&TokenType::RightBracket, "']' எதிர்பார்க்கப்படுகிறது")?;
+                expr = Expression::new(
+                    ExpressionKind::Index {
+                        object: Box::new(expr),
+                        index,
+                    },
+                    self.span_from(start),
+                );
             } else if self.match_token(&[TokenType::Dot]) {
                 let member = self.consume_identifier("புலம் பெயர் எதிர்பார்க்கப்படுகிறது")?;
-                expr = Expression::MemberAccess {
-                    object: Box::new(expr),
-                    member,
-                };
+                expr = Expression::new(
+                    ExpressionKind::MemberAccess {
+                        object: Box::new(expr),
+                        member,
+                    },
+                    self.span_from(start),
+                );
             } else {
                 break;
             }
@@ -676,12 +1220,42 @@ impl Parser {
         Ok(expr)
     }
 
+    /// An index or slice bound, absent before `:` or `]`
+    fn slice_bound(&mut self) -> Result<Option<Box<Expression>>, AgamError> {
+        if self.check(&TokenType::Colon) || self.check(&TokenType::RightBracket) {
+            return Ok(None);
+        }
+        Ok(Some(Box::new(self.expression()?)))
+    }
+
     fn finish_call(&mut self, callee: Expression) -> Result<Expression, AgamError> {
         let mut arguments = Vec::new();
+        let mut keywords: Vec<(String, Expression)> = Vec::new();
 
         if !self.check(&TokenType::RightParen) {
             loop {
-                arguments.push(self.expression()?);
+                let argument = self.expression()?;
+                match argument.kind {
+                    // Keyword argument: name = value
+                    ExpressionKind::Assignment { name, operator: None, value, .. } => {
+                        if keywords.iter().any(|(existing, _)| *existing == name) {
+                            return Err(AgamError::parser_error(
+                                argument.span.line,
+                                argument.span.column,
+                                format!("'{}' அளவுரு இருமுறை கொடுக்கப்பட்டது", name),
+                            ));
+                        }
+                        keywords.push((name, *value));
+                    }
+                    _ if !keywords.is_empty() => {
+                        return Err(AgamError::parser_error(
+                            argument.span.line,
+                            argument.span.column,
+                            "பெயரிட்ட அளவுருக்களுக்குப் பின் நிலை அளவுருக்கள் வர இயலாது".to_string(),
+                        ));
+                    }
+                    _ => arguments.push(argument),
+                }
                 if !self.match_token(&[TokenType::Comma]) {
                     break;
                 }
@@ -689,113 +1263,136 @@ impl Parser {
         }
 
         self.consume(&TokenType::RightParen, "')' எதிர்பார்க்கப்படுகிறது")?;
-
-        Ok(Expression::Call {
-            callee: Box::new(callee),
-            arguments,
-        })
+        let span = self.span_from(callee.span);
+
+        Ok(Expression::new(
+            ExpressionKind::Call {
+                callee: Box::new(callee),
+                arguments,
+                keywords,
+            },
+            span,
+        ))
     }
 
     fn primary(&mut self) -> Result<Expression, AgamError> {
+        let start = self.current_span();
+
         // Boolean true
         if self.match_token(&[TokenType::Unmai]) {
-            return Ok(Expression::Boolean(true));
+            return Ok(Expression::new(ExpressionKind::Boolean(true), start));
         }
 
         // Boolean false
         if self.match_token(&[TokenType::Poi]) {
-            return Ok(Expression::Boolean(false));
+            return Ok(Expression::new(ExpressionKind::Boolean(false), start));
         }
 
         // Null
         if self.match_token(&[TokenType::Illa]) {
-            return Ok(Expression::Null);
+            return Ok(Expression::new(ExpressionKind::Null, start));
         }
 
         // Number
+        if let Some(TokenType::Integer(n)) = self.peek_token_type() {
+            let n = Integer::from(n.clone());
+            self.advance();
+            return Ok(Expression::new(ExpressionKind::Integer(n), start));
+        }
         if let Some(TokenType::Number(n)) = self.peek_token_type() {
             let n = *n;
             self.advance();
-            return Ok(Expression::Number(n));
+            return Ok(Expression::new(ExpressionKind::Number(n), start));
         }
 
         // String
         if let Some(TokenType::String(s)) = self.peek_token_type() {
             let s = s.clone();
             self.advance();
-            return Ok(Expression::String(s));
+            return Ok(Expression::new(ExpressionKind::String(s), start));
         }
 
         // F-String (interpolated string): f"Hello {name}!"
         if let Some(TokenType::FString(content)) = self.peek_token_type() {
             let content = content.clone();
             self.advance();
-            return self.parse_fstring_parts(&content);
+            return self.parse_fstring_parts(&content, start);
         }
 
         // Identifier
         if let Some(TokenType::Identifier(name)) = self.peek_token_type() {
             let name = name.clone();
             self.advance();
-            return Ok(Expression::Identifier(name));
+            return Ok(Expression::new(ExpressionKind::Identifier { name, binding: Binding::Global }, start));
+        }
+
+        // Method receiver (தன் / self)
+        if self.match_token(&[TokenType::Than]) {
+            return Ok(Expression::new(
+                ExpressionKind::Identifier { name: SELF_NAME.to_string(), binding: Binding::Global },
+                start,
+            ));
         }
 
         // Input function (உள்ளீடு / input) - treat keyword as callable identifier
         if self.match_token(&[TokenType::Ulleedu]) {
-            return Ok(Expression::Identifier("உள்ளீடு".to_string()));
+            return Ok(Expression::new(
+                ExpressionKind::Identifier { name: "உள்ளீடு".to_string(), binding: Binding::Global },
+                start,
+            ));
         }
 
         // Lambda expression: செயலி(params): expression or lambda(params): expression
         if self.match_token(&[TokenType::Seyali]) {
-            return self.lambda_expression();
+            return self.lambda_expression(start);
+        }
+
+        // Match expression: பொருத்து value: followed by an indented block of arms
+        if self.match_token(&[TokenType::Poruthu]) {
+            return self.match_expression(start);
         }
 
         // Grouping or arrow lambda: (expr) or (params) => expr
         if self.match_token(&[TokenType::LeftParen]) {
-            // Check if this might be an arrow lambda
-            // Try to parse as parameters first, then check for =>
-            let start = self.current;
-            
-            // Try parsing as parameter list
-            let mut params = Vec::new();
-            let mut might_be_lambda = true;
+            // Check if this might be an arrow lambda: try parsing a
+            // parameter list, then check for ) =>
+            let checkpoint = self.current;
+            if let Ok(params) = self.parameters(Vec::new()) {
+                if self.match_token(&[TokenType::RightParen]) && self.match_token(&[TokenType::Arrow]) {
+                    let body = self.expression()?;
+                    return Ok(Expression::new(
+                        ExpressionKind::Lambda {
+                            params,
+                            body: LambdaBody::Expression(Box::new(body)),
+                        },
+                        self.span_from(start),
+                    ));
+                }
+            }
             
-            if !self.check(&TokenType::RightParen) {
-                loop {
-                    if let Some(TokenType::Identifier(name)) = self.peek_token_type() {
-                        params.push(name.clone());
-                        self.advance();
-                    } else {
-                        // Not a valid parameter, this is a grouping
-                        might_be_lambda = false;
-                        break;
-                    }
-                    
+            // Not a lambda, backtrack and parse as a tuple or grouping
+            self.current = checkpoint;
+            if self.match_token(&[TokenType::RightParen]) {
+                return Ok(Expression::new(ExpressionKind::Tuple(Vec::new()), self.span_from(start)));
+            }
+            let expr = self.expression()?;
+            if self.match_token(&[TokenType::Comma]) {
+                // (a, b) or (a,)
+                let mut elements = vec![expr];
+                while !self.check(&TokenType::RightParen) {
+                    elements.push(self.expression()?);
                     if !self.match_token(&[TokenType::Comma]) {
                         break;
                     }
                 }
+                self.consume(&TokenType::RightParen, "')' எதிர்பார்க்கப்படுகிறது")?;
+                return Ok(Expression::new(ExpressionKind::Tuple(elements), self.span_from(start)));
             }
-            
-            // Check for ) =>
-            if might_be_lambda && self.check(&TokenType::RightParen) {
-                self.advance(); // consume )
-                
-                if self.check(&TokenType::Arrow) {
-                    self.advance(); // consume =>
-                    let body = self.expression()?;
-                    return Ok(Expression::Lambda {
-                        params,
-                        body: Box::new(body),
-                    });
-                }
-            }
-            
-            // Not a lambda, backtrack and parse as grouping
-            self.current = start;
-            let expr = self.expression()?;
             self.consume(&TokenType::RightParen, "')' எதிர்பார்க்கப்படுகிறது")?;
-            return Ok(Expression::Grouping(Box::new(expr)));
+            return Ok(Expression::new(
+                ExpressionKind::Grouping(Box::new(expr)),
+                self.span_from(start),
+            ));
         }
 
         // List literal
@@ -804,13 +1401,22 @@ impl Parser {
             if !self.check(&TokenType::RightBracket) {
                 loop {
                     elements.push(self.expression()?);
+                    if elements.len() == 1 && self.check(&TokenType::Ovvoru) {
+                        let element = Box::new(elements.remove(0));
+                        let clauses = self.comprehension_clauses()?;
+                        self.consume(&TokenType::RightBracket, "']' எதிர்பார்க்கப்படுகிறது")?;
+                        return Ok(Expression::new(
+                            ExpressionKind::ListComprehension { element, clauses },
+                            self.span_from(start),
+                        ));
+                    }
                     if !self.match_token(&[TokenType::Comma]) {
                         break;
                     }
                 }
             }
             self.consume(&TokenType::RightBracket, "']' எதிர்பார்க்கப்படுகிறது")?;
-            return Ok(Expression::List(elements));
+            return Ok(Expression::new(ExpressionKind::List(elements), self.span_from(start)));
         }
 
         // Dictionary literal
@@ -821,6 +1427,18 @@ impl Parser {
                     let key = self.expression()?;
                     self.consume(&TokenType::Colon, "':' எதிர்பார்க்கப்படுகிறது")?;
                     let value = self.expression()?;
+                    if pairs.is_empty() && self.check(&TokenType::Ovvoru) {
+                        let clauses = self.comprehension_clauses()?;
+                        self.consume(&TokenType::RightBrace, "'}' எதிர்பார்க்கப்படுகிறது")?;
+                        return Ok(Expression::new(
+                            ExpressionKind::DictComprehension {
+                                key: Box::new(key),
+                                value: Box::new(value),
+                                clauses,
+                            },
+                            self.span_from(start),
+                        ));
+                    }
                     pairs.push((key, value));
                     if !self.match_token(&[TokenType::Comma]) {
                         break;
@@ -828,50 +1446,75 @@ impl Parser {
                 }
             }
             self.consume(&TokenType::RightBrace, "'}' எதிர்பார்க்கப்படுகிறது")?;
-            return Ok(Expression::Dict(pairs));
+            return Ok(Expression::new(ExpressionKind::Dict(pairs), self.span_from(start)));
         }
 
         Err(self.error("வெளிப்பாடு எதிர்பார்க்கப்படுகிறது"))
     }
 
-    /// Parse lambda expression: செயலி(params): expression
-    fn lambda_expression(&mut self) -> Result<Expression, AgamError> {
-        self.consume(&TokenType::LeftParen, "'(' எதிர்பார்க்கப்படுகிறது")?;
-        
-        let mut params = Vec::new();
-        if !self.check(&TokenType::RightParen) {
-            loop {
-                params.push(self.consume_identifier("அளவுரு பெயர் எதிர்பார்க்கப்படுகிறது")?);
-                if !self.match_token(&[TokenType::Comma]) {
-                    break;
-                }
+    /// Parse the clauses of a comprehension: one or more
+    /// `ஒவ்வொரு names உள்ள iterable`, each followed by any `என்றால் condition`
+    fn comprehension_clauses(&mut self) -> Result<Vec<ComprehensionClause>, AgamError> {
+        let mut clauses = Vec::new();
+        while self.match_token(&[TokenType::Ovvoru]) {
+            let variables = self.variable_names()?;
+            self.consume(&TokenType::Ulla, "'உள்ள' எதிர்பார்க்கப்படுகிறது")?;
+            let iterable = self.or()?;
+            clauses.push(ComprehensionClause::For {
+                bindings: vec![Binding::Global; variables.len()],
+                variables,
+                iterable,
+            });
+            while self.match_token(&[TokenType::Endraal]) {
+                clauses.push(ComprehensionClause::If(self.or()?));
             }
         }
-        
+        Ok(clauses)
+    }
+
+    /// Parse lambda expression: செயலி(params): expression, or
+    /// செயலி(params): followed by an indented block
+    fn lambda_expression(&mut self, start: Span) -> Result<Expression, AgamError> {
+        self.consume(&TokenType::LeftParen, "'(' எதிர்பார்க்கப்படுகிறது")?;
+        let params = self.parameters(Vec::new())?;
         self.consume(&TokenType::RightParen, "')' எதிர்பார்க்கப்படுகிறது")?;
         self.consume(&TokenType::Colon, "':' எதிர்பார்க்கப்படுகிறது")?;
+
+        if self.check(&TokenType::Newline) {
+            let span = self.span_from(start);
+            let body = self.block_contents()?;
+            self.end_expression_block();
+            return Ok(Expression::new(ExpressionKind::Lambda { params, body: LambdaBody::Block(body) }, span));
+        }
         
         let body = self.expression()?;
         
-        Ok(Expression::Lambda {
-            params,
-            body: Box::new(body),
-        })
+        Ok(Expression::new(
+            ExpressionKind::Lambda {
+                params,
+                body: LambdaBody::Expression(Box::new(body)),
+            },
+            self.span_from(start),
+        ))
     }
 
     /// Parse f-string content into parts
     /// Content like "Hello {name}! You are {age} years old" becomes:
     /// [Literal("Hello "), Expression(name), Literal("! You are "), Expression(age), Literal(" years old")]
-    fn parse_fstring_parts(&mut self, content: &str) -> Result<Expression, AgamError> {
+    fn parse_fstring_parts(&mut self, content: &str, span: Span) -> Result<Expression, AgamError> {
         let mut parts = Vec::new();
         let mut chars = content.chars().peekable();
         let mut current_literal = String::new();
+        // Column of the character just read; content starts after `f"`
+        let mut column = span.column + 1;
         
         while let Some(c) = chars.next() {
+            column += 1;
             if c == '{' {
                 // Check for escaped brace {{
                 if chars.peek() == Some(&'{') {
                     chars.next();
+                    column += 1;
                     current_literal.push('{');
                     continue;
                 }
@@ -883,10 +1526,12 @@ impl Parser {
                 }
                 
                 // Extract expression until }
+                let expr_column = column + 1;
                 let mut expr_str = String::new();
                 let mut brace_depth = 1;
                 
-                while let Some(c) = chars.next() {
+                for c in chars.by_ref() {
+                    column += 1;
                     if c == '{' {
                         brace_depth += 1;
                         expr_str.push(c);
@@ -906,12 +1551,14 @@ impl Parser {
                 }
                 
                 // Parse the expression
-                let expr = self.parse_embedded_expression(&expr_str)?;
+                let origin = Span::new(span.line, expr_column, 0);
+                let expr = self.parse_embedded_expression(&expr_str, origin)?;
                 parts.push(FStringPart::Expression(Box::new(expr)));
             } else if c == '}' {
                 // Check for escaped brace }}
                 if chars.peek() == Some(&'}') {
                     chars.next();
+                    column += 1;
                     current_literal.push('}');
                     continue;
                 }
@@ -927,15 +1574,22 @@ impl Parser {
             parts.push(FStringPart::Literal(current_literal));
         }
         
-        Ok(Expression::FString { parts })
+        Ok(Expression::new(ExpressionKind::FString { parts }, span))
     }
     
-    /// Parse an embedded expression within an f-string
-    fn parse_embedded_expression(&mut self, expr_str: &str) -> Result<Expression, AgamError> {
+    /// Parse an embedded expression within an f-string.
+    /// Token positions are shifted so they point into the enclosing source.
+    fn parse_embedded_expression(&mut self, expr_str: &str, origin: Span) -> Result<Expression, AgamError> {
         // Tokenize the expression
-        let tokens = crate::lexer::Lexer::tokenize(expr_str).map_err(|e| {
+        let mut tokens = crate::lexer::Lexer::tokenize(expr_str).map_err(|e| {
             self.error(&format!("f-சரத்தில் பிழை: {}", e))
         })?;
+        for token in &mut tokens {
+            if token.line == 1 {
+                token.column += origin.column - 1;
+            }
+            token.line += origin.line - 1;
+        }
         
         // Parse the expression
         let mut sub_parser = Parser::new(tokens);
@@ -943,6 +1597,19 @@ impl Parser {
     }
 
     // Helper methods
+    fn current_span(&self) -> Span {
+        let token = self.tokens.get(self.current).unwrap_or(&self.tokens[self.tokens.len() - 1]);
+        token.span()
+    }
+
+    /// Span from `start` to the end of the most recently consumed token
+    fn span_from(&self, start: Span) -> Span {
+        if self.current == 0 {
+            return start;
+        }
+        start.to(self.previous().span())
+    }
+
     fn peek_token_type(&self) -> Option<&TokenType> {
         self.tokens.get(self.current).map(|t| &t.token_type)
     }
@@ -0,0 +1,250 @@
+//! Declarations of a program with their positions
+//!
+//! Records every declared name with the lines it is visible on, so that
+//! a position in the source can be matched to its declaration without
+//! running anything. The language server and `agam translate` share it.
+
+use crate::lexer::{Token, TokenType};
+use crate::parser::ast::*;
+use crate::resolver::pattern_declarations;
+
+/// What a name declares, with the parts of the declaration that describe it
+#[derive(Debug, Clone, Copy)]
+pub enum Declared<'a> {
+    Variable { is_const: bool },
+    Parameter,
+    Function(&'a Parameters),
+    Method(&'a Parameters),
+    Struct(&'a [(String, Option<String>)]),
+    /// A struct field with its type, if written
+    Field(Option<&'a str>),
+    Enum,
+    /// An enum variant with the names of its payload fields
+    Variant(&'a [String]),
+    /// `இறக்குமதி module`
+    Module,
+    /// `இருந்து module இறக்குமதி name`
+    Import { module: &'a str },
+    /// `பிடி name`
+    CaughtError,
+    /// A variable bound by a match pattern
+    PatternVariable,
+}
+
+/// A name declared in a program; lines and columns are 1-based like `Span`
+#[derive(Debug, Clone)]
+pub struct Declaration<'a> {
+    pub name: &'a str,
+    pub declared: Declared<'a>,
+    /// Position of the name itself
+    pub line: usize,
+    pub column: usize,
+    /// First and last line of the whole declaration
+    pub lines: (usize, usize),
+    /// First and last line of the block the name is visible in
+    pub scope: (usize, usize),
+    /// Index of the struct or enum a field, method or variant belongs to
+    pub parent: Option<usize>,
+}
+
+/// Where a declaration is, as `Declaration` records it
+#[derive(Debug, Clone, Copy)]
+struct Site {
+    /// Where to start looking for the name
+    at: (usize, usize),
+    lines: (usize, usize),
+    scope: (usize, usize),
+    parent: Option<usize>,
+}
+
+impl Site {
+    fn new(at: (usize, usize), lines: (usize, usize), scope: (usize, usize)) -> Self {
+        Site { at, lines, scope, parent: None }
+    }
+
+    /// The same site for a member of declaration `parent`
+    fn member_of(self, parent: usize) -> Self {
+        Site { parent: Some(parent), ..self }
+    }
+}
+
+/// Every name `program` declares, in source order. `tokens` and `source`
+/// are what it was parsed from, used to place each name exactly.
+pub fn declarations<'a>(program: &'a Program, tokens: &[Token], source: &str) -> Vec<Declaration<'a>> {
+    Collector::collect(program, tokens, source)
+}
+
+/// Walks a parsed program recording its declarations
+struct Collector<'a, 't> {
+    tokens: &'t [Token],
+    lines: Vec<&'t str>,
+    declarations: Vec<Declaration<'a>>,
+}
+
+impl<'a, 't> Collector<'a, 't> {
+    fn collect(program: &'a Program, tokens: &'t [Token], source: &'t str) -> Vec<Declaration<'a>> {
+        let lines: Vec<&str> = source.lines().collect();
+        let last = lines.len().max(1);
+        let mut collector = Collector { tokens, lines, declarations: Vec::new() };
+        collector.block(&program.statements, (1, last));
+        collector.declarations
+    }
+
+    /// Declarations of a block whose names are visible on lines `scope`
+    fn block(&mut self, statements: &'a [Statement], scope: (usize, usize)) {
+        for (i, stmt) in statements.iter().enumerate() {
+            let end = statements.get(i + 1).map_or(scope.1, |next| next.span.line - 1);
+            let lines = (stmt.span.line, self.last_code_line(stmt.span.line, end));
+            self.statement(stmt, lines, scope);
+        }
+    }
+
+    /// Bodies that follow each other, such as the branches of an if; each
+    /// runs from its header line to the next one
+    fn bodies(&mut self, bodies: &[(usize, &'a [Statement])], end: usize) {
+        for (i, (start, body)) in bodies.iter().enumerate() {
+            let last = bodies.get(i + 1).map_or(end, |(next, _)| next - 1);
+            self.block(body, (*start, self.last_code_line(*start, last)));
+        }
+    }
+
+    fn statement(&mut self, stmt: &'a Statement, lines: (usize, usize), scope: (usize, usize)) {
+        let at = (stmt.span.line, stmt.span.column);
+        match &stmt.kind {
+            StatementKind::Let { name, is_const, .. } => {
+                self.add(name, Declared::Variable { is_const: *is_const }, Site::new(at, lines, scope));
+            }
+            StatementKind::LetUnpack { names, is_const, .. } => {
+                for name in names {
+                    self.add(name, Declared::Variable { is_const: *is_const }, Site::new(at, lines, scope));
+                }
+            }
+            StatementKind::For { variables, body, .. } => {
+                for name in variables {
+                    self.add(name, Declared::Variable { is_const: false }, Site::new(at, lines, scope));
+                }
+                self.block(body, lines);
+            }
+            StatementKind::While { body, .. } => self.block(body, lines),
+            StatementKind::If { then_branch, elif_branches, else_branch, .. } => {
+                let mut bodies = vec![(stmt.span.line, then_branch.as_slice())];
+                for (_, body) in elif_branches {
+                    bodies.push((header_line(body, stmt.span.line), body.as_slice()));
+                }
+                if let Some(body) = else_branch {
+                    bodies.push((header_line(body, stmt.span.line), body.as_slice()));
+                }
+                self.bodies(&bodies, lines.1);
+            }
+            StatementKind::Function { name, params, body, .. } => {
+                self.function(name, Declared::Function(params), params, body, Site::new(at, lines, scope));
+            }
+            StatementKind::Struct { name, fields, methods, .. } => {
+                let parent = self.add(name, Declared::Struct(fields), Site::new(at, lines, scope));
+                let from = self.position_after(parent);
+                for (field, type_name) in fields {
+                    let site = Site::new(from, lines, lines).member_of(parent);
+                    self.add(field, Declared::Field(type_name.as_deref()), site);
+                }
+                for (i, method) in methods.iter().enumerate() {
+                    let end = methods.get(i + 1).map_or(lines.1, |next| next.span.line - 1);
+                    let method_lines = (method.span.line, self.last_code_line(method.span.line, end));
+                    let at = (method.span.line, method.span.column);
+                    let site = Site::new(at, method_lines, lines).member_of(parent);
+                    self.function(&method.name, Declared::Method(&method.params), &method.params, &method.body, site);
+                }
+            }
+            StatementKind::Enum { name, variants, .. } => {
+                let parent = self.add(name, Declared::Enum, Site::new(at, lines, scope));
+                let from = self.position_after(parent);
+                for (variant, payload) in variants {
+                    self.add(variant, Declared::Variant(payload), Site::new(from, lines, lines).member_of(parent));
+                }
+            }
+            StatementKind::Import { module, items, .. } => match items {
+                None => {
+                    self.add(module, Declared::Module, Site::new(at, lines, scope));
+                }
+                Some(items) => {
+                    for item in items {
+                        self.add(item, Declared::Import { module }, Site::new(at, lines, scope));
+                    }
+                }
+            },
+            StatementKind::TryCatch { try_block, catch_clauses, finally_block } => {
+                let mut bodies = vec![(stmt.span.line, try_block.as_slice())];
+                for clause in catch_clauses {
+                    let at = (clause.span.line, clause.span.column);
+                    let clause_lines = (clause.span.line, lines.1);
+                    self.add(&clause.error_var, Declared::CaughtError, Site::new(at, clause_lines, scope));
+                    bodies.push((clause.span.line, clause.body.as_slice()));
+                }
+                if let Some(body) = finally_block {
+                    bodies.push((header_line(body, stmt.span.line), body.as_slice()));
+                }
+                self.bodies(&bodies, lines.1);
+            }
+            StatementKind::Match { arms, .. } => {
+                let mut bodies = Vec::new();
+                for (i, arm) in arms.iter().enumerate() {
+                    let end = arms.get(i + 1).map_or(lines.1, |next| next.span.line - 1);
+                    let arm_lines = (arm.span.line, self.last_code_line(arm.span.line, end));
+                    for (name, _) in pattern_declarations(&arm.pattern) {
+                        let at = (arm.span.line, arm.span.column);
+                        self.add(name, Declared::PatternVariable, Site::new(at, arm_lines, arm_lines));
+                    }
+                    bodies.push((arm.span.line, arm.body.as_slice()));
+                }
+                self.bodies(&bodies, lines.1);
+            }
+            _ => {}
+        }
+    }
+
+    /// A function or method with its parameters and body
+    fn function(&mut self, name: &'a str, declared: Declared<'a>, params: &'a Parameters, body: &'a [Statement], site: Site) {
+        let index = self.add(name, declared, site);
+        let from = self.position_after(index);
+        for param in params.signature.names().filter(|param| *param != SELF_NAME) {
+            self.add(param, Declared::Parameter, Site::new(from, site.lines, site.lines));
+        }
+        self.block(body, site.lines);
+    }
+
+    /// Record `name`, located at its first mention from `site.at` on
+    fn add(&mut self, name: &'a str, declared: Declared<'a>, site: Site) -> usize {
+        let Site { at, lines, scope, parent } = site;
+        let (line, column) = self
+            .tokens
+            .iter()
+            .find(|token| {
+                (token.line, token.column) >= at && matches!(&token.token_type, TokenType::Identifier(n) if n == name)
+            })
+            .map_or(at, |token| (token.line, token.column));
+        self.declarations.push(Declaration { name, declared, line, column, lines, scope, parent });
+        self.declarations.len() - 1
+    }
+
+    /// Just past the name of declaration `index`, where its parameters or
+    /// members start
+    fn position_after(&self, index: usize) -> (usize, usize) {
+        let declaration = &self.declarations[index];
+        (declaration.line, declaration.column + 1)
+    }
+
+    /// The last line from `start` to `end` holding code rather than blanks or comments
+    fn last_code_line(&self, start: usize, end: usize) -> usize {
+        (start..=end.max(start))
+            .rev()
+            .find(|&line| {
+                let text = self.lines.get(line - 1).map_or("", |text| text.trim());
+                !text.is_empty() && !text.starts_with('#')
+            })
+            .unwrap_or(start)
+    }
+}
+
+/// Line of the header of a branch such as `இல்லை:`, just above its body
+fn header_line(body: &[Statement], fallback: usize) -> usize {
+    body.first().map_or(fallback, |stmt| stmt.span.line.saturating_sub(1).max(fallback))
+}
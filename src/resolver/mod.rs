@@ -0,0 +1,10 @@
+//! Resolver module for Agam
+//! 
+//! Static pass between parsing and execution: binds every variable use to
+//! its declaration and reports scoping mistakes before the program runs
+
+pub mod declarations;
+pub mod resolver;
+
+pub use declarations::{declarations, Declaration, Declared};
+pub use resolver::{block_declarations, pattern_declarations, DeclarationKind, Resolver};
@@ -0,0 +1,677 @@
+//! Static resolver for Agam
+//!
+//! Walks the AST once before execution, mirroring the scopes the
+//! interpreter creates at runtime. Every variable use and declaration gets
+//! a `Binding`: top-level names stay globals looked up by name, everything
+//! else becomes a (depth, slot) pair. Scoping mistakes that never depend on
+//! runtime values are reported as compile errors.
+
+use std::collections::HashMap;
+
+use crate::error::AgamError;
+use crate::parser::ast::*;
+use crate::types::{error_kind_tamil, ERROR_KINDS};
+
+/// How a statement binds a name in its block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeclarationKind {
+    /// `மாறி` variable
+    Variable,
+    /// `மாறாத` constant, function, type or import
+    Constant,
+    /// Loop variable, caught error or match binding, which stays unbound
+    /// when its statement never binds it
+    Conditional,
+}
+
+/// Names a block declares directly, in order of appearance
+pub fn block_declarations(statements: &[Statement]) -> Vec<(&str, DeclarationKind)> {
+    let mut names = Vec::new();
+    for stmt in statements {
+        match &stmt.kind {
+            StatementKind::Let { name, is_const, .. } => {
+                let kind = if *is_const { DeclarationKind::Constant } else { DeclarationKind::Variable };
+                names.push((name.as_str(), kind));
+            }
+            StatementKind::LetUnpack { names: declared, is_const, .. } => {
+                let kind = if *is_const { DeclarationKind::Constant } else { DeclarationKind::Variable };
+                names.extend(declared.iter().map(|name| (name.as_str(), kind)));
+            }
+            StatementKind::Function { name, .. }
+            | StatementKind::Struct { name, .. }
+            | StatementKind::Enum { name, .. } => names.push((name.as_str(), DeclarationKind::Constant)),
+            StatementKind::Import { module, items, .. } => match items {
+                Some(items) => names.extend(items.iter().map(|item| (item.as_str(), DeclarationKind::Constant))),
+                None => names.push((module.as_str(), DeclarationKind::Constant)),
+            },
+            StatementKind::For { variables, .. } => {
+                names.extend(variables.iter().map(|name| (name.as_str(), DeclarationKind::Conditional)));
+            }
+            StatementKind::Match { arms, .. } => {
+                for arm in arms {
+                    pattern_variables(&arm.pattern, &mut names);
+                }
+            }
+            StatementKind::TryCatch { catch_clauses, .. } => {
+                names.extend(catch_clauses.iter().map(|c| (c.error_var.as_str(), DeclarationKind::Conditional)));
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+/// Names a match expression arm declares in its own block
+pub fn pattern_declarations(pattern: &Pattern) -> Vec<(&str, DeclarationKind)> {
+    let mut names = Vec::new();
+    pattern_variables(pattern, &mut names);
+    names
+}
+
+/// Variables a match pattern binds, in the order it binds them
+fn pattern_variables<'a>(pattern: &'a Pattern, names: &mut Vec<(&'a str, DeclarationKind)>) {
+    match pattern {
+        Pattern::Variable { name, .. } => names.push((name.as_str(), DeclarationKind::Conditional)),
+        Pattern::Literal(_) | Pattern::Wildcard => {}
+        Pattern::EnumVariant { payload, .. } => {
+            for part in payload.iter().flatten() {
+                pattern_variables(part, names);
+            }
+        }
+        Pattern::List { elements, rest } => {
+            for part in elements.iter().chain(rest.as_deref()) {
+                pattern_variables(part, names);
+            }
+        }
+        Pattern::Dict(entries) | Pattern::Struct { fields: entries, .. } => {
+            for (_, part) in entries {
+                pattern_variables(part, names);
+            }
+        }
+    }
+}
+
+/// A variable known to the resolver
+struct Var {
+    slot: usize,
+    is_const: bool,
+    /// False until the declaration is reached; a block's names are known
+    /// from its start so that nested functions can refer to later ones
+    declared: bool,
+}
+
+/// One runtime environment: a block, a call's parameters, or the top level
+struct Scope {
+    vars: HashMap<String, Var>,
+    /// Top level of a program or module, whose variables are globals
+    global: bool,
+    /// Function the scope belongs to; 0 for top-level code
+    function: usize,
+}
+
+impl Scope {
+    /// Scope with the names `statements` declare, not yet declared
+    fn for_block(statements: &[Statement], global: bool, function: usize) -> Self {
+        let mut scope = Scope { vars: HashMap::new(), global, function };
+        for (name, kind) in block_declarations(statements) {
+            scope.add(name, kind == DeclarationKind::Constant, false);
+        }
+        scope
+    }
+
+    fn add(&mut self, name: &str, is_const: bool, declared: bool) -> &mut Var {
+        let slot = self.vars.len();
+        self.vars
+            .entry(name.to_string())
+            .or_insert(Var { slot, is_const, declared })
+    }
+}
+
+/// What a use of a name refers to
+struct Resolved {
+    binding: Binding,
+    is_const: bool,
+    /// Function the variable belongs to; 0 for top-level code and globals
+    owner: usize,
+}
+
+/// Body of a function being resolved
+enum FunctionBody<'a> {
+    Block(&'a mut [Statement]),
+    /// Lambda body, evaluated in a block of its own
+    Expr(&'a mut Expression),
+}
+
+/// The resolver
+pub struct Resolver {
+    /// Open scopes, the top level first
+    scopes: Vec<Scope>,
+    /// Functions being resolved, innermost last
+    functions: Vec<usize>,
+    /// Variables each function being resolved declared with வெளிமாறி
+    nonlocals: Vec<Vec<String>>,
+    /// Whether each function being resolved contains விளை
+    yields: Vec<bool>,
+    /// முயற்சி statements with an இறுதியாக block open in the innermost function
+    finally_depth: usize,
+    function_count: usize,
+}
+
+impl Resolver {
+    /// Create a resolver. `globals` are the names already defined when the
+    /// program starts (built-ins, earlier REPL input) and whether each is constant
+    pub fn new(globals: HashMap<String, bool>) -> Self {
+        let mut top = Scope { vars: HashMap::new(), global: true, function: 0 };
+        for (name, is_const) in globals {
+            top.add(&name, is_const, true);
+        }
+        Resolver {
+            scopes: vec![top],
+            functions: Vec::new(),
+            nonlocals: Vec::new(),
+            yields: Vec::new(),
+            finally_depth: 0,
+            function_count: 0,
+        }
+    }
+
+    /// Resolve a program in place
+    pub fn resolve(&mut self, program: &mut Program) -> Result<(), AgamError> {
+        let top = self.scopes.first_mut().expect("top-level scope");
+        for (name, kind) in block_declarations(&program.statements) {
+            top.add(name, kind == DeclarationKind::Constant, false);
+        }
+
+        for stmt in &mut program.statements {
+            self.statement(stmt)?;
+        }
+        Ok(())
+    }
+
+    fn current_function(&self) -> usize {
+        self.functions.last().copied().unwrap_or(0)
+    }
+
+    fn block(&mut self, statements: &mut [Statement]) -> Result<(), AgamError> {
+        self.scopes.push(Scope::for_block(statements, false, self.current_function()));
+        let result = statements.iter_mut().try_for_each(|stmt| self.statement(stmt));
+        self.scopes.pop();
+        result
+    }
+
+    /// Declare `name` in the innermost scope once its statement runs
+    fn declare(&mut self, name: &str, is_const: bool) -> Binding {
+        let scope = self.scopes.last_mut().expect("open scope");
+        let global = scope.global;
+        let var = scope.add(name, is_const, true);
+        var.declared = true;
+        var.is_const = is_const;
+        if global {
+            Binding::Global
+        } else {
+            Binding::Local { depth: 0, slot: var.slot }
+        }
+    }
+
+    /// Find the variable a use of `name` refers to
+    fn lookup(&self, name: &str, span: Span) -> Result<Resolved, AgamError> {
+        let function = self.current_function();
+        let mut declared_later = false;
+
+        for (depth, scope) in self.scopes.iter().rev().enumerate() {
+            let Some(var) = scope.vars.get(name) else {
+                continue;
+            };
+            // Code of the same function runs in order, so it only sees
+            // declarations already made; nested functions run later
+            if !var.declared && scope.function == function {
+                declared_later = true;
+                continue;
+            }
+            let binding = if scope.global {
+                Binding::Global
+            } else {
+                Binding::Local { depth, slot: var.slot }
+            };
+            return Ok(Resolved { binding, is_const: var.is_const, owner: scope.function });
+        }
+
+        if declared_later {
+            return Err(AgamError::compile_error_at(
+                span,
+                format!("மாறி '{}' வரையறுக்கும் முன் பயன்படுத்தப்பட்டது", name),
+            ));
+        }
+        // Unknown names are globals that may be defined by the time the
+        // code runs; the interpreter reports them if not
+        Ok(Resolved { binding: Binding::Global, is_const: false, owner: 0 })
+    }
+
+    /// Resolve the blocks of a `முயற்சி` statement
+    fn try_catch(
+        &mut self,
+        try_block: &mut [Statement],
+        catch_clauses: &mut [CatchClause],
+        finally_block: &mut Option<Vec<Statement>>,
+    ) -> Result<(), AgamError> {
+        self.block(try_block)?;
+        for clause in catch_clauses {
+            if let Some(filter) = &clause.filter {
+                self.catch_filter(filter, clause.span)?;
+            }
+            clause.binding = self.declare(&clause.error_var, false);
+            self.block(&mut clause.body)?;
+        }
+        if let Some(body) = finally_block {
+            self.block(body)?;
+        }
+        Ok(())
+    }
+
+    /// Check that a `பிடி` filter names an error kind or something in
+    /// scope that could be a struct; any other name would never match
+    fn catch_filter(&self, filter: &str, span: Span) -> Result<(), AgamError> {
+        let is_kind = ERROR_KINDS.iter().any(|kind| filter == *kind || filter == error_kind_tamil(kind));
+        if is_kind || self.scopes.iter().any(|scope| scope.vars.contains_key(filter)) {
+            return Ok(());
+        }
+        Err(AgamError::compile_error_at(
+            span,
+            format!("'{}' பிழை வகையோ கட்டமைப்போ அல்ல; இந்த பிடி எதையும் பிடிக்காது", filter),
+        ))
+    }
+
+    /// Binding of a variable being assigned, which must not be a constant
+    fn assignment_target(&self, name: &str, span: Span) -> Result<Binding, AgamError> {
+        let resolved = self.lookup(name, span)?;
+        if resolved.is_const {
+            return Err(AgamError::compile_error_at(
+                span,
+                format!("'{}' மாறாத மாறி, மாற்ற இயலாது", name),
+            ));
+        }
+        // Assigning an enclosing function's variable needs வெளிமாறி
+        let outer = resolved.owner != 0 && resolved.owner != self.current_function();
+        if outer && !self.nonlocals.last().is_some_and(|names| names.iter().any(|n| n == name)) {
+            return Err(AgamError::compile_error_at(
+                span,
+                format!("'{}' வெளிச் செயலின் மாறி; மாற்றுவதற்கு முன் 'வெளிமாறி {}' என அறிவிக்கவும்", name, name),
+            ));
+        }
+        Ok(resolved.binding)
+    }
+
+    fn function(&mut self, params: &mut Parameters, body: FunctionBody) -> Result<(), AgamError> {
+        // Defaults are evaluated where the function is defined
+        for default in &mut params.defaults {
+            self.expression(default)?;
+        }
+
+        self.function_count += 1;
+        let function = self.function_count;
+        self.functions.push(function);
+        self.nonlocals.push(Vec::new());
+        self.yields.push(false);
+        let finally_depth = std::mem::take(&mut self.finally_depth);
+
+        let mut scope = Scope { vars: HashMap::new(), global: false, function };
+        for (slot, param) in params.signature.names().enumerate() {
+            scope.vars.insert(param.clone(), Var { slot, is_const: false, declared: true });
+        }
+        self.scopes.push(scope);
+
+        let result = match body {
+            FunctionBody::Block(statements) => self.block(statements),
+            FunctionBody::Expr(expr) => {
+                self.scopes.push(Scope { vars: HashMap::new(), global: false, function });
+                let result = self.expression(expr);
+                self.scopes.pop();
+                result
+            }
+        };
+
+        self.scopes.pop();
+        self.functions.pop();
+        self.nonlocals.pop();
+        params.is_generator = self.yields.pop().unwrap_or(false);
+        self.finally_depth = finally_depth;
+        result
+    }
+
+    fn statement(&mut self, stmt: &mut Statement) -> Result<(), AgamError> {
+        let span = stmt.span;
+        match &mut stmt.kind {
+            StatementKind::Let { name, value, is_const, binding } => {
+                self.expression(value)?;
+                *binding = self.declare(name, *is_const);
+            }
+
+            StatementKind::LetUnpack { names, value, is_const, bindings } => {
+                self.expression(value)?;
+                *bindings = names.iter().map(|name| self.declare(name, *is_const)).collect();
+            }
+
+            StatementKind::AssignUnpack { targets, value } => {
+                self.expression(value)?;
+                for target in targets {
+                    match &mut target.kind {
+                        ExpressionKind::Identifier { name, binding } => {
+                            *binding = self.assignment_target(name, target.span)?;
+                        }
+                        _ => self.expression(target)?,
+                    }
+                }
+            }
+
+            StatementKind::Expression(expr) => self.expression(expr)?,
+
+            StatementKind::Print(args) => {
+                for arg in args {
+                    self.expression(arg)?;
+                }
+            }
+
+            StatementKind::If { condition, then_branch, elif_branches, else_branch } => {
+                self.expression(condition)?;
+                self.block(then_branch)?;
+                for (cond, body) in elif_branches {
+                    self.expression(cond)?;
+                    self.block(body)?;
+                }
+                if let Some(body) = else_branch {
+                    self.block(body)?;
+                }
+            }
+
+            StatementKind::While { condition, body } => {
+                self.expression(condition)?;
+                self.block(body)?;
+            }
+
+            StatementKind::For { variables, iterable, body, bindings } => {
+                self.expression(iterable)?;
+                *bindings = variables.iter().map(|name| self.declare(name, false)).collect();
+                self.block(body)?;
+            }
+
+            StatementKind::Function { name, params, body, binding } => {
+                self.function(params, FunctionBody::Block(body))?;
+                *binding = self.declare(name, true);
+            }
+
+            StatementKind::Return(value) => {
+                if self.functions.is_empty() {
+                    return Err(AgamError::compile_error_at(
+                        span,
+                        "'திரும்பு' செயலுக்கு வெளியே பயன்படுத்த முடியாது".to_string(),
+                    ));
+                }
+                if let Some(expr) = value {
+                    self.expression(expr)?;
+                }
+            }
+
+            StatementKind::Nonlocal(names) => {
+                if self.functions.is_empty() {
+                    return Err(AgamError::compile_error_at(
+                        span,
+                        "'வெளிமாறி' செயலுக்கு வெளியே பயன்படுத்த முடியாது".to_string(),
+                    ));
+                }
+                for name in names.iter() {
+                    let owner = self.lookup(name, span)?.owner;
+                    if owner == 0 || owner == self.current_function() {
+                        return Err(AgamError::compile_error_at(
+                            span,
+                            format!("'{}' எந்த வெளிச் செயலின் மாறியும் அல்ல", name),
+                        ));
+                    }
+                    self.nonlocals.last_mut().expect("function").push(name.clone());
+                }
+            }
+
+            StatementKind::Yield(value) => {
+                if self.functions.is_empty() {
+                    return Err(AgamError::compile_error_at(
+                        span,
+                        "'விளை' செயலுக்கு வெளியே பயன்படுத்த முடியாது".to_string(),
+                    ));
+                }
+                // A suspended generator may never resume, for instance when
+                // its loop stops early, so an இறுதியாக block around or after
+                // the விளை could not be promised to run
+                if self.finally_depth > 0 {
+                    return Err(AgamError::compile_error_at(
+                        span,
+                        "'இறுதியாக' உள்ள 'முயற்சி' தொகுதிக்குள் 'விளை' பயன்படுத்த முடியாது".to_string(),
+                    ));
+                }
+                if let Some(yields) = self.yields.last_mut() {
+                    *yields = true;
+                }
+                self.expression(value)?;
+            }
+
+            StatementKind::Break | StatementKind::Continue => {}
+
+            StatementKind::Import { module, items, bindings } => {
+                *bindings = match items {
+                    Some(items) => items.iter().map(|item| self.declare(item, true)).collect(),
+                    None => vec![self.declare(module, true)],
+                };
+            }
+
+            StatementKind::TryCatch { try_block, catch_clauses, finally_block } => {
+                let has_finally = finally_block.is_some() as usize;
+                self.finally_depth += has_finally;
+                let result = self.try_catch(try_block, catch_clauses, finally_block);
+                self.finally_depth -= has_finally;
+                result?;
+            }
+
+            StatementKind::Throw(value) => {
+                if let Some(expr) = value {
+                    self.expression(expr)?;
+                }
+            }
+
+            StatementKind::Struct { name, methods, binding, .. } => {
+                for method in methods {
+                    self.function(&mut method.params, FunctionBody::Block(&mut method.body))?;
+                }
+                *binding = self.declare(name, true);
+            }
+
+            StatementKind::Enum { name, binding, .. } => {
+                *binding = self.declare(name, true);
+            }
+
+            StatementKind::Match { value, arms } => {
+                self.expression(value)?;
+                for arm in arms {
+                    self.pattern(&mut arm.pattern)?;
+                    if let Some(guard) = &mut arm.guard {
+                        self.expression(guard)?;
+                    }
+                    self.block(&mut arm.body)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn pattern(&mut self, pattern: &mut Pattern) -> Result<(), AgamError> {
+        match pattern {
+            Pattern::Literal(expr) => self.expression(expr)?,
+            Pattern::Variable { name, binding } => *binding = self.declare(name, false),
+            Pattern::Wildcard => {}
+            Pattern::EnumVariant { payload, .. } => {
+                for part in payload.iter_mut().flatten() {
+                    self.pattern(part)?;
+                }
+            }
+            Pattern::List { elements, rest } => {
+                for part in elements {
+                    self.pattern(part)?;
+                }
+                if let Some(rest) = rest {
+                    self.pattern(rest)?;
+                }
+            }
+            Pattern::Dict(entries) | Pattern::Struct { fields: entries, .. } => {
+                for (_, part) in entries {
+                    self.pattern(part)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// A comprehension's variables live in a block of its own, declared
+    /// clause by clause, with the element expressions resolved last
+    fn comprehension(&mut self, clauses: &mut [ComprehensionClause], elements: &mut [&mut Box<Expression>]) -> Result<(), AgamError> {
+        let function = self.current_function();
+        self.scopes.push(Scope { vars: HashMap::new(), global: false, function });
+        let result = (|| {
+            for clause in clauses.iter_mut() {
+                match clause {
+                    ComprehensionClause::For { variables, iterable, bindings } => {
+                        self.expression(iterable)?;
+                        *bindings = variables.iter().map(|name| self.declare(name, false)).collect();
+                    }
+                    ComprehensionClause::If(condition) => self.expression(condition)?,
+                }
+            }
+            elements.iter_mut().try_for_each(|element| self.expression(element))
+        })();
+        self.scopes.pop();
+        result
+    }
+
+    fn expression(&mut self, expr: &mut Expression) -> Result<(), AgamError> {
+        let span = expr.span;
+        match &mut expr.kind {
+            ExpressionKind::Identifier { name, binding } => {
+                *binding = self.lookup(name, span)?.binding;
+                // தன் is only ever bound as a method's receiver
+                if *binding == Binding::Global && name == SELF_NAME {
+                    return Err(AgamError::compile_error_at(
+                        span,
+                        "'தன்' முறைக்கு வெளியே பயன்படுத்த முடியாது".to_string(),
+                    ));
+                }
+            }
+
+            ExpressionKind::Assignment { name, value, binding, .. } => {
+                self.expression(value)?;
+                *binding = self.assignment_target(name, span)?;
+            }
+
+            ExpressionKind::Lambda { params, body } => {
+                let body = match body {
+                    LambdaBody::Expression(expr) => FunctionBody::Expr(expr),
+                    LambdaBody::Block(statements) => FunctionBody::Block(statements),
+                };
+                self.function(params, body)?;
+            }
+
+            ExpressionKind::Match { value, arms } => {
+                self.expression(value)?;
+                for arm in arms {
+                    // Each arm binds its pattern in a block of its own
+                    let function = self.current_function();
+                    self.scopes.push(Scope { vars: HashMap::new(), global: false, function });
+                    let result = (|| {
+                        self.pattern(&mut arm.pattern)?;
+                        if let Some(guard) = &mut arm.guard {
+                            self.expression(guard)?;
+                        }
+                        self.expression(&mut arm.value)
+                    })();
+                    self.scopes.pop();
+                    result?;
+                }
+            }
+
+            ExpressionKind::ListComprehension { element, clauses } => {
+                self.comprehension(clauses, &mut [element])?;
+            }
+
+            ExpressionKind::DictComprehension { key, value, clauses } => {
+                self.comprehension(clauses, &mut [key, value])?;
+            }
+
+            ExpressionKind::FString { parts } => {
+                for part in parts {
+                    if let FStringPart::Expression(inner) = part {
+                        self.expression(inner)?;
+                    }
+                }
+            }
+            ExpressionKind::Binary { left, right, .. } => {
+                self.expression(left)?;
+                self.expression(right)?;
+            }
+            ExpressionKind::Unary { operand, .. } => self.expression(operand)?,
+            ExpressionKind::Call { callee, arguments, keywords } => {
+                self.expression(callee)?;
+                for arg in arguments {
+                    self.expression(arg)?;
+                }
+                for (_, arg) in keywords {
+                    self.expression(arg)?;
+                }
+            }
+            ExpressionKind::List(elements) | ExpressionKind::Tuple(elements) => {
+                for element in elements {
+                    self.expression(element)?;
+                }
+            }
+            ExpressionKind::Dict(pairs) => {
+                for (key, value) in pairs {
+                    self.expression(key)?;
+                    self.expression(value)?;
+                }
+            }
+            ExpressionKind::Index { object, index } => {
+                self.expression(object)?;
+                self.expression(index)?;
+            }
+            ExpressionKind::Slice { object, bounds } => {
+                self.expression(object)?;
+                for expr in bounds.parts_mut().into_iter().flatten() {
+                    self.expression(expr)?;
+                }
+            }
+            ExpressionKind::SliceAssignment { object, bounds, value } => {
+                self.expression(object)?;
+                for expr in bounds.parts_mut().into_iter().flatten() {
+                    self.expression(expr)?;
+                }
+                self.expression(value)?;
+            }
+            ExpressionKind::Grouping(inner) => self.expression(inner)?,
+            ExpressionKind::MemberAccess { object, .. } => self.expression(object)?,
+            ExpressionKind::StructInit { arguments, .. } => {
+                for arg in arguments {
+                    self.expression(arg)?;
+                }
+            }
+            ExpressionKind::IndexAssignment { object, index, value, .. } => {
+                self.expression(object)?;
+                self.expression(index)?;
+                self.expression(value)?;
+            }
+            ExpressionKind::MemberAssignment { object, value, .. } => {
+                self.expression(object)?;
+                self.expression(value)?;
+            }
+            ExpressionKind::Integer(_)
+            | ExpressionKind::Number(_)
+            | ExpressionKind::String(_)
+            | ExpressionKind::Boolean(_)
+            | ExpressionKind::Null => {}
+        }
+        Ok(())
+    }
+}
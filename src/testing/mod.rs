@@ -0,0 +1,11 @@
+//! Test framework for Agam programs
+//!
+//! `agam test` finds the test functions in `.agam` files, runs each in an
+//! interpreter of its own and reports the results as TAP or JUnit XML.
+//! Tests check their expectations with the உறுதி/assert built-ins.
+
+pub mod runner;
+pub mod report;
+
+pub use runner::{discover, run_tests, test_names, Outcome, TestResult, TEST_PREFIXES};
+pub use report::{junit, tap};
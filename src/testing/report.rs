@@ -0,0 +1,122 @@
+//! Test reports: TAP for people and TAP consumers, JUnit XML for CI servers
+
+use std::time::Duration;
+
+use crate::testing::runner::{Outcome, TestResult};
+
+/// TAP version 13: one `ok`/`not ok` line per test with its time, and a
+/// YAML block giving the error of each test that did not pass. What a
+/// test printed comes before its line as `#` comments.
+pub fn tap(results: &[TestResult]) -> String {
+    let mut out = format!("TAP version 13\n1..{}\n", results.len());
+    for (number, result) in results.iter().enumerate() {
+        for line in result.output.lines() {
+            out.push_str(&format!("# {}\n", line));
+        }
+        let status = if result.passed() { "ok" } else { "not ok" };
+        out.push_str(&format!("{} {} - {}: {}\n", status, number + 1, result.file, result.name));
+        out.push_str("  ---\n");
+        out.push_str(&format!("  duration_ms: {:.3}\n", milliseconds(result.duration)));
+        if let Outcome::Failed(error) | Outcome::Errored(error) = &result.outcome {
+            let (line, column) = error.location();
+            let severity = if matches!(result.outcome, Outcome::Failed(_)) { "fail" } else { "error" };
+            out.push_str(&format!("  severity: {}\n", severity));
+            out.push_str(&format!("  kind: {}\n", error.kind()));
+            out.push_str(&format!("  at: \"{}:{}:{}\"\n", result.file, line, column));
+            out.push_str("  message: |-\n");
+            for line in error.message().lines() {
+                out.push_str(&format!("    {}\n", line));
+            }
+        }
+        out.push_str("  ...\n");
+    }
+
+    let passed = results.iter().filter(|result| result.passed()).count();
+    let total: Duration = results.iter().map(|result| result.duration).sum();
+    out.push_str(&format!("# சோதனைகள் (tests) {}\n", results.len()));
+    out.push_str(&format!("# வெற்றி (pass) {}\n", passed));
+    out.push_str(&format!("# தோல்வி (fail) {}\n", results.len() - passed));
+    out.push_str(&format!("# நேரம் (time) {:.3} ms\n", milliseconds(total)));
+    out
+}
+
+/// JUnit XML with a `<testsuite>` per file. A failed assertion is a
+/// `<failure>`, any other error an `<error>`; times are in seconds.
+pub fn junit(results: &[TestResult]) -> String {
+    let mut files: Vec<&str> = Vec::new();
+    for result in results {
+        if !files.contains(&result.file.as_str()) {
+            files.push(&result.file);
+        }
+    }
+
+    let counts = |results: &[&TestResult]| {
+        let failures = results.iter().filter(|result| matches!(result.outcome, Outcome::Failed(_))).count();
+        let errors = results.iter().filter(|result| matches!(result.outcome, Outcome::Errored(_))).count();
+        let time: Duration = results.iter().map(|result| result.duration).sum();
+        format!(
+            "tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.6}\"",
+            results.len(),
+            failures,
+            errors,
+            time.as_secs_f64()
+        )
+    };
+
+    let all: Vec<&TestResult> = results.iter().collect();
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!("<testsuites name=\"agam\" {}>\n", counts(&all)));
+    for file in files {
+        let suite: Vec<&TestResult> = results.iter().filter(|result| result.file == file).collect();
+        out.push_str(&format!("  <testsuite name=\"{}\" {}>\n", escape(file), counts(&suite)));
+        for result in suite {
+            out.push_str(&format!(
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.6}\"",
+                escape(&result.name),
+                escape(file),
+                result.duration.as_secs_f64()
+            ));
+            let problem = match &result.outcome {
+                Outcome::Passed => None,
+                Outcome::Failed(error) => Some(("failure", error)),
+                Outcome::Errored(error) => Some(("error", error)),
+            };
+            if problem.is_none() && result.output.is_empty() {
+                out.push_str("/>\n");
+                continue;
+            }
+            out.push_str(">\n");
+            if let Some((tag, error)) = problem {
+                let message = error.message();
+                let summary = message.lines().next().unwrap_or_default();
+                out.push_str(&format!(
+                    "      <{} message=\"{}\" type=\"{}\">{}</{}>\n",
+                    tag,
+                    escape(summary),
+                    error.kind(),
+                    escape(&error.to_string()),
+                    tag
+                ));
+            }
+            if !result.output.is_empty() {
+                out.push_str(&format!("      <system-out>{}</system-out>\n", escape(&result.output)));
+            }
+            out.push_str("    </testcase>\n");
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+fn milliseconds(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,150 @@
+//! Finding and running tests
+//!
+//! A test is a top-level function whose name starts with `சோதனை_` or
+//! `test_`; one that declares parameters is reported as an error. Each test
+//! gets an `Interpreter` of its own: the file's top-level code runs first,
+//! then the test function is called from its declaration, so no test sees
+//! what another one changed.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::error::AgamError;
+use crate::interpreter::ops::capture_output;
+use crate::interpreter::{Backend, Interpreter};
+use crate::lexer::Lexer;
+use crate::parser::{Parameters, Parser, Program, Span, StatementKind};
+
+/// Name prefixes that make a top-level function a test
+pub const TEST_PREFIXES: [&str; 2] = ["சோதனை_", "test_"];
+
+/// How a test ended
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    Passed,
+    /// An உறுதி assertion did not hold
+    Failed(AgamError),
+    /// Any other error, including a file that does not parse
+    Errored(AgamError),
+}
+
+/// A test that has run
+#[derive(Debug, Clone)]
+pub struct TestResult {
+    /// Path of the file, as it was found
+    pub file: String,
+    pub name: String,
+    pub outcome: Outcome,
+    pub duration: Duration,
+    /// What the test printed
+    pub output: String,
+}
+
+impl TestResult {
+    pub fn passed(&self) -> bool {
+        matches!(self.outcome, Outcome::Passed)
+    }
+}
+
+/// The `.agam` files at `path`: the file itself, or every one found under
+/// a directory, skipping hidden directories and `target`, in path order
+pub fn discover(path: &Path) -> io::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files = Vec::new();
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            if path.is_dir() {
+                if !name.starts_with('.') && name != "target" {
+                    pending.push(path);
+                }
+            } else if path.extension().is_some_and(|extension| extension == "agam") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// A test function found in a program
+struct TestCase<'a> {
+    name: &'a str,
+    /// Where the function is declared; the test is called from here
+    span: Span,
+    params: &'a Parameters,
+}
+
+/// The test functions a program defines, in source order
+fn test_cases(program: &Program) -> impl Iterator<Item = TestCase<'_>> {
+    program.statements.iter().filter_map(|stmt| match &stmt.kind {
+        StatementKind::Function { name, params, .. } if TEST_PREFIXES.iter().any(|prefix| name.starts_with(prefix)) => {
+            Some(TestCase { name, span: stmt.span, params })
+        }
+        _ => None,
+    })
+}
+
+/// Names of the tests a program defines, in source order
+pub fn test_names(program: &Program) -> Vec<String> {
+    test_cases(program).map(|case| case.name.to_string()).collect()
+}
+
+/// Run every test in `source`, read from `file`. A file that does not
+/// parse gives a single errored result named after the file.
+pub fn run_tests(file: &str, source: &str, backend: Backend) -> Vec<TestResult> {
+    let program = match Lexer::tokenize(source).and_then(|tokens| Parser::new(tokens).parse()) {
+        Ok(program) => program,
+        Err(error) => {
+            return vec![TestResult {
+                file: file.to_string(),
+                name: file.to_string(),
+                outcome: Outcome::Errored(error),
+                duration: Duration::ZERO,
+                output: String::new(),
+            }];
+        }
+    };
+
+    test_cases(&program)
+        .map(|case| {
+            let start = Instant::now();
+            let (result, output) = match check_signature(&case) {
+                Ok(()) => capture_output(|| run_test(file, &program, &case, backend)),
+                Err(error) => (Err(error), String::new()),
+            };
+            let outcome = match result {
+                Ok(()) => Outcome::Passed,
+                Err(error @ AgamError::AssertionError { .. }) => Outcome::Failed(error),
+                Err(error) => Outcome::Errored(error),
+            };
+            TestResult { file: file.to_string(), name: case.name.to_string(), outcome, duration: start.elapsed(), output }
+        })
+        .collect()
+}
+
+/// Tests are called without arguments, so they may not declare parameters
+fn check_signature(case: &TestCase) -> Result<(), AgamError> {
+    let signature = &case.params.signature;
+    if signature.params.is_empty() && signature.rest.is_none() && signature.keywords.is_none() {
+        return Ok(());
+    }
+    Err(AgamError::compile_error_at(
+        case.span,
+        format!("சோதனைச் செயல் '{}' அளவுருக்கள் எடுக்கக் கூடாது", case.name),
+    ))
+}
+
+fn run_test(file: &str, program: &Program, case: &TestCase, backend: Backend) -> Result<(), AgamError> {
+    let mut interpreter = Interpreter::with_backend(backend);
+    interpreter.set_file(file);
+    interpreter.execute(program)?;
+    interpreter.call(case.name, case.span)?;
+    Ok(())
+}
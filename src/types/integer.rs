@@ -0,0 +1,165 @@
+//! Whole numbers for Agam
+//!
+//! Integers are machine words while they fit and switch to `BigInt` only
+//! when a result overflows, so everyday arithmetic never allocates
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Neg, Not};
+
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive};
+
+/// Whole number of any size. `Big` only ever holds numbers outside the
+/// `i64` range, so each number has exactly one representation
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Integer {
+    Small(i64),
+    Big(BigInt),
+}
+
+impl Integer {
+    /// The number as a `BigInt`, borrowed when it already is one
+    pub fn big(&self) -> Cow<'_, BigInt> {
+        match self {
+            Integer::Small(n) => Cow::Owned(BigInt::from(*n)),
+            Integer::Big(n) => Cow::Borrowed(n),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        matches!(self, Integer::Small(0))
+    }
+
+    pub fn is_negative(&self) -> bool {
+        match self {
+            Integer::Small(n) => *n < 0,
+            Integer::Big(n) => n.is_negative(),
+        }
+    }
+
+    pub fn abs(&self) -> Integer {
+        match self {
+            Integer::Small(n) => n.checked_abs().map(Integer::Small).unwrap_or_else(|| Integer::from(BigInt::from(*n).abs())),
+            Integer::Big(n) => Integer::Big(n.abs()),
+        }
+    }
+
+    pub fn pow(&self, exponent: u32) -> Integer {
+        match self {
+            Integer::Small(n) => match n.checked_pow(exponent) {
+                Some(result) => Integer::Small(result),
+                None => Integer::Big(BigInt::from(*n).pow(exponent)),
+            },
+            Integer::Big(n) => Integer::Big(n.pow(exponent)),
+        }
+    }
+}
+
+impl From<BigInt> for Integer {
+    fn from(n: BigInt) -> Self {
+        match n.to_i64() {
+            Some(small) => Integer::Small(small),
+            None => Integer::Big(n),
+        }
+    }
+}
+
+macro_rules! from_primitive {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Integer {
+            fn from(n: $t) -> Self {
+                match i64::try_from(n) {
+                    Ok(small) => Integer::Small(small),
+                    Err(_) => Integer::Big(BigInt::from(n)),
+                }
+            }
+        })*
+    };
+}
+
+from_primitive!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl ToPrimitive for Integer {
+    fn to_i64(&self) -> Option<i64> {
+        match self {
+            Integer::Small(n) => Some(*n),
+            Integer::Big(n) => n.to_i64(),
+        }
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        match self {
+            Integer::Small(n) => n.to_u64(),
+            Integer::Big(n) => n.to_u64(),
+        }
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        match self {
+            Integer::Small(n) => Some(*n as f64),
+            Integer::Big(n) => n.to_f64(),
+        }
+    }
+}
+
+impl Add for &Integer {
+    type Output = Integer;
+
+    fn add(self, other: &Integer) -> Integer {
+        match (self, other) {
+            (Integer::Small(a), Integer::Small(b)) => match a.checked_add(*b) {
+                Some(sum) => Integer::Small(sum),
+                None => Integer::Big(BigInt::from(*a) + b),
+            },
+            _ => Integer::from(self.big().as_ref() + other.big().as_ref()),
+        }
+    }
+}
+
+impl Neg for Integer {
+    type Output = Integer;
+
+    fn neg(self) -> Integer {
+        match self {
+            Integer::Small(n) => n.checked_neg().map(Integer::Small).unwrap_or_else(|| Integer::Big(-BigInt::from(n))),
+            Integer::Big(n) => Integer::from(-n),
+        }
+    }
+}
+
+impl Not for Integer {
+    type Output = Integer;
+
+    fn not(self) -> Integer {
+        match self {
+            Integer::Small(n) => Integer::Small(!n),
+            Integer::Big(n) => Integer::from(!n),
+        }
+    }
+}
+
+impl Ord for Integer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Integer::Small(a), Integer::Small(b)) => a.cmp(b),
+            _ => self.big().cmp(&other.big()),
+        }
+    }
+}
+
+impl PartialOrd for Integer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Integer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Integer::Small(n) => write!(f, "{}", n),
+            Integer::Big(n) => write!(f, "{}", n),
+        }
+    }
+}
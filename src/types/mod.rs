@@ -2,6 +2,8 @@
 //! 
 //! Runtime value types and type system
 
+pub mod integer;
 pub mod values;
 
+pub use integer::*;
 pub use values::*;
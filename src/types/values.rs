@@ -2,17 +2,26 @@
 //! 
 //! Represents values during program execution
 
+use std::any::Any;
 use std::collections::HashMap;
 use std::fmt;
 use std::rc::Rc;
 use std::cell::RefCell;
 
+use num_traits::ToPrimitive;
+
+use super::Integer;
+use crate::error::AgamError;
+use crate::parser::ast::{Signature, Span};
 use crate::parser::Statement;
+use crate::vm::Closure;
 
 /// Runtime values
 #[derive(Clone)]
 pub enum Value {
-    /// Number (எண்)
+    /// Whole number of any size (முழுஎண்)
+    Integer(Integer),
+    /// Decimal number (தசமஎண்)
     Number(f64),
     /// String (சரம்)
     String(String),
@@ -22,43 +31,71 @@ pub enum Value {
     Null,
     /// List (பட்டியல்)
     List(Rc<RefCell<Vec<Value>>>),
+    /// Immutable tuple (தொகுதி)
+    Tuple(Rc<[Value]>),
+    /// Lazy sequence of integers from `வரம்பு` (வரம்பு)
+    Range { start: i64, end: i64, step: i64 },
+    /// Iteration in progress, advanced by each item taken (மீளி)
+    Iterator(Rc<RefCell<Iter>>),
     /// Dictionary (அகராதி)
     Dict(Rc<RefCell<HashMap<String, Value>>>),
     /// Function (செயல்)
     Function(AgamFunction),
     /// Native/built-in function
     NativeFunction(NativeFunction),
+    /// Function compiled for the bytecode VM
+    Closure(Rc<Closure>),
     /// User-defined struct instance
     Struct {
         name: String,
         fields: Rc<RefCell<HashMap<String, Value>>>,
+        /// Methods of its definition, shared by all instances
+        methods: Rc<HashMap<String, Value>>,
     },
-    /// Enum variant
+    /// Enum variant, with its payload fields in declaration order
     EnumVariant {
         enum_name: String,
         variant: String,
+        payload: Vec<(String, Value)>,
+    },
+    /// Constructor of an enum variant that carries a payload: `Shape.Circle`
+    VariantConstructor {
+        enum_name: String,
+        variant: String,
+        fields: Vec<String>,
     },
     /// Struct definition (type)
     StructDef {
         name: String,
         field_names: Vec<String>,
+        /// Method functions, taking the receiver first
+        methods: Rc<HashMap<String, Value>>,
+    },
+    /// Method bound to its receiver: `obj.method`
+    BoundMethod {
+        receiver: Box<Value>,
+        method: Box<Value>,
     },
     /// Enum definition (type)
     EnumDef {
         name: String,
-        variants: Vec<String>,
+        /// Variant names with their payload field names
+        variants: Vec<(String, Vec<String>)>,
     },
     /// Module namespace (கூறு)
     Module {
         name: String,
         exports: Rc<RefCell<HashMap<String, Value>>>,
     },
+    /// Error caught by பிடி (பிழை)
+    Error(Rc<AgamError>),
 }
 
 impl fmt::Debug for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Value::Number(n) => write!(f, "{}", n),
+            Value::Integer(n) => write!(f, "{}", n),
+            Value::Number(n) => write!(f, "{}", format_float(*n)),
             Value::String(s) => write!(f, "\"{}\"", s),
             Value::Boolean(b) => write!(f, "{}", if *b { "உண்மை" } else { "பொய்" }),
             Value::Null => write!(f, "இல்லா"),
@@ -73,6 +110,27 @@ impl fmt::Debug for Value {
                 }
                 write!(f, "]")
             }
+            Value::Tuple(items) => {
+                write!(f, "(")?;
+                for (i, v) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{:?}", v)?;
+                }
+                if items.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            Value::Range { start, end, step } => {
+                write!(f, "வரம்பு({}, {}", start, end)?;
+                if *step != 1 {
+                    write!(f, ", {}", step)?;
+                }
+                write!(f, ")")
+            }
+            Value::Iterator(_) => write!(f, "<மீளி>"),
             Value::Dict(dict) => {
                 let dict = dict.borrow();
                 write!(f, "{{")?;
@@ -85,8 +143,9 @@ impl fmt::Debug for Value {
                 write!(f, "}}")
             }
             Value::Function(func) => write!(f, "<செயல் {}>", func.name),
+            Value::Closure(closure) => write!(f, "<செயல் {}>", closure.proto.name),
             Value::NativeFunction(func) => write!(f, "<உள்ளமைப்பு செயல் {}>", func.name),
-            Value::Struct { name, fields } => {
+            Value::Struct { name, fields, .. } => {
                 let fields = fields.borrow();
                 write!(f, "{} {{", name)?;
                 for (i, (k, v)) in fields.iter().enumerate() {
@@ -95,10 +154,24 @@ impl fmt::Debug for Value {
                 }
                 write!(f, "}}")
             }
-            Value::EnumVariant { enum_name, variant } => write!(f, "{}.{}", enum_name, variant),
+            Value::EnumVariant { enum_name, variant, payload } => {
+                write!(f, "{}.{}", enum_name, variant)?;
+                if !payload.is_empty() {
+                    write!(f, "(")?;
+                    for (i, (_, v)) in payload.iter().enumerate() {
+                        if i > 0 { write!(f, ", ")?; }
+                        write!(f, "{:?}", v)?;
+                    }
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+            Value::VariantConstructor { enum_name, variant, .. } => write!(f, "<மாறுபாடு {}.{}>", enum_name, variant),
             Value::StructDef { name, .. } => write!(f, "<கட்டமைப்பு {}>", name),
+            Value::BoundMethod { method, .. } => write!(f, "{}", method),
             Value::EnumDef { name, .. } => write!(f, "<விருப்பம் {}>", name),
             Value::Module { name, .. } => write!(f, "<கூறு {}>", name),
+            Value::Error(error) => write!(f, "{}", error),
         }
     }
 }
@@ -106,13 +179,8 @@ impl fmt::Debug for Value {
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Value::Number(n) => {
-                if n.fract() == 0.0 {
-                    write!(f, "{}", *n as i64)
-                } else {
-                    write!(f, "{}", n)
-                }
-            }
+            Value::Integer(n) => write!(f, "{}", n),
+            Value::Number(n) => write!(f, "{}", format_float(*n)),
             Value::String(s) => write!(f, "{}", s),
             Value::Boolean(b) => write!(f, "{}", if *b { "உண்மை" } else { "பொய்" }),
             Value::Null => write!(f, "இல்லா"),
@@ -127,6 +195,27 @@ impl fmt::Display for Value {
                 }
                 write!(f, "]")
             }
+            Value::Tuple(items) => {
+                write!(f, "(")?;
+                for (i, v) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", v)?;
+                }
+                if items.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            Value::Range { start, end, step } => {
+                write!(f, "வரம்பு({}, {}", start, end)?;
+                if *step != 1 {
+                    write!(f, ", {}", step)?;
+                }
+                write!(f, ")")
+            }
+            Value::Iterator(_) => write!(f, "<மீளி>"),
             Value::Dict(dict) => {
                 let dict = dict.borrow();
                 write!(f, "{{")?;
@@ -139,8 +228,9 @@ impl fmt::Display for Value {
                 write!(f, "}}")
             }
             Value::Function(func) => write!(f, "<செயல் {}>", func.name),
+            Value::Closure(closure) => write!(f, "<செயல் {}>", closure.proto.name),
             Value::NativeFunction(func) => write!(f, "<உள்ளமைப்பு செயல் {}>", func.name),
-            Value::Struct { name, fields } => {
+            Value::Struct { name, fields, .. } => {
                 let fields = fields.borrow();
                 write!(f, "{} {{", name)?;
                 for (i, (k, v)) in fields.iter().enumerate() {
@@ -149,10 +239,24 @@ impl fmt::Display for Value {
                 }
                 write!(f, "}}")
             }
-            Value::EnumVariant { enum_name, variant } => write!(f, "{}.{}", enum_name, variant),
+            Value::EnumVariant { enum_name, variant, payload } => {
+                write!(f, "{}.{}", enum_name, variant)?;
+                if !payload.is_empty() {
+                    write!(f, "(")?;
+                    for (i, (_, v)) in payload.iter().enumerate() {
+                        if i > 0 { write!(f, ", ")?; }
+                        write!(f, "{}", v)?;
+                    }
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+            Value::VariantConstructor { enum_name, variant, .. } => write!(f, "<மாறுபாடு {}.{}>", enum_name, variant),
             Value::StructDef { name, .. } => write!(f, "<கட்டமைப்பு {}>", name),
+            Value::BoundMethod { method, .. } => write!(f, "{}", method),
             Value::EnumDef { name, .. } => write!(f, "<விருப்பம் {}>", name),
             Value::Module { name, .. } => write!(f, "<கூறு {}>", name),
+            Value::Error(error) => write!(f, "{}", error),
         }
     }
 }
@@ -160,78 +264,289 @@ impl fmt::Display for Value {
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
+            (Value::Integer(a), Value::Integer(b)) => a == b,
             (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::Integer(a), Value::Number(b)) | (Value::Number(b), Value::Integer(a)) => {
+                a.to_f64() == Some(*b)
+            }
             (Value::String(a), Value::String(b)) => a == b,
             (Value::Boolean(a), Value::Boolean(b)) => a == b,
             (Value::Null, Value::Null) => true,
             (Value::List(a), Value::List(b)) => Rc::ptr_eq(a, b),
+            (Value::Tuple(a), Value::Tuple(b)) => a == b,
+            // Ranges are equal when they produce the same numbers
+            (Value::Range { start: s1, end: e1, step: t1 }, Value::Range { start: s2, end: e2, step: t2 }) => {
+                let len = range_len(*s1, *e1, *t1);
+                len == range_len(*s2, *e2, *t2) && (len == 0 || (s1 == s2 && (len == 1 || t1 == t2)))
+            }
+            (Value::Iterator(a), Value::Iterator(b)) => Rc::ptr_eq(a, b),
             (Value::Dict(a), Value::Dict(b)) => Rc::ptr_eq(a, b),
+            (Value::EnumVariant { enum_name: e1, variant: v1, payload: p1 },
+             Value::EnumVariant { enum_name: e2, variant: v2, payload: p2 }) => e1 == e2 && v1 == v2 && p1 == p2,
             _ => false,
         }
     }
 }
 
 impl Value {
+    /// Integer value from any primitive integer
+    pub fn int(n: impl Into<Integer>) -> Value {
+        Value::Integer(n.into())
+    }
+
+    /// Numeric value as a float, for integers and decimals alike
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Integer(n) => n.to_f64(),
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Integer value that fits in an `i64`
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Integer(n) => n.to_i64(),
+            _ => None,
+        }
+    }
+
     /// Check if value is truthy
     pub fn is_truthy(&self) -> bool {
         match self {
             Value::Null => false,
             Value::Boolean(b) => *b,
+            Value::Integer(n) => !n.is_zero(),
             Value::Number(n) => *n != 0.0,
             Value::String(s) => !s.is_empty(),
             Value::List(list) => !list.borrow().is_empty(),
+            Value::Tuple(items) => !items.is_empty(),
+            Value::Range { start, end, step } => range_len(*start, *end, *step) > 0,
+            Value::Iterator(_) => true,
             Value::Dict(dict) => !dict.borrow().is_empty(),
             Value::Function(_) => true,
+            Value::Closure(_) => true,
             Value::NativeFunction(_) => true,
             Value::Struct { .. } => true,
             Value::EnumVariant { .. } => true,
+            Value::VariantConstructor { .. } => true,
             Value::StructDef { .. } => true,
+            Value::BoundMethod { .. } => true,
             Value::EnumDef { .. } => true,
             Value::Module { .. } => true,
+            Value::Error(_) => true,
         }
     }
 
     /// Get the type name in Tamil
     pub fn type_name(&self) -> &'static str {
         match self {
-            Value::Number(_) => "எண்",
+            Value::Integer(_) => "முழுஎண்",
+            Value::Number(_) => "தசமஎண்",
             Value::String(_) => "சரம்",
             Value::Boolean(_) => "உண்மைபொய்",
             Value::Null => "இல்லா",
             Value::List(_) => "பட்டியல்",
+            Value::Tuple(_) => "தொகுதி",
+            Value::Range { .. } => "வரம்பு",
+            Value::Iterator(_) => "மீளி",
             Value::Dict(_) => "அகராதி",
-            Value::Function(_) => "செயல்",
+            Value::Function(_) | Value::Closure(_) | Value::BoundMethod { .. } | Value::VariantConstructor { .. } => "செயல்",
             Value::NativeFunction(_) => "உள்ளமைப்பு_செயல்",
             Value::Struct { name: _, .. } => "கட்டமைப்பு",
             Value::EnumVariant { .. } => "விருப்பம்_மதிப்பு",
             Value::StructDef { .. } => "கட்டமைப்பு_வரையறை",
             Value::EnumDef { .. } => "விருப்பம்_வரையறை",
             Value::Module { .. } => "கூறு",
+            Value::Error(_) => "பிழை",
         }
     }
+
+    /// Field of a caught error: kind, message, location, stack or thrown value
+    pub fn error_field(error: &AgamError, field: &str) -> Option<Value> {
+        let (line, column) = error.location();
+        let value = match field {
+            "kind" => Value::String(error.kind().to_string()),
+            "வகை" => Value::String(error_kind_tamil(error.kind()).to_string()),
+            "message" | "செய்தி" => Value::String(error.message()),
+            "line" | "வரி" => Value::int(line),
+            "column" | "நெடுவரிசை" => Value::int(column),
+            "value" | "மதிப்பு" => match error {
+                AgamError::Thrown { value, .. } => (**value).clone(),
+                _ => Value::Null,
+            },
+            "stack" | "பின்தடம்" => {
+                let frames = error
+                    .stack()
+                    .iter()
+                    .map(|frame| {
+                        let mut dict = HashMap::new();
+                        let file = frame.file.clone().map(Value::String).unwrap_or(Value::Null);
+                        for (tamil, english, value) in [
+                            ("செயல்", "function", Value::String(frame.function.clone())),
+                            ("கோப்பு", "file", file),
+                            ("வரி", "line", Value::int(frame.line)),
+                            ("நெடுவரிசை", "column", Value::int(frame.column)),
+                        ] {
+                            dict.insert(tamil.to_string(), value.clone());
+                            dict.insert(english.to_string(), value);
+                        }
+                        Value::Dict(Rc::new(RefCell::new(dict)))
+                    })
+                    .collect();
+                Value::List(Rc::new(RefCell::new(frames)))
+            }
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// Every error kind, as `AgamError::kind` names it
+pub const ERROR_KINDS: [&str; 7] = ["lexer", "parser", "compile", "runtime", "assertion", "thrown", "file"];
+
+/// Tamil name for an error kind, as exposed under the `வகை` field
+pub fn error_kind_tamil(kind: &str) -> &'static str {
+    match kind {
+        "lexer" => "சொற்பிழை",
+        "parser" => "தொடரியல்",
+        "compile" => "தொகுப்பு",
+        "runtime" => "இயக்கம்",
+        "assertion" => "உறுதிப்படுத்தல்",
+        "thrown" => "வீசப்பட்டது",
+        _ => "கோப்பு",
+    }
 }
 
 /// User-defined function
 #[derive(Clone)]
 pub struct AgamFunction {
     pub name: String,
-    pub params: Vec<String>,
-    pub body: Vec<Statement>,
+    pub signature: Rc<Signature>,
+    /// Values of the optional parameters' defaults, computed at definition
+    pub defaults: Rc<[Value]>,
+    pub body: Rc<[Statement]>,
     pub closure: Rc<RefCell<Environment>>,
+    /// Source file the function was defined in, for tracebacks
+    pub file: Option<Rc<str>>,
+    /// Calls return a generator instead of running the body
+    pub is_generator: bool,
 }
 
 impl AgamFunction {
     pub fn new(
         name: String,
-        params: Vec<String>,
-        body: Vec<Statement>,
+        signature: Rc<Signature>,
+        defaults: Rc<[Value]>,
+        body: Rc<[Statement]>,
         closure: Rc<RefCell<Environment>>,
+        file: Option<Rc<str>>,
+        is_generator: bool,
     ) -> Self {
         AgamFunction {
             name,
-            params,
+            signature,
+            defaults,
             body,
             closure,
+            file,
+            is_generator,
+        }
+    }
+}
+
+/// Number of values in `வரம்பு(start, end, step)`
+pub fn range_len(start: i64, end: i64, step: i64) -> i64 {
+    let (start, end, step) = (start as i128, end as i128, step as i128);
+    let len = if step > 0 {
+        (end - start + step - 1) / step
+    } else {
+        (start - end - step - 1) / -step
+    };
+    len.max(0) as i64
+}
+
+/// State of an iteration: what a `ஒவ்வொரு` loop, `எண்ணிடு` or `ஜோடி`
+/// takes items from, one at a time
+pub enum Iter {
+    /// Numbers of a range still to come
+    Range { next: i64, end: i64, step: i64 },
+    /// A list, read by position up to the length it had when the iteration
+    /// started: changes to its items are seen, items appended are not
+    List { list: Rc<RefCell<Vec<Value>>>, index: usize, end: usize },
+    /// Items fixed when the iteration started: tuple items, the letters
+    /// of a string or the keys of a dictionary
+    Items(std::vec::IntoIter<Value>),
+    /// An iterator value; taking an item from it advances it for everyone
+    Shared(Rc<RefCell<Iter>>),
+    /// `(count, item)` pairs
+    Enumerate { inner: Box<Iter>, count: Integer },
+    /// Tuples of one item from each, until the shortest runs out
+    Zip(Vec<Iter>),
+    /// Bound `அடுத்து` method of a struct, called for each item until it
+    /// returns இல்லா
+    Method(Value),
+    /// A call to a function containing `விளை`, suspended in whatever form
+    /// the running interpreter keeps it and resumed through `Caller::resume`
+    Generator(Box<dyn Any>),
+}
+
+impl Iter {
+    /// The next item, or `None` when the iteration is over. `span` is
+    /// where the items are being taken, for errors raised by `அடுத்து` methods
+    pub fn next(&mut self, caller: &mut dyn Caller, span: Span) -> Result<Option<Value>, AgamError> {
+        match self {
+            Iter::Range { next, end, step } => {
+                let done = if *step > 0 { *next >= *end } else { *next <= *end };
+                if done {
+                    return Ok(None);
+                }
+                let item = Value::int(*next);
+                // Past i64::MAX there is nothing left to produce
+                *next = next.checked_add(*step).unwrap_or(*end);
+                Ok(Some(item))
+            }
+            Iter::List { list, index, end } => {
+                if *index >= *end {
+                    return Ok(None);
+                }
+                let item = list.borrow().get(*index).cloned();
+                *index += 1;
+                Ok(item)
+            }
+            Iter::Items(items) => Ok(items.next()),
+            Iter::Shared(iter) => {
+                let mut iter = iter.try_borrow_mut().map_err(|_| {
+                    AgamError::runtime_error_at(span, "மீளி ஏற்கனவே இயங்கிக்கொண்டிருக்கிறது".to_string())
+                })?;
+                iter.next(caller, span)
+            }
+            Iter::Enumerate { inner, count } => {
+                let Some(item) = inner.next(caller, span)? else {
+                    return Ok(None);
+                };
+                let pair = Value::Tuple(Rc::from([Value::Integer(count.clone()), item]));
+                *count = &*count + &Integer::Small(1);
+                Ok(Some(pair))
+            }
+            Iter::Zip(iters) => {
+                if iters.is_empty() {
+                    return Ok(None);
+                }
+                let mut items = Vec::with_capacity(iters.len());
+                for iter in iters.iter_mut() {
+                    match iter.next(caller, span)? {
+                        Some(item) => items.push(item),
+                        None => return Ok(None),
+                    }
+                }
+                Ok(Some(Value::Tuple(items.into())))
+            }
+            Iter::Method(next) => match caller.call(next, Vec::new(), span)? {
+                Value::Null => Ok(None),
+                item => Ok(Some(item)),
+            },
+            Iter::Generator(generator) => caller.resume(generator.as_mut(), span),
         }
     }
 }
@@ -239,12 +554,33 @@ impl AgamFunction {
 /// Native function type
 pub type NativeFn = fn(&[Value]) -> Result<Value, String>;
 
+/// Native function that calls back into Agam code; `Span` is where it was called
+pub type CallbackFn = fn(&[Value], &mut dyn Caller, Span) -> Result<Value, AgamError>;
+
+/// The running interpreter, as seen by native functions that call Agam
+/// functions (`செயல்`, lambdas, built-ins) passed to them
+pub trait Caller {
+    /// Call `function` with `args`, as if called at `span`
+    fn call(&mut self, function: &Value, args: Vec<Value>, span: Span) -> Result<Value, AgamError>;
+
+    /// Run a generator made by this interpreter until its next `விளை`,
+    /// returning the value given, or `None` once the function has finished
+    fn resume(&mut self, generator: &mut dyn Any, span: Span) -> Result<Option<Value>, AgamError>;
+}
+
+/// How a native function is implemented
+#[derive(Clone, Copy)]
+pub enum NativeImpl {
+    Plain(NativeFn),
+    Callback(CallbackFn),
+}
+
 /// Native function wrapper
 #[derive(Clone)]
 pub struct NativeFunction {
     pub name: String,
     pub arity: Option<usize>, // None means variadic
-    pub function: NativeFn,
+    pub function: NativeImpl,
 }
 
 impl NativeFunction {
@@ -252,7 +588,17 @@ impl NativeFunction {
         NativeFunction {
             name: name.to_string(),
             arity,
-            function,
+            function: NativeImpl::Plain(function),
+        }
+    }
+
+    /// Native function given the interpreter and its call site: one that
+    /// takes Agam functions as arguments or raises errors of its own kind
+    pub fn with_callback(name: &str, arity: Option<usize>, function: CallbackFn) -> Self {
+        NativeFunction {
+            name: name.to_string(),
+            arity,
+            function: NativeImpl::Callback(function),
         }
     }
 }
@@ -260,33 +606,141 @@ impl NativeFunction {
 /// Variable environment with scope chain
 #[derive(Clone)]
 pub struct Environment {
-    values: HashMap<String, (Value, bool)>, // (value, is_const)
+    /// Variables defined by name, as (value, is_const); `None` for a name
+    /// given an index before it was defined
+    values: Vec<Option<(Value, bool)>>,
+    /// Index of each name in `values`. Indices never change, so compiled
+    /// code can refer to a global by index
+    indices: HashMap<String, usize>,
+    /// Block and call variables, by the slot the resolver assigned;
+    /// `None` until the declaration runs
+    slots: Vec<Option<(Value, bool)>>,
     parent: Option<Rc<RefCell<Environment>>>,
 }
 
 impl Environment {
     pub fn new() -> Self {
         Environment {
-            values: HashMap::new(),
+            values: Vec::new(),
+            indices: HashMap::new(),
+            slots: Vec::new(),
             parent: None,
         }
     }
 
     pub fn with_parent(parent: Rc<RefCell<Environment>>) -> Self {
         Environment {
-            values: HashMap::new(),
+            values: Vec::new(),
+            indices: HashMap::new(),
+            slots: Vec::new(),
             parent: Some(parent),
         }
     }
 
+    /// Define the variable in a resolved slot of this environment
+    pub fn define_slot(&mut self, slot: usize, value: Value, is_const: bool) {
+        if slot >= self.slots.len() {
+            self.slots.resize(slot + 1, None);
+        }
+        self.slots[slot] = Some((value, is_const));
+    }
+
+    /// Get the variable in `slot` of the environment `depth` levels up
+    pub fn get_at(&self, depth: usize, slot: usize) -> Option<Value> {
+        if depth > 0 {
+            return self.parent.as_ref()?.borrow().get_at(depth - 1, slot);
+        }
+        self.slots.get(slot)?.as_ref().map(|(value, _)| value.clone())
+    }
+
+    /// Assign the variable `name` in `slot` of the environment `depth` levels up
+    pub fn assign_at(&mut self, depth: usize, slot: usize, name: &str, value: Value) -> Result<(), String> {
+        if depth > 0 {
+            return match &self.parent {
+                Some(parent) => parent.borrow_mut().assign_at(depth - 1, slot, name, value),
+                None => Err(format!("வரையறுக்கப்படாத மாறி '{}'", name)),
+            };
+        }
+        match self.slots.get_mut(slot) {
+            Some(Some((_, true))) => Err(format!("'{}' மாறாத மாறி, மாற்ற இயலாது", name)),
+            Some(Some(entry)) => {
+                entry.0 = value;
+                Ok(())
+            }
+            _ => Err(format!("வரையறுக்கப்படாத மாறி '{}'", name)),
+        }
+    }
+
+    /// Get a global (top-level) variable
+    pub fn get_global(&self, name: &str) -> Option<Value> {
+        match &self.parent {
+            Some(parent) => parent.borrow().get_global(name),
+            None => self.entry(name).map(|(value, _)| value.clone()),
+        }
+    }
+
+    /// Assign a global (top-level) variable
+    pub fn assign_global(&mut self, name: &str, value: Value) -> Result<(), String> {
+        match &self.parent {
+            Some(parent) => parent.borrow_mut().assign_global(name, value),
+            None => self.assign(name, value),
+        }
+    }
+
+    /// Names defined by name in this environment, with whether each is constant
+    pub fn declared(&self) -> HashMap<String, bool> {
+        self.indices
+            .iter()
+            .filter_map(|(name, &index)| Some((name.clone(), self.values[index].as_ref()?.1)))
+            .collect()
+    }
+
+    /// Index of `name` in this environment, giving it one if it has none.
+    /// The variable itself stays undefined until `define` or `define_index`
+    pub fn index_of(&mut self, name: &str) -> usize {
+        if let Some(&index) = self.indices.get(name) {
+            return index;
+        }
+        self.values.push(None);
+        self.indices.insert(name.to_string(), self.values.len() - 1);
+        self.values.len() - 1
+    }
+
+    /// Get the variable at `index` of this environment
+    pub fn get_index(&self, index: usize) -> Option<Value> {
+        self.values[index].as_ref().map(|(value, _)| value.clone())
+    }
+
+    /// Define the variable at `index` of this environment
+    pub fn define_index(&mut self, index: usize, value: Value, is_const: bool) {
+        self.values[index] = Some((value, is_const));
+    }
+
+    /// Assign the variable `name` at `index` of this environment
+    pub fn assign_index(&mut self, index: usize, name: &str, value: Value) -> Result<(), String> {
+        match &mut self.values[index] {
+            Some((_, true)) => Err(format!("'{}' மாறாத மாறி, மாற்ற இயலாது", name)),
+            Some(entry) => {
+                entry.0 = value;
+                Ok(())
+            }
+            None => Err(format!("வரையறுக்கப்படாத மாறி '{}'", name)),
+        }
+    }
+
+    fn entry(&self, name: &str) -> Option<&(Value, bool)> {
+        self.values[*self.indices.get(name)?].as_ref()
+    }
+
     /// Define a new variable
     pub fn define(&mut self, name: String, value: Value, is_const: bool) {
-        self.values.insert(name, (value, is_const));
+        let index = self.index_of(&name);
+        self.values[index] = Some((value, is_const));
     }
 
     /// Get a variable's value
     pub fn get(&self, name: &str) -> Option<Value> {
-        if let Some((value, _)) = self.values.get(name) {
+        if let Some((value, _)) = self.entry(name) {
             return Some(value.clone());
         }
         if let Some(parent) = &self.parent {
@@ -297,7 +751,7 @@ impl Environment {
 
     /// Check if a variable is const
     pub fn is_const(&self, name: &str) -> Option<bool> {
-        if let Some((_, is_const)) = self.values.get(name) {
+        if let Some((_, is_const)) = self.entry(name) {
             return Some(*is_const);
         }
         if let Some(parent) = &self.parent {
@@ -308,12 +762,10 @@ impl Environment {
 
     /// Assign to an existing variable
     pub fn assign(&mut self, name: &str, value: Value) -> Result<(), String> {
-        if let Some((_, is_const)) = self.values.get(name) {
-            if *is_const {
-                return Err(format!("'{}' மாறாத மாறி, மாற்ற இயலாது", name));
+        if let Some(&index) = self.indices.get(name) {
+            if self.values[index].is_some() {
+                return self.assign_index(index, name, value);
             }
-            self.values.insert(name.to_string(), (value, false));
-            return Ok(());
         }
         if let Some(parent) = &self.parent {
             return parent.borrow_mut().assign(name, value);
@@ -323,7 +775,11 @@ impl Environment {
 
     /// Get all variable names in this environment (not including parent)
     pub fn get_all_names(&self) -> Vec<String> {
-        self.values.keys().cloned().collect()
+        self.indices
+            .iter()
+            .filter(|(_, &index)| self.values[index].is_some())
+            .map(|(name, _)| name.clone())
+            .collect()
     }
 }
 
@@ -332,3 +788,14 @@ impl Default for Environment {
         Self::new()
     }
 }
+
+/// Format a decimal number so it never reads as an integer (`3.0`, not `3`)
+fn format_float(n: f64) -> String {
+    if !n.is_finite() || n.fract() != 0.0 {
+        n.to_string()
+    } else if n.abs() < 1e16 {
+        format!("{:.1}", n)
+    } else {
+        format!("{:e}", n)
+    }
+}
@@ -0,0 +1,234 @@
+//! Bytecode for the Agam VM
+//!
+//! Instructions operate on a value stack. Locals live in numbered frame
+//! slots; locals captured by nested functions, and locals a statement may
+//! leave unbound, live in shared cells.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::interpreter::ops::PatternShape;
+use crate::parser::ast::{BinaryOp, Signature, Span, UnaryOp};
+use crate::types::{Environment, Value};
+
+/// A single VM instruction. Operands index the chunk's tables or frame slots
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    /// Push `constants[i]`
+    Constant(u32),
+    Null,
+    True,
+    False,
+    Pop,
+
+    // Variables
+    LoadLocal(u32),
+    /// Store top of stack into a slot, leaving it on the stack
+    StoreLocal(u32),
+    /// Give a cell local a fresh, unbound cell (on entering its block)
+    NewCell(u32),
+    /// Bind a cell to the top of stack, leaving it on the stack
+    DefineCell(u32),
+    /// Cell and upvalue access; `names[i]` is reported if the cell is unbound
+    LoadCell(u32, u32),
+    StoreCell(u32, u32),
+    LoadUpvalue(u32, u32),
+    StoreUpvalue(u32, u32),
+    /// Push the global at index `a`; `names[b]` is reported if it is undefined
+    LoadGlobal(u32, u32),
+    /// Assign the global at index `a`, named `names[b]`, leaving the value on the stack
+    StoreGlobal(u32, u32),
+    /// Define the global at index `i`, popping the value; flag marks மாறாத
+    DefineGlobal(u32, bool),
+    /// Raise "cannot assign to constant `names[i]`"
+    ConstAssign(u32),
+
+    // Operators
+    Binary(BinaryOp),
+    Unary(UnaryOp),
+    /// Literal pattern test: pops two values, pushes whether they match
+    ValuesEqual,
+    /// Pops a value; if it has the shape `shapes[i]`, pushes its parts and
+    /// true, otherwise pushes false
+    Destructure(u32),
+
+    // Control flow
+    Jump(u32),
+    /// Pop the condition and jump if it is falsy
+    JumpIfFalse(u32),
+    /// `மற்றும்`: jump keeping the value if falsy, else pop it
+    JumpIfFalseOrPop(u32),
+    /// `அல்லது`: jump keeping the value if truthy, else pop it
+    JumpIfTrueOrPop(u32),
+    /// Count a loop iteration in slot `i`, failing past the loop limit
+    LoopGuard(u32),
+    /// Pop an iterable and store an iterator over it in slot `i`
+    ForPrep(u32),
+    /// Push the next item of the loop in slot `i`, or jump when done
+    ForNext(u32, u32),
+
+    // Functions
+    /// Call with `n` arguments above the callee
+    Call(u32),
+    /// Call with `a` positional arguments followed by the keyword arguments
+    /// named in `keywords[b]`
+    CallKeywords(u32, u32),
+    Return,
+    /// Hand the top of the stack out of a generator, suspending its frame
+    Yield,
+    /// Create a closure over `functions[i]`, popping its parameters' defaults
+    Closure(u32),
+
+    // Data
+    BuildList(u32),
+    BuildTuple(u32),
+    /// Pops a tuple or list of exactly `n` items and pushes them last to
+    /// first, leaving the first item on top
+    Unpack(u32),
+    /// Build a dictionary from `n` key/value pairs
+    BuildDict(u32),
+    /// Pop a value and append it to the list in slot `i`: a list comprehension
+    ListAppend(u32),
+    /// Pop a key and value and insert them into the dictionary in slot `i`
+    DictInsert(u32),
+    /// Concatenate `n` f-string parts
+    BuildString(u32),
+    /// Pops object and index, pushes `object[index]`
+    Index,
+    /// Pops object, index and value, stores and pushes the value
+    StoreIndex,
+    /// Pops object, start, end and step (null when omitted), pushes the slice
+    Slice,
+    /// Pops object, start, end, step and values, stores and pushes the values
+    StoreSlice,
+    GetMember(u32),
+    /// Pops object and value, stores `object.names[i]` and pushes the value
+    SetMember(u32),
+    /// Pops a struct definition named `names[a]` and `b` field values
+    StructInit(u32, u32),
+
+    // Statements
+    /// Print `n` values separated by spaces
+    Print(u32),
+    /// Run `imports[i]` and push the imported values
+    Import(u32),
+    /// Pop the method closures of `structs[i]` and push its definition
+    Struct(u32),
+    /// Raise a runtime error with message `constants[i]`
+    Error(u32),
+
+    // Errors
+    /// Install a handler jumping to the target when an error is raised
+    SetupTry(u32),
+    PopTry,
+    /// Peek the caught error and make it the one a bare `வீசு` re-raises
+    PushHandled,
+    PopHandled,
+    /// If the caught error on the stack does not match filter `names[a]`, jump to `b`
+    CatchMatch(u32, u32),
+    /// Pop a value and raise it
+    Throw,
+    /// Raise the error being handled (bare `வீசு`)
+    Rethrow,
+    /// Pop a caught error and raise it again
+    Reraise,
+    /// Pop the `பொருத்து` value and raise "no arm matched"
+    NoMatch,
+}
+
+/// An `இறக்குமதி` statement
+#[derive(Debug, Clone)]
+pub struct ImportSpec {
+    pub module: String,
+    /// Names to import; `None` imports the module as a namespace
+    pub items: Option<Vec<String>>,
+}
+
+/// A `கட்டமைப்பு` definition; its methods are created at runtime as closures
+#[derive(Debug, Clone)]
+pub struct StructSpec {
+    pub name: String,
+    pub field_names: Vec<String>,
+    /// Method names, in the order their closures are pushed
+    pub methods: Vec<String>,
+}
+
+/// Compiled code with its constant tables
+#[derive(Debug, Clone, Default)]
+pub struct Chunk {
+    pub code: Vec<Op>,
+    /// Source location of each instruction
+    pub spans: Vec<Span>,
+    pub constants: Vec<Value>,
+    pub names: Vec<String>,
+    pub functions: Vec<Rc<FunctionProto>>,
+    pub imports: Vec<ImportSpec>,
+    pub structs: Vec<StructSpec>,
+    /// Shapes checked by destructuring match patterns
+    pub shapes: Vec<PatternShape>,
+    /// Names of the keyword arguments of each `CallKeywords`
+    pub keywords: Vec<Rc<[String]>>,
+}
+
+impl Chunk {
+    /// Append an instruction, returning its index
+    pub fn emit(&mut self, op: Op, span: Span) -> usize {
+        self.code.push(op);
+        self.spans.push(span);
+        self.code.len() - 1
+    }
+
+    pub fn add_constant(&mut self, value: Value) -> u32 {
+        self.constants.push(value);
+        (self.constants.len() - 1) as u32
+    }
+
+    /// Intern a name, reusing an existing entry
+    pub fn add_name(&mut self, name: &str) -> u32 {
+        if let Some(i) = self.names.iter().position(|n| n == name) {
+            return i as u32;
+        }
+        self.names.push(name.to_string());
+        (self.names.len() - 1) as u32
+    }
+}
+
+/// A variable shared between a frame and its closures; `None` until bound
+pub type Cell = Rc<RefCell<Option<Value>>>;
+
+/// Where a closure finds a captured variable when it is created
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Capture {
+    /// A cell of the enclosing frame
+    Cell(u32),
+    /// An upvalue of the enclosing closure
+    Upvalue(u32),
+}
+
+/// A compiled function (or the top level of a program or module)
+#[derive(Debug)]
+pub struct FunctionProto {
+    pub name: String,
+    pub signature: Signature,
+    /// Parameter slots, including `*rest` and `**keywords`
+    pub arity: usize,
+    pub chunk: Chunk,
+    /// Frame slots needed, parameters first
+    pub local_count: usize,
+    pub cell_count: usize,
+    pub captures: Vec<Capture>,
+    /// Source file, for tracebacks
+    pub file: Option<Rc<str>>,
+    /// Calls return a generator instead of running the body
+    pub is_generator: bool,
+}
+
+/// A function value created by the VM
+pub struct Closure {
+    pub proto: Rc<FunctionProto>,
+    pub upvalues: Vec<Cell>,
+    /// Values of the optional parameters' defaults
+    pub defaults: Vec<Value>,
+    /// Globals of the program or module that defined the function
+    pub globals: Rc<RefCell<Environment>>,
+}
@@ -0,0 +1,1458 @@
+//! Compiler from the AST to VM bytecode
+//!
+//! Expects a resolved program. Top-level names become globals at indices
+//! of the environment the code will run in, block and function variables
+//! get numbered frame slots, and
+//! variables captured by nested functions (or that a statement may leave
+//! unbound) get cells shared with closures.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use crate::error::AgamError;
+use crate::interpreter::ops;
+use crate::parser::ast::*;
+use crate::resolver::{block_declarations, pattern_declarations, DeclarationKind};
+use crate::types::{Environment, Value};
+use crate::vm::bytecode::{Capture, Chunk, FunctionProto, ImportSpec, Op, StructSpec};
+
+/// Compile a whole program (or module) into a function named `name`, to
+/// run with `globals`
+pub fn compile(
+    program: &Program,
+    name: &str,
+    file: Option<Rc<str>>,
+    globals: &Rc<RefCell<Environment>>,
+) -> Result<Rc<FunctionProto>, AgamError> {
+    let mut compiler = Compiler {
+        functions: Vec::new(),
+        file,
+        globals: Rc::clone(globals),
+        statement_span: Span::default(),
+    };
+
+    let mut captured = HashSet::new();
+    captured_names_in_block(&program.statements, false, &mut captured);
+    compiler.functions.push(FunctionState::new(name, Signature::default(), captured, true));
+
+    let last = program.statements.len().saturating_sub(1);
+    for (i, stmt) in program.statements.iter().enumerate() {
+        compiler.statement_span = stmt.span;
+        match &stmt.kind {
+            // The program's value is that of its final expression statement
+            StatementKind::Expression(expr) if i == last => {
+                compiler.expression(expr)?;
+                compiler.emit(Op::Return, stmt.span);
+            }
+            _ => compiler.statement(stmt)?,
+        }
+    }
+    let end = program.statements.last().map(|s| s.span).unwrap_or_default();
+    compiler.emit(Op::Null, end);
+    compiler.emit(Op::Return, end);
+
+    let state = compiler.functions.pop().expect("top-level function state");
+    Ok(Rc::new(state.finish(compiler.file)))
+}
+
+/// Where a variable lives in its frame
+#[derive(Debug, Clone, Copy)]
+enum Slot {
+    Local(u32),
+    Cell(u32),
+}
+
+#[derive(Debug, Clone)]
+struct Var {
+    name: String,
+    slot: Slot,
+    is_const: bool,
+    /// Captured variables get their cell when the block starts; until the
+    /// declaration runs, code in the same function still sees outer names
+    declared: bool,
+}
+
+/// A block of variables
+struct Scope {
+    vars: Vec<Var>,
+    /// The program's top level, whose variables are globals
+    global: bool,
+    slot_start: u32,
+    cell_start: u32,
+}
+
+struct LoopState {
+    continue_target: usize,
+    break_jumps: Vec<usize>,
+    /// Depth of the exit stack when the loop started
+    exit_depth: usize,
+    /// Iteration counter slot of the loop
+    guard: Option<u32>,
+}
+
+/// Cleanup owed when control leaves a try statement early
+#[derive(Clone, Copy)]
+enum Exit<'a> {
+    /// An installed error handler
+    Try,
+    /// An error being handled by a பிடி block
+    Handled,
+    /// A finally block to run
+    Finally(&'a [Statement]),
+}
+
+struct Upvalue {
+    name: String,
+    capture: Capture,
+    is_const: bool,
+}
+
+/// What a name refers to
+enum Target {
+    Local(u32),
+    Cell(u32),
+    Upvalue(u32),
+    Global,
+}
+
+struct FunctionState<'a> {
+    name: String,
+    signature: Signature,
+    chunk: Chunk,
+    scopes: Vec<Scope>,
+    next_slot: u32,
+    max_slots: u32,
+    next_cell: u32,
+    max_cells: u32,
+    /// Names used inside functions nested in this one; variables with these
+    /// names are kept in cells
+    captured: HashSet<String>,
+    upvalues: Vec<Upvalue>,
+    loops: Vec<LoopState>,
+    exits: Vec<Exit<'a>>,
+}
+
+impl<'a> FunctionState<'a> {
+    fn new(name: &str, signature: Signature, captured: HashSet<String>, script: bool) -> Self {
+        FunctionState {
+            name: name.to_string(),
+            signature,
+            chunk: Chunk::default(),
+            scopes: vec![Scope {
+                vars: Vec::new(),
+                global: script,
+                slot_start: 0,
+                cell_start: 0,
+            }],
+            next_slot: 0,
+            max_slots: 0,
+            next_cell: 0,
+            max_cells: 0,
+            captured,
+            upvalues: Vec::new(),
+            loops: Vec::new(),
+            exits: Vec::new(),
+        }
+    }
+
+    fn alloc_slot(&mut self) -> u32 {
+        let slot = self.next_slot;
+        self.next_slot += 1;
+        self.max_slots = self.max_slots.max(self.next_slot);
+        slot
+    }
+
+    fn alloc_cell(&mut self) -> u32 {
+        let cell = self.next_cell;
+        self.next_cell += 1;
+        self.max_cells = self.max_cells.max(self.next_cell);
+        cell
+    }
+
+    /// Find a variable in this function's open blocks
+    fn find(&self, name: &str, declared_only: bool) -> Option<&Var> {
+        self.scopes
+            .iter()
+            .rev()
+            .filter(|scope| !scope.global)
+            .flat_map(|scope| scope.vars.iter().rev())
+            .find(|var| var.name == name && (var.declared || !declared_only))
+    }
+
+    fn finish(self, file: Option<Rc<str>>) -> FunctionProto {
+        FunctionProto {
+            name: self.name,
+            arity: self.signature.slot_count(),
+            signature: self.signature,
+            chunk: self.chunk,
+            local_count: self.max_slots as usize,
+            cell_count: self.max_cells as usize,
+            captures: self.upvalues.into_iter().map(|u| u.capture).collect(),
+            file,
+            is_generator: false,
+        }
+    }
+}
+
+/// Body of a function being compiled
+enum Body<'a> {
+    Block(&'a [Statement]),
+    /// Lambda body: an expression whose value is returned
+    Expr(&'a Expression),
+}
+
+struct Compiler<'a> {
+    /// Functions being compiled, outermost (the program) first
+    functions: Vec<FunctionState<'a>>,
+    file: Option<Rc<str>>,
+    /// Globals the code will run with, which give top-level names their indices
+    globals: Rc<RefCell<Environment>>,
+    /// Top-level statement being compiled, where a stray நிறுத்து/தொடர் is reported
+    statement_span: Span,
+}
+
+impl<'a> Compiler<'a> {
+    fn current(&mut self) -> &mut FunctionState<'a> {
+        self.functions.last_mut().expect("function state")
+    }
+
+    fn emit(&mut self, op: Op, span: Span) -> usize {
+        self.current().chunk.emit(op, span)
+    }
+
+    fn here(&mut self) -> usize {
+        self.current().chunk.code.len()
+    }
+
+    fn constant(&mut self, value: Value) -> u32 {
+        self.current().chunk.add_constant(value)
+    }
+
+    fn name(&mut self, name: &str) -> u32 {
+        self.current().chunk.add_name(name)
+    }
+
+    /// Index of the global `name`
+    fn global(&mut self, name: &str) -> u32 {
+        self.globals.borrow_mut().index_of(name) as u32
+    }
+
+    /// Point the jump at `at` to the current position
+    fn patch(&mut self, at: usize) {
+        let target = self.here() as u32;
+        let code = &mut self.current().chunk.code;
+        code[at] = match code[at] {
+            Op::Jump(_) => Op::Jump(target),
+            Op::JumpIfFalse(_) => Op::JumpIfFalse(target),
+            Op::JumpIfFalseOrPop(_) => Op::JumpIfFalseOrPop(target),
+            Op::JumpIfTrueOrPop(_) => Op::JumpIfTrueOrPop(target),
+            Op::ForNext(slot, _) => Op::ForNext(slot, target),
+            Op::SetupTry(_) => Op::SetupTry(target),
+            Op::CatchMatch(filter, _) => Op::CatchMatch(filter, target),
+            op => op,
+        };
+    }
+
+    /// A list comprehension, or a dictionary one when `value` is given: a
+    /// loop per ஒவ்வொரு clause in a scope of its own, adding to the result
+    /// kept in a hidden slot
+    fn comprehension(
+        &mut self,
+        element: &'a Expression,
+        value: Option<&'a Expression>,
+        clauses: &'a [ComprehensionClause],
+        span: Span,
+    ) -> Result<(), AgamError> {
+        let declarations = clauses
+            .iter()
+            .flat_map(|clause| match clause {
+                ComprehensionClause::For { variables, .. } => variables.as_slice(),
+                ComprehensionClause::If(_) => &[],
+            })
+            .map(|name| (name.as_str(), DeclarationKind::Variable))
+            .collect();
+        self.begin_scope_with(declarations, span);
+
+        let result = self.hidden_slot();
+        self.emit(if value.is_some() { Op::BuildDict(0) } else { Op::BuildList(0) }, span);
+        self.emit(Op::StoreLocal(result), span);
+        self.emit(Op::Pop, span);
+
+        let mut loops = Vec::new();
+        for clause in clauses {
+            match clause {
+                ComprehensionClause::For { variables, iterable, .. } => {
+                    let items = self.hidden_slot();
+                    self.expression(iterable)?;
+                    self.emit(Op::ForPrep(items), iterable.span);
+                    let start = self.emit(Op::ForNext(items, 0), span);
+                    if variables.len() > 1 {
+                        self.emit(Op::Unpack(variables.len() as u32), span);
+                    }
+                    for variable in variables {
+                        self.declare(variable, false, span);
+                    }
+                    loops.push(start);
+                }
+                ComprehensionClause::If(condition) => {
+                    let start = *loops.last().expect("a ஒவ்வொரு clause comes first");
+                    self.expression(condition)?;
+                    self.emit(Op::JumpIfFalse(start as u32), condition.span);
+                }
+            }
+        }
+
+        self.expression(element)?;
+        match value {
+            Some(value) => {
+                self.expression(value)?;
+                self.emit(Op::DictInsert(result), span);
+            }
+            None => {
+                self.emit(Op::ListAppend(result), span);
+            }
+        }
+        for start in loops.into_iter().rev() {
+            self.emit(Op::Jump(start as u32), span);
+            self.patch(start);
+        }
+
+        self.emit(Op::LoadLocal(result), span);
+        self.end_scope();
+        Ok(())
+    }
+
+    // ============= Scopes and variables =============
+
+    /// Open a block. Variables of the block that nested functions capture,
+    /// or that may stay unbound, get fresh cells now, as each run of a block
+    /// has its own variables
+    fn begin_scope(&mut self, statements: &[Statement], span: Span) {
+        self.begin_scope_with(block_declarations(statements), span);
+    }
+
+    /// Open a block whose declarations are known up front
+    fn begin_scope_with(&mut self, declarations: Vec<(&str, DeclarationKind)>, span: Span) {
+        let state = self.current();
+        let mut scope = Scope {
+            vars: Vec::new(),
+            global: false,
+            slot_start: state.next_slot,
+            cell_start: state.next_cell,
+        };
+        let mut cells = Vec::new();
+        for (name, kind) in declarations {
+            let needs_cell = kind == DeclarationKind::Conditional || state.captured.contains(name);
+            if needs_cell && !scope.vars.iter().any(|v| v.name == name) {
+                let cell = state.alloc_cell();
+                cells.push(cell);
+                scope.vars.push(Var { name: name.to_string(), slot: Slot::Cell(cell), is_const: false, declared: false });
+            }
+        }
+        state.scopes.push(scope);
+        for cell in cells {
+            self.emit(Op::NewCell(cell), span);
+        }
+    }
+
+    fn end_scope(&mut self) {
+        let state = self.current();
+        let scope = state.scopes.pop().expect("open scope");
+        state.next_slot = scope.slot_start;
+        state.next_cell = scope.cell_start;
+    }
+
+    /// A slot for compiler temporaries in the current block
+    fn hidden_slot(&mut self) -> u32 {
+        self.current().alloc_slot()
+    }
+
+    /// Define `name` in the current block from the value on top of the stack
+    fn declare(&mut self, name: &str, is_const: bool, span: Span) {
+        let state = self.current();
+        let scope = state.scopes.last_mut().expect("open scope");
+        if scope.global {
+            let index = self.global(name);
+            self.emit(Op::DefineGlobal(index, is_const), span);
+            return;
+        }
+
+        let slot = match scope.vars.iter_mut().rev().find(|v| v.name == name) {
+            // Declaring again in the same block replaces the variable
+            Some(var) => {
+                var.declared = true;
+                var.is_const = is_const;
+                var.slot
+            }
+            None => {
+                let slot = Slot::Local(state.next_slot);
+                state.alloc_slot();
+                let scope = state.scopes.last_mut().expect("open scope");
+                scope.vars.push(Var { name: name.to_string(), slot, is_const, declared: true });
+                slot
+            }
+        };
+        match slot {
+            Slot::Local(i) => self.emit(Op::StoreLocal(i), span),
+            Slot::Cell(i) => self.emit(Op::DefineCell(i), span),
+        };
+        self.emit(Op::Pop, span);
+    }
+
+    /// Resolve a name used in the current function
+    fn resolve(&mut self, name: &str) -> (Target, bool) {
+        let depth = self.functions.len() - 1;
+        if let Some(var) = self.functions[depth].find(name, true) {
+            let target = match var.slot {
+                Slot::Local(i) => Target::Local(i),
+                Slot::Cell(i) => Target::Cell(i),
+            };
+            return (target, var.is_const);
+        }
+        match self.resolve_upvalue(depth, name) {
+            Some((index, is_const)) => (Target::Upvalue(index), is_const),
+            None => (Target::Global, false),
+        }
+    }
+
+    /// Capture `name` from the functions enclosing `functions[depth]`
+    fn resolve_upvalue(&mut self, depth: usize, name: &str) -> Option<(u32, bool)> {
+        if depth == 0 {
+            return None;
+        }
+        if let Some(i) = self.functions[depth].upvalues.iter().position(|u| u.name == name) {
+            let upvalue = &self.functions[depth].upvalues[i];
+            return Some((i as u32, upvalue.is_const));
+        }
+
+        // Nested functions usually run after the enclosing block finished
+        // declaring, so captures may refer to variables declared later
+        let (capture, is_const) = match self.functions[depth - 1].find(name, false) {
+            Some(Var { slot: Slot::Cell(cell), is_const, .. }) => (Capture::Cell(*cell), *is_const),
+            Some(Var { slot: Slot::Local(_), .. }) => return None,
+            None => {
+                let (index, is_const) = self.resolve_upvalue(depth - 1, name)?;
+                (Capture::Upvalue(index), is_const)
+            }
+        };
+
+        let upvalues = &mut self.functions[depth].upvalues;
+        upvalues.push(Upvalue { name: name.to_string(), capture, is_const });
+        Some(((upvalues.len() - 1) as u32, is_const))
+    }
+
+    fn load_variable(&mut self, name: &str, span: Span) {
+        let index = self.name(name);
+        let op = match self.resolve(name).0 {
+            Target::Local(i) => Op::LoadLocal(i),
+            Target::Cell(i) => Op::LoadCell(i, index),
+            Target::Upvalue(i) => Op::LoadUpvalue(i, index),
+            Target::Global => Op::LoadGlobal(self.global(name), index),
+        };
+        self.emit(op, span);
+    }
+
+    /// Assign the value on top of the stack, leaving it there
+    fn store_variable(&mut self, name: &str, span: Span) {
+        let (target, is_const) = self.resolve(name);
+        let index = self.name(name);
+        let op = if is_const {
+            Op::ConstAssign(index)
+        } else {
+            match target {
+                Target::Local(i) => Op::StoreLocal(i),
+                Target::Cell(i) => Op::StoreCell(i, index),
+                Target::Upvalue(i) => Op::StoreUpvalue(i, index),
+                Target::Global => Op::StoreGlobal(self.global(name), index),
+            }
+        };
+        self.emit(op, span);
+    }
+
+    // ============= Statements =============
+
+    fn block(&mut self, statements: &'a [Statement], span: Span) -> Result<(), AgamError> {
+        self.begin_scope(statements, span);
+        for stmt in statements {
+            self.statement(stmt)?;
+        }
+        self.end_scope();
+        Ok(())
+    }
+
+    fn statement(&mut self, stmt: &'a Statement) -> Result<(), AgamError> {
+        let span = stmt.span;
+        match &stmt.kind {
+            StatementKind::Let { name, value, is_const, .. } => {
+                self.expression(value)?;
+                self.declare(name, *is_const, span);
+            }
+
+            StatementKind::LetUnpack { names, value, is_const, .. } => {
+                self.expression(value)?;
+                self.emit(Op::Unpack(names.len() as u32), value.span);
+                for name in names {
+                    self.declare(name, *is_const, span);
+                }
+            }
+
+            // a, b = b, a: every value is computed before any target is assigned
+            StatementKind::AssignUnpack { targets, value } => {
+                self.expression(value)?;
+                self.emit(Op::Unpack(targets.len() as u32), value.span);
+                for target in targets {
+                    let span = target.span;
+                    match &target.kind {
+                        ExpressionKind::Identifier { name, .. } => self.store_variable(name, span),
+                        ExpressionKind::Index { object, index } => {
+                            let value_slot = self.hidden_slot();
+                            self.emit(Op::StoreLocal(value_slot), span);
+                            self.emit(Op::Pop, span);
+                            self.expression(object)?;
+                            self.expression(index)?;
+                            self.emit(Op::LoadLocal(value_slot), span);
+                            self.emit(Op::StoreIndex, span);
+                        }
+                        ExpressionKind::MemberAccess { object, member } => {
+                            let value_slot = self.hidden_slot();
+                            self.emit(Op::StoreLocal(value_slot), span);
+                            self.emit(Op::Pop, span);
+                            self.expression(object)?;
+                            self.emit(Op::LoadLocal(value_slot), span);
+                            let member = self.name(member);
+                            self.emit(Op::SetMember(member), span);
+                        }
+                        _ => unreachable!("the parser only allows assignable targets"),
+                    }
+                    self.emit(Op::Pop, span);
+                }
+            }
+
+            StatementKind::Expression(expr) => {
+                self.expression(expr)?;
+                self.emit(Op::Pop, span);
+            }
+
+            StatementKind::Print(args) => {
+                for arg in args {
+                    self.expression(arg)?;
+                }
+                self.emit(Op::Print(args.len() as u32), span);
+            }
+
+            StatementKind::If { condition, then_branch, elif_branches, else_branch } => {
+                let mut end_jumps = Vec::new();
+                let branches = std::iter::once((condition, then_branch))
+                    .chain(elif_branches.iter().map(|(c, b)| (c, b)));
+                for (cond, body) in branches {
+                    self.expression(cond)?;
+                    let next = self.emit(Op::JumpIfFalse(0), span);
+                    self.block(body, span)?;
+                    end_jumps.push(self.emit(Op::Jump(0), span));
+                    self.patch(next);
+                }
+                if let Some(else_body) = else_branch {
+                    self.block(else_body, span)?;
+                }
+                for jump in end_jumps {
+                    self.patch(jump);
+                }
+            }
+
+            StatementKind::While { condition, body } => {
+                let counter = self.hidden_slot();
+                let zero = self.constant(Value::Number(0.0));
+                self.emit(Op::Constant(zero), span);
+                self.emit(Op::StoreLocal(counter), span);
+                self.emit(Op::Pop, span);
+
+                let start = self.emit(Op::LoopGuard(counter), span);
+                self.expression(condition)?;
+                let exit = self.emit(Op::JumpIfFalse(0), span);
+                self.loop_body(body, start, Some(counter), span)?;
+                self.emit(Op::Jump(start as u32), span);
+                self.patch(exit);
+                self.end_loop();
+            }
+
+            StatementKind::For { variables, iterable, body, .. } => {
+                let items = self.hidden_slot();
+                self.expression(iterable)?;
+                self.emit(Op::ForPrep(items), iterable.span);
+                let counter = self.hidden_slot();
+                let zero = self.constant(Value::Number(0.0));
+                self.emit(Op::Constant(zero), span);
+                self.emit(Op::StoreLocal(counter), span);
+                self.emit(Op::Pop, span);
+
+                let start = self.emit(Op::ForNext(items, 0), span);
+                self.emit(Op::LoopGuard(counter), span);
+                if variables.len() > 1 {
+                    self.emit(Op::Unpack(variables.len() as u32), span);
+                }
+                for variable in variables {
+                    self.declare(variable, false, span);
+                }
+                self.loop_body(body, start, Some(counter), span)?;
+                self.emit(Op::Jump(start as u32), span);
+                self.patch(start);
+                self.end_loop();
+            }
+
+            StatementKind::Function { name, params, body, .. } => {
+                let index = self.function(name, params, Body::Block(body), span)?;
+                self.emit(Op::Closure(index), span);
+                self.declare(name, true, span);
+            }
+
+            StatementKind::Return(value) => {
+                match value {
+                    Some(expr) => self.expression(expr)?,
+                    None => {
+                        self.emit(Op::Null, span);
+                    }
+                }
+                self.exits(0, span)?;
+                self.emit(Op::Return, span);
+            }
+
+            // வெளிமாறி only affects what the resolver allows
+            StatementKind::Nonlocal(_) => {}
+
+            StatementKind::Yield(value) => {
+                self.expression(value)?;
+                self.emit(Op::Yield, span);
+                // Loops count their iterations from the last resume, so a
+                // generator may loop for ever
+                let guards: Vec<u32> = self.current().loops.iter().filter_map(|l| l.guard).collect();
+                for counter in guards {
+                    let zero = self.constant(Value::Number(0.0));
+                    self.emit(Op::Constant(zero), span);
+                    self.emit(Op::StoreLocal(counter), span);
+                    self.emit(Op::Pop, span);
+                }
+            }
+
+            StatementKind::Break | StatementKind::Continue => {
+                let is_break = matches!(stmt.kind, StatementKind::Break);
+                self.loop_jump(is_break, span)?;
+            }
+
+            StatementKind::Import { module, items, .. } => {
+                let spec = ImportSpec { module: module.clone(), items: items.clone() };
+                let chunk = &mut self.current().chunk;
+                chunk.imports.push(spec);
+                let index = (chunk.imports.len() - 1) as u32;
+                self.emit(Op::Import(index), span);
+                match items {
+                    // Imported values are pushed in order
+                    Some(names) => {
+                        for name in names.iter().rev() {
+                            self.declare(name, true, span);
+                        }
+                    }
+                    None => self.declare(module, true, span),
+                }
+            }
+
+            StatementKind::TryCatch { try_block, catch_clauses, finally_block } => {
+                self.try_statement(try_block, catch_clauses, finally_block.as_deref(), span)?;
+            }
+
+            StatementKind::Throw(value) => match value {
+                Some(expr) => {
+                    self.expression(expr)?;
+                    self.emit(Op::Throw, span);
+                }
+                None => {
+                    self.emit(Op::Rethrow, span);
+                }
+            },
+
+            StatementKind::Struct { name, fields, methods, .. } => {
+                for method in methods {
+                    let qualified = ops::method_name(name, &method.name);
+                    let index = self.function(&qualified, &method.params, Body::Block(&method.body), method.span)?;
+                    self.emit(Op::Closure(index), method.span);
+                }
+                let spec = StructSpec {
+                    name: name.clone(),
+                    field_names: fields.iter().map(|(n, _)| n.clone()).collect(),
+                    methods: methods.iter().map(|m| m.name.clone()).collect(),
+                };
+                let chunk = &mut self.current().chunk;
+                chunk.structs.push(spec);
+                let index = (chunk.structs.len() - 1) as u32;
+                self.emit(Op::Struct(index), span);
+                self.declare(name, true, span);
+            }
+
+            StatementKind::Enum { name, variants, .. } => {
+                let def = self.constant(Value::EnumDef { name: name.clone(), variants: variants.clone() });
+                self.emit(Op::Constant(def), span);
+                self.declare(name, true, span);
+            }
+
+            StatementKind::Match { value, arms } => {
+                let subject = self.hidden_slot();
+                self.expression(value)?;
+                self.emit(Op::StoreLocal(subject), span);
+                self.emit(Op::Pop, span);
+
+                let mut end_jumps = Vec::new();
+                for arm in arms {
+                    let mut next = Vec::new();
+                    self.pattern(&arm.pattern, subject, arm.span, &mut next)?;
+                    if let Some(guard) = &arm.guard {
+                        self.expression(guard)?;
+                        next.push(self.emit(Op::JumpIfFalse(0), guard.span));
+                    }
+                    self.block(&arm.body, arm.span)?;
+                    end_jumps.push(self.emit(Op::Jump(0), arm.span));
+                    for jump in next {
+                        self.patch(jump);
+                    }
+                }
+                self.emit(Op::LoadLocal(subject), span);
+                self.emit(Op::NoMatch, span);
+                for jump in end_jumps {
+                    self.patch(jump);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Test a match pattern against the value in `subject`, collecting the
+    /// jumps to patch to the next arm (irrefutable patterns have none)
+    fn pattern(&mut self, pattern: &'a Pattern, subject: u32, span: Span, next: &mut Vec<usize>) -> Result<(), AgamError> {
+        match pattern {
+            Pattern::Wildcard => {}
+            Pattern::Variable { name, .. } => {
+                self.emit(Op::LoadLocal(subject), span);
+                self.declare(name, false, span);
+            }
+            Pattern::Literal(expr) => {
+                self.expression(expr)?;
+                self.emit(Op::LoadLocal(subject), span);
+                self.emit(Op::ValuesEqual, span);
+                next.push(self.emit(Op::JumpIfFalse(0), span));
+            }
+            // Destructuring: check the shape, keep each part in a hidden
+            // slot, then match the parts in order
+            _ => {
+                let (shape, parts) = ops::pattern_parts(pattern).expect("destructuring pattern");
+                let chunk = &mut self.current().chunk;
+                chunk.shapes.push(shape);
+                let shape = (chunk.shapes.len() - 1) as u32;
+                self.emit(Op::LoadLocal(subject), span);
+                self.emit(Op::Destructure(shape), span);
+                next.push(self.emit(Op::JumpIfFalse(0), span));
+
+                let slots: Vec<u32> = parts.iter().map(|_| self.hidden_slot()).collect();
+                for &slot in slots.iter().rev() {
+                    self.emit(Op::StoreLocal(slot), span);
+                    self.emit(Op::Pop, span);
+                }
+                for (part, slot) in parts.into_iter().zip(slots) {
+                    self.pattern(part, slot, span, next)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Push slice bounds, with null for the omitted ones
+    fn slice_bounds(&mut self, bounds: &'a SliceBounds, span: Span) -> Result<(), AgamError> {
+        for part in bounds.parts() {
+            match part {
+                Some(expr) => self.expression(expr)?,
+                None => {
+                    self.emit(Op::Null, span);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn loop_body(&mut self, body: &'a [Statement], continue_target: usize, guard: Option<u32>, span: Span) -> Result<(), AgamError> {
+        let exit_depth = self.current().exits.len();
+        self.current().loops.push(LoopState { continue_target, break_jumps: Vec::new(), exit_depth, guard });
+        self.block(body, span)
+    }
+
+    fn end_loop(&mut self) {
+        let state = self.current().loops.pop().expect("open loop");
+        for jump in state.break_jumps {
+            self.patch(jump);
+        }
+    }
+
+    fn loop_jump(&mut self, is_break: bool, span: Span) -> Result<(), AgamError> {
+        let Some(exit_depth) = self.current().loops.last().map(|l| l.exit_depth) else {
+            if self.functions.len() > 1 {
+                // Outside a loop a நிறுத்து/தொடர் ends the function
+                self.emit(Op::Null, span);
+                self.exits(0, span)?;
+                self.emit(Op::Return, span);
+            } else {
+                // Finally blocks still run before the error
+                self.exits(0, span)?;
+                let message = if is_break {
+                    "'நிறுத்து' வளைய வெளியே பயன்படுத்த முடியாது"
+                } else {
+                    "'தொடர்' வளைய வெளியே பயன்படுத்த முடியாது"
+                };
+                let message = self.constant(Value::String(message.to_string()));
+                let at = self.statement_span;
+                self.emit(Op::Error(message), at);
+            }
+            return Ok(());
+        };
+
+        self.exits(exit_depth, span)?;
+        if is_break {
+            let jump = self.emit(Op::Jump(0), span);
+            self.current().loops.last_mut().expect("open loop").break_jumps.push(jump);
+        } else {
+            let target = self.current().loops.last().expect("open loop").continue_target;
+            self.emit(Op::Jump(target as u32), span);
+        }
+        Ok(())
+    }
+
+    /// Emit the cleanup for leaving try statements down to exit depth `depth`
+    fn exits(&mut self, depth: usize, span: Span) -> Result<(), AgamError> {
+        let mut i = self.current().exits.len();
+        while i > depth {
+            i -= 1;
+            match self.current().exits[i] {
+                Exit::Try => {
+                    self.emit(Op::PopTry, span);
+                }
+                Exit::Handled => {
+                    self.emit(Op::PopHandled, span);
+                }
+                Exit::Finally(body) => {
+                    // The finally block runs outside its try statement, so
+                    // hide the exits and loops that belong inside it
+                    let state = self.current();
+                    let saved_exits: Vec<_> = state.exits.drain(i..).collect();
+                    let loop_start = state.loops.iter().position(|l| l.exit_depth > i).unwrap_or(state.loops.len());
+                    let saved_loops: Vec<_> = state.loops.drain(loop_start..).collect();
+                    self.block(body, span)?;
+                    let state = self.current();
+                    state.exits.extend(saved_exits);
+                    state.loops.extend(saved_loops);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn try_statement(
+        &mut self,
+        try_block: &'a [Statement],
+        catch_clauses: &'a [CatchClause],
+        finally_block: Option<&'a [Statement]>,
+        span: Span,
+    ) -> Result<(), AgamError> {
+        if let Some(body) = finally_block {
+            self.current().exits.push(Exit::Finally(body));
+        }
+
+        // Try body, protected by the handler
+        let handler = self.emit(Op::SetupTry(0), span);
+        self.current().exits.push(Exit::Try);
+        self.block(try_block, span)?;
+        self.current().exits.pop();
+        self.emit(Op::PopTry, span);
+        let mut done_jumps = vec![self.emit(Op::Jump(0), span)];
+
+        // The handler starts with the caught error on the stack
+        self.patch(handler);
+        let mut cleanup_jumps = Vec::new();
+        for clause in catch_clauses {
+            let next = match &clause.filter {
+                Some(filter) => {
+                    let filter = self.name(filter);
+                    Some(self.emit(Op::CatchMatch(filter, 0), clause.span))
+                }
+                None => None,
+            };
+            self.emit(Op::PushHandled, clause.span);
+            self.declare(&clause.error_var, false, clause.span);
+            self.current().exits.push(Exit::Handled);
+            if finally_block.is_some() {
+                // Errors inside the catch body still run the finally block
+                cleanup_jumps.push(self.emit(Op::SetupTry(0), clause.span));
+                self.current().exits.push(Exit::Try);
+            }
+            self.block(&clause.body, clause.span)?;
+            if finally_block.is_some() {
+                self.current().exits.pop();
+                self.emit(Op::PopTry, clause.span);
+            }
+            self.current().exits.pop();
+            self.emit(Op::PopHandled, clause.span);
+            done_jumps.push(self.emit(Op::Jump(0), clause.span));
+            if let Some(next) = next {
+                self.patch(next);
+            }
+        }
+
+        if finally_block.is_some() {
+            self.current().exits.pop();
+        }
+
+        // No clause matched: run finally and raise the error again
+        let unmatched = self.emit(Op::Jump(0), span);
+        if !cleanup_jumps.is_empty() {
+            // An error escaped a catch body: stop handling the caught one
+            for jump in cleanup_jumps {
+                self.patch(jump);
+            }
+            self.emit(Op::PopHandled, span);
+        }
+        self.patch(unmatched);
+        if let Some(body) = finally_block {
+            self.block(body, span)?;
+        }
+        self.emit(Op::Reraise, span);
+
+        for jump in done_jumps {
+            self.patch(jump);
+        }
+        if let Some(body) = finally_block {
+            self.block(body, span)?;
+        }
+        Ok(())
+    }
+
+    // ============= Functions =============
+
+    /// Compile a nested function, returning its index in the chunk. Its
+    /// defaults are pushed here, for `Op::Closure` to take
+    fn function(&mut self, name: &str, params: &'a Parameters, body: Body<'a>, span: Span) -> Result<u32, AgamError> {
+        for default in &params.defaults {
+            self.expression(default)?;
+        }
+
+        let mut captured = HashSet::new();
+        match body {
+            Body::Block(statements) => captured_names_in_block(statements, false, &mut captured),
+            Body::Expr(expr) => captured_names_in_expr(expr, false, &mut captured),
+        }
+
+        let signature = &params.signature;
+        let mut state = FunctionState::new(name, signature.clone(), captured, false);
+        for param in signature.names() {
+            let slot = Slot::Local(state.alloc_slot());
+            state.scopes[0].vars.push(Var { name: param.clone(), slot, is_const: false, declared: true });
+        }
+        self.functions.push(state);
+
+        // Captured parameters move into cells
+        for i in 0..signature.slot_count() {
+            let state = self.current();
+            if state.captured.contains(&state.scopes[0].vars[i].name) {
+                let cell = state.alloc_cell();
+                state.scopes[0].vars[i].slot = Slot::Cell(cell);
+                self.emit(Op::NewCell(cell), span);
+                self.emit(Op::LoadLocal(i as u32), span);
+                self.emit(Op::DefineCell(cell), span);
+                self.emit(Op::Pop, span);
+            }
+        }
+
+        match body {
+            Body::Block(statements) => {
+                self.block(statements, span)?;
+                self.emit(Op::Null, span);
+            }
+            Body::Expr(expr) => self.expression(expr)?,
+        }
+        self.emit(Op::Return, span);
+
+        let state = self.functions.pop().expect("function state");
+        let proto = FunctionProto { is_generator: params.is_generator, ..state.finish(self.file.clone()) };
+        let chunk = &mut self.current().chunk;
+        chunk.functions.push(Rc::new(proto));
+        Ok((chunk.functions.len() - 1) as u32)
+    }
+
+    // ============= Expressions =============
+
+    fn expression(&mut self, expr: &'a Expression) -> Result<(), AgamError> {
+        let span = expr.span;
+        match &expr.kind {
+            ExpressionKind::Integer(n) => {
+                let index = self.constant(Value::Integer(n.clone()));
+                self.emit(Op::Constant(index), span);
+            }
+            ExpressionKind::Number(n) => {
+                let index = self.constant(Value::Number(*n));
+                self.emit(Op::Constant(index), span);
+            }
+            ExpressionKind::String(s) => {
+                let index = self.constant(Value::String(s.clone()));
+                self.emit(Op::Constant(index), span);
+            }
+            ExpressionKind::FString { parts } => {
+                for part in parts {
+                    match part {
+                        FStringPart::Literal(s) => {
+                            let index = self.constant(Value::String(s.clone()));
+                            self.emit(Op::Constant(index), span);
+                        }
+                        FStringPart::Expression(inner) => self.expression(inner)?,
+                    }
+                }
+                self.emit(Op::BuildString(parts.len() as u32), span);
+            }
+            ExpressionKind::Boolean(b) => {
+                self.emit(if *b { Op::True } else { Op::False }, span);
+            }
+            ExpressionKind::Null => {
+                self.emit(Op::Null, span);
+            }
+            ExpressionKind::Identifier { name, .. } => self.load_variable(name, span),
+
+            ExpressionKind::Binary { left, operator, right } => {
+                self.expression(left)?;
+                let short_circuit = match operator {
+                    BinaryOp::And => Some(Op::JumpIfFalseOrPop(0)),
+                    BinaryOp::Or => Some(Op::JumpIfTrueOrPop(0)),
+                    _ => None,
+                };
+                match short_circuit {
+                    Some(op) => {
+                        let jump = self.emit(op, span);
+                        self.expression(right)?;
+                        self.patch(jump);
+                    }
+                    None => {
+                        self.expression(right)?;
+                        self.emit(Op::Binary(*operator), span);
+                    }
+                }
+            }
+
+            ExpressionKind::Unary { operator, operand } => {
+                self.expression(operand)?;
+                self.emit(Op::Unary(*operator), span);
+            }
+
+            ExpressionKind::Call { callee, arguments, keywords } => {
+                self.expression(callee)?;
+                for arg in arguments {
+                    self.expression(arg)?;
+                }
+                if keywords.is_empty() {
+                    self.emit(Op::Call(arguments.len() as u32), span);
+                } else {
+                    for (_, arg) in keywords {
+                        self.expression(arg)?;
+                    }
+                    let chunk = &mut self.current().chunk;
+                    chunk.keywords.push(keywords.iter().map(|(name, _)| name.clone()).collect());
+                    let index = (chunk.keywords.len() - 1) as u32;
+                    self.emit(Op::CallKeywords(arguments.len() as u32, index), span);
+                }
+            }
+
+            ExpressionKind::List(elements) => {
+                for element in elements {
+                    self.expression(element)?;
+                }
+                self.emit(Op::BuildList(elements.len() as u32), span);
+            }
+
+            ExpressionKind::Tuple(elements) => {
+                for element in elements {
+                    self.expression(element)?;
+                }
+                self.emit(Op::BuildTuple(elements.len() as u32), span);
+            }
+
+            ExpressionKind::Dict(pairs) => {
+                for (key, value) in pairs {
+                    self.expression(key)?;
+                    self.expression(value)?;
+                }
+                self.emit(Op::BuildDict(pairs.len() as u32), span);
+            }
+
+            ExpressionKind::ListComprehension { element, clauses } => {
+                self.comprehension(element, None, clauses, span)?;
+            }
+
+            ExpressionKind::DictComprehension { key, value, clauses } => {
+                self.comprehension(key, Some(value), clauses, span)?;
+            }
+
+            ExpressionKind::Index { object, index } => {
+                self.expression(object)?;
+                self.expression(index)?;
+                self.emit(Op::Index, span);
+            }
+
+            ExpressionKind::Slice { object, bounds } => {
+                self.expression(object)?;
+                self.slice_bounds(bounds, span)?;
+                self.emit(Op::Slice, span);
+            }
+
+            ExpressionKind::Grouping(inner) => self.expression(inner)?,
+
+            ExpressionKind::Assignment { name, operator, value, .. } => {
+                if let Some(operator) = operator {
+                    self.load_variable(name, span);
+                    self.expression(value)?;
+                    self.emit(Op::Binary(*operator), span);
+                } else {
+                    self.expression(value)?;
+                }
+                self.store_variable(name, span);
+            }
+
+            ExpressionKind::MemberAccess { object, member } => {
+                self.expression(object)?;
+                let member = self.name(member);
+                self.emit(Op::GetMember(member), span);
+            }
+
+            ExpressionKind::StructInit { name, arguments } => {
+                self.load_variable(name, span);
+                for arg in arguments {
+                    self.expression(arg)?;
+                }
+                let name = self.name(name);
+                self.emit(Op::StructInit(name, arguments.len() as u32), span);
+            }
+
+            ExpressionKind::IndexAssignment { object, index, operator: None, value } => {
+                self.expression(object)?;
+                self.expression(index)?;
+                self.expression(value)?;
+                self.emit(Op::StoreIndex, span);
+            }
+
+            // list[i] += value: object and index are evaluated once
+            ExpressionKind::IndexAssignment { object, index, operator: Some(operator), value } => {
+                let object_slot = self.hidden_slot();
+                let index_slot = self.hidden_slot();
+                self.expression(object)?;
+                self.emit(Op::StoreLocal(object_slot), span);
+                self.expression(index)?;
+                self.emit(Op::StoreLocal(index_slot), span);
+                self.emit(Op::Index, span);
+                self.expression(value)?;
+                self.emit(Op::Binary(*operator), span);
+                let result_slot = self.hidden_slot();
+                self.emit(Op::StoreLocal(result_slot), span);
+                self.emit(Op::Pop, span);
+                self.emit(Op::LoadLocal(object_slot), span);
+                self.emit(Op::LoadLocal(index_slot), span);
+                self.emit(Op::LoadLocal(result_slot), span);
+                self.emit(Op::StoreIndex, span);
+            }
+
+            ExpressionKind::SliceAssignment { object, bounds, value } => {
+                self.expression(object)?;
+                self.slice_bounds(bounds, span)?;
+                self.expression(value)?;
+                self.emit(Op::StoreSlice, span);
+            }
+
+            ExpressionKind::MemberAssignment { object, member, operator, value } => {
+                self.expression(object)?;
+                let member = self.name(member);
+                if let Some(operator) = operator {
+                    // obj.field += value: the object is evaluated once
+                    let object_slot = self.hidden_slot();
+                    self.emit(Op::StoreLocal(object_slot), span);
+                    self.emit(Op::LoadLocal(object_slot), span);
+                    self.emit(Op::GetMember(member), span);
+                    self.expression(value)?;
+                    self.emit(Op::Binary(*operator), span);
+                } else {
+                    self.expression(value)?;
+                }
+                self.emit(Op::SetMember(member), span);
+            }
+
+            ExpressionKind::Lambda { params, body } => {
+                let index = match body {
+                    LambdaBody::Expression(expr) => self.function("<lambda>", params, Body::Expr(expr), expr.span)?,
+                    LambdaBody::Block(statements) => self.function("<lambda>", params, Body::Block(statements), span)?,
+                };
+                self.emit(Op::Closure(index), span);
+            }
+
+            ExpressionKind::Match { value, arms } => {
+                let subject = self.hidden_slot();
+                self.expression(value)?;
+                self.emit(Op::StoreLocal(subject), span);
+                self.emit(Op::Pop, span);
+
+                let mut end_jumps = Vec::new();
+                for arm in arms {
+                    // Each arm binds its pattern in a block of its own
+                    self.begin_scope_with(pattern_declarations(&arm.pattern), arm.span);
+                    let mut next = Vec::new();
+                    self.pattern(&arm.pattern, subject, arm.span, &mut next)?;
+                    if let Some(guard) = &arm.guard {
+                        self.expression(guard)?;
+                        next.push(self.emit(Op::JumpIfFalse(0), guard.span));
+                    }
+                    self.expression(&arm.value)?;
+                    self.end_scope();
+                    end_jumps.push(self.emit(Op::Jump(0), arm.span));
+                    for jump in next {
+                        self.patch(jump);
+                    }
+                }
+                self.emit(Op::LoadLocal(subject), span);
+                self.emit(Op::NoMatch, span);
+                for jump in end_jumps {
+                    self.patch(jump);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ============= Name analysis =============
+
+/// Collect names used inside functions nested in `statements`. `nested`
+/// is true once inside such a function
+fn captured_names_in_block(statements: &[Statement], nested: bool, out: &mut HashSet<String>) {
+    for stmt in statements {
+        captured_names_in_stmt(stmt, nested, out);
+    }
+}
+
+fn captured_names_in_stmt(stmt: &Statement, nested: bool, out: &mut HashSet<String>) {
+    match &stmt.kind {
+        StatementKind::Let { value, .. } | StatementKind::LetUnpack { value, .. } => {
+            captured_names_in_expr(value, nested, out);
+        }
+        StatementKind::AssignUnpack { targets, value } => {
+            captured_names_in_expr(value, nested, out);
+            for target in targets {
+                captured_names_in_expr(target, nested, out);
+            }
+        }
+        StatementKind::Expression(expr) => captured_names_in_expr(expr, nested, out),
+        StatementKind::Print(args) => {
+            for arg in args {
+                captured_names_in_expr(arg, nested, out);
+            }
+        }
+        StatementKind::If { condition, then_branch, elif_branches, else_branch } => {
+            captured_names_in_expr(condition, nested, out);
+            captured_names_in_block(then_branch, nested, out);
+            for (cond, body) in elif_branches {
+                captured_names_in_expr(cond, nested, out);
+                captured_names_in_block(body, nested, out);
+            }
+            if let Some(body) = else_branch {
+                captured_names_in_block(body, nested, out);
+            }
+        }
+        StatementKind::While { condition, body } => {
+            captured_names_in_expr(condition, nested, out);
+            captured_names_in_block(body, nested, out);
+        }
+        StatementKind::For { iterable, body, .. } => {
+            captured_names_in_expr(iterable, nested, out);
+            captured_names_in_block(body, nested, out);
+        }
+        StatementKind::Function { name, params, body, .. } => {
+            // The function refers to itself by name for recursion
+            out.insert(name.clone());
+            captured_names_in_defaults(params, nested, out);
+            captured_names_in_block(body, true, out);
+        }
+        StatementKind::Return(Some(expr)) | StatementKind::Throw(Some(expr)) | StatementKind::Yield(expr) => {
+            captured_names_in_expr(expr, nested, out);
+        }
+        StatementKind::TryCatch { try_block, catch_clauses, finally_block } => {
+            captured_names_in_block(try_block, nested, out);
+            for clause in catch_clauses {
+                captured_names_in_block(&clause.body, nested, out);
+            }
+            if let Some(body) = finally_block {
+                captured_names_in_block(body, nested, out);
+            }
+        }
+        StatementKind::Struct { methods, .. } => {
+            for method in methods {
+                captured_names_in_defaults(&method.params, nested, out);
+                captured_names_in_block(&method.body, true, out);
+            }
+        }
+        StatementKind::Match { value, arms } => {
+            captured_names_in_expr(value, nested, out);
+            for arm in arms {
+                captured_names_in_pattern(&arm.pattern, nested, out);
+                if let Some(guard) = &arm.guard {
+                    captured_names_in_expr(guard, nested, out);
+                }
+                captured_names_in_block(&arm.body, nested, out);
+            }
+        }
+        StatementKind::Return(None)
+        | StatementKind::Throw(None)
+        | StatementKind::Nonlocal(_)
+        | StatementKind::Break
+        | StatementKind::Continue
+        | StatementKind::Import { .. }
+        | StatementKind::Enum { .. } => {}
+    }
+}
+
+/// Default values are evaluated where the function is defined
+fn captured_names_in_defaults(params: &Parameters, nested: bool, out: &mut HashSet<String>) {
+    for default in &params.defaults {
+        captured_names_in_expr(default, nested, out);
+    }
+}
+
+fn captured_names_in_pattern(pattern: &Pattern, nested: bool, out: &mut HashSet<String>) {
+    match pattern {
+        Pattern::Literal(expr) => captured_names_in_expr(expr, nested, out),
+        Pattern::Variable { .. } | Pattern::Wildcard => {}
+        _ => {
+            if let Some((_, parts)) = ops::pattern_parts(pattern) {
+                for part in parts {
+                    captured_names_in_pattern(part, nested, out);
+                }
+            }
+        }
+    }
+}
+
+fn captured_names_in_expr(expr: &Expression, nested: bool, out: &mut HashSet<String>) {
+    match &expr.kind {
+        ExpressionKind::Identifier { name, .. } => {
+            if nested {
+                out.insert(name.clone());
+            }
+        }
+        ExpressionKind::Assignment { name, value, .. } => {
+            if nested {
+                out.insert(name.clone());
+            }
+            captured_names_in_expr(value, nested, out);
+        }
+        ExpressionKind::StructInit { name, arguments } => {
+            if nested {
+                out.insert(name.clone());
+            }
+            for arg in arguments {
+                captured_names_in_expr(arg, nested, out);
+            }
+        }
+        ExpressionKind::Lambda { params, body } => {
+            captured_names_in_defaults(params, nested, out);
+            match body {
+                LambdaBody::Expression(expr) => captured_names_in_expr(expr, true, out),
+                LambdaBody::Block(statements) => captured_names_in_block(statements, true, out),
+            }
+        }
+        ExpressionKind::Match { value, arms } => {
+            captured_names_in_expr(value, nested, out);
+            for arm in arms {
+                captured_names_in_pattern(&arm.pattern, nested, out);
+                if let Some(guard) = &arm.guard {
+                    captured_names_in_expr(guard, nested, out);
+                }
+                captured_names_in_expr(&arm.value, nested, out);
+            }
+        }
+        ExpressionKind::FString { parts } => {
+            for part in parts {
+                if let FStringPart::Expression(inner) = part {
+                    captured_names_in_expr(inner, nested, out);
+                }
+            }
+        }
+        ExpressionKind::Binary { left, right, .. } => {
+            captured_names_in_expr(left, nested, out);
+            captured_names_in_expr(right, nested, out);
+        }
+        ExpressionKind::Unary { operand, .. } => captured_names_in_expr(operand, nested, out),
+        ExpressionKind::Call { callee, arguments, keywords } => {
+            captured_names_in_expr(callee, nested, out);
+            for arg in arguments.iter().chain(keywords.iter().map(|(_, arg)| arg)) {
+                captured_names_in_expr(arg, nested, out);
+            }
+        }
+        ExpressionKind::List(elements) | ExpressionKind::Tuple(elements) => {
+            for element in elements {
+                captured_names_in_expr(element, nested, out);
+            }
+        }
+        ExpressionKind::Dict(pairs) => {
+            for (key, value) in pairs {
+                captured_names_in_expr(key, nested, out);
+                captured_names_in_expr(value, nested, out);
+            }
+        }
+        ExpressionKind::ListComprehension { element, clauses } => {
+            captured_names_in_expr(element, nested, out);
+            captured_names_in_clauses(clauses, nested, out);
+        }
+        ExpressionKind::DictComprehension { key, value, clauses } => {
+            captured_names_in_expr(key, nested, out);
+            captured_names_in_expr(value, nested, out);
+            captured_names_in_clauses(clauses, nested, out);
+        }
+        ExpressionKind::Index { object, index } => {
+            captured_names_in_expr(object, nested, out);
+            captured_names_in_expr(index, nested, out);
+        }
+        ExpressionKind::Slice { object, bounds } => {
+            captured_names_in_expr(object, nested, out);
+            for expr in bounds.parts().into_iter().flatten() {
+                captured_names_in_expr(expr, nested, out);
+            }
+        }
+        ExpressionKind::SliceAssignment { object, bounds, value } => {
+            captured_names_in_expr(object, nested, out);
+            for expr in bounds.parts().into_iter().flatten() {
+                captured_names_in_expr(expr, nested, out);
+            }
+            captured_names_in_expr(value, nested, out);
+        }
+        ExpressionKind::Grouping(inner) => captured_names_in_expr(inner, nested, out),
+        ExpressionKind::MemberAccess { object, .. } => captured_names_in_expr(object, nested, out),
+        ExpressionKind::IndexAssignment { object, index, value, .. } => {
+            captured_names_in_expr(object, nested, out);
+            captured_names_in_expr(index, nested, out);
+            captured_names_in_expr(value, nested, out);
+        }
+        ExpressionKind::MemberAssignment { object, value, .. } => {
+            captured_names_in_expr(object, nested, out);
+            captured_names_in_expr(value, nested, out);
+        }
+        ExpressionKind::Integer(_)
+        | ExpressionKind::Number(_)
+        | ExpressionKind::String(_)
+        | ExpressionKind::Boolean(_)
+        | ExpressionKind::Null => {}
+    }
+}
+
+fn captured_names_in_clauses(clauses: &[ComprehensionClause], nested: bool, out: &mut HashSet<String>) {
+    for clause in clauses {
+        match clause {
+            ComprehensionClause::For { iterable, .. } => captured_names_in_expr(iterable, nested, out),
+            ComprehensionClause::If(condition) => captured_names_in_expr(condition, nested, out),
+        }
+    }
+}
@@ -0,0 +1,773 @@
+//! Stack machine executing compiled Agam bytecode
+//!
+//! Behaves like the tree-walking evaluator, which remains the reference:
+//! the same values, error messages, error locations and tracebacks.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::error::{AgamError, StackFrame};
+use crate::interpreter::ops;
+use crate::parser::ast::{Program, Span};
+use crate::types::{Caller, Environment, Iter, Value};
+use crate::resolver::Resolver;
+use crate::vm::bytecode::{Capture, Cell, Closure, FunctionProto, Op};
+use crate::vm::compiler;
+
+/// An active function call
+struct Frame {
+    closure: Rc<Closure>,
+    /// Next instruction
+    ip: usize,
+    /// Stack index of the first local (the callee sits just below)
+    base: usize,
+    cells: Vec<Cell>,
+    /// Location of the call in the caller
+    call_site: Span,
+}
+
+/// An installed பிடி handler
+struct Handler {
+    /// Number of frames when installed; the handler belongs to the last one
+    frame_depth: usize,
+    stack_len: usize,
+    handled_len: usize,
+    target: usize,
+}
+
+/// The frame of a generator call while it is not running. The VM's
+/// generators are `Option<Suspended>`, `None` once finished
+struct Suspended {
+    closure: Rc<Closure>,
+    ip: usize,
+    cells: Vec<Cell>,
+    /// The callee, then the frame's locals and temporaries
+    stack: Vec<Value>,
+    /// Its பிடி handlers, with lengths counted from the start of `stack`
+    /// and of `handled`
+    handlers: Vec<Handler>,
+    /// Errors its பிடி blocks are handling
+    handled: Vec<AgamError>,
+}
+
+/// The bytecode virtual machine
+pub struct Vm {
+    globals: Rc<RefCell<Environment>>,
+    stack: Vec<Value>,
+    frames: Vec<Frame>,
+    handlers: Vec<Handler>,
+    /// Errors being handled by enclosing பிடி blocks, innermost last
+    handled: Vec<AgamError>,
+    /// Path of the main program
+    file: Option<Rc<str>>,
+}
+
+impl Vm {
+    pub fn new() -> Self {
+        Vm {
+            globals: ops::builtin_environment(),
+            stack: Vec::new(),
+            frames: Vec::new(),
+            handlers: Vec::new(),
+            handled: Vec::new(),
+            file: None,
+        }
+    }
+
+    /// Set the path of the main program, used in tracebacks
+    pub fn set_file(&mut self, path: &str) {
+        self.file = Some(Rc::from(path));
+    }
+
+    /// The value of a global variable, if it is defined
+    pub fn global(&self, name: &str) -> Option<Value> {
+        self.globals.borrow().get(name)
+    }
+
+    /// Resolve, compile and run a program. Globals persist between runs
+    pub fn execute(&mut self, program: &Program) -> Result<Value, AgamError> {
+        let mut program = program.clone();
+        Resolver::new(self.globals.borrow().declared()).resolve(&mut program)?;
+        let proto = compiler::compile(&program, ops::TOP_LEVEL_NAME, self.file.clone(), &self.globals)?;
+        let globals = Rc::clone(&self.globals);
+        self.run_script(proto, globals, Span::default())
+    }
+
+    /// Run a compiled program or module body to completion
+    fn run_script(&mut self, proto: Rc<FunctionProto>, globals: Rc<RefCell<Environment>>, call_site: Span) -> Result<Value, AgamError> {
+        let closure = Rc::new(Closure { proto, upvalues: Vec::new(), defaults: Vec::new(), globals });
+        let depth = self.frames.len();
+        self.stack.push(Value::Closure(Rc::clone(&closure)));
+        self.push_frame(closure, call_site);
+        self.run(depth)
+    }
+
+    fn push_frame(&mut self, closure: Rc<Closure>, call_site: Span) {
+        let base = self.stack.len() - closure.proto.arity;
+        self.stack.resize(base + closure.proto.local_count.max(closure.proto.arity), Value::Null);
+        let cells = (0..closure.proto.cell_count)
+            .map(|_| Rc::new(RefCell::new(None)))
+            .collect();
+        self.frames.push(Frame { closure, ip: 0, base, cells, call_site });
+    }
+
+    /// Execute until the frame count drops back to `depth`
+    fn run(&mut self, depth: usize) -> Result<Value, AgamError> {
+        let handled_len = self.handled.len();
+        loop {
+            let error = match self.dispatch(depth) {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+
+            // Unwind to the innermost handler inside this run
+            match self.handlers.last() {
+                Some(handler) if handler.frame_depth > depth => {
+                    let handler = self.handlers.pop().expect("handler");
+                    let error = self.attach_stack(error);
+                    self.frames.truncate(handler.frame_depth);
+                    self.stack.truncate(handler.stack_len);
+                    self.handled.truncate(handler.handled_len);
+                    self.frames.last_mut().expect("frame").ip = handler.target;
+                    self.stack.push(Value::Error(Rc::new(error)));
+                }
+                _ => {
+                    // Errors leaving a function or module record the call chain
+                    let error = if self.frames.len() > 1 || self.entered_by_call() { self.attach_stack(error) } else { error };
+                    let stack_len = self.frames[depth].base - 1;
+                    self.frames.truncate(depth);
+                    self.stack.truncate(stack_len);
+                    self.handled.truncate(handled_len);
+                    return Err(error);
+                }
+            }
+        }
+    }
+
+    /// Snapshot the call chain for an error raised at `line:column`
+    fn stack_trace(&self, line: usize, column: usize) -> Vec<StackFrame> {
+        let frame_info = |frame: &Frame| {
+            let proto = &frame.closure.proto;
+            (proto.name.clone(), proto.file.as_deref().map(str::to_string))
+        };
+        let mut trace = Vec::with_capacity(self.frames.len() + 1);
+        if let Some(first) = self.frames.first().filter(|_| self.entered_by_call()) {
+            trace.push(StackFrame {
+                function: ops::TOP_LEVEL_NAME.to_string(),
+                file: self.file.as_deref().map(str::to_string),
+                line: first.call_site.line,
+                column: first.call_site.column,
+            });
+        }
+        for pair in self.frames.windows(2) {
+            let (function, file) = frame_info(&pair[0]);
+            trace.push(StackFrame {
+                function,
+                file,
+                line: pair[1].call_site.line,
+                column: pair[1].call_site.column,
+            });
+        }
+        if let Some(frame) = self.frames.last() {
+            let (function, file) = frame_info(frame);
+            trace.push(StackFrame { function, file, line, column });
+        }
+        trace
+    }
+
+    /// Whether the outermost frame is a function called from outside the
+    /// VM, as the test runner does, rather than a program being run
+    fn entered_by_call(&self) -> bool {
+        self.frames.first().is_some_and(|frame| frame.closure.proto.name != ops::TOP_LEVEL_NAME)
+    }
+
+    /// Record the call chain on an error unless a deeper frame already did
+    fn attach_stack(&self, error: AgamError) -> AgamError {
+        match error {
+            AgamError::RuntimeError { line, column, message, stack } if stack.is_empty() => {
+                AgamError::RuntimeError { line, column, message, stack: self.stack_trace(line, column) }
+            }
+            AgamError::AssertionError { line, column, message, stack } if stack.is_empty() => {
+                AgamError::AssertionError { line, column, message, stack: self.stack_trace(line, column) }
+            }
+            AgamError::Thrown { line, column, value, stack } if stack.is_empty() => {
+                AgamError::Thrown { line, column, value, stack: self.stack_trace(line, column) }
+            }
+            other => other,
+        }
+    }
+
+    fn pop(&mut self) -> Value {
+        self.stack.pop().expect("value stack underflow")
+    }
+
+    fn peek(&self) -> &Value {
+        self.stack.last().expect("value stack underflow")
+    }
+
+    /// Pop the top `n` values, in push order
+    fn pop_n(&mut self, n: u32) -> Vec<Value> {
+        let start = self.stack.len() - n as usize;
+        self.stack.split_off(start)
+    }
+
+    /// Execute instructions until the frame at `depth` returns or a
+    /// generator's frame yields, giving the value, or an error is raised
+    fn dispatch(&mut self, depth: usize) -> Result<Value, AgamError> {
+        loop {
+            let frame = self.frames.last_mut().expect("frame");
+            let ip = frame.ip;
+            frame.ip += 1;
+            let base = frame.base;
+            // Borrowed from `self.frames`: arms that go on to use it after
+            // calling back into `self` pop their operands off `self.stack` directly
+            let closure = &self.frames.last().expect("frame").closure;
+            let chunk = &closure.proto.chunk;
+            let op = chunk.code[ip];
+            let span = chunk.spans[ip];
+
+            match op {
+                Op::Constant(i) => self.stack.push(chunk.constants[i as usize].clone()),
+                Op::Null => self.stack.push(Value::Null),
+                Op::True => self.stack.push(Value::Boolean(true)),
+                Op::False => self.stack.push(Value::Boolean(false)),
+                Op::Pop => {
+                    self.pop();
+                }
+
+                Op::LoadLocal(i) => {
+                    let value = self.stack[base + i as usize].clone();
+                    self.stack.push(value);
+                }
+                Op::StoreLocal(i) => {
+                    let value = self.peek().clone();
+                    self.stack[base + i as usize] = value;
+                }
+                Op::NewCell(i) => {
+                    let frame = self.frames.last_mut().expect("frame");
+                    frame.cells[i as usize] = Rc::new(RefCell::new(None));
+                }
+                Op::DefineCell(i) => {
+                    let value = self.peek().clone();
+                    *self.frames.last().expect("frame").cells[i as usize].borrow_mut() = Some(value);
+                }
+                Op::LoadCell(i, name) => {
+                    let cell = Rc::clone(&self.frames.last().expect("frame").cells[i as usize]);
+                    let value = load_cell(&cell, &chunk.names[name as usize], span)?;
+                    self.stack.push(value);
+                }
+                Op::StoreCell(i, name) => {
+                    let cell = Rc::clone(&self.frames.last().expect("frame").cells[i as usize]);
+                    store_cell(&cell, self.peek().clone(), &chunk.names[name as usize], span)?;
+                }
+                Op::LoadUpvalue(i, name) => {
+                    let value = load_cell(&closure.upvalues[i as usize], &chunk.names[name as usize], span)?;
+                    self.stack.push(value);
+                }
+                Op::StoreUpvalue(i, name) => {
+                    store_cell(&closure.upvalues[i as usize], self.peek().clone(), &chunk.names[name as usize], span)?;
+                }
+                Op::LoadGlobal(i, name) => {
+                    let value = closure.globals.borrow().get_index(i as usize).ok_or_else(|| {
+                        AgamError::runtime_error_at(span, format!("வரையறுக்கப்படாத மாறி '{}'", chunk.names[name as usize]))
+                    })?;
+                    self.stack.push(value);
+                }
+                Op::StoreGlobal(i, name) => {
+                    let value = self.peek().clone();
+                    closure
+                        .globals
+                        .borrow_mut()
+                        .assign_index(i as usize, &chunk.names[name as usize], value)
+                        .map_err(|msg| AgamError::runtime_error_at(span, msg))?;
+                }
+                Op::DefineGlobal(i, is_const) => {
+                    let value = self.stack.pop().expect("value stack underflow");
+                    closure.globals.borrow_mut().define_index(i as usize, value, is_const);
+                }
+                Op::ConstAssign(i) => {
+                    return Err(AgamError::runtime_error_at(
+                        span,
+                        format!("'{}' மாறாத மாறி, மாற்ற இயலாது", chunk.names[i as usize]),
+                    ));
+                }
+
+                Op::Binary(operator) => {
+                    let right = self.pop();
+                    let left = self.stack.last_mut().expect("value stack underflow");
+                    *left = ops::binary_op(&operator, left, &right, span)?;
+                }
+                Op::Unary(operator) => {
+                    let value = self.pop();
+                    self.stack.push(ops::unary_op(&operator, value, span)?);
+                }
+                Op::ValuesEqual => {
+                    let right = self.pop();
+                    let left = self.pop();
+                    self.stack.push(Value::Boolean(ops::values_equal(&left, &right)));
+                }
+                Op::Destructure(i) => {
+                    let value = self.stack.pop().expect("value stack underflow");
+                    match ops::destructure(&chunk.shapes[i as usize], &value) {
+                        Some(parts) => {
+                            self.stack.extend(parts);
+                            self.stack.push(Value::Boolean(true));
+                        }
+                        None => self.stack.push(Value::Boolean(false)),
+                    }
+                }
+
+                Op::Jump(target) => self.jump(target),
+                Op::JumpIfFalse(target) => {
+                    if !self.pop().is_truthy() {
+                        self.jump(target);
+                    }
+                }
+                Op::JumpIfFalseOrPop(target) => {
+                    if self.peek().is_truthy() {
+                        self.pop();
+                    } else {
+                        self.jump(target);
+                    }
+                }
+                Op::JumpIfTrueOrPop(target) => {
+                    if self.peek().is_truthy() {
+                        self.jump(target);
+                    } else {
+                        self.pop();
+                    }
+                }
+                Op::LoopGuard(slot) => {
+                    let counter = &mut self.stack[base + slot as usize];
+                    let iterations = match counter {
+                        Value::Number(n) => *n as usize + 1,
+                        _ => 1,
+                    };
+                    if iterations > ops::MAX_LOOP_ITERATIONS {
+                        return Err(ops::loop_limit_error(span));
+                    }
+                    *counter = Value::Number(iterations as f64);
+                }
+                Op::ForPrep(slot) => {
+                    let iterable = self.pop();
+                    let iter = ops::iterate(&iterable, self, span)?;
+                    self.stack[base + slot as usize] = Value::Iterator(Rc::new(RefCell::new(iter)));
+                }
+                Op::ForNext(slot, target) => {
+                    let Value::Iterator(iter) = &self.stack[base + slot as usize] else {
+                        unreachable!("ForNext without ForPrep");
+                    };
+                    let iter = Rc::clone(iter);
+                    let item = iter.borrow_mut().next(self, span)?;
+                    match item {
+                        Some(item) => self.stack.push(item),
+                        None => self.jump(target),
+                    }
+                }
+
+                Op::Call(argc) => self.call(argc as usize, &[], 0, span)?,
+                Op::CallKeywords(argc, i) => {
+                    let keywords = Rc::clone(&chunk.keywords[i as usize]);
+                    self.call(argc as usize, &keywords, 0, span)?
+                }
+                Op::Return => {
+                    let result = self.pop();
+                    let frame = self.frames.pop().expect("frame");
+                    self.stack.truncate(frame.base - 1);
+                    if self.frames.len() == depth {
+                        return Ok(result);
+                    }
+                    self.stack.push(result);
+                }
+                // The generator's frame stays; `resume` puts it away
+                Op::Yield => return Ok(self.pop()),
+                Op::Closure(i) => {
+                    let proto = Rc::clone(&chunk.functions[i as usize]);
+                    let frame = self.frames.last().expect("frame");
+                    let upvalues = proto
+                        .captures
+                        .iter()
+                        .map(|capture| match capture {
+                            Capture::Cell(c) => Rc::clone(&frame.cells[*c as usize]),
+                            Capture::Upvalue(u) => Rc::clone(&closure.upvalues[*u as usize]),
+                        })
+                        .collect();
+                    let globals = Rc::clone(&closure.globals);
+                    let defaults = self.pop_n((proto.signature.params.len() - proto.signature.required) as u32);
+                    self.stack.push(Value::Closure(Rc::new(Closure { proto, upvalues, defaults, globals })));
+                }
+
+                Op::BuildList(n) => {
+                    let items = self.pop_n(n);
+                    self.stack.push(Value::List(Rc::new(RefCell::new(items))));
+                }
+                Op::BuildTuple(n) => {
+                    let items = self.pop_n(n);
+                    self.stack.push(Value::Tuple(items.into()));
+                }
+                Op::Unpack(n) => {
+                    let value = self.pop();
+                    let items = ops::unpack(&value, n as usize, span)?;
+                    self.stack.extend(items.into_iter().rev());
+                }
+                Op::BuildDict(n) => {
+                    let mut map = HashMap::new();
+                    let mut values = self.pop_n(n * 2).into_iter();
+                    while let (Some(key), Some(value)) = (values.next(), values.next()) {
+                        map.insert(ops::dict_key(&key), value);
+                    }
+                    self.stack.push(Value::Dict(Rc::new(RefCell::new(map))));
+                }
+                Op::ListAppend(slot) => {
+                    let value = self.pop();
+                    let Value::List(list) = &self.stack[base + slot as usize] else {
+                        unreachable!("ListAppend without a list");
+                    };
+                    list.borrow_mut().push(value);
+                }
+                Op::DictInsert(slot) => {
+                    let value = self.pop();
+                    let key = ops::dict_key(&self.pop());
+                    let Value::Dict(map) = &self.stack[base + slot as usize] else {
+                        unreachable!("DictInsert without a dictionary");
+                    };
+                    map.borrow_mut().insert(key, value);
+                }
+                Op::BuildString(n) => {
+                    let text: String = self.pop_n(n).iter().map(|v| v.to_string()).collect();
+                    self.stack.push(Value::String(text));
+                }
+                Op::Index => {
+                    let index = self.pop();
+                    let object = self.pop();
+                    self.stack.push(ops::index_get(&object, &index, span)?);
+                }
+                Op::StoreIndex => {
+                    let value = self.pop();
+                    let index = self.pop();
+                    let object = self.pop();
+                    ops::index_set(&object, &index, value.clone(), span)?;
+                    self.stack.push(value);
+                }
+                Op::Slice => {
+                    let step = self.pop();
+                    let end = self.pop();
+                    let start = self.pop();
+                    let object = self.pop();
+                    self.stack.push(ops::slice_get(&object, &start, &end, &step, span)?);
+                }
+                Op::StoreSlice => {
+                    let value = self.pop();
+                    let step = self.pop();
+                    let end = self.pop();
+                    let start = self.pop();
+                    let object = self.pop();
+                    ops::slice_set(&object, &start, &end, &step, &value, span)?;
+                    self.stack.push(value);
+                }
+                Op::GetMember(i) => {
+                    let object = self.stack.pop().expect("value stack underflow");
+                    self.stack.push(ops::member_get(object, &chunk.names[i as usize], span)?);
+                }
+                Op::SetMember(i) => {
+                    let value = self.stack.pop().expect("value stack underflow");
+                    let object = self.stack.pop().expect("value stack underflow");
+                    ops::member_set(&object, &chunk.names[i as usize], value.clone(), span)?;
+                    self.stack.push(value);
+                }
+                Op::StructInit(i, argc) => {
+                    let args = self.stack.split_off(self.stack.len() - argc as usize);
+                    match self.stack.pop().expect("value stack underflow") {
+                        Value::StructDef { name, field_names, methods } => {
+                            self.stack.push(ops::construct_struct(name, &field_names, &methods, args, span)?);
+                        }
+                        _ => {
+                            return Err(AgamError::runtime_error_at(
+                                span,
+                                format!("'{}' ஒரு கட்டமைப்பு அல்ல", chunk.names[i as usize]),
+                            ))
+                        }
+                    }
+                }
+
+                Op::Print(n) => {
+                    let values: Vec<String> = self.pop_n(n).iter().map(|v| v.to_string()).collect();
+                    ops::print_line(&values.join(" "));
+                }
+                Op::Import(i) => {
+                    let spec = chunk.imports[i as usize].clone();
+                    self.import(&spec.module, spec.items.as_deref(), span)?
+                }
+                Op::Struct(i) => {
+                    let spec = &chunk.structs[i as usize];
+                    let closures = self.stack.split_off(self.stack.len() - spec.methods.len());
+                    let methods = spec.methods.iter().cloned().zip(closures).collect();
+                    self.stack.push(Value::StructDef {
+                        name: spec.name.clone(),
+                        field_names: spec.field_names.clone(),
+                        methods: Rc::new(methods),
+                    });
+                }
+                Op::Error(i) => {
+                    return Err(AgamError::runtime_error_at(span, chunk.constants[i as usize].to_string()));
+                }
+
+                Op::SetupTry(target) => self.handlers.push(Handler {
+                    frame_depth: self.frames.len(),
+                    stack_len: self.stack.len(),
+                    handled_len: self.handled.len(),
+                    target: target as usize,
+                }),
+                Op::PopTry => {
+                    self.handlers.pop();
+                }
+                Op::PushHandled => {
+                    if let Value::Error(error) = self.peek() {
+                        let error = (**error).clone();
+                        self.handled.push(error);
+                    }
+                }
+                Op::PopHandled => {
+                    self.handled.pop();
+                }
+                Op::CatchMatch(filter, target) => {
+                    let matched = match self.peek() {
+                        Value::Error(error) => ops::catch_filter_matches(&chunk.names[filter as usize], error),
+                        _ => false,
+                    };
+                    if !matched {
+                        self.jump(target);
+                    }
+                }
+                Op::Throw => {
+                    let value = self.pop();
+                    return Err(ops::throw_value(value, span));
+                }
+                Op::Rethrow => {
+                    return Err(self.handled.last().cloned().unwrap_or_else(|| ops::nothing_to_rethrow(span)));
+                }
+                Op::Reraise => {
+                    let value = self.pop();
+                    return Err(ops::throw_value(value, span));
+                }
+                Op::NoMatch => {
+                    let value = self.pop();
+                    return Err(ops::no_match(&value, span));
+                }
+            }
+        }
+    }
+
+    fn jump(&mut self, target: u32) {
+        self.frames.last_mut().expect("frame").ip = target as usize;
+    }
+
+    /// Call the value below the top `argc` arguments and the values of the
+    /// `keywords` arguments; the first `bound` arguments were supplied by the
+    /// call itself (a method's receiver)
+    fn call(&mut self, argc: usize, keywords: &[String], bound: usize, span: Span) -> Result<(), AgamError> {
+        // The program's own frame does not count as a call
+        if self.frames.len() > ops::MAX_RECURSION_DEPTH {
+            return Err(ops::recursion_limit_error(span));
+        }
+
+        let callee_at = self.stack.len() - argc - keywords.len() - 1;
+        let callee = self.stack[callee_at].clone();
+        match callee {
+            // Fast path: the arguments are already in their slots
+            Value::Closure(closure)
+                if keywords.is_empty() && closure.proto.signature.is_simple() && argc == closure.proto.arity =>
+            {
+                self.push_frame(closure, span);
+                self.start_generator();
+                return Ok(());
+            }
+            // Method call: replace the callee with the method and its receiver
+            Value::BoundMethod { receiver, method } => {
+                self.stack[callee_at] = *method;
+                self.stack.insert(callee_at + 1, *receiver);
+                return self.call(argc + 1, keywords, bound + 1, span);
+            }
+            _ => {}
+        }
+
+        let values = self.pop_n(keywords.len() as u32);
+        let keywords: Vec<(String, Value)> = keywords.iter().cloned().zip(values).collect();
+        let args = self.pop_n(argc as u32);
+        self.pop();
+        match callee {
+            Value::Closure(closure) => {
+                let proto = &closure.proto;
+                let slots = ops::bind_arguments(&proto.name, &proto.signature, &closure.defaults, bound, args, keywords, span)?;
+                self.stack.push(Value::Closure(Rc::clone(&closure)));
+                self.stack.extend(slots);
+                self.push_frame(closure, span);
+                self.start_generator();
+            }
+            Value::NativeFunction(func) => {
+                ops::check_native_keywords(&func, &keywords, span)?;
+                let result = ops::call_native(&func, &args, span, self)?;
+                self.stack.push(result);
+            }
+            Value::StructDef { name, field_names, methods } => {
+                let args = ops::constructor_arguments(&name, &field_names, args, keywords, span)?;
+                self.stack.push(ops::construct_struct(name, &field_names, &methods, args, span)?);
+            }
+            Value::VariantConstructor { enum_name, variant, fields } => {
+                let args = ops::constructor_arguments(&variant, &fields, args, keywords, span)?;
+                self.stack.push(ops::construct_variant(enum_name, variant, &fields, args, span)?);
+            }
+            callee => return Err(ops::not_callable(&callee, span)),
+        }
+        Ok(())
+    }
+
+    /// If the frame just pushed is a generator's, set it aside unstarted
+    /// and push the generator in place of the call's result
+    fn start_generator(&mut self) {
+        if !self.frames.last().expect("frame").closure.proto.is_generator {
+            return;
+        }
+        let frame = self.frames.pop().expect("frame");
+        let generator = Some(Suspended {
+            closure: frame.closure,
+            ip: frame.ip,
+            cells: frame.cells,
+            stack: self.stack.split_off(frame.base - 1),
+            handlers: Vec::new(),
+            handled: Vec::new(),
+        });
+        self.stack.push(Value::Iterator(Rc::new(RefCell::new(Iter::Generator(Box::new(generator))))));
+    }
+
+    /// Run a generator's frame from where it stopped until its next விளை
+    fn resume_generator(&mut self, generator: &mut Option<Suspended>, span: Span) -> Result<Option<Value>, AgamError> {
+        let Some(suspended) = generator.take() else {
+            return Ok(None);
+        };
+        if self.frames.len() > ops::MAX_RECURSION_DEPTH {
+            return Err(ops::recursion_limit_error(span));
+        }
+
+        let depth = self.frames.len();
+        let start = self.stack.len();
+        let handled_len = self.handled.len();
+        self.stack.extend(suspended.stack);
+        self.handlers.extend(suspended.handlers.into_iter().map(|handler| Handler {
+            frame_depth: depth + 1,
+            stack_len: start + handler.stack_len,
+            handled_len: handled_len + handler.handled_len,
+            target: handler.target,
+        }));
+        self.handled.extend(suspended.handled);
+        self.frames.push(Frame {
+            closure: suspended.closure,
+            ip: suspended.ip,
+            base: start + 1,
+            cells: suspended.cells,
+            call_site: span,
+        });
+
+        let value = self.run(depth)?;
+        if self.frames.len() == depth {
+            // Returned: the generator has finished
+            return Ok(None);
+        }
+
+        // Stopped at a விளை: put the frame away again
+        let frame = self.frames.pop().expect("frame");
+        let first_handler = self.handlers.iter().position(|h| h.frame_depth > depth).unwrap_or(self.handlers.len());
+        let handlers = self
+            .handlers
+            .drain(first_handler..)
+            .map(|handler| Handler {
+                frame_depth: 0,
+                stack_len: handler.stack_len - start,
+                handled_len: handler.handled_len - handled_len,
+                target: handler.target,
+            })
+            .collect();
+        *generator = Some(Suspended {
+            closure: frame.closure,
+            ip: frame.ip,
+            cells: frame.cells,
+            stack: self.stack.split_off(start),
+            handlers,
+            handled: self.handled.split_off(handled_len),
+        });
+        Ok(Some(value))
+    }
+
+    /// Run a module in its own globals and push what it exports
+    fn import(&mut self, module: &str, items: Option<&[String]>, span: Span) -> Result<(), AgamError> {
+        let program = ops::parse_module(module, span)?;
+        let path: Rc<str> = Rc::from(ops::module_path(module).as_str());
+        let module_env = ops::builtin_environment();
+        let proto = compiler::compile(&program, &ops::module_frame_name(module), Some(path), &module_env)?;
+
+        self.run_script(proto, Rc::clone(&module_env), span)?;
+
+        match items {
+            Some(items) => {
+                for item in items {
+                    let value = ops::module_item(module, &module_env, item, span)?;
+                    self.stack.push(value);
+                }
+            }
+            None => self.stack.push(ops::module_value(module, &module_env)),
+        }
+        Ok(())
+    }
+}
+
+impl Caller for Vm {
+    /// Run a call to completion from inside a native function; errors not
+    /// handled inside the call leave the stack as it was
+    fn call(&mut self, function: &Value, args: Vec<Value>, span: Span) -> Result<Value, AgamError> {
+        let depth = self.frames.len();
+        let stack_len = self.stack.len();
+        let argc = args.len();
+        self.stack.push(function.clone());
+        self.stack.extend(args);
+        if let Err(error) = Vm::call(self, argc, &[], 0, span) {
+            self.stack.truncate(stack_len);
+            return Err(error);
+        }
+        if self.frames.len() == depth {
+            // Native functions and constructors finish immediately
+            return Ok(self.pop());
+        }
+        self.run(depth)
+    }
+
+    fn resume(&mut self, generator: &mut dyn Any, span: Span) -> Result<Option<Value>, AgamError> {
+        let generator = generator.downcast_mut::<Option<Suspended>>().expect("generator made by the VM");
+        self.resume_generator(generator, span)
+    }
+}
+
+/// Read a cell, failing if its variable is unbound
+fn load_cell(cell: &Cell, name: &str, span: Span) -> Result<Value, AgamError> {
+    cell.borrow().clone().ok_or_else(|| {
+        AgamError::runtime_error_at(span, format!("வரையறுக்கப்படாத மாறி '{}'", name))
+    })
+}
+
+/// Assign a cell, failing if its variable is unbound
+fn store_cell(cell: &Cell, value: Value, name: &str, span: Span) -> Result<(), AgamError> {
+    match &mut *cell.borrow_mut() {
+        Some(slot) => {
+            *slot = value;
+            Ok(())
+        }
+        None => Err(AgamError::runtime_error_at(span, format!("வரையறுக்கப்படாத மாறி '{}'", name))),
+    }
+}
+
+impl Default for Vm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
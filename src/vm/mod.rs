@@ -0,0 +1,13 @@
+//! Bytecode backend for Agam
+//!
+//! Compiles the AST to bytecode with resolved variable slots and runs it
+//! on a stack machine. Selected with `agam --vm`; the tree-walking
+//! interpreter stays the reference implementation.
+
+pub mod bytecode;
+pub mod compiler;
+pub mod machine;
+
+pub use bytecode::{Chunk, Closure, FunctionProto, Op};
+pub use compiler::compile;
+pub use machine::Vm;
@@ -2,16 +2,41 @@
 //! 
 //! Tests the core lexer, parser, and interpreter functionality
 
-use agam::{Lexer, Parser, Interpreter};
-use agam::types::Value;
+use agam::{Lexer, Parser, Interpreter, AgamError};
+use agam::interpreter::Backend;
+use agam::parser::StatementKind;
+use agam::formatter::{format_source, translate_source, FormatOptions, Spelling};
+use agam::checker::{check_source, Severity};
+use agam::testing::{junit, run_tests, tap, Outcome};
+use agam::types::{NativeImpl, Value};
+
+/// Helper to run code on one backend
+fn execute_on(backend: Backend, source: &str) -> Result<Value, AgamError> {
+    let tokens = Lexer::tokenize(source)?;
+    let program = Parser::new(tokens).parse()?;
+    Interpreter::with_backend(backend).execute(&program)
+}
+
+/// Helper to run code on the tree-walker and the VM, checking that both
+/// give the same value or the same error
+fn execute(source: &str) -> Result<Value, AgamError> {
+    let reference = execute_on(Backend::TreeWalker, source);
+    let vm = execute_on(Backend::Vm, source);
+    match (&reference, &vm) {
+        (Ok(a), Ok(b)) => assert_eq!(a.to_string(), b.to_string(), "backends disagree on {:?}", source),
+        (Err(a), Err(b)) => {
+            assert_eq!(a.to_string(), b.to_string(), "backends disagree on {:?}", source);
+            assert_eq!(a.location(), b.location(), "backends disagree on {:?}", source);
+            assert_eq!(a.stack(), b.stack(), "backends disagree on {:?}", source);
+        }
+        _ => panic!("backends disagree on {:?}: {:?} vs {:?}", source, reference, vm),
+    }
+    reference
+}
 
 /// Helper to run code and get result
 fn run(source: &str) -> Result<Value, String> {
-    let tokens = Lexer::tokenize(source).map_err(|e| format!("{}", e))?;
-    let mut parser = Parser::new(tokens);
-    let program = parser.parse().map_err(|e| format!("{}", e))?;
-    let mut interpreter = Interpreter::new();
-    interpreter.execute(&program).map_err(|e| format!("{}", e))
+    execute(source).map_err(|e| format!("{}", e))
 }
 
 /// Helper to check if code executes without error
@@ -19,6 +44,27 @@ fn run_ok(source: &str) -> bool {
     run(source).is_ok()
 }
 
+/// Helper to get the runtime error raised by code
+fn runtime_error(source: &str) -> AgamError {
+    match execute(source) {
+        Err(error @ (AgamError::RuntimeError { .. } | AgamError::AssertionError { .. } | AgamError::Thrown { .. })) => error,
+        other => panic!("expected runtime error, got {:?}", other),
+    }
+}
+
+/// Helper to get the compile error reported for code
+fn compile_error(source: &str) -> AgamError {
+    match execute(source) {
+        Err(error @ AgamError::CompileError { .. }) => error,
+        other => panic!("expected compile error, got {:?}", other),
+    }
+}
+
+/// Helper to get the (line, column) of the runtime error raised by code
+fn runtime_error_location(source: &str) -> (usize, usize) {
+    runtime_error(source).location()
+}
+
 // ============= Lexer Tests =============
 
 #[test]
@@ -55,6 +101,21 @@ fn test_parse_function() {
     assert!(parser.parse().is_ok());
 }
 
+#[test]
+fn test_parse_spans() {
+    let tokens = Lexer::tokenize("மாறி x = 5\nஎன்றால் x > 1:\n    அச்சிடு(x)").unwrap();
+    let program = Parser::new(tokens).parse().unwrap();
+    assert_eq!((program.statements[0].span.line, program.statements[0].span.column), (1, 1));
+    assert_eq!(program.statements[1].span.line, 2);
+    match &program.statements[1].kind {
+        StatementKind::If { condition, then_branch, .. } => {
+            assert_eq!((condition.span.line, condition.span.column, condition.span.length), (2, 9, 5));
+            assert_eq!((then_branch[0].span.line, then_branch[0].span.column), (3, 5));
+        }
+        other => panic!("expected if statement, got {:?}", other),
+    }
+}
+
 // ============= Interpreter Tests =============
 
 #[test]
@@ -106,6 +167,311 @@ fn test_pattern_match_wildcard() {
     assert!(run_ok("மாறி x = 99\nபொருத்து x:\n    _ => அச்சிடு(\"any\")"));
 }
 
+// ============= Error Location Tests =============
+
+#[test]
+fn test_error_location_undefined_variable() {
+    assert_eq!(runtime_error_location("மாறி a = 1\nமாறி b = a + c"), (2, 14));
+}
+
+#[test]
+fn test_error_location_type_mismatch() {
+    assert_eq!(runtime_error_location("மாறி a = 1\nமாறி b = \"x\" - a"), (2, 10));
+}
+
+#[test]
+fn test_error_location_index_out_of_range() {
+    assert_eq!(runtime_error_location("மாறி l = [1, 2]\nஅச்சிடு(l[5])"), (2, 9));
+}
+
+#[test]
+fn test_error_location_arity() {
+    let code = "செயல் f(x):\n    திரும்பு x\n\nமாறி y = f(1, 2)";
+    assert_eq!(runtime_error_location(code), (4, 10));
+}
+
+#[test]
+fn test_error_location_inside_function_and_throw() {
+    let code = "செயல் f(x):\n    திரும்பு x / 0\n\nf(1)";
+    assert_eq!(runtime_error_location(code), (2, 14));
+    assert_eq!(runtime_error_location("என்றால் உண்மை:\n    வீசு \"bad\""), (2, 5));
+}
+
+#[test]
+fn test_stack_trace_across_calls() {
+    let code = "செயல் உள்(x):\n    திரும்பு x + y\n\nசெயல் வெளி(a):\n    திரும்பு உள்(a)\n\nவெளி(1)";
+    let error = runtime_error(code);
+    let frames: Vec<(&str, usize, usize)> = error
+        .stack()
+        .iter()
+        .map(|frame| (frame.function.as_str(), frame.line, frame.column))
+        .collect();
+    assert_eq!(frames, vec![("<நிரல்>", 7, 1), ("வெளி", 5, 14), ("உள்", 2, 18)]);
+
+    // Errors outside any function carry no traceback
+    assert!(runtime_error("அச்சிடு(y)").stack().is_empty());
+}
+
+#[test]
+fn test_format_error_traceback() {
+    let code = "செயல் f(x):\n    திரும்பு x / 0\n\nf(1)";
+    let output = agam::error::format_error(&runtime_error(code), code);
+    assert!(output.contains("பின்தடம் (Traceback, most recent call last):"));
+    assert!(output.contains("வரி 4, <நிரல்> இல்\n    f(1)"));
+    assert!(output.contains("வரி 2, f இல்\n    திரும்பு x / 0"));
+}
+
+// ============= Error Handling Tests =============
+
+#[test]
+fn test_caught_runtime_error_fields() {
+    let code = r#"
+முயற்சி:
+    மாறி x = 1 / 0
+பிடி e:
+    என்றால் e.kind != "runtime" அல்லது e.வகை != "இயக்கம்":
+        வீசு "kind"
+    என்றால் e.message != "பூஜ்ஜியத்தால் வகுக்க இயலாது":
+        வீசு "message"
+    என்றால் e.line != 3 அல்லது e.column != 14:
+        வீசு "location"
+    என்றால் வகை(e) != "பிழை":
+        வீசு "type"
+"#;
+    assert!(run_ok(code));
+}
+
+#[test]
+fn test_thrown_value_recovered_intact() {
+    let code = r#"
+கட்டமைப்பு Problem:
+    code
+முயற்சி:
+    வீசு {"code": 404}
+பிடி e:
+    என்றால் e.kind != "thrown" அல்லது e.value["code"] != 404:
+        வீசு "dict"
+முயற்சி:
+    வீசு Problem(7)
+பிடி e:
+    என்றால் e.மதிப்பு.code != 7:
+        வீசு "struct"
+"#;
+    assert!(run_ok(code));
+
+    // Printing a caught error still shows the full message
+    let code = "மாறி msg = \"\"\nமுயற்சி:\n    வீசு \"bad\"\nபிடி e:\n    msg = சரமாக(e)\nmsg";
+    assert_eq!(
+        run(code).unwrap(),
+        Value::String("இயக்க பிழை (Runtime Error) [3:5]: வீசப்பட்ட பிழை: bad".to_string())
+    );
+}
+
+#[test]
+fn test_caught_error_stack_and_rethrow() {
+    let code = r#"
+செயல் f():
+    வீசு "inner"
+முயற்சி:
+    f()
+பிடி e:
+    என்றால் நீளம்(e.stack) != 2 அல்லது e.stack[1]["function"] != "f":
+        வீசு "stack"
+    வீசு e
+"#;
+    let error = runtime_error(code);
+    assert_eq!(error.kind(), "thrown");
+    assert_eq!(error.location(), (3, 5));
+}
+
+#[test]
+fn test_typed_catch_clauses() {
+    let code = r#"
+கட்டமைப்பு Problem:
+    code
+செயல் classify(n):
+    முயற்சி:
+        என்றால் n == 1:
+            வீசு Problem(5)
+        என்றால் n == 2:
+            வீசு "text"
+        மாறி x = 1 / 0
+    பிடி Problem e:
+        திரும்பு "problem"
+    பிடி இயக்கம் e:
+        திரும்பு "runtime"
+    catch thrown e:
+        திரும்பு "thrown"
+என்றால் classify(1) != "problem" அல்லது classify(2) != "thrown" அல்லது classify(3) != "runtime":
+    வீசு "wrong clause"
+"#;
+    assert!(run_ok(code));
+
+    // Unmatched errors keep propagating
+    let error = runtime_error("முயற்சி:\n    வீசு \"x\"\nபிடி இயக்கம் e:\n    அச்சிடு(e)");
+    assert_eq!(error.kind(), "thrown");
+
+    // A filter that is neither an error kind nor a name in scope never matches
+    let error = compile_error("முயற்சி:\n    அச்சிடு(1)\nபிடி runtme e:\n    அச்சிடு(e)");
+    assert!(error.message().contains("'runtme' பிழை வகையோ கட்டமைப்போ அல்ல"), "{}", error.message());
+    assert_eq!(error.location(), (3, 1));
+    let code = "செயல் f():\n    முயற்சி:\n        வீசு Later(1)\n    பிடி Later e:\n        திரும்பு \"later\"\nகட்டமைப்பு Later:\n    code\nf()";
+    assert_eq!(run(code).unwrap().to_string(), "later");
+}
+
+#[test]
+fn test_finally_runs_on_control_flow() {
+    let code = r#"
+மாறி log = []
+செயல் f():
+    முயற்சி:
+        திரும்பு 1
+    இறுதியாக:
+        சேர்(log, "return")
+f()
+ஒவ்வொரு i உள்ள [1, 2]:
+    முயற்சி:
+        என்றால் i == 1:
+            தொடர்
+        நிறுத்து
+    finally:
+        சேர்(log, i)
+முயற்சி:
+    மாறி x = 1 / 0
+பிடி e:
+    சேர்(log, "caught")
+இறுதியாக:
+    சேர்(log, "done")
+என்றால் சரமாக(log) != "[return, 1, 2, caught, done]":
+    வீசு சரமாக(log)
+"#;
+    assert!(run_ok(code));
+
+    // Finally also runs when the error is not caught
+    let code = "மாறி log = []\nமுயற்சி:\n    முயற்சி:\n        வீசு 1\n    இறுதியாக:\n        சேர்(log, 1)\nபிடி e:\n    மாறி caught = e\nநீளம்(log)";
+    assert_eq!(run(code).unwrap(), Value::Number(1.0));
+}
+
+#[test]
+fn test_bare_rethrow() {
+    let code = "முயற்சி:\n    மாறி x = 1 / 0\nபிடி e:\n    வீசு";
+    let error = runtime_error(code);
+    assert_eq!(error.kind(), "runtime");
+    assert_eq!(error.location(), (2, 14));
+
+    assert_eq!(runtime_error_location("வீசு"), (1, 1));
+    assert!(Parser::new(Lexer::tokenize("முயற்சி:\n    அச்சிடு(1)\nx = 1").unwrap()).parse().is_err());
+}
+
+// ============= Bytecode VM Tests =============
+
+#[test]
+fn test_closures_share_captured_variables() {
+    let code = r#"
+செயல் counter(start):
+    மாறி n = start
+    செயல் next():
+        வெளிமாறி n
+        n = n + 1
+        திரும்பு n
+    திரும்பு next
+மாறி a = counter(0)
+மாறி b = counter(10)
+a()
+a()
+b()
+மாறி adder = (x) => (y) => x + y
+[a(), b(), adder(2)(3)]
+"#;
+    assert_eq!(run(code).unwrap().to_string(), "[3, 12, 5]");
+}
+
+#[test]
+fn test_block_variables_and_shadowing() {
+    let code = r#"
+மாறி total = 100
+செயல் sum_odd(limit):
+    மாறி total = 0
+    ஒவ்வொரு i உள்ள வரம்பு(limit):
+        என்றால் i % 2 == 0:
+            தொடர்
+        என்றால் i > 7:
+            நிறுத்து
+        total = total + i
+        மாறி total = -1
+    திரும்பு total
+[sum_odd(20), total]
+"#;
+    assert_eq!(run(code).unwrap().to_string(), "[16, 100]");
+}
+
+#[test]
+fn test_loop_exits_run_finally() {
+    let code = r#"
+மாறி log = []
+செயல் f():
+    ஒவ்வொரு i உள்ள [1, 2, 3]:
+        முயற்சி:
+            என்றால் i == 1:
+                தொடர்
+            என்றால் i == 2:
+                நிறுத்து
+        இறுதியாக:
+            சேர்(log, i)
+    முயற்சி:
+        முயற்சி:
+            வீசு "x"
+        பிடி e:
+            திரும்பு "returned"
+        இறுதியாக:
+            சேர்(log, "inner")
+    இறுதியாக:
+        சேர்(log, "outer")
+[f(), log]
+"#;
+    assert_eq!(run(code).unwrap().to_string(), "[returned, [1, 2, inner, outer]]");
+}
+
+#[test]
+fn test_vm_recursion_limit() {
+    // The VM keeps calls off the native stack, so the limit is reachable
+    // even in debug builds
+    let code = "செயல் infinite():\n    திரும்பு infinite()\ninfinite()";
+    let error = execute_on(Backend::Vm, code).unwrap_err();
+    assert!(error.message().contains("1000"), "{}", error);
+    assert_eq!(error.location(), (2, 14));
+}
+
+#[test]
+#[ignore = "timing comparison; run with `cargo test --release -- --ignored`"]
+fn test_vm_outpaces_tree_walker_on_loops() {
+    let programs = [
+        // Globals
+        "மாறி i = 0\nமாறி total = 0\nவரை i < 300000:\n    total = total + i\n    i = i + 1\ntotal",
+        // Locals, with integers and with decimals
+        "செயல் run():\n    மாறி i = 0\n    மாறி total = 0\n    வரை i < 300000:\n        total = total + i\n        i = i + 1\n    திரும்பு total\nrun()",
+        "செயல் run():\n    மாறி i = 0\n    மாறி total = 0.5\n    வரை i < 300000:\n        total = total + 1.5\n        i = i + 1\n    திரும்பு total\nrun()",
+        "செயல் run():\n    மாறி total = 0\n    ஒவ்வொரு i உள்ள வரம்பு(300000):\n        total = total + i\n    திரும்பு total\nrun()",
+        // Calls
+        "செயல் fib(n):\n    என்றால் n < 2:\n        திரும்பு n\n    திரும்பு fib(n - 1) + fib(n - 2)\nfib(22)",
+    ];
+    let fastest = |backend: Backend, program: &agam::parser::Program| {
+        (0..3)
+            .map(|_| {
+                let start = std::time::Instant::now();
+                Interpreter::with_backend(backend).execute(program).unwrap();
+                start.elapsed()
+            })
+            .min()
+            .unwrap()
+    };
+    for source in programs {
+        let program = Parser::new(Lexer::tokenize(source).unwrap()).parse().unwrap();
+        let (tree_walker, vm) = (fastest(Backend::TreeWalker, &program), fastest(Backend::Vm, &program));
+        assert!(vm < tree_walker, "VM took {:?}, tree-walker {:?} on {:?}", vm, tree_walker, source);
+    }
+}
+
 // ============= Security Tests (ignored - cause stack overflow) =============
 
 #[test]
@@ -123,3 +489,1429 @@ fn test_loop_iteration_limit() {
     let result = run(code);
     assert!(result.is_err());
 }
+
+#[test]
+#[ignore]
+fn test_for_loop_iteration_limit() {
+    let error = runtime_error("ஒவ்வொரு i உள்ள வரம்பு(10 ** 18):\n    மாறி x = i");
+    assert!(error.message().contains("10000000"), "{}", error);
+    assert_eq!(error.location(), (1, 1));
+}
+
+// ============= Resolver Tests =============
+
+#[test]
+fn test_resolver_compile_errors() {
+    // Reported before anything runs
+    let error = compile_error("அச்சிடு(\"ran\")\nஅச்சிடு(x)\nமாறி x = 1");
+    assert_eq!(error.kind(), "compile");
+    assert_eq!(error.location(), (2, 9));
+
+    let error = compile_error("செயல் f():\n    மாறி y = x + 1\n    மாறி x = 2\n    திரும்பு y");
+    assert_eq!(error.location(), (2, 14));
+
+    let error = compile_error("மாறாத k = 1\nசெயல் f():\n    k = 2");
+    assert!(error.to_string().contains("'k' மாறாத மாறி"));
+    assert_eq!(error.location(), (3, 5));
+
+    let error = compile_error("மாறி x = 1\nதிரும்பு x");
+    assert_eq!(error.location(), (2, 1));
+}
+
+#[test]
+fn test_resolver_scoping() {
+    // Nested functions may use names their enclosing block declares later
+    let code = r#"
+செயல் outer():
+    செயல் inner():
+        திரும்பு later * 2
+    மாறி later = 21
+    திரும்பு inner()
+outer()
+"#;
+    assert_eq!(run(code).unwrap(), Value::Number(42.0));
+
+    // A loop variable stays unbound when the loop never runs
+    let code = "செயல் f():\n    ஒவ்வொரு i உள்ள []:\n        அச்சிடு(i)\n    திரும்பு i\nf()";
+    assert_eq!(runtime_error_location(code), (4, 14));
+    assert!(runtime_error(code).to_string().contains("வரையறுக்கப்படாத மாறி 'i'"));
+}
+
+// ============= Higher-order Builtin Tests =============
+
+#[test]
+fn test_higher_order_builtins() {
+    let code = r#"
+மாறி nums = [3, 1, 4, 1, 5]
+செயல் neg(x):
+    திரும்பு 0 - x
+மாறி groups = குழுவாக்கு(nums, (x) => x % 2)
+மாறி r = [வரைபடம்(nums, செயலி(x): x * 2), filter(nums, (x) => x > 2)]
+சேர்(r, சுருக்கு(nums, (a, b) => a + b))
+சேர்(r, reduce([], (a, b) => a + b, 10))
+சேர்(r, ஏதாவது(nums, (x) => x > 4))
+சேர்(r, all(nums, (x) => x > 1))
+சேர்(r, any([0, இல்லா]))
+சேர்(r, கண்டுபிடி(nums, (x) => x > 3))
+சேர்(r, find(nums, (x) => x > 10))
+சேர்(r, வரிசைப்படுத்து(nums, neg))
+சேர்(r, sort_by(["bb", "a", "ccc"], நீளம்))
+சேர்(r, groups["1"])
+சேர்(r, groups["0"])
+r
+"#;
+    assert_eq!(
+        run(code).unwrap().to_string(),
+        "[[6, 2, 8, 2, 10], [3, 4, 5], 14, 10, உண்மை, பொய், பொய், 4, இல்லா, [5, 4, 3, 1, 1], [a, bb, ccc], [3, 1, 1, 5], [4]]"
+    );
+}
+
+#[test]
+fn test_higher_order_callback_errors() {
+    // Errors inside the callback point into it and can be caught
+    let code = "மாறி nums = [1, 2]\nவரைபடம்(nums, (x) => x / 0)";
+    let error = runtime_error(code);
+    assert_eq!(error.location(), (2, 22));
+    assert_eq!(error.stack().len(), 2);
+
+    let code = r#"
+மாறி caught = பொய்
+முயற்சி:
+    வடிகட்டு([1, 2], (x) => y)
+பிடி e:
+    caught = e.message
+caught
+"#;
+    assert_eq!(run(code).unwrap().to_string(), "வரையறுக்கப்படாத மாறி 'y'");
+
+    assert_eq!(runtime_error_location("சுருக்கு([], (a, b) => a)"), (1, 1));
+    assert_eq!(runtime_error_location("வரைபடம்([1], 5)"), (1, 1));
+}
+
+// ============= Struct Method Tests =============
+
+#[test]
+fn test_struct_methods() {
+    let code = r#"
+கட்டமைப்பு Counter:
+    count
+    செயல் increment(step):
+        தன்.count = தன்.count + step
+        திரும்பு தன்
+    செயல் value():
+        திரும்பு self.count
+    செயல் twice():
+        தன்.increment(1).increment(1)
+        திரும்பு வரைபடம்([1, 2], (x) => x + தன்.count)
+
+மாறி c = Counter(0)
+c.increment(5)
+மாறி before = c.value()
+மாறி bound = c.value
+[before, c.twice(), bound(), c.count]
+"#;
+    assert_eq!(run(code).unwrap().to_string(), "[5, [8, 9], 7, 7]");
+}
+
+#[test]
+fn test_struct_method_errors() {
+    let code = "கட்டமைப்பு P:\n    x\n    செயல் get():\n        திரும்பு தன்.x / 0\nமாறி p = P(1)\np.get()";
+    let error = runtime_error(code);
+    assert_eq!(error.location(), (4, 18));
+    assert_eq!(error.stack()[1].function, "P.get");
+
+    // Arity errors do not count the receiver
+    let error = runtime_error("கட்டமைப்பு P:\n    x\n    செயல் get():\n        திரும்பு 1\nP(1).get(2)");
+    assert!(error.to_string().contains("0 அளவுருக்கள் தேவை, 1 கொடுக்கப்பட்டது"));
+
+    assert_eq!(runtime_error_location("கட்டமைப்பு P:\n    x\nP(1).missing()"), (3, 1));
+    assert_eq!(compile_error("அச்சிடு(தன்)").location(), (1, 9));
+}
+
+// ============= Destructuring Pattern Tests =============
+
+#[test]
+fn test_enum_payload_patterns() {
+    let code = r#"
+விருப்பம் Shape:
+    Circle(radius)
+    Rect(width, height)
+    Empty
+
+செயல் area(s):
+    பொருத்து s:
+        Shape.Circle(r) => திரும்பு 3 * r * r
+        Shape.Rect(w, h) என்றால் w == h => திரும்பு "square"
+        Shape.Rect(w, h) => திரும்பு w * h
+        Shape.Empty => திரும்பு 0
+
+மாறி c = Shape.Circle(2)
+[area(c), area(Shape.Rect(3, 3)), area(Shape.Rect(2, 5)), area(Shape.Empty), c.radius, c == Shape.Circle(2), c]
+"#;
+    assert_eq!(run(code).unwrap().to_string(), "[12, square, 10, 0, 2, உண்மை, Shape.Circle(2)]");
+}
+
+#[test]
+fn test_list_dict_struct_patterns() {
+    let code = r#"
+கட்டமைப்பு Point:
+    x
+    y
+
+செயல் describe(v):
+    பொருத்து v:
+        [] => திரும்பு "empty"
+        [first, ...rest] => திரும்பு f"{first}+{நீளம்(rest)}"
+        {"name": n, "age": a} என்றால் a >= 18 => திரும்பு f"adult {n}"
+        {"name": n} => திரும்பு f"minor {n}"
+        Point{x: 0, y} => திரும்பு f"axis {y}"
+        Point{x, y} என்றால் x == y => திரும்பு "diagonal"
+        _ => திரும்பு "other"
+
+[describe([]), describe([1, 2, 3]), describe({"name": "A", "age": 20}), describe({"name": "B", "age": 3}), describe(Point(0, 4)), describe(Point(2, 2)), describe(Point(1, 2))]
+"#;
+    assert_eq!(
+        run(code).unwrap().to_string(),
+        "[empty, 1+2, adult A, minor B, axis 4, diagonal, other]"
+    );
+
+    // Nested patterns bind every part
+    let code = "விருப்பம் E:\n    Pair(a, b)\nமாறி total = 0\nபொருத்து [E.Pair(1, [2, 3]), 4]:\n    [E.Pair(a, [b, ..._]), c] => total = a + b + c\ntotal";
+    assert_eq!(run(code).unwrap().to_string(), "7");
+}
+
+#[test]
+fn test_pattern_errors() {
+    let error = runtime_error("விருப்பம் E:\n    Pair(a, b)\nE.Pair(1)");
+    assert!(error.to_string().contains("மாறுபாடு 'E.Pair' 2 மதிப்புகளை எதிர்பார்க்கிறது, 1 கொடுக்கப்பட்டது"));
+
+    // A pattern with the wrong payload length does not match
+    assert_eq!(runtime_error_location("விருப்பம் E:\n    One(a)\nபொருத்து E.One(1):\n    E.One(a, b) => 1"), (3, 1));
+
+    assert!(!run_ok("பொருத்து [1]:\n    [...rest, last] => 1"));
+}
+
+// ============= Match Expression Tests =============
+
+#[test]
+fn test_match_expression() {
+    let code = r#"
+செயல் kind(day):
+    திரும்பு பொருத்து day:
+        6 => "weekend"
+        d என்றால் d >= 1 மற்றும் d <= 5 => "weekday"
+        _ => "invalid"
+
+மாறி n = 5
+மாறி size = பொருத்து [n, n * 2]:
+    [a, b] என்றால் b > 8 => f"big {a}"
+    _ => "small"
+மாறி adder = பொருத்து 3:
+    k => (x) => x + k
+மாறி nested = பொருத்து 1:
+    1 => பொருத்து 2:
+        2 => "inner"
+    _ => "outer"
+[kind(6), kind(2), kind(9), size, adder(1), nested]
+"#;
+    assert_eq!(run(code).unwrap().to_string(), "[weekend, weekday, invalid, big 5, 4, inner]");
+
+    // Arm variables belong to their arm
+    let error = runtime_error("மாறி x = பொருத்து 1:\n    k => k\nk");
+    assert_eq!(error.location(), (3, 1));
+}
+
+#[test]
+fn test_match_expression_no_match() {
+    let error = runtime_error("மாறி x = 1\nமாறி y = பொருத்து x * 4:\n    1 => \"one\"\n    2 => \"two\"");
+    assert!(error.to_string().contains("எந்த வடிவமும் பொருந்தவில்லை: 4"));
+    assert_eq!(error.location(), (2, 10));
+}
+
+// ============= Slicing Tests =============
+
+#[test]
+fn test_slicing() {
+    let code = "மாறி a = [0, 1, 2, 3, 4, 5]\n[a[1:3], a[:2], a[-2:], a[::2], a[::-1], a[5:1:-2], a[10:], a[:]]";
+    assert_eq!(
+        run(code).unwrap().to_string(),
+        "[[1, 2], [0, 1], [4, 5], [0, 2, 4], [5, 4, 3, 2, 1, 0], [5, 3], [], [0, 1, 2, 3, 4, 5]]"
+    );
+
+    // Strings slice by character; எழுத்துகள் splits into letters
+    let code = "மாறி s = \"வணக்கம்\"\n[s[0:2], s[-1:], எழுத்துகள்(s), எழுத்துகள்(s)[1:3]]";
+    assert_eq!(run(code).unwrap().to_string(), "[வண, ், [வ, ண, க், க, ம்], [ண, க்]]");
+
+    let code = "மாறி n = 2\nமாறி a = [1, 2, 3]\na[n - 1:]";
+    assert_eq!(run(code).unwrap().to_string(), "[2, 3]");
+
+    // Ranges slice to ranges
+    let code = "மாறி r = வரம்பு(10)\n[r[2:8:2], பட்டியலாக(r[2:8:2]), பட்டியலாக(r[::-3]), பட்டியலாக(வரம்பு(10, 0, -2)[1:]), r[5:2], r[4:5]]";
+    assert_eq!(run(code).unwrap().to_string(), "[வரம்பு(2, 8, 2), [2, 4, 6], [9, 6, 3, 0], [8, 6, 4, 2], வரம்பு(5, 2), வரம்பு(4, 5)]");
+    assert_eq!(run("நீளம்(வரம்பு(10 ** 18)[1::7])").unwrap().to_string(), "142857142857142857");
+    let code = "மாறி r = வரம்பு(-9223372036854775807, 9223372036854775807, 4611686018427387904)\n[பட்டியலாக(r[::-1]), நீளம்(r[::5]), பட்டியலாக(r[1:][::-1])]";
+    assert_eq!(run(code).unwrap().to_string(), "[[4611686018427387905, 1, -4611686018427387903, -9223372036854775807], 1, [4611686018427387905, 1, -4611686018427387903]]");
+}
+
+#[test]
+fn test_slice_assignment() {
+    let code = "மாறி a = [0, 1, 2, 3, 4]\na[1:3] = [\"x\"]\nமாறி b = [1, 2]\nb[:0] = b\nமாறி c = [0, 0, 0, 0]\nc[::2] = [1, 2]\n[a, b, c]";
+    assert_eq!(run(code).unwrap().to_string(), "[[0, x, 3, 4], [1, 2, 1, 2], [1, 0, 2, 0]]");
+
+    let error = runtime_error("மாறி a = [0, 0, 0]\na[::2] = [1]");
+    assert!(error.to_string().contains("துண்டுக்கு 2 மதிப்புகள் தேவை, 1 கொடுக்கப்பட்டது"));
+    assert!(runtime_error("[1, 2][::0]").to_string().contains("பூஜ்ஜியமாக"));
+    assert!(runtime_error("மாறி s = \"ab\"\ns[0:1] = [\"c\"]").to_string().contains("'சரம்' வகையை துண்டு ஒதுக்க இயலாது"));
+    assert_eq!(runtime_error_location("மாறி a = [1]\nமாறி x = a[\"1\":]"), (2, 10));
+}
+
+// ============= Compound Assignment Tests =============
+
+#[test]
+fn test_compound_assignment() {
+    let code = "மாறி x = 1\nx += 4\nx *= 3\nx -= 1\nx /= 2\nx %= 4\nமாறி s = \"அ\"\ns += \"ஆ\"\n[x, s]";
+    assert_eq!(run(code).unwrap().to_string(), "[3.0, அஆ]");
+
+    // Index and member targets are evaluated once
+    let code = r#"
+மாறி calls = 0
+செயல் pick(v):
+    calls += 1
+    திரும்பு v
+கட்டமைப்பு Box:
+    n
+மாறி a = [10, 20]
+மாறி b = Box(1)
+மாறி d = {"k": 1}
+pick(a)[pick(1)] += 5
+pick(b).n *= 7
+d["k"] -= 3
+[a, b.n, d["k"], calls]
+"#;
+    assert_eq!(run(code).unwrap().to_string(), "[[10, 25], 7, -2, 3]");
+
+    // Works on captured variables too
+    let code = "செயல் counter():\n    மாறி n = 0\n    திரும்பு செயலி():\n        வெளிமாறி n\n        திரும்பு n += 1\nமாறி next = counter()\nnext()\nnext()";
+    assert_eq!(run(code).unwrap().to_string(), "2");
+}
+
+#[test]
+fn test_compound_assignment_errors() {
+    assert_eq!(compile_error("மாறாத K = 1\nK += 1").location(), (2, 1));
+    assert_eq!(runtime_error_location("மாறி x = \"a\"\nx -= 1"), (2, 1));
+    assert_eq!(runtime_error_location("y += 1"), (1, 1));
+    assert!(!run_ok("மாறி a = [1]\na[0:1] += [2]"));
+}
+
+// ============= Operator Tests =============
+
+#[test]
+fn test_power_floor_division_and_bitwise() {
+    let code = "[2 ** 10, 2 ** 3 ** 2, -2 ** 2, 2 ** -1, 7 // 2, -7 // 2, 7.5 // 2]";
+    assert_eq!(run(code).unwrap().to_string(), "[1024, 512, -4, 0.5, 3, -4, 3.0]");
+
+    let code = "[6 & 3, 6 | 3, 6 ^ 3, ~5, 1 << 4, -16 >> 2, 1 + 2 << 1, 1 | 2 == 3]";
+    assert_eq!(run(code).unwrap().to_string(), "[2, 7, 5, -6, 16, -4, 6, உண்மை]");
+
+    let code = "மாறி x = 3\nx **= 2\nx //= 4\nx";
+    assert_eq!(run(code).unwrap().to_string(), "2");
+
+    assert_eq!(runtime_error_location("மாறி x = 1\nx = 1.5 & 1"), (2, 5));
+    assert_eq!(runtime_error_location("1 << -1"), (1, 1));
+    assert_eq!(run("[0 << 100000000000, 5 >> 100000000000, -5 >> 100000000000]").unwrap().to_string(), "[0, 0, -1]");
+
+    // Shifts too big for memory are errors a பிடி can catch
+    let code = "மாறி r = 0\nமுயற்சி:\n    r = 1 << 100000000000\nபிடி e:\n    r = e.செய்தி\nr";
+    assert_eq!(run(code).unwrap().to_string(), "முழுஎண் முடிவு மிகப் பெரியது (67108864 பிட்களுக்கு மேல்)");
+    assert_eq!(runtime_error_location("~\"அ\""), (1, 1));
+    assert_eq!(runtime_error_location("1 // 0"), (1, 1));
+}
+
+#[test]
+fn test_membership_operator() {
+    let code = r#"[2 உள்ள [1, 2, 3], "a" in {"a": 1}, "ell" உள்ள "hello", 5 in [1], 1 + 1 in [2]]"#;
+    assert_eq!(run(code).unwrap().to_string(), "[உண்மை, உண்மை, உண்மை, பொய், உண்மை]");
+
+    // Still the for-loop keyword
+    let code = "மாறி n = 0\nஒவ்வொரு i உள்ள [1, 2, 3]:\n    என்றால் i உள்ள [2, 3]:\n        n += i\nn";
+    assert_eq!(run(code).unwrap().to_string(), "5");
+
+    // `இல்ல` applies to the whole comparison, as `not` does in Python
+    let code = "[இல்ல 5 உள்ள [1], not 1 in [1], இல்ல 1 == 2, இல்ல 1 < 2 மற்றும் உண்மை, (இல்ல 1) உள்ள [பொய்]]";
+    assert_eq!(run(code).unwrap().to_string(), "[உண்மை, பொய், உண்மை, பொய், உண்மை]");
+
+    assert_eq!(runtime_error_location("1 உள்ள 5"), (1, 1));
+    assert_eq!(runtime_error_location("1 in \"123\""), (1, 1));
+}
+
+// ============= Integer Tests =============
+
+#[test]
+fn test_integers() {
+    // Integers never lose precision
+    let code = "செயல் f(n):\n    என்றால் n <= 1:\n        திரும்பு 1\n    திரும்பு n * f(n - 1)\nf(25)";
+    assert_eq!(run(code).unwrap().to_string(), "15511210043330985984000000");
+    assert_eq!(run("2 ** 100 + 1").unwrap().to_string(), "1267650600228229401496703205377");
+    assert_eq!(run("[1 ** 10000000000, (-1) ** 10000000001, 0 ** 10000000000, 7 ** 0]").unwrap().to_string(), "[1, -1, 0, 1]");
+    assert_eq!(runtime_error("2 ** 10000000000").message(), "முழுஎண் முடிவு மிகப் பெரியது (67108864 பிட்களுக்கு மேல்)");
+    assert_eq!(runtime_error_location("மாறி x = 2\nx **= 10 ** 20"), (2, 1));
+
+    // Machine-word results that overflow carry on exactly, and shrink back
+    let code = "மாறி m = 9223372036854775807\n[m + 1, -m - 2, m * 2, (-m - 1) // -1, (-m - 1) % -1, 1 << 63, m + 1 - 1 == m, -(-m - 1), ~m]";
+    assert_eq!(
+        run(code).unwrap().to_string(),
+        "[9223372036854775808, -9223372036854775809, 18446744073709551614, 9223372036854775808, 0, 9223372036854775808, உண்மை, 9223372036854775808, -9223372036854775808]"
+    );
+    assert_eq!(run("[3037000500 ** 2, -7 // 2, 7 % -3, -1 >> 100, 5 >> 64]").unwrap().to_string(), "[9223372037000250000, -4, -2, -1, 0]");
+
+    // Repetition counts must fit; only negative counts give ""
+    assert_eq!(run("[\"ab\" * 2, \"ab\" * -3, \"\" * (10 ** 20)]").unwrap().to_string(), "[abab, , ]");
+    assert_eq!(runtime_error("\"ab\" * (10 ** 20)").message(), "சரம் மிக நீளமாகும்: 100000000000000000000 முறை திரும்பச் செய்ய இயலாது");
+    assert_eq!(run("௧௨ * ௧௦").unwrap().to_string(), "120");
+
+    // Decimals only come from decimal operands and `/`
+    let code = "[7 / 2, 6 / 2, 1 + 0.5, 2 * 1.5, 7 // 2, -7 // 2, -7 % 3, 7.5 % 2, 2 ** -1]";
+    assert_eq!(run(code).unwrap().to_string(), "[3.5, 3.0, 1.5, 3.0, 3, -4, 2, 1.5, 0.5]");
+    let code = "[1 == 1.0, 2 < 2.5, வகை(42), வகை(4.0), நீளம்(\"abc\")]";
+    assert_eq!(run(code).unwrap().to_string(), "[உண்மை, உண்மை, முழுஎண், தசமஎண், 3]");
+
+    // Conversions
+    let code = "[எண்ணாக(\"123456789012345678901234567890\"), எண்ணாக(3.9), எண்ணாக(\"-2.5\"), தசமாக(2), தளம்(2.5), கூரை(2.5), கூட்டு([1, 2, 3]), கூட்டு([1, 0.5])]";
+    assert_eq!(
+        run(code).unwrap().to_string(),
+        "[123456789012345678901234567890, 3, -2, 2.0, 2, 3, 6, 1.5]"
+    );
+
+    // A whole decimal is the same dictionary key as its integer
+    let code = "மாறி d = {}\nd[3] = \"x\"\nd[2.0] = \"y\"\n[d[6 / 2], 6 / 2 உள்ள d, d[2], 2.5 உள்ள d, {4.0: 1}[4]]";
+    assert_eq!(run(code).unwrap().to_string(), "[x, உண்மை, y, பொய், 1]");
+
+    // Indices must be integers
+    assert_eq!(run("[10, 20, 30][4 // 2]").unwrap().to_string(), "30");
+    assert_eq!(runtime_error_location("மாறி a = [1, 2]\na[4 / 2]"), (2, 1));
+    assert_eq!(runtime_error_location("1 % 0"), (1, 1));
+}
+
+// ============= Parameter Tests =============
+
+#[test]
+fn test_default_and_keyword_arguments() {
+    let code = "செயல் f(a, b = 2, c = 3):\n    திரும்பு [a, b, c]\n[f(1), f(1, 5), f(1, c = 0), f(b = 4, a = 5)]";
+    assert_eq!(run(code).unwrap().to_string(), "[[1, 2, 3], [1, 5, 3], [1, 2, 0], [5, 4, 3]]");
+
+    // Defaults are evaluated once, where the function is defined
+    let code = "மாறி n = 10\nசெயல் f(x = n):\n    திரும்பு x\nn = 20\nf()";
+    assert_eq!(run(code).unwrap().to_string(), "10");
+
+    // Lambdas, methods and constructors take keyword arguments too
+    let code = "மாறி g = (x, y = 10) => x - y\nமாறி h = செயலி(x = 1): x * 2\n[g(1), g(y = 1, x = 5), h()]";
+    assert_eq!(run(code).unwrap().to_string(), "[-9, 4, 2]");
+    let code = "கட்டமைப்பு P:\n    x\n    y\n    செயல் shift(dx = 0, dy = 0):\n        திரும்பு [தன்.x + dx, தன்.y + dy]\nP(y = 2, x = 1).shift(dy = 5)";
+    assert_eq!(run(code).unwrap().to_string(), "[1, 7]");
+}
+
+#[test]
+fn test_variadic_parameters() {
+    let code = "செயல் f(a, *rest):\n    திரும்பு [a, rest]\n[f(1), f(1, 2, 3)]";
+    assert_eq!(run(code).unwrap().to_string(), "[[1, []], [1, [2, 3]]]");
+
+    let code = "செயல் f(a, **options):\n    திரும்பு [a, நீளம்(options), options[\"x\"]]\nf(1, x = 5)";
+    assert_eq!(run(code).unwrap().to_string(), "[1, 1, 5]");
+
+    let code = "செயல் f(*items, **named):\n    திரும்பு [items, நீளம்(named)]\nf()";
+    assert_eq!(run(code).unwrap().to_string(), "[[], 0]");
+}
+
+#[test]
+fn test_parameter_errors() {
+    let code = "செயல் f(a, b = 2):\n    திரும்பு a\n";
+    let error = runtime_error(&format!("{}f(b = 1)", code));
+    assert!(error.to_string().contains("'f' செயலுக்கு 'a' அளவுரு கொடுக்கப்படவில்லை"));
+    let error = runtime_error(&format!("{}f(1, z = 1)", code));
+    assert!(error.to_string().contains("'f' செயலுக்கு 'z' என்ற அளவுரு இல்லை"));
+    let error = runtime_error(&format!("{}f(1, a = 1)", code));
+    assert!(error.to_string().contains("'a' அளவுரு இருமுறை கொடுக்கப்பட்டது"));
+    let error = runtime_error(&format!("{}f(1, 2, 3)", code));
+    assert!(error.to_string().contains("அதிகபட்சம் 2 அளவுருக்கள், 3 கொடுக்கப்பட்டது"));
+    assert_eq!(runtime_error_location(&format!("{}\nf()", code)), (4, 1));
+
+    let error = runtime_error("நீளம்(x = [1])");
+    assert!(error.to_string().contains("பெயரிட்ட அளவுருக்களை ஏற்காது"));
+
+    // Malformed parameter lists and calls
+    assert!(!run_ok("செயல் f(a = 1, b):\n    திரும்பு a\n1"));
+    assert!(!run_ok("செயல் f(a, a):\n    திரும்பு a\n1"));
+    assert!(!run_ok("செயல் f(**k, a):\n    திரும்பு a\n1"));
+    assert!(!run_ok("செயல் f(*r, a):\n    திரும்பு a\n1"));
+    assert!(!run_ok("செயல் f(a):\n    திரும்பு a\nf(a = 1, 2)"));
+    assert!(!run_ok("செயல் f(a):\n    திரும்பு a\nf(a = 1, a = 2)"));
+}
+
+// ============= Closure Tests =============
+
+#[test]
+fn test_block_lambdas() {
+    let code = r#"
+மாறி classify = செயலி(n):
+    என்றால் n % 2 == 0:
+        திரும்பு "இரட்டை"
+    திரும்பு "ஒற்றை"
+மாறி labels = வரைபடம்([1, 2, 3], செயலி(n):
+    மாறி label = classify(n)
+    திரும்பு f"{n}:{label}"
+)
+மாறி nothing = செயலி():
+    மாறி x = 1
+[labels, nothing()]
+"#;
+    assert_eq!(run(code).unwrap().to_string(), "[[1:ஒற்றை, 2:இரட்டை, 3:ஒற்றை], இல்லா]");
+
+    // Block lambdas end their statement and can be nested
+    let code = "மாறி make = செயலி(x):
+    திரும்பு செயலி(y):
+        திரும்பு x * y
+மாறி triple = make(3)
+triple(5)";
+    assert_eq!(run(code).unwrap().to_string(), "15");
+    let error = runtime_error("மாறி f = செயலி():
+    திரும்பு 1 / 0
+f()");
+    assert_eq!(error.location(), (2, 14));
+    assert_eq!(error.stack()[1].function, "<lambda>");
+}
+
+#[test]
+fn test_nonlocal_assignment() {
+    // Callbacks that update state in the function that created them
+    let code = r#"
+செயல் tally(items):
+    மாறி total = 0
+    மாறி count = 0
+    ஒவ்வொரு item உள்ள items:
+        செயல் add(x):
+            nonlocal total, count
+            total += x
+            count += 1
+        add(item)
+    திரும்பு [total, count]
+செயல் accumulator():
+    மாறி sum = 0
+    திரும்பு செயலி(x):
+        வெளிமாறி sum
+        sum += x
+        திரும்பு sum
+மாறி acc = accumulator()
+acc(5)
+[tally([1, 2, 3]), acc(10)]
+"#;
+    assert_eq!(run(code).unwrap().to_string(), "[[6, 3], 15]");
+
+    // Reading needs no declaration; top-level variables stay assignable
+    let code = "மாறி hits = 0
+செயல் f(x):
+    hits += 1
+    திரும்பு செயலி(): x * 2
+f(4)()
+f(1)
+hits";
+    assert_eq!(run(code).unwrap().to_string(), "2");
+
+    let error = compile_error("செயல் f():
+    மாறி n = 0
+    செயல் g():
+        n = 1
+    திரும்பு g");
+    assert_eq!(error.location(), (4, 9));
+    assert!(error.to_string().contains("'வெளிமாறி n' என அறிவிக்கவும்"));
+    assert!(compile_error("மாறி n = 0
+செயல் f():
+    வெளிமாறி n
+    n = 1").to_string().contains("வெளிச் செயலின் மாறியும் அல்ல"));
+    assert!(compile_error("செயல் f(n):
+    வெளிமாறி n
+    n = 1").to_string().contains("வெளிச் செயலின் மாறியும் அல்ல"));
+    assert_eq!(compile_error("வெளிமாறி n").location(), (1, 1));
+    assert_eq!(compile_error("செயல் f():
+    மாறி n = 0
+    திரும்பு () => n += 1").location(), (3, 20));
+}
+
+// ============= Tuple Tests =============
+
+#[test]
+fn test_tuples() {
+    let code = r#"மாறி t = (1, "இரு", 3.0)
+[t, வகை(t), நீளம்(t), t[1], t[-1], t[0:2], 3.0 உள்ள t]"#;
+    assert_eq!(run(code).unwrap().to_string(), "[(1, இரு, 3.0), தொகுதி, 3, இரு, 3.0, (1, இரு), உண்மை]");
+    assert_eq!(run("[(), (5,), (1 + 2), (1, 2) == (1, 2), (1, 2) == [1, 2]]").unwrap().to_string(), "[(), (5,), 3, உண்மை, பொய்]");
+    assert_eq!(run("மாறி p = 1, 2\np").unwrap().to_string(), "(1, 2)");
+
+    // Tuples are immutable
+    let error = runtime_error("மாறி t = (1, 2)\nt[0] = 5");
+    assert!(error.to_string().contains("தொகுதியை மாற்ற இயலாது"));
+    assert_eq!(error.location(), (2, 1));
+
+    // List patterns match tuples of the same length
+    let code = "மாறி total = 0\nபொருத்து (1, 2):\n    [a, b] => total = a + b\ntotal";
+    assert_eq!(run(code).unwrap().to_string(), "3");
+}
+
+#[test]
+fn test_multiple_return_and_destructuring() {
+    let code = "செயல் divide(a, b):
+    திரும்பு a // b, a % b
+மாறி q, r = divide(17, 5)
+மாறாத x, y = [10, 20]
+[q, r, x + y, divide(9, 3)]";
+    assert_eq!(run(code).unwrap().to_string(), "[3, 2, 30, (3, 0)]");
+
+    // Loop variables unpack each item
+    let code = r#"மாறி out = []
+ஒவ்வொரு k, v உள்ள [(1, "ஒன்று"), (2, "இரண்டு")]:
+    சேர்(out, f"{k}={v}")
+out"#;
+    assert_eq!(run(code).unwrap().to_string(), "[1=ஒன்று, 2=இரண்டு]");
+
+    // Values are counted against the names
+    let error = runtime_error("மாறி a, b = [1, 2, 3]");
+    assert!(error.to_string().contains("2 மதிப்புகளைப் பிரிக்க எதிர்பார்க்கப்பட்டது, 3 உள்ளன"));
+    assert_eq!(error.location(), (1, 13));
+    let error = runtime_error("ஒவ்வொரு a, b உள்ள [1]:\n    அச்சிடு(a)");
+    assert!(error.to_string().contains("'முழுஎண்' வகையைப் பிரிக்க இயலாது"));
+    assert!(compile_error("மாறாத a, b = 1, 2\na, b = 3, 4").to_string().contains("'a' மாறாத மாறி"));
+}
+
+#[test]
+fn test_multiple_assignment() {
+    let code = "மாறி a = 1
+மாறி b = 2
+a, b = b, a
+[a, b]";
+    assert_eq!(run(code).unwrap().to_string(), "[2, 1]");
+
+    // Index and member targets; all values are computed first
+    let code = "கட்டமைப்பு Point:
+    x
+    y
+மாறி p = Point(1, 2)
+p.x, p.y = p.y, p.x
+மாறி xs = [3, 1, 2]
+xs[0], xs[2] = xs[2], xs[0]
+[p.x, p.y, xs]";
+    assert_eq!(run(code).unwrap().to_string(), "[2, 1, [2, 1, 3]]");
+
+    // Captured variables are assigned through வெளிமாறி
+    let code = "செயல் counter():
+    மாறி low = 0
+    மாறி high = 0
+    செயல் bump():
+        வெளிமாறி low, high
+        low, high = low + 1, high + 10
+    bump()
+    bump()
+    திரும்பு low, high
+counter()";
+    assert_eq!(run(code).unwrap().to_string(), "(2, 20)");
+
+    assert!(!run_ok("மாறி a = 1\na, 2 = 1, 2"));
+    assert!(!run_ok("மாறி a = 1\nமாறி b = 1\na, b += 1, 2"));
+}
+
+// ============= Iteration Tests =============
+
+#[test]
+fn test_lazy_ranges() {
+    let code = "மாறி r = வரம்பு(0, 10, 3)\n[r, நீளம்(r), r[1], 9 உள்ள r, 4 உள்ள r, பட்டியலாக(r), கூட்டு(வரம்பு(101))]";
+    assert_eq!(run(code).unwrap().to_string(), "[வரம்பு(0, 10, 3), 4, 3, உண்மை, பொய், [0, 3, 6, 9], 5050]");
+
+    // Numbers are produced one at a time, so huge ranges cost nothing
+    let code = "மாறி last = 0
+ஒவ்வொரு i உள்ள வரம்பு(1000000000):
+    என்றால் i == 3:
+        நிறுத்து
+    last = i
+last";
+    assert_eq!(run(code).unwrap().to_string(), "2");
+    assert!(runtime_error("வரம்பு(1, 5, 0)").to_string().contains("படி அளவு பூஜ்ஜியமாக இருக்க முடியாது"));
+    let message = runtime_error("வரம்பு(10 ** 20)").message();
+    assert!(message.contains("வரம்பு() எல்லை 100000000000000000000 அனுமதிக்கப்பட்ட வரம்பிற்கு வெளியே"), "{}", message);
+    assert!(runtime_error("வரம்பு(1.5)").message().contains("வரம்பு() எண் அளவுருக்கள் தேவை"));
+}
+
+#[test]
+fn test_appending_inside_a_for_loop() {
+    // A loop visits the items the list had when it began, seeing changes to them
+    let code = "மாறி l = [1, 2, 3]
+ஒவ்வொரு x உள்ள l:
+    சேர்(l, x)
+    l[2] = 30
+l";
+    assert_eq!(run(code).unwrap().to_string(), "[1, 2, 30, 1, 2, 30]");
+    let code = "மாறி l = [1, 2, 3, 4]\nமாறி seen = []\nஒவ்வொரு x உள்ள l:\n    சேர்(seen, x)\n    நீக்கு(l)\nseen";
+    assert_eq!(run(code).unwrap().to_string(), "[1, 2]");
+}
+
+#[test]
+fn test_dict_iteration() {
+    let code = r#"மாறி d = {"b": 2, "a": 1}
+மாறி out = []
+ஒவ்வொரு k உள்ள d:
+    சேர்(out, k)
+ஒவ்வொரு k, v உள்ள உருப்படிகள்(d):
+    சேர்(out, v)
+[out, சாவிகள்(d), மதிப்புகள்(d), உருப்படிகள்(d)]"#;
+    assert_eq!(run(code).unwrap().to_string(), "[[a, b, 1, 2], [a, b], [1, 2], [(a, 1), (b, 2)]]");
+}
+
+#[test]
+fn test_enumerate_and_zip() {
+    let code = r#"மாறி out = []
+ஒவ்வொரு i, x உள்ள எண்ணிடு(["அ", "ஆ"], 1):
+    சேர்(out, f"{i}{x}")
+[out, பட்டியலாக(ஜோடி([1, 2, 3], "அஆ")), தொகுதியாக(எண்ணிடு("ab"))]"#;
+    assert_eq!(run(code).unwrap().to_string(), "[[1அ, 2ஆ], [(1, அ), (2, ஆ)], ((0, a), (1, b))]");
+
+    // An iterator is used up once it has been looped over
+    assert_eq!(run("மாறி e = எண்ணிடு([5])\n[பட்டியலாக(e), பட்டியலாக(e)]").unwrap().to_string(), "[[(0, 5)], []]");
+}
+
+#[test]
+fn test_struct_iteration() {
+    let code = "கட்டமைப்பு Countdown:
+    n
+    செயல் அடுத்து():
+        என்றால் தன்.n == 0:
+            திரும்பு இல்லா
+        தன்.n = தன்.n - 1
+        திரும்பு தன்.n + 1
+கட்டமைப்பு Bag:
+    items
+    செயல் மீள்():
+        திரும்பு தன்.items
+மாறி out = []
+ஒவ்வொரு x உள்ள Countdown(3):
+    சேர்(out, x)
+[out, பட்டியலாக(Bag([7, 8])), வடிகட்டு(Countdown(4), (x) => x % 2 == 0)]";
+    assert_eq!(run(code).unwrap().to_string(), "[[3, 2, 1], [7, 8], [4, 2]]");
+
+    let error = runtime_error("கட்டமைப்பு P:\n    x\nஒவ்வொரு i உள்ள P(1):\n    அச்சிடு(i)");
+    assert!(error.to_string().contains("'கட்டமைப்பு' வகையை மீளமுடியாது"));
+    assert_eq!(runtime_error_location("ஒவ்வொரு x உள்ள 5:\n    அச்சிடு(x)"), (1, 16));
+}
+
+// ============= Generator Tests =============
+
+#[test]
+fn test_generators() {
+    let code = "செயல் எண்கள்(n):
+    மாறி i = 0
+    வரை i < n:
+        விளை i
+        i += 1
+
+செயல் இயற்கை():
+    மாறி i = 1
+    வரை உண்மை:
+        விளை i
+        i += 1
+
+மாறி out = []
+ஒவ்வொரு x உள்ள இயற்கை():
+    என்றால் x > 3:
+        நிறுத்து
+    சேர்(out, x)
+மாறி g = எண்கள்(2)
+[out, பட்டியலாக(எண்கள்(4)), அடுத்து(g), அடுத்து(g), அடுத்து(g), அடுத்து(g, \"முடிந்தது\"), வகை(g)]";
+    assert_eq!(run(code).unwrap().to_string(), "[[1, 2, 3], [0, 1, 2, 3], 0, 1, இல்லா, முடிந்தது, மீளி]");
+
+    // Execution resumes inside loops, branches, match arms and catch clauses
+    let code = r#"செயல் parts(xs):
+    ஒவ்வொரு i, x உள்ள எண்ணிடு(xs):
+        என்றால் i % 2 == 0:
+            விளை x, "இரட்டை"
+        இல்லை:
+            பொருத்து x:
+                "b" => விளை "பி"
+                _ => விளை x
+    திரும்பு "மறைவு"
+
+செயல் safe(xs):
+    ஒவ்வொரு x உள்ள xs:
+        முயற்சி:
+            விளை 10 // x
+        பிடி e:
+            விளை "பிழை"
+            விளை e.செய்தி
+        விளை_முடிவு = விளை_முடிவு + 1
+
+மாறி விளை_முடிவு = 0
+[பட்டியலாக(parts(["a", "b", "c", "d"])), பட்டியலாக(safe([2, 0, 5])), விளை_முடிவு]"#;
+    assert_eq!(
+        run(code).unwrap().to_string(),
+        "[[(a, இரட்டை), பி, (c, இரட்டை), d], [5, பிழை, பூஜ்ஜியத்தால் வகுக்க இயலாது, 2], 3]"
+    );
+
+    // Block lambdas can be generators too
+    let code = "மாறி countdown = செயலி(n):\n    வரை n > 0:\n        விளை n\n        n -= 1\nகூட்டு(countdown(4))";
+    assert_eq!(run(code).unwrap().to_string(), "10");
+}
+
+#[test]
+fn test_generator_errors() {
+    // Errors surface when the body reaches them, inside the generator's frame
+    let error = runtime_error("செயல் bad():\n    விளை 1\n    விளை 1 / 0\nபட்டியலாக(bad())");
+    assert_eq!(error.location(), (3, 10));
+    let frames: Vec<(&str, usize, usize)> = error
+        .stack()
+        .iter()
+        .map(|frame| (frame.function.as_str(), frame.line, frame.column))
+        .collect();
+    assert_eq!(frames, vec![("<நிரல்>", 4, 1), ("bad", 3, 10)]);
+
+    let error = runtime_error("செயல் f():\n    விளை அடுத்து(g)\nமாறி g = f()\nஅடுத்து(g)");
+    assert!(error.to_string().contains("மீளி ஏற்கனவே இயங்கிக்கொண்டிருக்கிறது"));
+    assert!(runtime_error("அடுத்து([1])").to_string().contains("அடுத்து() ஒரு மீளி தேவை, 'பட்டியல்' கொடுக்கப்பட்டது"));
+
+    assert!(compile_error("விளை 1").to_string().contains("'விளை' செயலுக்கு வெளியே பயன்படுத்த முடியாது"));
+    let error = compile_error("செயல் f():\n    முயற்சி:\n        இல்லாதது()\n    இறுதியாக:\n        விளை 2");
+    assert!(error.to_string().contains("'இறுதியாக' உள்ள 'முயற்சி' தொகுதிக்குள் 'விளை' பயன்படுத்த முடியாது"));
+    assert_eq!(error.location(), (5, 9));
+
+    // A loop that stops early never resumes the generator, so a இறுதியாக
+    // after the விளை could not run; the விளை is rejected instead
+    let code = "மாறி closed = பொய்
+செயல் items():
+    முயற்சி:
+        விளை 1
+        விளை 2
+    இறுதியாக:
+        closed = உண்மை
+ஒவ்வொரு x உள்ள items():
+    நிறுத்து";
+    assert_eq!(compile_error(code).location(), (4, 9));
+    let error = compile_error("செயல் f():\n    முயற்சி:\n        பூஜ்ஜியம்()\n    பிடி e:\n        விளை e\n    இறுதியாக:\n        அச்சிடு(1)");
+    assert_eq!(error.location(), (5, 9));
+
+    // Generators defined inside such a முயற்சி are fine
+    let code = "செயல் f():\n    முயற்சி:\n        செயல் g():\n            விளை 1\n        திரும்பு பட்டியலாக(g())\n    இறுதியாக:\n        மாறி done = உண்மை\nf()";
+    assert_eq!(run(code).unwrap().to_string(), "[1]");
+}
+
+// ============= Comprehension Tests =============
+
+#[test]
+fn test_list_comprehensions() {
+    let code = "மாறி xs = [3, -1, 4, -1, 5]
+[[x * 2 ஒவ்வொரு x உள்ள xs என்றால் x > 0], [i ஒவ்வொரு i உள்ள வரம்பு(10) என்றால் i % 2 == 0 என்றால் i > 4]]";
+    assert_eq!(run(code).unwrap().to_string(), "[[6, 8, 10], [6, 8]]");
+
+    // Later clauses loop inside earlier ones, and may use their variables
+    let code = "[(a, b) ஒவ்வொரு a உள்ள வரம்பு(3) ஒவ்வொரு b உள்ள வரம்பு(a) என்றால் a + b > 1]";
+    assert_eq!(run(code).unwrap().to_string(), "[(2, 0), (2, 1)]");
+    let code = "[f\"{k}={v}\" ஒவ்வொரு k, v உள்ள உருப்படிகள்({\"b\": 2, \"a\": 1})]";
+    assert_eq!(run(code).unwrap().to_string(), "[a=1, b=2]");
+
+    // Comprehension variables stay inside it, and shadow outer ones
+    let code = "மாறி x = 100
+செயல் f(n):
+    திரும்பு [i * n ஒவ்வொரு i உள்ள வரம்பு(n)]
+[[x ஒவ்வொரு x உள்ள [x, x + 1]], x, f(3)]";
+    assert_eq!(run(code).unwrap().to_string(), "[[100, 101], 100, [0, 3, 6]]");
+    assert!(runtime_error("மாறி a = [y ஒவ்வொரு y உள்ள [1]]\ny").to_string().contains("வரையறுக்கப்படாத மாறி 'y'"));
+
+    // Closures made in a comprehension share its variables
+    let code = "மாறி fs = [செயலி(): x ஒவ்வொரு x உள்ள வரம்பு(3)]\n[f() ஒவ்வொரு f உள்ள fs]";
+    assert_eq!(run(code).unwrap().to_string(), "[2, 2, 2]");
+
+    assert_eq!(runtime_error_location("மாறி a = [1 ஒவ்வொரு y உள்ள 5]"), (1, 28));
+}
+
+#[test]
+fn test_dict_comprehensions() {
+    let code = "மாறி d = {k: v * 10 ஒவ்வொரு k, v உள்ள உருப்படிகள்({\"அ\": 1, \"ஆ\": 2, \"இ\": 3}) என்றால் v != 2}
+[நீளம்(d), d[\"அ\"], d[\"இ\"], {x % 3: x ஒவ்வொரு x உள்ள வரம்பு(5)}[1]]";
+    assert_eq!(run(code).unwrap().to_string(), "[2, 10, 30, 4]");
+}
+
+// ============= Language Server Tests =============
+
+/// Frame JSON-RPC messages the way an editor sends them
+fn lsp_input(messages: &[serde_json::Value]) -> Vec<u8> {
+    let mut input = Vec::new();
+    for message in messages {
+        let body = message.to_string();
+        input.extend(format!("Content-Length: {}\r\n\r\n{}", body.len(), body).into_bytes());
+    }
+    input
+}
+
+/// Every message the server sent back
+fn lsp_output(output: &[u8]) -> Vec<serde_json::Value> {
+    let mut reader = std::io::BufReader::new(output);
+    let mut messages = Vec::new();
+    while let Some(body) = agam::lsp::server::read_message(&mut reader).unwrap() {
+        messages.push(serde_json::from_str(&body).unwrap());
+    }
+    messages
+}
+
+/// Open `source` as a document, send the requests with ids from 2 on, and
+/// return the notifications and the results in order
+fn lsp_session(source: &str, requests: &[(&str, serde_json::Value)]) -> (Vec<serde_json::Value>, Vec<serde_json::Value>) {
+    use serde_json::json;
+    let uri = "file:///test.agam";
+    let mut messages = vec![
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+        json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}),
+        json!({"jsonrpc": "2.0", "method": "textDocument/didOpen",
+               "params": {"textDocument": {"uri": uri, "languageId": "agam", "version": 1, "text": source}}}),
+    ];
+    for (i, (method, params)) in requests.iter().enumerate() {
+        let mut params = params.clone();
+        params["textDocument"] = json!({"uri": uri});
+        messages.push(json!({"jsonrpc": "2.0", "id": i + 2, "method": method, "params": params}));
+    }
+    messages.push(json!({"jsonrpc": "2.0", "id": 99, "method": "shutdown"}));
+    messages.push(json!({"jsonrpc": "2.0", "method": "exit"}));
+
+    let mut output = Vec::new();
+    assert!(agam::lsp::serve(&lsp_input(&messages)[..], &mut output).unwrap());
+    let (notifications, responses): (Vec<_>, Vec<_>) = lsp_output(&output).into_iter().partition(|m| m.get("id").is_none());
+    let results = responses.into_iter().filter(|r| r["id"] != 1 && r["id"] != 99).map(|r| r["result"].clone()).collect();
+    (notifications, results)
+}
+
+fn at(line: u64, character: u64) -> serde_json::Value {
+    serde_json::json!({"position": {"line": line, "character": character}})
+}
+
+#[test]
+fn test_lsp_diagnostics() {
+    let (notifications, _) = lsp_session("மாறி x = 1\nx = (2\n", &[]);
+    let diagnostics = &notifications[0]["params"]["diagnostics"];
+    assert_eq!(notifications[0]["method"], "textDocument/publishDiagnostics");
+    assert_eq!(diagnostics.as_array().unwrap().len(), 1);
+    assert_eq!(diagnostics[0]["code"], "parser");
+    assert_eq!(diagnostics[0]["range"]["start"]["line"], 1);
+
+    // Resolver errors are reported too, and a clean file clears the list
+    let (notifications, _) = lsp_session("திரும்பு 1\n", &[]);
+    assert_eq!(notifications[0]["params"]["diagnostics"][0]["code"], "compile");
+    let (notifications, _) = lsp_session("மாறி x = 1\n", &[]);
+    assert_eq!(notifications[0]["params"]["diagnostics"], serde_json::json!([]));
+}
+
+#[test]
+fn test_lsp_navigation() {
+    let source = "செயல் கூட்டல்(a, b = 2):
+    திரும்பு a + b
+மாறி x = 1
+செயல் f(x):
+    திரும்பு x * 2
+அச்சிடு(கூட்டல்(x), நீளம்(\"அ\"))
+";
+    let (_, results) = lsp_session(source, &[
+        ("textDocument/definition", at(5, 10)),
+        ("textDocument/definition", at(4, 14)),
+        ("textDocument/definition", at(5, 16)),
+        ("textDocument/hover", at(5, 10)),
+        ("textDocument/hover", at(5, 23)),
+        ("textDocument/definition", at(5, 1)),
+    ]);
+    // கூட்டல் is declared at 0:6, the parameter x shadows the global inside f
+    assert_eq!(results[0]["range"]["start"], serde_json::json!({"line": 0, "character": 6}));
+    assert_eq!(results[1]["range"]["start"], serde_json::json!({"line": 3, "character": 8}));
+    assert_eq!(results[2]["range"]["start"], serde_json::json!({"line": 2, "character": 5}));
+    assert_eq!(results[3]["contents"]["value"], "```agam\nசெயல் கூட்டல்(a, b = 2)\n```");
+    // A builtin shows its call and what it does, like a declared function
+    assert_eq!(
+        results[4]["contents"]["value"],
+        "```agam\nசெயல் நீளம்(value)\n```\n\n**நீளம்** / `len` · Number of items in a string, list, tuple, dictionary or range"
+    );
+    let english = agam::lsp::analysis::builtin_doc("sort_by").unwrap();
+    assert!(english.starts_with("```agam\nசெயல் sort_by(items, function)\n```\n\n**வரிசைப்படுத்து** / `sort_by`"), "{}", english);
+    assert!(results[5].is_null());
+}
+
+#[test]
+fn test_lsp_completion_and_symbols() {
+    let source = "கட்டமைப்பு புள்ளி:
+    x
+    செயல் தூரம்():
+        திரும்பு தன்.x
+விருப்பம் நிறம்:
+    சிவப்பு
+செயல் f(அளவு):
+    மாறி p = புள்ளி(1)
+    திரும்பு p.x
+";
+    let (_, results) = lsp_session(source, &[
+        ("textDocument/completion", at(7, 4)),
+        ("textDocument/completion", at(8, 15)),
+        ("textDocument/documentSymbol", serde_json::json!({})),
+    ]);
+    let labels = |result: &serde_json::Value| -> Vec<String> {
+        result.as_array().unwrap().iter().map(|item| item["label"].as_str().unwrap().to_string()).collect()
+    };
+    let names = labels(&results[0]);
+    for expected in ["அளவு", "p", "புள்ளி", "f", "நீளம்", "len", "செயல்", "while"] {
+        assert!(names.iter().any(|name| name == expected), "{} missing from {:?}", expected, names);
+    }
+    // After a dot only members are offered
+    assert_eq!(labels(&results[1]), vec!["x", "தூரம்", "சிவப்பு"]);
+
+    let symbols = results[2].as_array().unwrap();
+    let outline: Vec<(&str, u64, usize)> = symbols
+        .iter()
+        .map(|s| (s["name"].as_str().unwrap(), s["kind"].as_u64().unwrap(), s["children"].as_array().unwrap().len()))
+        .collect();
+    assert_eq!(outline, vec![("புள்ளி", 23, 2), ("நிறம்", 10, 1), ("f", 12, 0)]);
+    assert_eq!(symbols[0]["children"][1]["name"], "தூரம்");
+    assert_eq!(symbols[0]["range"]["end"]["line"], 3);
+}
+
+#[test]
+fn test_lsp_binary_over_pipes() {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+    let messages = [
+        serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "unknown/method"}),
+        serde_json::json!({"jsonrpc": "2.0", "method": "exit"}),
+    ];
+    let mut child = Command::new(env!("CARGO_BIN_EXE_agam"))
+        .arg("lsp")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(&lsp_input(&messages)).unwrap();
+    let output = child.wait_with_output().unwrap();
+    let replies = lsp_output(&output.stdout);
+    assert_eq!(replies[0]["result"]["capabilities"]["hoverProvider"], true);
+    assert_eq!(replies[1]["error"]["code"], -32601);
+    // Exiting without a shutdown request is reported in the exit code
+    assert_eq!(output.status.code(), Some(1));
+}
+
+// ============= Formatter Tests =============
+
+/// Format with the default options, checking that formatting again changes nothing
+fn fmt(source: &str) -> String {
+    let formatted = format_source(source, &FormatOptions::default()).unwrap();
+    assert_eq!(format_source(&formatted, &FormatOptions::default()).unwrap(), formatted);
+    formatted
+}
+
+#[test]
+fn test_fmt_layout() {
+    let code = "மாறி x=1+2*-3
+மாறி a,b=(x,[1,2]),{\"k\":x**2}
+செயல் f(n,m=2,*rest):
+  என்றால் n>m மற்றும் இல்ல n==0:
+        திரும்பு n//m,rest[1:]
+  இல்லை:
+        திரும்பு (n,)
+அச்சிடு(f(7,m=3))
+மாறி g=(y)=>y%2";
+    let expected = "மாறி x = 1 + 2 * -3
+மாறி a, b = (x, [1, 2]), {\"k\": x ** 2}
+
+செயல் f(n, m = 2, *rest):
+    என்றால் n > m மற்றும் இல்ல n == 0:
+        திரும்பு n // m, rest[1:]
+    இல்லை:
+        திரும்பு (n,)
+
+அச்சிடு(f(7, m = 3))
+மாறி g = செயலி(y): y % 2
+";
+    assert_eq!(fmt(code), expected);
+    assert_eq!(run(&fmt(code)).unwrap().to_string(), run(code).unwrap().to_string());
+
+    // Declared, assigned, returned and yielded values lose their outer parentheses alike
+    let code = "மாறி a = ((1 + 2))\na = (a * (a - 1))\nசெயல் f():\n    திரும்பு (a)\nசெயல் g():\n    விளை ((1, 2))\n    திரும்பு (a,)";
+    let expected = "மாறி a = 1 + 2\na = a * (a - 1)\n\nசெயல் f():\n    திரும்பு a\n\nசெயல் g():\n    விளை 1, 2\n    திரும்பு (a,)\n";
+    assert_eq!(fmt(code), expected);
+
+    // Runs of blank lines shrink to one, and blocks never start with one
+    assert_eq!(fmt("\n\nமாறி a = 1\n\n\n\nமாறி b = 2\nஎன்றால் a:\n\n    b = 3\n\n"), "மாறி a = 1\n\nமாறி b = 2\nஎன்றால் a:\n    b = 3\n");
+
+    // Block lambdas and match expressions keep their indented bodies
+    let code = "செயல் h(xs):\n    திரும்பு வரைபடம்(செயலி(v):\n        திரும்பு பொருத்து v:\n            1 => \"ஒன்று\"\n            _ => f\"{v}!\"\n    , xs)\n\nஅச்சிடு(h([1, 2]))\n";
+    assert_eq!(fmt(code), code);
+    assert_eq!(fmt("அச்சிடு(\"a\\t\\\"b\\\"\", f\"{{x}} {1 + 1}\")"), "அச்சிடு(\"a\\t\\\"b\\\"\", f\"{{x}} {1 + 1}\")\n");
+
+    // Source that does not parse is reported, not formatted
+    let error = format_source("மாறி = 1", &FormatOptions::default()).unwrap_err();
+    assert_eq!(error.kind(), "parser");
+}
+
+#[test]
+fn test_fmt_comments() {
+    let code = "# தலைப்பு
+மாறி x = 1    # ஒன்று
+
+# செயல் பற்றி
+செயல் f():
+    # உள்ளே
+    என்றால் x:   # சோதனை
+        திரும்பு 1
+        # முடிவு
+    இல்லை:
+        திரும்பு 2
+கட்டமைப்பு P:
+    a  # புலம்
+    # முறை
+    செயல் m():
+        திரும்பு தன்.a
+# கடைசி";
+    let expected = "# தலைப்பு
+மாறி x = 1  # ஒன்று
+
+# செயல் பற்றி
+செயல் f():
+    # உள்ளே
+    என்றால் x:  # சோதனை
+        திரும்பு 1
+        # முடிவு
+    இல்லை:
+        திரும்பு 2
+
+கட்டமைப்பு P:
+    a  # புலம்
+    # முறை
+    செயல் m():
+        திரும்பு தன்.a
+# கடைசி
+";
+    assert_eq!(fmt(code), expected);
+}
+
+#[test]
+fn test_fmt_keywords() {
+    let tamil = "செயல் f(தன்_இல்லை):
+    ஒவ்வொரு i உள்ள வரம்பு(3):
+        என்றால் i உள்ள [1] அல்லது உண்மை:
+            தொடர்
+    முயற்சி:
+        வீசு இல்லா
+    பிடி e:
+        அச்சிடு(e)
+    திரும்பு உள்ளீடு
+";
+    let english = "fn f(தன்_இல்லை):
+    for i in வரம்பு(3):
+        if i in [1] or true:
+            continue
+    try:
+        throw null
+    catch e:
+        print(e)
+    return input
+";
+    let to = |source: &str, spelling| format_source(source, &FormatOptions { keywords: Some(spelling) }).unwrap();
+    assert_eq!(to(tamil, Spelling::English), english);
+    assert_eq!(to(english, Spelling::Tamil), tamil);
+
+    // By default keywords follow the spelling most of the file uses
+    assert_eq!(fmt("let x = 1\nif x and true:\n    அச்சிடு(x)"), "let x = 1\nif x and true:\n    print(x)\n");
+    assert_eq!(fmt("மாறி x = 1\nif x:\n    அச்சிடு(x)"), "மாறி x = 1\nஎன்றால் x:\n    அச்சிடு(x)\n");
+}
+
+#[test]
+fn test_fmt_binary_check() {
+    use std::process::Command;
+    let path = std::env::temp_dir().join(format!("agam_fmt_{}.agam", std::process::id()));
+    std::fs::write(&path, "மாறி x=1\nஅச்சிடு(x)\n").unwrap();
+    let agam = |args: &[&str]| Command::new(env!("CARGO_BIN_EXE_agam")).args(args).arg(&path).output().unwrap();
+
+    // --check lists the file without touching it
+    let output = agam(&["fmt", "--check"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("agam_fmt_"));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "மாறி x=1\nஅச்சிடு(x)\n");
+
+    assert!(agam(&["fmt", "--keywords=english"]).status.success());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "let x = 1\nprint(x)\n");
+    assert!(agam(&["fmt", "--check"]).status.success());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_translate_keywords_and_builtins() {
+    let tamil = "# நீளம் என்றால் len\nமாறி xs = [3, 1, 2]\nஎன்றால் நீளம்(xs) > 2 மற்றும் உண்மை:\n    அச்சிடு(\"நீளம் if\", f\"{அதிகபட்சம்(xs)} {{நீளம்}}\", வரிசை(xs))\n";
+    let english = "# நீளம் என்றால் len\nlet xs = [3, 1, 2]\nif len(xs) > 2 and true:\n    print(\"நீளம் if\", f\"{max(xs)} {{நீளம்}}\", sort(xs))\n";
+    assert_eq!(translate_source(tamil, Spelling::English).unwrap(), english);
+    assert_eq!(translate_source(english, Spelling::Tamil).unwrap(), tamil);
+    assert_eq!(run(english).unwrap().to_string(), run(tamil).unwrap().to_string());
+
+    // Mixed code comes out in one language, with its layout untouched
+    assert_eq!(translate_source("let x=1\nஎன்றால் x :  # c\n   print(வகை(x))", Spelling::Tamil).unwrap(), "மாறி x=1\nஎன்றால் x :  # c\n   அச்சிடு(வகை(x))");
+
+    // Names the program declares itself are its own, even when a built-in has them
+    let code = "செயல் கூட்டு(a, len = 2):
+    திரும்பு a + len
+கட்டமைப்பு P:
+    வகை
+மாறி p = P(வகை = 1)
+மாறி f = செயலி(max): max
+அச்சிடு(கூட்டு(1, len = 3), p.வகை, f(2), [sum ஒவ்வொரு sum உள்ள [1]], கூட்டு)";
+    let translated = translate_source(code, Spelling::English).unwrap();
+    assert_eq!(translated, code.replace("செயல் ", "fn ").replace("திரும்பு", "return").replace("கட்டமைப்பு", "struct")
+        .replace("மாறி ", "let ").replace("செயலி", "lambda").replace("அச்சிடு", "print").replace("ஒவ்வொரு", "for").replace("உள்ள", "in"));
+    assert!(translate_source("மாறி = 1", Spelling::English).is_err());
+}
+
+#[test]
+fn test_builtins_come_in_tamil_english_pairs() {
+    // `agam translate` and hover pair each built-in with the entry after it
+    let builtins = agam::interpreter::builtin::create_builtins();
+    assert_eq!(builtins.len() % 2, 0);
+    for pair in builtins.chunks(2) {
+        let ((tamil, first), (english, second)) = (&pair[0], &pair[1]);
+        assert!(!tamil.is_ascii() && english.is_ascii(), "{} / {}", tamil, english);
+        // Hover describes every built-in
+        assert!(agam::interpreter::builtin::BUILTIN_DOCS.iter().any(|(name, _, _)| name == tamil), "{}", tamil);
+        assert_eq!(first.arity, second.arity, "{} / {}", tamil, english);
+        let same = match (&first.function, &second.function) {
+            (NativeImpl::Plain(a), NativeImpl::Plain(b)) => std::ptr::fn_addr_eq(*a, *b),
+            (NativeImpl::Callback(a), NativeImpl::Callback(b)) => std::ptr::fn_addr_eq(*a, *b),
+            _ => false,
+        };
+        assert!(same, "{} / {}", tamil, english);
+    }
+}
+
+// ============= Checker Tests =============
+
+/// Each diagnostic as (line, column, code)
+fn check(source: &str) -> Vec<(usize, usize, &'static str)> {
+    check_source(source).iter().map(|d| (d.line, d.column, d.code)).collect()
+}
+
+#[test]
+fn test_check_diagnostics() {
+    let code = "இறக்குமதி கணிதம்
+விருப்பம் நிறம்:
+    சிவப்பு
+    பச்சை(அளவு)
+    நீலம்
+கட்டமைப்பு புள்ளி:
+    x
+    y
+    x
+செயல் f(n):
+    மாறி பயனில்லை = 1
+    மாறி நீளம் = n
+    என்றால் n > 1:
+        திரும்பு நீளம்
+        அச்சிடு(\"இல்லை\")
+    திரும்பு தெரியாதது
+செயல் g(c):
+    பொருத்து c:
+        நிறம்.சிவப்பு => அச்சிடு(\"r\")
+        நிறம்.பச்சை(_) => அச்சிடு(\"g\")
+அச்சிடு(f(2), len(1, 2), max(1, 2, 3))";
+    assert_eq!(check(code), vec![
+        (1, 11, "unused-import"),
+        (9, 5, "duplicate-field"),
+        (11, 10, "unused-variable"),
+        (12, 10, "shadowed-builtin"),
+        (15, 9, "unreachable"),
+        (16, 14, "undefined-name"),
+        (18, 5, "non-exhaustive-match"),
+        (21, 15, "arity"),
+    ]);
+    let diagnostics = check_source(code);
+    assert_eq!(diagnostics[6].message, "'நிறம்' இன் எல்லா வகைகளும் பொருத்தப்படவில்லை: நிறம்.நீலம்");
+    assert_eq!(diagnostics[7].message, "'len' செயலுக்கு 1 அளவுருக்கள் தேவை, 2 கொடுக்கப்பட்டது");
+    assert_eq!(diagnostics[7].severity, Severity::Error);
+    assert_eq!(diagnostics[6].severity, Severity::Warning);
+
+    // Errors that stop analysis are reported by kind
+    assert_eq!(check("மாறி = 1"), vec![(1, 6, "parser")]);
+    assert_eq!(check("திரும்பு 1"), vec![(1, 1, "compile")]);
+}
+
+#[test]
+fn test_check_clean_program() {
+    // Forward references, closures, recursion, lambdas, comprehensions,
+    // guards and wildcards are all fine
+    let code = "விருப்பம் வடிவம்:
+    வட்டம்(r)
+    சதுரம்(s)
+செயல் பரப்பு(v):
+    திரும்பு பொருத்து v:
+        வடிவம்.வட்டம்(r) => 3 * r * r
+        வடிவம்.சதுரம்(s) => s * s
+செயல் எண்ணி():
+    மாறி n = 0
+    செயல் அடுத்த():
+        வெளிமாறி n
+        n += 1
+        திரும்பு n
+    திரும்பு அடுத்த
+செயல் பெரியது(x):
+    பொருத்து x:
+        வடிவம்.வட்டம்(r) என்றால் r > 10 => திரும்பு உண்மை
+        _ => திரும்பு பொய்
+செயல் முதல்(n):
+    என்றால் n < 2:
+        திரும்பு n
+    திரும்பு முதல்(n - 1) + len(பின்(n))
+செயல் பின்(n):
+    மாறி _தவிர் = 0
+    ஒவ்வொரு i உள்ள வரம்பு(n):
+        என்றால் i > 2:
+            நிறுத்து
+    திரும்பு [x * 2 ஒவ்வொரு x உள்ள வரம்பு(n) என்றால் x > 0]
+மாறி சேர்த்தல் = (a, b) => a + b
+அச்சிடு(பரப்பு(வடிவம்.சதுரம்(2)), எண்ணி()(), பெரியது(1), முதல்(3), சேர்த்தல்(1, 2), max(1, 2, 3))
+சேர்த்தல்(1, 1)";
+    assert_eq!(check(code), vec![]);
+    assert_eq!(run(code).unwrap().to_string(), "2");
+}
+
+#[test]
+fn test_check_binary_formats() {
+    use std::process::Command;
+    let path = std::env::temp_dir().join(format!("agam_check_{}.agam", std::process::id()));
+    let agam = |args: &[&str]| Command::new(env!("CARGO_BIN_EXE_agam")).arg("check").args(args).arg(&path).output().unwrap();
+
+    std::fs::write(&path, "செயல் f():\n    மாறி x = 1\n    திரும்பு 2\nஅச்சிடு(f())\n").unwrap();
+    let output = agam(&[]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(stdout.contains(":2:10: எச்சரிக்கை (warning) [unused-variable]: மாறி 'x' பயன்படுத்தப்படவில்லை"));
+    assert!(stdout.ends_with("0 பிழைகள், 1 எச்சரிக்கைகள்\n"));
+
+    std::fs::write(&path, "அச்சிடு(y)\n").unwrap();
+    let output = agam(&["--format", "json"]);
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["code"], "undefined-name");
+    assert_eq!(json[0]["severity"], "error");
+    assert_eq!((json[0]["line"].as_u64(), json[0]["column"].as_u64()), (Some(1), Some(9)));
+    assert_eq!(json[0]["file"], path.to_string_lossy().as_ref());
+    std::fs::remove_file(&path).unwrap();
+}
+
+// ============= Test Framework Tests =============
+
+#[test]
+fn test_assert_builtins() {
+    let code = "உறுதி(1 < 2)
+assert(உண்மை, \"செய்தி\")
+உறுதி_சமம்([1, {\"a\": (2, 3)}], [1, {\"a\": (2, 3)}])
+assert_ne(1, \"1\")
+உறுதி_வேறு([1], [2])
+1";
+    assert_eq!(run(code).unwrap().to_string(), "1");
+
+    assert_eq!(runtime_error("உறுதி(1 > 2, \"பெரியது\")").message(), "உறுதிப்படுத்தல் தோல்வி (பெரியது)");
+    assert_eq!(runtime_error("assert_eq(\"1\", 1)").message(),
+        "உறுதிப்படுத்தல் தோல்வி: மதிப்புகள் சமமில்லை\n  இடது:  \"1\"\n  வலது: 1");
+    assert_eq!(runtime_error("உறுதி_சமம்(1, 1.5)").location(), (1, 1));
+    assert_eq!(runtime_error("உறுதி_வேறு([1], [1])").message(),
+        "உறுதிப்படுத்தல் தோல்வி: மதிப்புகள் சமம்\n  இரண்டும்: [1]");
+    assert_eq!(runtime_error("உறுதி()").message(), "உறுதி() 1-2 அளவுருக்கள் எடுக்கும்");
+
+    // Failed assertions are errors of their own kind; misuse is a runtime error
+    assert_eq!(runtime_error("உறுதி(பொய்)").kind(), "assertion");
+    assert_eq!(runtime_error("உறுதி_சமம்(1)").kind(), "runtime");
+    let code = "மாறி seen = []
+முயற்சி:
+    உறுதி_சமம்(1, 2)
+பிடி இயக்கம் e:
+    சேர்(seen, \"runtime\")
+பிடி உறுதிப்படுத்தல் e:
+    சேர்(seen, e.kind)
+    சேர்(seen, e.வகை)
+seen";
+    assert_eq!(run(code).unwrap().to_string(), "[assertion, உறுதிப்படுத்தல்]");
+
+    // Multi-line values are diffed line by line
+    assert_eq!(runtime_error("உறுதி_சமம்(\"a\\nb\\nc\", \"a\\nx\\nc\\nd\", \"வரிகள்\")").message(),
+        "உறுதிப்படுத்தல் தோல்வி: மதிப்புகள் சமமில்லை (வரிகள்)\n  --- இடது\n  +++ வலது\n    a\n  - b\n  + x\n    c\n  + d");
+}
+
+#[test]
+fn test_runner_outcomes_and_reports() {
+    let code = "மாறி எண்ணிக்கை = [0]
+செயல் சோதனை_ஒன்று():
+    சேர்(எண்ணிக்கை, 1)
+    அச்சிடு(\"<ஒன்று>\")
+    உறுதி_சமம்(நீளம்(எண்ணிக்கை), 2)
+செயல் test_isolated():
+    உறுதி_சமம்(நீளம்(எண்ணிக்கை), 1)
+செயல் test_fails():
+    உறுதி_சமம்(1 + 1, 3)
+செயல் test_errors():
+    வீசு \"உடைந்தது\"
+செயல் உதவி():
+    உறுதி(பொய்)";
+    for backend in [Backend::TreeWalker, Backend::Vm] {
+        let results = run_tests("math.agam", code, backend);
+        let names: Vec<&str> = results.iter().map(|result| result.name.as_str()).collect();
+        assert_eq!(names, ["சோதனை_ஒன்று", "test_isolated", "test_fails", "test_errors"]);
+        assert!(results[0].passed() && results[1].passed());
+        assert_eq!(results[0].output, "<ஒன்று>\n");
+        assert!(matches!(&results[2].outcome, Outcome::Failed(error) if error.location() == (9, 5)));
+        assert!(matches!(&results[3].outcome, Outcome::Errored(error) if error.kind() == "thrown"));
+
+        let report = tap(&results);
+        assert!(report.starts_with("TAP version 13\n1..4\n# <ஒன்று>\nok 1 - math.agam: சோதனை_ஒன்று\n  ---\n  duration_ms: "));
+        assert!(report.contains("not ok 3 - math.agam: test_fails\n"));
+        assert!(report.contains("  severity: fail\n  kind: assertion\n  at: \"math.agam:9:5\"\n  message: |-\n    உறுதிப்படுத்தல் தோல்வி: மதிப்புகள் சமமில்லை\n      இடது:  2\n      வலது: 3\n  ...\n"));
+        assert!(report.contains("not ok 4 - math.agam: test_errors\n"));
+        assert!(report.contains("# வெற்றி (pass) 2\n# தோல்வி (fail) 2\n"));
+
+        let xml = junit(&results);
+        assert!(xml.contains("<testsuite name=\"math.agam\" tests=\"4\" failures=\"1\" errors=\"1\" time=\""));
+        assert!(xml.contains("<system-out>&lt;ஒன்று&gt;\n</system-out>"));
+        assert!(xml.contains("<failure message=\"உறுதிப்படுத்தல் தோல்வி: மதிப்புகள் சமமில்லை\" type=\"assertion\">"));
+        assert!(xml.contains("<error message=\"உடைந்தது\" type=\"thrown\">"));
+    }
+
+    // Tests are called from their declarations, and may not take parameters
+    let code = "செயல் test_throws():\n    வீசு \"x\"\nசெயல் test_args(a, *rest):\n    உறுதி(உண்மை)";
+    for backend in [Backend::TreeWalker, Backend::Vm] {
+        let results = run_tests("calls.agam", code, backend);
+        let Outcome::Errored(error) = &results[0].outcome else { panic!("test_throws should error") };
+        let frames: Vec<(&str, usize, usize)> =
+            error.stack().iter().map(|frame| (frame.function.as_str(), frame.line, frame.column)).collect();
+        assert_eq!(frames, vec![("<நிரல்>", 1, 1), ("test_throws", 2, 5)]);
+        assert!(matches!(&results[1].outcome, Outcome::Errored(error)
+            if error.kind() == "compile" && error.location() == (3, 1)
+                && error.message() == "சோதனைச் செயல் 'test_args' அளவுருக்கள் எடுக்கக் கூடாது"));
+    }
+
+    // A file that does not parse is one errored result
+    let results = run_tests("bad.agam", "செயல் test_x(:\n", Backend::TreeWalker);
+    assert_eq!(results.len(), 1);
+    assert!(matches!(&results[0].outcome, Outcome::Errored(error) if error.kind() == "parser"));
+}
+
+#[test]
+fn test_runner_binary() {
+    use std::process::Command;
+    let dir = std::env::temp_dir().join(format!("agam_test_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("nested")).unwrap();
+    std::fs::write(dir.join("a.agam"), "செயல் test_a():\n    உறுதி(உண்மை)\n").unwrap();
+    std::fs::write(dir.join("nested").join("b.agam"), "செயல் சோதனை_b():\n    உறுதி_சமம்(1, 1)\n").unwrap();
+    let agam = |args: &[&str]| Command::new(env!("CARGO_BIN_EXE_agam")).arg("test").args(args).arg(&dir).output().unwrap();
+
+    let output = agam(&[]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(stdout.starts_with("TAP version 13\n1..2\n"));
+    assert!(stdout.contains("a.agam: test_a\n") && stdout.contains("b.agam: சோதனை_b\n"));
+
+    std::fs::write(dir.join("c.agam"), "செயல் test_c():\n    உறுதி(பொய்)\n").unwrap();
+    let output = agam(&["--vm", "--format", "junit"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(stdout.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"agam\" tests=\"3\" failures=\"1\" errors=\"0\""));
+    std::fs::remove_dir_all(&dir).unwrap();
+}